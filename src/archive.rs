@@ -0,0 +1,316 @@
+use std::path::Path;
+
+use crate::deflate::{crc32, deflate, inflate};
+use crate::tar;
+
+// Archive formats whose members can be renamed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+impl Kind {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".tar") {
+            Some(Kind::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Kind::TarGz)
+        } else if name.ends_with(".zip") {
+            Some(Kind::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+// Rewrite every member of the archive. The function receives the path and content of a member,
+// may change both and returns the number of substitutions it made. Returns the repacked archive
+// and the total number of substitutions.
+pub fn rewrite(kind: Kind, bytes: &[u8], mut f: impl FnMut(&mut String, &mut Vec<u8>) -> usize) -> Result<(Vec<u8>, usize), String> {
+    match kind {
+        Kind::Tar => rewrite_tar(bytes, &mut f),
+        Kind::TarGz => {
+            let (tar, mtime) = gunzip(bytes)?;
+            let (tar, substitutions) = rewrite_tar(&tar, &mut f)?;
+            Ok((gzip(&tar, mtime), substitutions))
+        }
+        Kind::Zip => rewrite_zip(bytes, &mut f),
+    }
+}
+
+fn rewrite_tar(bytes: &[u8], f: &mut impl FnMut(&mut String, &mut Vec<u8>) -> usize) -> Result<(Vec<u8>, usize), String> {
+    let mut substitutions = 0;
+    let mut writer = tar::Writer::new(vec![]);
+    for mut entry in tar::read(bytes)? {
+        substitutions += f(&mut entry.name, &mut entry.data);
+        writer.entry(&entry).map_err(|e| e.to_string())?;
+    }
+    Ok((writer.finish().map_err(|e| e.to_string())?, substitutions))
+}
+
+// Decompress a gzip file (RFC 1952), returning the data and the modification time of the header
+fn gunzip(bytes: &[u8]) -> Result<(Vec<u8>, u32), String> {
+    if bytes.len() < 18 || bytes[0..3] != [0x1f, 0x8b, 8] {
+        return Err("Not a gzip file".to_string());
+    }
+    let flags = bytes[3];
+    let mtime = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+    let mut pos = 10;
+    if flags & 0x04 != 0 {
+        let extra = u16::from_le_bytes([bytes[pos], bytes[pos + 1]]) as usize;
+        pos += 2 + extra;
+    }
+    // Zero terminated file name and comment
+    for flag in [0x08, 0x10] {
+        if flags & flag != 0 {
+            pos += bytes.get(pos..).and_then(|rest| rest.iter().position(|&b| b == 0)).ok_or("Invalid gzip header")? + 1;
+        }
+    }
+    if flags & 0x02 != 0 {
+        pos += 2;
+    }
+    let (data, used) = inflate(bytes.get(pos..).ok_or("Invalid gzip header")?)?;
+    let trailer = bytes.get(pos + used..pos + used + 8).ok_or("Missing gzip trailer")?;
+    if u32::from_le_bytes(trailer[..4].try_into().unwrap()) != crc32(&data) {
+        return Err("Invalid gzip checksum".to_string());
+    }
+    Ok((data, mtime))
+}
+
+fn gzip(data: &[u8], mtime: u32) -> Vec<u8> {
+    let mut out = vec![0x1f, 0x8b, 8, 0];
+    out.extend_from_slice(&mtime.to_le_bytes());
+    out.extend_from_slice(&[0, 255]);
+    out.extend_from_slice(&deflate(data));
+    out.extend_from_slice(&crc32(data).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out
+}
+
+// Signatures of the zip records
+const LOCAL_HEADER: u32 = 0x04034b50;
+const CENTRAL_HEADER: u32 = 0x02014b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x06054b50;
+
+// Compression methods
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+
+// Name is UTF-8
+const UTF8_FLAG: u16 = 0x0800;
+
+// A member of a zip archive with the metadata that is kept when it is repacked
+struct ZipMember {
+    name: String,
+    data: Vec<u8>,
+    version_made_by: u16,
+    time: u16,
+    date: u16,
+    internal_attributes: u16,
+    external_attributes: u32,
+}
+
+fn u16_at(bytes: &[u8], pos: usize) -> Result<u16, String> {
+    bytes
+        .get(pos..pos + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| "Unexpected end of zip file".to_string())
+}
+
+fn u32_at(bytes: &[u8], pos: usize) -> Result<u32, String> {
+    bytes
+        .get(pos..pos + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| "Unexpected end of zip file".to_string())
+}
+
+fn read_zip(bytes: &[u8]) -> Result<Vec<ZipMember>, String> {
+    // The end of central directory record is followed by a comment of at most 65535 bytes
+    let search_start = bytes.len().saturating_sub(22 + 65535);
+    let end = (search_start..bytes.len().saturating_sub(21))
+        .rev()
+        .find(|&pos| u32_at(bytes, pos) == Ok(END_OF_CENTRAL_DIRECTORY))
+        .ok_or("Not a zip file")?;
+    let count = u16_at(bytes, end + 10)? as usize;
+    let mut pos = u32_at(bytes, end + 16)? as usize;
+    if count == 0xFFFF || pos == 0xFFFFFFFF {
+        return Err("ZIP64 archives are not supported".to_string());
+    }
+
+    let mut members = vec![];
+    for _ in 0..count {
+        if u32_at(bytes, pos)? != CENTRAL_HEADER {
+            return Err("Invalid central directory".to_string());
+        }
+        let flags = u16_at(bytes, pos + 8)?;
+        if flags & 1 != 0 {
+            return Err("Encrypted archives are not supported".to_string());
+        }
+        let method = u16_at(bytes, pos + 10)?;
+        let crc = u32_at(bytes, pos + 16)?;
+        let compressed_size = u32_at(bytes, pos + 20)? as usize;
+        let name_length = u16_at(bytes, pos + 28)? as usize;
+        let extra_length = u16_at(bytes, pos + 30)? as usize;
+        let comment_length = u16_at(bytes, pos + 32)? as usize;
+        let offset = u32_at(bytes, pos + 42)? as usize;
+        let name = bytes.get(pos + 46..pos + 46 + name_length).ok_or("Unexpected end of zip file")?;
+
+        if u32_at(bytes, offset)? != LOCAL_HEADER {
+            return Err("Invalid local header".to_string());
+        }
+        let data_start = offset + 30 + u16_at(bytes, offset + 26)? as usize + u16_at(bytes, offset + 28)? as usize;
+        let raw = bytes.get(data_start..data_start + compressed_size).ok_or("Unexpected end of zip file")?;
+        let data = match method {
+            STORED => raw.to_vec(),
+            DEFLATED => inflate(raw)?.0,
+            _ => return Err(format!("Compression method {method} is not supported")),
+        };
+        if crc32(&data) != crc {
+            return Err(format!("Invalid checksum of {}", String::from_utf8_lossy(name)));
+        }
+
+        members.push(ZipMember {
+            name: String::from_utf8_lossy(name).to_string(),
+            data,
+            version_made_by: u16_at(bytes, pos + 4)?,
+            time: u16_at(bytes, pos + 12)?,
+            date: u16_at(bytes, pos + 14)?,
+            internal_attributes: u16_at(bytes, pos + 36)?,
+            external_attributes: u32_at(bytes, pos + 38)?,
+        });
+        pos += 46 + name_length + extra_length + comment_length;
+    }
+    Ok(members)
+}
+
+fn write_zip(members: &[ZipMember]) -> Vec<u8> {
+    let mut out = vec![];
+    let mut central = vec![];
+    for member in members {
+        let offset = out.len() as u32;
+        let crc = crc32(&member.data);
+        let compressed = deflate(&member.data);
+        let (method, data) = if compressed.len() < member.data.len() { (DEFLATED, &compressed) } else { (STORED, &member.data) };
+        let version_needed: u16 = if method == DEFLATED { 20 } else { 10 };
+
+        // Fields shared by the local and central header, from the version needed to the name length
+        let mut common = vec![];
+        common.extend_from_slice(&version_needed.to_le_bytes());
+        common.extend_from_slice(&UTF8_FLAG.to_le_bytes());
+        common.extend_from_slice(&method.to_le_bytes());
+        common.extend_from_slice(&member.time.to_le_bytes());
+        common.extend_from_slice(&member.date.to_le_bytes());
+        common.extend_from_slice(&crc.to_le_bytes());
+        common.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        common.extend_from_slice(&(member.data.len() as u32).to_le_bytes());
+        common.extend_from_slice(&(member.name.len() as u16).to_le_bytes());
+
+        out.extend_from_slice(&LOCAL_HEADER.to_le_bytes());
+        out.extend_from_slice(&common);
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(member.name.as_bytes());
+        out.extend_from_slice(data);
+
+        central.extend_from_slice(&CENTRAL_HEADER.to_le_bytes());
+        central.extend_from_slice(&member.version_made_by.to_le_bytes());
+        central.extend_from_slice(&common);
+        central.extend_from_slice(&[0; 6]); // Extra field and comment length, disk number
+        central.extend_from_slice(&member.internal_attributes.to_le_bytes());
+        central.extend_from_slice(&member.external_attributes.to_le_bytes());
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(member.name.as_bytes());
+    }
+
+    let central_offset = out.len() as u32;
+    out.extend_from_slice(&central);
+    out.extend_from_slice(&END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+    out.extend_from_slice(&[0; 4]); // Disk numbers
+    out.extend_from_slice(&(members.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(members.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    out.extend_from_slice(&central_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out
+}
+
+fn rewrite_zip(bytes: &[u8], f: &mut impl FnMut(&mut String, &mut Vec<u8>) -> usize) -> Result<(Vec<u8>, usize), String> {
+    let mut members = read_zip(bytes)?;
+    if members.len() >= 0xFFFF || bytes.len() as u64 >= 0xFFFFFFFF {
+        return Err("ZIP64 archives are not supported".to_string());
+    }
+    let mut substitutions = 0;
+    for member in &mut members {
+        substitutions += f(&mut member.name, &mut member.data);
+    }
+    Ok((write_zip(&members), substitutions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rename(name: &mut String, data: &mut Vec<u8>) -> usize {
+        let count = name.matches("old").count() + data.windows(3).filter(|w| w == b"old").count();
+        *name = name.replace("old", "new");
+        *data = String::from_utf8_lossy(data).replace("old", "new").into_bytes();
+        count
+    }
+
+    #[test]
+    fn test_kind_from_path() {
+        assert_eq!(Kind::from_path(Path::new("a/template.tar")), Some(Kind::Tar));
+        assert_eq!(Kind::from_path(Path::new("release.TAR.GZ")), Some(Kind::TarGz));
+        assert_eq!(Kind::from_path(Path::new("release.tgz")), Some(Kind::TarGz));
+        assert_eq!(Kind::from_path(Path::new("release.zip")), Some(Kind::Zip));
+        assert_eq!(Kind::from_path(Path::new("main.rs")), None);
+    }
+
+    #[test]
+    fn test_rewrite_zip() {
+        let members = vec![
+            ZipMember {
+                name: "old/".to_string(),
+                data: vec![],
+                version_made_by: 0x031E,
+                time: 0,
+                date: 0x21,
+                internal_attributes: 0,
+                external_attributes: 0o40755 << 16,
+            },
+            ZipMember {
+                name: "old/old.txt".to_string(),
+                data: "old project ".repeat(20).into_bytes(),
+                version_made_by: 0x031E,
+                time: 0,
+                date: 0x21,
+                internal_attributes: 1,
+                external_attributes: 0o100644 << 16,
+            },
+        ];
+        let (bytes, substitutions) = rewrite(Kind::Zip, &write_zip(&members), rename).unwrap();
+        assert_eq!(substitutions, 23);
+        let members = read_zip(&bytes).unwrap();
+        assert_eq!(members[1].name, "new/new.txt");
+        assert_eq!(members[1].data, "new project ".repeat(20).into_bytes());
+        assert_eq!(members[1].external_attributes, 0o100644 << 16);
+    }
+
+    #[test]
+    fn test_rewrite_tar_gz() {
+        let mut writer = tar::Writer::new(vec![]);
+        writer.header("old.txt", tar::FILE, 0o644, 0, 3, "").unwrap();
+        writer.content(&mut &b"old"[..]).unwrap();
+        let archive = gzip(&writer.finish().unwrap(), 7);
+
+        let (bytes, substitutions) = rewrite(Kind::TarGz, &archive, rename).unwrap();
+        assert_eq!(substitutions, 2);
+        let (tar, mtime) = gunzip(&bytes).unwrap();
+        assert_eq!(mtime, 7);
+        let entries = tar::read(&tar).unwrap();
+        assert_eq!((entries[0].name.as_str(), entries[0].data.as_slice()), ("new.txt", &b"new"[..]));
+        assert!(rewrite(Kind::TarGz, b"not gzip", rename).is_err());
+    }
+}
@@ -0,0 +1,25 @@
+use std::path::Path;
+
+// The name of the primary file of an Arduino sketch in its renamed folder. The IDE only opens a
+// sketch whose primary file is named like its folder, so "OldName/OldName.ino" has to become
+// "new-name/new-name.ino" even when the case variants would make it "NewName.ino". None for other
+// files.
+pub fn primary_sketch(dir: &Path, file_name: &str, new_dir: &Path) -> Option<String> {
+    let (stem, extension) = file_name.rsplit_once('.')?;
+    let new_stem = new_dir.file_name()?.to_string_lossy();
+    (matches!(extension, "ino" | "pde") && dir.file_name()? == stem).then(|| format!("{new_stem}.{extension}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_primary_sketch() {
+        let (dir, new_dir) = (Path::new("sketches/OldName"), Path::new("sketches/new-name"));
+        assert_eq!(primary_sketch(dir, "OldName.ino", new_dir), Some("new-name.ino".to_string()));
+        assert_eq!(primary_sketch(dir, "OldName.pde", new_dir), Some("new-name.pde".to_string()));
+        assert_eq!(primary_sketch(dir, "Helpers.ino", new_dir), None);
+        assert_eq!(primary_sketch(dir, "OldName.h", new_dir), None);
+    }
+}
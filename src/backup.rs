@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::tar;
+
+// Write a tar archive of the project before it is renamed in place. Paths ignored by git are left
+// out when the project is in a git work tree, they can be regenerated. The archive isn't compressed,
+// deflate::deflate needs all of the data in memory while the entries are streamed to the file.
+// Returns the number of archived entries.
+pub fn create_backup(input: &Path, archive: &Path) -> std::io::Result<usize> {
+    let ignored = ignored_paths(input);
+    let root = PathBuf::from(input.file_name().unwrap());
+    let mut writer = TarWriter {
+        tar: tar::Writer::new(BufWriter::new(File::create(archive)?)),
+        entries: 0,
+    };
+    let result = writer.add(input, &root, &ignored).and_then(|_| writer.tar.finish());
+    if result.is_err() {
+        let _ = std::fs::remove_file(archive);
+    }
+    result.map(|_| writer.entries)
+}
+
+// Paths in the project that git ignores, empty if git isn't available or the project isn't in a
+// work tree
+fn ignored_paths(input: &Path) -> HashSet<PathBuf> {
+    let Ok(output) = Command::new("git")
+        .args(["ls-files", "--others", "--ignored", "--exclude-standard", "--directory", "-z"])
+        .current_dir(input)
+        .output()
+    else {
+        return HashSet::new();
+    };
+    if !output.status.success() {
+        return HashSet::new();
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(|path| input.join(path.trim_end_matches('/')))
+        .collect()
+}
+
+struct TarWriter {
+    tar: tar::Writer<BufWriter<File>>,
+    entries: usize,
+}
+
+impl TarWriter {
+    fn add(&mut self, path: &Path, name: &Path, ignored: &HashSet<PathBuf>) -> std::io::Result<()> {
+        if ignored.contains(path) {
+            return Ok(());
+        }
+        let metadata = std::fs::symlink_metadata(path)?;
+        let name = name.to_string_lossy().replace('\\', "/");
+        let mode = mode(&metadata);
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |duration| duration.as_secs());
+        if metadata.is_dir() {
+            self.tar.header(&format!("{name}/"), tar::DIRECTORY, mode, mtime, 0, "")?;
+            let mut paths = path.read_dir()?.map(|entry| entry.map(|entry| entry.path())).collect::<Result<Vec<_>, _>>()?;
+            paths.sort();
+            for child in paths {
+                let child_name = Path::new(&name).join(child.file_name().unwrap());
+                self.add(&child, &child_name, ignored)?;
+            }
+        } else if metadata.is_symlink() {
+            let target = std::fs::read_link(path)?;
+            self.tar.header(&name, tar::SYMLINK, mode, mtime, 0, &target.to_string_lossy())?;
+        } else {
+            self.tar.header(&name, tar::FILE, mode, mtime, metadata.len(), "")?;
+            self.tar.content(&mut File::open(path)?)?;
+        }
+        self.entries += 1;
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn mode(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn mode(metadata: &std::fs::Metadata) -> u32 {
+    match (metadata.is_dir(), metadata.permissions().readonly()) {
+        (true, _) => 0o755,
+        (false, true) => 0o444,
+        (false, false) => 0o644,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_backup() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-backup-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("my-project/src")).unwrap();
+        std::fs::write(dir.join("my-project/src/main.rs"), "fn main() {}").unwrap();
+
+        let archive = dir.join("my-project-backup.tar");
+        assert_eq!(create_backup(&dir.join("my-project"), &archive).unwrap(), 3);
+        let entries = tar::read(&std::fs::read(&archive).unwrap()).unwrap();
+        let names = entries.iter().map(|entry| entry.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["my-project/", "my-project/src/", "my-project/src/main.rs"]);
+        assert_eq!(entries[2].data, b"fn main() {}");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
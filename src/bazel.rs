@@ -0,0 +1,83 @@
+use std::ops::Range;
+use std::path::Path;
+
+use crate::syntax::{self, Language, Node};
+
+// The attributes of repository rules and module extensions that locate the sources of an external
+// repository, which don't change with the name of the project
+const SOURCE_ATTRIBUTES: [&str; 10] = ["url", "urls", "strip_prefix", "sha256", "integrity", "remote", "commit", "tag", "branch", "registry"];
+
+// Whether the file is read by Bazel: a BUILD file, a WORKSPACE, the MODULE.bazel of a module or a
+// Starlark extension like "defs.bzl"
+pub fn is_build_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| {
+        let name = name.to_string_lossy();
+        matches!(name.as_ref(), "BUILD" | "BUILD.bazel" | "WORKSPACE" | "WORKSPACE.bazel" | "WORKSPACE.bzlmod" | "MODULE.bazel")
+            || name.ends_with(".bzl")
+    })
+}
+
+// The ranges of a Bazel file the name is renamed in: everything but the strings of the attributes
+// locating external sources, like `urls = ["https://github.com/acme/old-name/..."]` or
+// `strip_prefix = "old-name-1.0"`. The names of workspaces, modules, repositories and targets and
+// the labels like "@old_name//old_name:lib" stay in, wherever they are.
+pub fn names(text: &str) -> Vec<Range<usize>> {
+    let spans = syntax::spans(text, Language::Python);
+    let token = |index: usize| &text[spans[index].range.clone()];
+    let mut ranges: Vec<Range<usize>> = vec![];
+    // The attribute whose value is being read, with the depth of the brackets it is at
+    let mut attribute: Option<(&str, usize)> = None;
+    let mut depth = 0usize;
+    for (index, span) in spans.iter().enumerate() {
+        match span.node {
+            Node::Identifier => {
+                let assigned = spans.get(index + 1).is_some_and(|_| {
+                    let next = token(index + 1).trim_start();
+                    next.starts_with('=') && !next.starts_with("==")
+                });
+                if assigned {
+                    attribute = Some((token(index), depth));
+                }
+            }
+            Node::String if attribute.is_some_and(|(attribute, _)| SOURCE_ATTRIBUTES.contains(&attribute)) => continue,
+            Node::Other => {
+                for c in token(index).chars() {
+                    match c {
+                        '(' | '[' | '{' => depth += 1,
+                        ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                        _ => {}
+                    }
+                    // The value ends with the next argument, the end of the call or the line
+                    if attribute.is_some_and(|(_, at)| depth < at || (depth == at && matches!(c, ',' | '\n' | ';'))) {
+                        attribute = None;
+                    }
+                }
+            }
+            _ => {}
+        }
+        match ranges.last_mut() {
+            Some(last) if last.end == span.range.start => last.end = span.range.end,
+            _ => ranges.push(span.range.clone()),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        let text = "http_archive(\n    name = \"my_app_deps\",\n    urls = [\"https://x/my-app/v1.tar.gz\", \"https://y/my-app.tgz\"],\n    \
+                    strip_prefix = \"my-app-1.0\",\n)\ncc_library(name = \"my_app\", deps = [\"@my_app_deps//:lib\"])\n";
+        let kept = names(text).into_iter().fold(String::new(), |kept, range| kept + &text[range]);
+        assert_eq!(
+            kept,
+            "http_archive(\n    name = \"my_app_deps\",\n    urls = [, ],\n    strip_prefix = ,\n)\ncc_library(name = \"my_app\", deps = [\"@my_app_deps//:lib\"])\n"
+        );
+        assert!(is_build_file(Path::new("src/BUILD.bazel")));
+        assert!(is_build_file(Path::new("tools/defs.bzl")));
+        assert!(!is_build_file(Path::new("BUILD.md")));
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::json::{self, Value};
+
+// Progress of a run, the files of the project that were written completely. Every file is appended
+// as a JSON string on its own line as soon as it is done, so the checkpoint stays valid when the
+// run is interrupted at any point.
+pub struct Checkpoint {
+    path: PathBuf,
+    root: PathBuf,
+    done: HashSet<PathBuf>,
+    file: File,
+}
+
+impl Checkpoint {
+    // Open the checkpoint for a run of the project at the root. When resuming the files of the
+    // previous run are loaded, otherwise it starts empty.
+    pub fn open(path: &Path, root: &Path, resume: bool) -> std::io::Result<Self> {
+        let mut done = HashSet::new();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if !resume {
+            file.set_len(0)?;
+        } else if let Ok(content) = std::fs::read_to_string(path) {
+            // A line cut off by the interruption doesn't parse and is ignored, but must be ended
+            // before new lines are appended
+            for line in content.lines() {
+                if let Ok(Value::String(relative)) = json::parse(line) {
+                    done.insert(PathBuf::from(relative));
+                }
+            }
+            if !content.is_empty() && !content.ends_with('\n') {
+                writeln!(file)?;
+            }
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            root: root.to_path_buf(),
+            done,
+            file,
+        })
+    }
+
+    fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.root).unwrap_or(path)
+    }
+
+    pub fn is_done(&self, path: &Path) -> bool {
+        self.done.contains(self.relative(path))
+    }
+
+    pub fn mark_done(&mut self, path: &Path) -> std::io::Result<()> {
+        let relative = self.relative(path).to_path_buf();
+        writeln!(self.file, "{}", json::string(&relative.to_string_lossy()))?;
+        self.file.flush()?;
+        self.done.insert(relative);
+        Ok(())
+    }
+
+    // Remove the checkpoint once the run is complete
+    pub fn remove(self) -> std::io::Result<()> {
+        std::fs::remove_file(&self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-checkpoint-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("checkpoint");
+        let root = Path::new("/project");
+
+        let mut checkpoint = Checkpoint::open(&path, root, false).unwrap();
+        checkpoint.mark_done(&root.join("src/main.rs")).unwrap();
+        checkpoint.mark_done(&root.join("line\nbreak.txt")).unwrap();
+        assert!(checkpoint.is_done(&root.join("src/main.rs")));
+        drop(checkpoint);
+
+        // An interrupted write leaves a partial line behind
+        std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"\"README").unwrap();
+        let mut checkpoint = Checkpoint::open(&path, root, true).unwrap();
+        assert!(checkpoint.is_done(&root.join("src/main.rs")));
+        assert!(checkpoint.is_done(&root.join("line\nbreak.txt")));
+        assert!(!checkpoint.is_done(&root.join("README")));
+        checkpoint.mark_done(&root.join("README")).unwrap();
+        drop(checkpoint);
+        assert!(Checkpoint::open(&path, root, true).unwrap().is_done(&root.join("README")));
+
+        // Without resuming the previous progress is discarded
+        let checkpoint = Checkpoint::open(&path, root, false).unwrap();
+        assert!(!checkpoint.is_done(&root.join("src/main.rs")));
+        checkpoint.remove().unwrap();
+        assert!(!path.exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
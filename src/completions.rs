@@ -0,0 +1,399 @@
+use clap::builder::PossibleValue;
+use clap::{Arg, ArgAction, Command, ValueHint};
+
+// Shells that completion scripts can be generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+// Generate a completion script for the command. The script is derived from the command definition
+// so new flags are picked up without changes here. It walks the same clap::Command that
+// clap_complete would, which isn't available to the offline build, for the four shells it covers.
+pub fn generate(shell: Shell, cmd: &Command) -> String {
+    let mut cmd = cmd.clone();
+    cmd.build();
+    match shell {
+        Shell::Bash => bash(&cmd),
+        Shell::Zsh => zsh(&cmd),
+        Shell::Fish => fish(&cmd),
+        Shell::Powershell => powershell(&cmd),
+    }
+}
+
+// What kind of value an argument completes to
+enum ValueKind {
+    None,
+    Values(Vec<String>),
+    Directory,
+    File,
+    Any,
+}
+
+fn visible_args(cmd: &Command) -> impl Iterator<Item = &Arg> {
+    cmd.get_arguments().filter(|arg| !arg.is_hide_set())
+}
+
+fn visible_subcommands(cmd: &Command) -> impl Iterator<Item = &Command> {
+    cmd.get_subcommands().filter(|sub| !sub.is_hide_set())
+}
+
+fn value_kind(arg: &Arg) -> ValueKind {
+    if !arg.get_action().takes_values() {
+        return ValueKind::None;
+    }
+    let values = arg
+        .get_possible_values()
+        .iter()
+        .filter(|value| !value.is_hide_set())
+        .map(PossibleValue::get_name)
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if !values.is_empty() {
+        return ValueKind::Values(values);
+    }
+    match arg.get_value_hint() {
+        ValueHint::DirPath => ValueKind::Directory,
+        ValueHint::FilePath | ValueHint::AnyPath => ValueKind::File,
+        _ => ValueKind::Any,
+    }
+}
+
+// Abbreviations whose period doesn't end a sentence
+const ABBREVIATIONS: [&str; 5] = ["e.g.", "i.e.", "etc.", "vs.", "cf."];
+
+// First sentence of the help text of an argument or command
+fn help_line(help: Option<&clap::builder::StyledStr>) -> String {
+    let help = help.map(|help| help.to_string()).unwrap_or_default();
+    let line = help.lines().next().unwrap_or_default();
+    let end = line
+        .match_indices(". ")
+        .map(|(at, _)| at + 1)
+        .find(|&end| {
+            let word = line[..end].rsplit([' ', '(']).next().unwrap_or_default();
+            !ABBREVIATIONS.contains(&word.to_lowercase().as_str())
+        })
+        .unwrap_or(line.len());
+    line[..end].trim_end_matches('.').to_string()
+}
+
+// All the ways an option can be written on the command line, e.g. "-n --name"
+fn flags(arg: &Arg) -> Vec<String> {
+    let mut flags = vec![];
+    if let Some(short) = arg.get_short() {
+        flags.push(format!("-{short}"));
+    }
+    if let Some(long) = arg.get_long() {
+        flags.push(format!("--{long}"));
+    }
+    flags
+}
+
+fn function_name(cmd: &Command) -> String {
+    format!("_{}", cmd.get_name().replace('-', "_"))
+}
+
+fn bash(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let function = function_name(cmd);
+    let mut out = format!(
+        "{function}() {{\n    local cur prev cmd opts\n    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n    cmd=\"\"\n\n"
+    );
+
+    // Find the subcommand, if any
+    out.push_str("    for word in \"${COMP_WORDS[@]:1:COMP_CWORD-1}\"; do\n        case \"$word\" in\n");
+    for sub in visible_subcommands(cmd) {
+        out.push_str(&format!("            {0}) cmd=\"{0}\"; break ;;\n", sub.get_name()));
+    }
+    out.push_str("        esac\n    done\n\n    case \"$cmd\" in\n");
+
+    for (label, cmd) in std::iter::once(("\"\"", cmd)).chain(visible_subcommands(cmd).map(|sub| (sub.get_name(), sub))) {
+        out.push_str(&format!("        {label})\n            case \"$prev\" in\n"));
+        for arg in visible_args(cmd) {
+            let flags = flags(arg);
+            if flags.is_empty() {
+                continue;
+            }
+            let reply = match value_kind(arg) {
+                ValueKind::None => continue,
+                ValueKind::Values(values) => format!("COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))", values.join(" ")),
+                ValueKind::Directory => "COMPREPLY=($(compgen -d -- \"$cur\"))".to_string(),
+                ValueKind::File => "COMPREPLY=($(compgen -f -- \"$cur\"))".to_string(),
+                ValueKind::Any => "COMPREPLY=()".to_string(),
+            };
+            out.push_str(&format!("                {}) {reply}; return 0 ;;\n", flags.join("|")));
+        }
+        out.push_str("            esac\n");
+
+        let mut words = visible_args(cmd).flat_map(flags).collect::<Vec<_>>();
+        for arg in visible_args(cmd).filter(|arg| arg.is_positional()) {
+            if let ValueKind::Values(values) = value_kind(arg) {
+                words.extend(values);
+            }
+        }
+        words.extend(visible_subcommands(cmd).map(|sub| sub.get_name().to_string()));
+        out.push_str(&format!("            opts=\"{}\"\n            ;;\n", words.join(" ")));
+    }
+    out.push_str("    esac\n\n    COMPREPLY=($(compgen -W \"$opts\" -- \"$cur\"))\n}\n\n");
+    out.push_str(&format!("complete -F {function} -o bashdefault -o default {name}\n"));
+    out
+}
+
+// Escape text used inside a single quoted zsh _arguments spec
+fn zsh_escape(text: &str) -> String {
+    text.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+fn zsh_args(cmd: &Command) -> Vec<String> {
+    let mut specs = vec![];
+    for arg in visible_args(cmd) {
+        let help = zsh_escape(&help_line(arg.get_help()));
+        let value_name = arg.get_id().as_str().to_uppercase();
+        let action = match value_kind(arg) {
+            ValueKind::None => String::new(),
+            ValueKind::Values(values) => format!(":{value_name}:({})", values.join(" ")),
+            ValueKind::Directory => format!(":{value_name}:_files -/"),
+            ValueKind::File => format!(":{value_name}:_files"),
+            ValueKind::Any => format!(":{value_name}:"),
+        };
+        if arg.is_positional() {
+            specs.push(format!("'{action}'"));
+            continue;
+        }
+        let repeat = if matches!(arg.get_action(), ArgAction::Count | ArgAction::Append) { "*" } else { "" };
+        for flag in flags(arg) {
+            specs.push(format!("'{repeat}{flag}[{help}]{action}'"));
+        }
+    }
+    specs
+}
+
+fn zsh(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let function = function_name(cmd);
+    let mut specs = zsh_args(cmd);
+    let has_subcommands = visible_subcommands(cmd).next().is_some();
+    if has_subcommands {
+        specs.push(format!("':: :{function}_commands'"));
+        specs.push("'*::arg:->args'".to_string());
+    }
+
+    let mut out = format!("#compdef {name}\n\n{function}() {{\n    local context state state_descr line\n    typeset -A opt_args\n\n");
+    out.push_str(&format!("    _arguments -s -C \\\n        {}\n", specs.join(" \\\n        ")));
+
+    if has_subcommands {
+        out.push_str("\n    case $state in\n        (args)\n            case $line[1] in\n");
+        for sub in visible_subcommands(cmd) {
+            out.push_str(&format!(
+                "                ({}) _arguments -s \\\n                    {} ;;\n",
+                sub.get_name(),
+                zsh_args(sub).join(" \\\n                    ")
+            ));
+        }
+        out.push_str("            esac\n            ;;\n    esac\n");
+    }
+    out.push_str("}\n\n");
+
+    if has_subcommands {
+        out.push_str(&format!("{function}_commands() {{\n    local commands\n    commands=(\n"));
+        for sub in visible_subcommands(cmd) {
+            out.push_str(&format!(
+                "        '{}:{}'\n",
+                sub.get_name(),
+                zsh_escape(&help_line(sub.get_about()))
+            ));
+        }
+        out.push_str("    )\n    _describe -t commands 'command' commands\n}\n\n");
+    }
+
+    out.push_str(&format!("if [ \"$funcstack[1]\" = \"{function}\" ]; then\n    {function} \"$@\"\nelse\n    compdef {function} {name}\nfi\n"));
+    out
+}
+
+// Escape text used inside a single quoted fish string
+fn fish_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+fn fish_args(name: &str, condition: &str, cmd: &Command) -> String {
+    let mut out = String::new();
+    for arg in visible_args(cmd) {
+        let help = fish_escape(&help_line(arg.get_help()));
+        let values = match value_kind(arg) {
+            ValueKind::None => String::new(),
+            ValueKind::Values(values) => format!(" -r -f -a \"{}\"", values.join(" ")),
+            ValueKind::Directory => " -r -f -a \"(__fish_complete_directories)\"".to_string(),
+            ValueKind::File => " -r -F".to_string(),
+            ValueKind::Any => " -r".to_string(),
+        };
+        let mut line = format!("complete -c {name} -n \"{condition}\"");
+        if let Some(short) = arg.get_short() {
+            line.push_str(&format!(" -s {short}"));
+        }
+        if let Some(long) = arg.get_long() {
+            line.push_str(&format!(" -l {long}"));
+        }
+        line.push_str(&format!(" -d '{help}'{values}\n"));
+        out.push_str(&line);
+    }
+    out
+}
+
+fn fish(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let mut out = fish_args(name, "__fish_use_subcommand", cmd);
+    for sub in visible_subcommands(cmd) {
+        out.push_str(&format!(
+            "complete -c {name} -n \"__fish_use_subcommand\" -f -a \"{}\" -d '{}'\n",
+            sub.get_name(),
+            fish_escape(&help_line(sub.get_about()))
+        ));
+    }
+    for sub in visible_subcommands(cmd) {
+        out.push_str(&fish_args(name, &format!("__fish_seen_subcommand_from {}", sub.get_name()), sub));
+    }
+    out
+}
+
+// Escape text used inside a single quoted PowerShell string
+fn powershell_escape(text: &str) -> String {
+    text.replace('\'', "''")
+}
+
+fn powershell_results(cmd: &Command) -> String {
+    let mut out = String::new();
+    for arg in visible_args(cmd) {
+        let help = powershell_escape(&help_line(arg.get_help()));
+        if arg.is_positional() {
+            if let ValueKind::Values(values) = value_kind(arg) {
+                for value in values {
+                    out.push_str(&format!(
+                        "            [CompletionResult]::new('{value}', '{value}', [CompletionResultType]::ParameterValue, '{value}')\n"
+                    ));
+                }
+            }
+            continue;
+        }
+        for flag in flags(arg) {
+            out.push_str(&format!(
+                "            [CompletionResult]::new('{flag}', '{flag}', [CompletionResultType]::ParameterName, '{help}')\n"
+            ));
+        }
+    }
+    for sub in visible_subcommands(cmd) {
+        out.push_str(&format!(
+            "            [CompletionResult]::new('{0}', '{0}', [CompletionResultType]::ParameterValue, '{1}')\n",
+            sub.get_name(),
+            powershell_escape(&help_line(sub.get_about()))
+        ));
+    }
+    out
+}
+
+fn powershell(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let mut out = format!(
+        "using namespace System.Management.Automation
+using namespace System.Management.Automation.Language
+
+Register-ArgumentCompleter -Native -CommandName '{name}' -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $commandElements = $commandAst.CommandElements
+    $command = @(
+        '{name}'
+        for ($i = 1; $i -lt $commandElements.Count; $i++) {{
+            $element = $commandElements[$i]
+            if ($element -isnot [StringConstantExpressionAst] -or
+                $element.StringConstantType -ne [StringConstantType]::BareWord -or
+                $element.Value.StartsWith('-') -or
+                $element.Value -eq $wordToComplete) {{
+                break
+            }}
+            $element.Value
+        }}) -join ';'
+
+    $completions = @(switch ($command) {{
+"
+    );
+    out.push_str(&format!("        '{name}' {{\n{}            break\n        }}\n", powershell_results(cmd)));
+    for sub in visible_subcommands(cmd) {
+        out.push_str(&format!(
+            "        '{name};{}' {{\n{}            break\n        }}\n",
+            sub.get_name(),
+            powershell_results(sub)
+        ));
+    }
+    out.push_str(
+        "    })
+
+    $completions.Where{ $_.CompletionText -like \"$wordToComplete*\" } |
+        Sort-Object -Property ListItemText
+}
+",
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_command() -> Command {
+        Command::new("my-tool")
+            .arg(Arg::new("name").short('n').long("name").help("New name of the project.\nExample: \"new\""))
+            .arg(Arg::new("input").long("input").value_hint(ValueHint::DirPath).help("Input path (e.g. a checkout). Must exist"))
+            .arg(Arg::new("format").long("format").value_parser(["pretty", "json"]).help("Log format"))
+            .arg(Arg::new("quiet").short('q').long("quiet").action(ArgAction::SetTrue).help("Don't print"))
+            .subcommand(
+                Command::new("completions")
+                    .about("Generate completions")
+                    .arg(Arg::new("shell").value_parser(["bash", "zsh"]).required(true)),
+            )
+    }
+
+    #[test]
+    fn test_bash() {
+        let script = generate(Shell::Bash, &test_command());
+        assert!(script.contains("complete -F _my_tool -o bashdefault -o default my-tool"));
+        assert!(script.contains("--format) COMPREPLY=($(compgen -W \"pretty json\" -- \"$cur\")); return 0 ;;"));
+        assert!(script.contains("--input) COMPREPLY=($(compgen -d -- \"$cur\")); return 0 ;;"));
+        assert!(script.contains("completions) cmd=\"completions\"; break ;;"));
+        assert!(script.contains("opts=\"-h --help bash zsh\""));
+    }
+
+    #[test]
+    fn test_zsh() {
+        let script = generate(Shell::Zsh, &test_command());
+        assert!(script.starts_with("#compdef my-tool\n"));
+        assert!(script.contains("'-n[New name of the project]:NAME:'"));
+        assert!(script.contains("'--input[Input path (e.g. a checkout)]:INPUT:_files -/'"));
+        assert!(script.contains("'--quiet[Don'\\''t print]'"));
+        assert!(script.contains("'completions:Generate completions'"));
+        assert!(script.contains("':SHELL:(bash zsh)'"));
+    }
+
+    #[test]
+    fn test_fish() {
+        let script = generate(Shell::Fish, &test_command());
+        assert!(script.contains("complete -c my-tool -n \"__fish_use_subcommand\" -s q -l quiet -d 'Don\\'t print'\n"));
+        assert!(script.contains("-l format -d 'Log format' -r -f -a \"pretty json\""));
+        assert!(script.contains("-l input -d 'Input path (e.g. a checkout)'"));
+        assert!(script.contains("complete -c my-tool -n \"__fish_use_subcommand\" -f -a \"completions\" -d 'Generate completions'"));
+    }
+
+    #[test]
+    fn test_powershell() {
+        let script = generate(Shell::Powershell, &test_command());
+        assert!(script.contains("Register-ArgumentCompleter -Native -CommandName 'my-tool'"));
+        assert!(script.contains("[CompletionResult]::new('--quiet', '--quiet', [CompletionResultType]::ParameterName, 'Don''t print')"));
+        assert!(script.contains("'my-tool;completions' {"));
+        assert!(script.contains("[CompletionResult]::new('zsh', 'zsh', [CompletionResultType]::ParameterValue, 'zsh')"));
+    }
+}
@@ -0,0 +1,124 @@
+use std::ops::Range;
+
+use crate::json::{self, Value};
+use crate::md5;
+
+// The keys of composer.json that make up the content hash of composer.lock, besides the platform
+// of the config
+const RELEVANT_KEYS: [&str; 11] = [
+    "name",
+    "version",
+    "require",
+    "require-dev",
+    "conflict",
+    "replace",
+    "provide",
+    "minimum-stability",
+    "prefer-stable",
+    "repositories",
+    "extra",
+];
+
+// The content hash Composer records in composer.lock for a composer.json, the MD5 of its relevant
+// keys sorted and encoded like PHP's json_encode
+pub fn content_hash(composer_json: &str) -> Option<String> {
+    let Value::Object(entries) = json::parse(composer_json).ok()? else {
+        return None;
+    };
+    let mut relevant = entries.iter().filter(|(key, _)| RELEVANT_KEYS.contains(&key.as_str())).cloned().collect::<Vec<_>>();
+    if let Some(platform) = entries.iter().find(|(key, _)| key == "config").and_then(|(_, config)| config.get("platform")) {
+        relevant.push(("config".to_string(), Value::Object(vec![("platform".to_string(), platform.clone())])));
+    }
+    relevant.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let mut encoded = String::new();
+    encode(&Value::Object(relevant), &mut encoded);
+    Some(md5::hash(encoded.as_bytes()))
+}
+
+// The range of the content hash in the text of a composer.lock, without its quotes
+pub fn lock_hash(composer_lock: &str) -> Option<Range<usize>> {
+    json::string_values(composer_lock).ok()?.into_iter().find(|(path, _)| path.len() == 1 && path[0] == "content-hash").map(|(_, range)| range)
+}
+
+// Encode a value like PHP's json_encode without flags: slashes and every character outside ASCII
+// are escaped, and objects are decoded to PHP arrays first, so an empty object becomes [] and one
+// keyed "0", "1", ... a list
+fn encode(value: &Value, out: &mut String) {
+    match value {
+        Value::String(s) => encode_string(s, out),
+        Value::Array(items) => encode_list(items.iter(), out),
+        Value::Object(entries) if entries.iter().enumerate().all(|(i, (key, _))| *key == i.to_string()) => {
+            encode_list(entries.iter().map(|(_, value)| value), out)
+        }
+        Value::Object(entries) => {
+            out.push('{');
+            for (i, (key, value)) in entries.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                encode_string(key, out);
+                out.push(':');
+                encode(value, out);
+            }
+            out.push('}');
+        }
+        value => out.push_str(&value.to_string()),
+    }
+}
+
+fn encode_list<'a>(items: impl Iterator<Item = &'a Value>, out: &mut String) {
+    out.push('[');
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        encode(item, out);
+    }
+    out.push(']');
+}
+
+fn encode_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '/' => out.push_str("\\/"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_ascii() && !c.is_ascii_control() => out.push(c),
+            c => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    out.push_str(&format!("\\u{unit:04x}"));
+                }
+            }
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hash() {
+        let mut encoded = String::new();
+        let value = json::parse(r#"{"a/b": "é/x", "empty": {}, "list": {"0": 1, "1": true}, "n": [1.5, null]}"#).unwrap();
+        encode(&value, &mut encoded);
+        assert_eq!(encoded, r#"{"a\/b":"\u00e9\/x","empty":[],"list":[1,true],"n":[1.5,null]}"#);
+
+        // Only the relevant keys count, sorted, with the platform of the config
+        let composer_json = r#"{"require": {"php": ">=8.1"}, "name": "acme/my-lib", "autoload": {"psr-4": {"Acme\\MyLib\\": "src/"}},
+            "config": {"platform": {"php": "8.1"}, "sort-packages": true}}"#;
+        let relevant = r#"{"config":{"platform":{"php":"8.1"}},"name":"acme\/my-lib","require":{"php":">=8.1"}}"#;
+        assert_eq!(content_hash(composer_json), Some(md5::hash(relevant.as_bytes())));
+        assert_eq!(content_hash("[]"), None);
+
+        let composer_lock = "{\n    \"_readme\": [\"content-hash\"],\n    \"content-hash\": \"0123abcd\",\n    \"packages\": []\n}\n";
+        assert_eq!(lock_hash(composer_lock).map(|range| &composer_lock[range]), Some("0123abcd"));
+    }
+}
@@ -0,0 +1,183 @@
+use std::path::{Path, PathBuf};
+
+use crate::json::{self, Value};
+use crate::regex::Regex;
+use crate::syntax::Node;
+use crate::{CaseInfo, glob};
+
+// Name of the config file that is read from the project directory without --config
+pub const FILE_NAME: &str = ".project-renamer.json";
+
+// Replacement rules for the files matching glob patterns, e.g.
+//
+//   {"rules": [
+//     {"files": "*.lock", "skip": true},
+//     {"files": ["*.md", "docs/**"], "cases": ["My Project", "my project"]},
+//     {"files": "src/**", "nodes": ["identifiers", "comments"]},
+//     {"files": "*.yaml", "regex": [{"pattern": "image: old/(\\w+)", "replacement": "image: new/$1"}]},
+//     {"files": "k8s/*.yaml", "keys": ["metadata.name", "metadata.labels.*"]}
+//   ]}
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    // The file the rules were read from
+    pub path: Option<PathBuf>,
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub files: Vec<String>,
+    // Leave the content of the files unchanged
+    pub skip: bool,
+    // The case styles that are replaced in the files, written like "my project" would be
+    pub cases: Option<Vec<CaseInfo>>,
+    // The kinds of tokens the name is replaced in, for source code in a recognized language
+    pub nodes: Option<Vec<Node>>,
+    // Regular expressions that are replaced in the files as well
+    pub regex: Vec<(Regex, String)>,
+    // The key paths of the values the name is only replaced in, for data files like YAML
+    pub keys: Option<Vec<String>>,
+}
+
+// The rules that apply to a single file. When several rules set the case styles, the kinds of
+// tokens or the key paths the last one wins.
+#[derive(Debug, Default)]
+pub struct FileRules<'a> {
+    pub skip: bool,
+    pub cases: Option<&'a [CaseInfo]>,
+    pub nodes: Option<&'a [Node]>,
+    pub regex: Vec<&'a (Regex, String)>,
+    pub keys: Option<&'a [String]>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read the config {}: {e}", path.display()))?;
+        let mut config = Self::parse(&text).map_err(|e| format!("Invalid config {}: {e}", path.display()))?;
+        config.path = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let value = json::parse(text).map_err(|e| e.to_string())?;
+        let rules = match value.get("rules") {
+            Some(Value::Array(rules)) => rules.iter().map(parse_rule).collect::<Result<Vec<_>, _>>()?,
+            None => vec![],
+            Some(_) => return Err("rules must be an array".to_string()),
+        };
+        Ok(Self { path: None, rules })
+    }
+
+    // The rules for the file at the path relative to the project
+    pub fn for_file(&self, relative: &Path) -> FileRules<'_> {
+        let mut found = FileRules::default();
+        for rule in &self.rules {
+            if !rule.files.iter().any(|pattern| glob::matches(pattern, relative)) {
+                continue;
+            }
+            found.skip |= rule.skip;
+            if let Some(cases) = &rule.cases {
+                found.cases = Some(cases);
+            }
+            if let Some(nodes) = &rule.nodes {
+                found.nodes = Some(nodes);
+            }
+            if let Some(keys) = &rule.keys {
+                found.keys = Some(keys);
+            }
+            found.regex.extend(&rule.regex);
+        }
+        found
+    }
+}
+
+fn parse_rule(value: &Value) -> Result<Rule, String> {
+    let files = match value.get("files") {
+        Some(Value::String(pattern)) => vec![pattern.clone()],
+        Some(Value::Array(patterns)) => strings(patterns, "files")?,
+        _ => return Err("every rule needs a files pattern or an array of them".to_string()),
+    };
+    let skip = match value.get("skip") {
+        None => false,
+        Some(Value::Bool(skip)) => *skip,
+        Some(_) => return Err("skip must be true or false".to_string()),
+    };
+    let cases = match value.get("cases") {
+        None => None,
+        Some(Value::Array(cases)) => Some(strings(cases, "cases")?.iter().map(|case| CaseInfo::detect(case).0).collect()),
+        Some(_) => return Err("cases must be an array".to_string()),
+    };
+    let nodes = match value.get("nodes") {
+        None => None,
+        Some(Value::Array(nodes)) => Some(
+            strings(nodes, "nodes")?
+                .iter()
+                .map(|node| Node::from_name(node).ok_or_else(|| format!("unknown node {node}, expected identifiers, strings or comments")))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        Some(_) => return Err("nodes must be an array".to_string()),
+    };
+    let regex = match value.get("regex") {
+        None => vec![],
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                match (item.get("pattern").and_then(Value::as_str), item.get("replacement").and_then(Value::as_str)) {
+                    (Some(pattern), Some(replacement)) => Ok((Regex::new(pattern)?, replacement.to_string())),
+                    _ => Err("every regex needs a pattern and a replacement".to_string()),
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()?,
+        Some(_) => return Err("regex must be an array".to_string()),
+    };
+    let keys = match value.get("keys") {
+        None => None,
+        Some(Value::Array(keys)) => Some(strings(keys, "keys")?),
+        Some(_) => return Err("keys must be an array".to_string()),
+    };
+    Ok(Rule { files, skip, cases, nodes, regex, keys })
+}
+
+fn strings(values: &[Value], name: &str) -> Result<Vec<String>, String> {
+    values
+        .iter()
+        .map(|value| value.as_str().map(str::to_string).ok_or_else(|| format!("{name} must only contain strings")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_file() {
+        let config = Config::parse(
+            r#"{"rules": [
+                {"files": "*.lock", "skip": true},
+                {"files": ["*.md"], "cases": ["My Project", "my project"]},
+                {"files": "*.yaml", "regex": [{"pattern": "v(\\d+)", "replacement": "version $1"}]},
+                {"files": "src/**", "nodes": ["identifiers"]},
+                {"files": "k8s/*.yaml", "keys": ["metadata.name"]}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(config.for_file(Path::new("web/yarn.lock")).skip);
+        let readme = config.for_file(Path::new("README.md"));
+        assert!(!readme.skip);
+        assert_eq!(readme.cases.unwrap(), [CaseInfo::detect("My Project").0, CaseInfo::detect("my project").0]);
+        let values = config.for_file(Path::new("chart/values.yaml"));
+        assert_eq!((values.cases, values.regex.len()), (None, 1));
+        assert_eq!(values.regex[0].0.replace_all("v2", &values.regex[0].1).0, "version 2");
+        assert_eq!(config.for_file(Path::new("src/main.rs")).nodes, Some(&[Node::Identifier][..]));
+        assert_eq!(config.for_file(Path::new("k8s/deploy.yaml")).keys, Some(&["metadata.name".to_string()][..]));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Config::parse(r#"{"rules": [{"skip": true}]}"#).is_err());
+        assert!(Config::parse(r#"{"rules": [{"files": "*", "regex": [{"pattern": "("}]}]}"#).is_err());
+        assert!(Config::parse(r#"{"rules": [{"files": "*", "regex": [{"pattern": "(", "replacement": ""}]}]}"#).is_err());
+        assert!(Config::parse(r#"{"rules": [{"files": "*", "nodes": ["keywords"]}]}"#).is_err());
+        assert!(Config::parse("{}").unwrap().rules.is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+use std::path::Path;
+
+use crate::Substitution;
+
+// Lines a license header block can be made of, after the indentation
+const COMMENT_MARKERS: [&str; 8] = ["//", "#", "/*", "*", "--", ";", "<!--", "\"\"\""];
+
+// Headers longer than this are file content
+const MAX_HEADER_LINES: usize = 50;
+
+// Update the holder, and the year range to the given year, in the copyright lines of the license
+// header of a file, e.g. "// Copyright (c) 2019-2023 Acme Inc." or
+// "# SPDX-FileCopyrightText: 2021 Acme Inc.". The header is the leading comment block, license files
+// like LICENSE and NOTICE are all header. Only lines of the old holder are changed, so the copyright
+// of third party code stays as it is.
+pub fn update(path: &Path, text: &str, old_holder: &str, new_holder: &str, year: Option<u32>) -> (String, Vec<Substitution>) {
+    let license_file = path.file_name().and_then(|name| name.to_str()).is_some_and(|name| {
+        let name = name.to_ascii_uppercase();
+        ["LICENSE", "LICENCE", "COPYING", "NOTICE"].iter().any(|prefix| name.starts_with(prefix))
+    });
+
+    let mut out = String::with_capacity(text.len());
+    let mut holders = 0;
+    let mut years: Vec<Substitution> = vec![];
+    let mut in_header = true;
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim_start();
+        if !license_file {
+            let is_comment = trimmed.trim_end().is_empty()
+                || (index == 0 && trimmed.starts_with("#!"))
+                || COMMENT_MARKERS.iter().any(|marker| trimmed.starts_with(marker));
+            in_header &= is_comment && index < MAX_HEADER_LINES;
+        }
+        match in_header.then(|| update_line(line, old_holder, new_holder, year)).flatten() {
+            Some((updated, changed_years)) => {
+                out.push_str(&updated);
+                holders += 1;
+                if let Some((from, to)) = changed_years {
+                    match years.iter_mut().find(|s| s.from == from && s.to == to) {
+                        Some(existing) => existing.count += 1,
+                        None => years.push(Substitution { from, to, count: 1 }),
+                    }
+                }
+            }
+            None => out.push_str(line),
+        }
+    }
+
+    let mut substitutions = vec![];
+    if holders > 0 && old_holder != new_holder {
+        substitutions.push(Substitution { from: old_holder.to_string(), to: new_holder.to_string(), count: holders });
+    }
+    substitutions.extend(years);
+    (out, substitutions)
+}
+
+// The line with the holder replaced and the years updated, and the years before and after if they
+// changed. None when it isn't a copyright line of the old holder.
+fn update_line(line: &str, old_holder: &str, new_holder: &str, year: Option<u32>) -> Option<(String, Option<(String, String)>)> {
+    let lower = line.to_ascii_lowercase();
+    let keyword = ["spdx-filecopyrighttext:", "copyright"].iter().find_map(|keyword| {
+        lower.find(keyword).map(|start| start + keyword.len())
+    })?;
+
+    // "(c)" or "©" and the years, e.g. "2019-2023" or "2019, 2021"
+    let mut years_start = keyword;
+    loop {
+        let rest = &line[years_start..];
+        let skipped = rest.len() - rest.trim_start().len();
+        let rest = rest.trim_start();
+        let marker = ["(c)", "(C)", "©"].iter().find(|marker| rest.starts_with(**marker)).map_or(0, |marker| marker.len());
+        if skipped == 0 && marker == 0 {
+            break;
+        }
+        years_start += skipped + marker;
+    }
+    let years_len = line[years_start..]
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '–' | ',' | ' ')))
+        .unwrap_or(line.len() - years_start);
+    let years_text = line[years_start..years_start + years_len].trim_end_matches([',', ' ']);
+    let numbers = years_text
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|number| number.len() == 4)
+        .filter_map(|number| number.parse::<u32>().ok())
+        .collect::<Vec<_>>();
+    let (&first, &last) = (numbers.first()?, numbers.iter().max()?);
+
+    let holder_start = years_start + years_text.len();
+    let holder_offset = line[holder_start..].find(old_holder)?;
+    // Only a separator may come between the years and the holder
+    if !line[holder_start..holder_start + holder_offset].chars().all(|c| matches!(c, ' ' | ',')) {
+        return None;
+    }
+
+    let new_years = match year {
+        Some(year) if year > last => format!("{first}-{year}"),
+        _ => years_text.to_string(),
+    };
+    let updated = format!(
+        "{}{new_years}{}{new_holder}{}",
+        &line[..years_start],
+        &line[holder_start..holder_start + holder_offset],
+        &line[holder_start + holder_offset + old_holder.len()..]
+    );
+    let changed_years = (new_years != years_text).then(|| (years_text.to_string(), new_years));
+    Some((updated, changed_years))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update() {
+        let text = "#!/bin/sh\n# Copyright (c) 2019-2023 Acme Inc.\n# SPDX-FileCopyrightText: 2024 Acme Inc.\n\
+                    # Copyright 2010 Google LLC\n\necho 'Copyright 2020 Acme Inc.'\n";
+        let (out, substitutions) = update(Path::new("run.sh"), text, "Acme Inc.", "Globex LLC", Some(2024));
+        assert_eq!(
+            out,
+            "#!/bin/sh\n# Copyright (c) 2019-2024 Globex LLC\n# SPDX-FileCopyrightText: 2024 Globex LLC\n\
+             # Copyright 2010 Google LLC\n\necho 'Copyright 2020 Acme Inc.'\n"
+        );
+        assert_eq!(
+            substitutions,
+            vec![
+                Substitution { from: "Acme Inc.".to_string(), to: "Globex LLC".to_string(), count: 2 },
+                Substitution { from: "2019-2023".to_string(), to: "2019-2024".to_string(), count: 1 },
+            ]
+        );
+
+        let license = "MIT License\n\nCopyright © 2021, Acme Inc.\n\nPermission is hereby granted";
+        let (out, _) = update(Path::new("LICENSE.md"), license, "Acme Inc.", "Globex LLC", None);
+        assert_eq!(out, "MIT License\n\nCopyright © 2021, Globex LLC\n\nPermission is hereby granted");
+    }
+}
@@ -0,0 +1,135 @@
+use std::ops::Range;
+use std::path::Path;
+
+use crate::json;
+use crate::structured;
+use crate::syntax::{self, Language, Node};
+
+// The attributes of a Conan recipe and the methods of its requirements() that take package
+// references like "my-lib/1.0@acme/stable", and the sections of a conanfile.txt listing them
+const REQUIREMENTS: [&str; 4] = ["requires", "tool_requires", "build_requires", "test_requires"];
+
+// The paths of vcpkg.json holding the names of the port and the packages it depends on
+const VCPKG_NAMES: [&[&str]; 4] = [&["name"], &["dependencies", "*"], &["dependencies", "*", "name"], &["overrides", "*", "name"]];
+
+// Whether the file is the metadata of a Conan or vcpkg package: a "conanfile.py" recipe, a
+// "conanfile.txt" or the "vcpkg.json" of a project or port
+pub fn is_manifest(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == "conanfile.py" || name == "conanfile.txt" || name == "vcpkg.json")
+}
+
+// The ranges of the package names in a Conan or vcpkg manifest: the name of the package and the
+// names of the packages it requires or depends on, without their versions, users and channels.
+// Everything else, like the urls of the sources and the descriptions, is left out.
+pub fn names(path: &Path, text: &str) -> Vec<Range<usize>> {
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some("conanfile.py") => recipe_names(text),
+        Some("conanfile.txt") => requirement_names(text),
+        _ => {
+            let patterns = VCPKG_NAMES.map(|pattern| pattern.iter().map(|segment| segment.to_string()).collect::<Vec<_>>());
+            let values = json::string_values(text).unwrap_or_default().into_iter();
+            values.filter(|(path, _)| patterns.iter().any(|pattern| structured::matches(pattern, path))).map(|(_, range)| range).collect()
+        }
+    }
+}
+
+// The string of the name attribute of a recipe and the names in the references it requires, as
+// attributes like `requires = "my-lib/1.0", "zlib/1.3"` or calls like `self.requires("my-lib/1.0")`
+fn recipe_names(text: &str) -> Vec<Range<usize>> {
+    let spans = syntax::spans(text, Language::Python);
+    let token = |index: usize| &text[spans[index].range.clone()];
+    let mut ranges = vec![];
+    // The attribute or method whose value or arguments are being read, with the depth of the
+    // brackets it is at
+    let mut context: Option<(&str, usize)> = None;
+    let mut depth = 0usize;
+    for (index, span) in spans.iter().enumerate() {
+        match span.node {
+            Node::Identifier => {
+                let next = spans.get(index + 1).map(|_| token(index + 1).trim_start()).unwrap_or_default();
+                if (next.starts_with('=') && !next.starts_with("==")) || next.starts_with('(') {
+                    context = Some((token(index), depth));
+                }
+            }
+            Node::String => {
+                let literal = token(index);
+                let quote = if literal.starts_with("\"\"\"") || literal.starts_with("'''") { 3 } else { 1 };
+                if literal.len() < 2 * quote {
+                    continue;
+                }
+                let contents = span.range.start + quote..span.range.end - quote;
+                match context {
+                    Some(("name", _)) => ranges.push(contents),
+                    Some((context, _)) if REQUIREMENTS.contains(&context) => ranges.push(reference_name(&text[contents.clone()], contents.start)),
+                    _ => {}
+                }
+            }
+            Node::Other => {
+                for c in token(index).chars() {
+                    match c {
+                        '(' | '[' | '{' => depth += 1,
+                        ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                        _ => {}
+                    }
+                    // A value ends with the line, or with the next argument inside a call
+                    if context.is_some_and(|(_, at)| depth < at || (depth == at && (matches!(c, '\n' | ';') || (c == ',' && at > 0)))) {
+                        context = None;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    ranges
+}
+
+// The names in the references listed in the requirement sections of a conanfile.txt
+fn requirement_names(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut requirements = false;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(section) = trimmed.strip_prefix('[').and_then(|section| section.strip_suffix(']')) {
+            requirements = REQUIREMENTS.contains(&section.trim());
+        } else if requirements {
+            ranges.push(reference_name(trimmed, start + line.find(trimmed).unwrap_or(0)));
+        }
+    }
+    ranges
+}
+
+// The range of the name in a reference like "my-lib/1.0@acme/stable" starting at the offset
+fn reference_name(reference: &str, start: usize) -> Range<usize> {
+    start..start + reference.find(['/', '@', '#', '[']).unwrap_or(reference.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        let recipe = "class MyLibConan(ConanFile):\n    name = \"my-lib\"\n    url = \"https://github.com/acme/my-lib\"\n    \
+                      requires = \"my-lib-core/1.0@acme/stable\", \"zlib/1.3\"\n\n    def requirements(self):\n        \
+                      self.requires(\"my-lib-utils/[>=1.0]\", visible=False)\n        self.output.info(\"my-lib\")\n";
+        let found = names(Path::new("conanfile.py"), recipe).into_iter().map(|range| &recipe[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-lib", "my-lib-core", "zlib", "my-lib-utils"]);
+
+        let txt = "[requires]\nmy-lib-core/1.0\n# my-lib-old/0.1\n\n[generators]\nmy-lib-gen\n[tool_requires]\n  my-lib-tools/2.0#rev\n";
+        let found = names(Path::new("conanfile.txt"), txt).into_iter().map(|range| &txt[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-lib-core", "my-lib-tools"]);
+
+        let vcpkg = r#"{"name": "my-lib", "homepage": "https://github.com/acme/my-lib", "dependencies": ["my-lib-core", {"name": "fmt"}],
+            "overrides": [{"name": "my-lib-utils", "version": "1.0"}]}"#;
+        let found = names(Path::new("vcpkg.json"), vcpkg).into_iter().map(|range| &vcpkg[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-lib", "my-lib-core", "fmt", "my-lib-utils"]);
+        assert!(is_manifest(Path::new("ports/my-lib/vcpkg.json")));
+        assert!(!is_manifest(Path::new("ports/my-lib/portfile.cmake")));
+    }
+}
@@ -0,0 +1,88 @@
+use std::ops::Range;
+use std::path::Path;
+
+// The fields of debian/control naming the source and binary packages, the packages they relate to
+// and what they are
+const CONTROL_FIELDS: [&str; 18] = [
+    "Source",
+    "Package",
+    "Depends",
+    "Pre-Depends",
+    "Recommends",
+    "Suggests",
+    "Enhances",
+    "Breaks",
+    "Conflicts",
+    "Replaces",
+    "Provides",
+    "Build-Depends",
+    "Build-Depends-Indep",
+    "Build-Depends-Arch",
+    "Build-Conflicts",
+    "Build-Conflicts-Indep",
+    "Build-Conflicts-Arch",
+    "Description",
+];
+
+// Whether the file is the debian/control or the debian/changelog of a Debian source package
+pub fn is_package_file(path: &Path) -> bool {
+    path.parent().and_then(Path::file_name).is_some_and(|dir| dir == "debian")
+        && path.file_name().is_some_and(|name| name == "control" || name == "changelog")
+}
+
+// The ranges of the names in debian/control or debian/changelog. In the control file the values of
+// the fields naming packages and their relationships and the Description, with their continuation
+// lines, but not the Maintainer, Homepage or Vcs fields. In the changelog only the package names
+// the entries start with, so the history of the package and the trailer lines, whose format dpkg is
+// strict about, stay as they are.
+pub fn names(path: &Path, text: &str) -> Vec<Range<usize>> {
+    let changelog = path.file_name().is_some_and(|name| name == "changelog");
+    let mut ranges = vec![];
+    // Whether the field continued on the indented lines is renamed
+    let mut renamed = false;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        if content.starts_with([' ', '\t']) {
+            if renamed && !changelog {
+                ranges.push(start..start + content.len());
+            }
+        } else if changelog {
+            ranges.push(start..start + content.find(' ').unwrap_or(content.len()));
+        } else if let Some((field, _)) = content.split_once(':') {
+            renamed = CONTROL_FIELDS.iter().any(|name| name.eq_ignore_ascii_case(field));
+            if renamed {
+                ranges.push(start + field.len() + 1..start + content.len());
+            }
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        let control = "Source: my-app\nMaintainer: My App Team <team@my-app.org>\nBuild-Depends: debhelper-compat (= 13),\n libmy-app-dev\n\
+                       Homepage: https://my-app.org\n\nPackage: my-app\nDepends: ${misc:Depends}, my-app-data\nDescription: my-app server\n \
+                       The my-app server.\n";
+        let found = names(Path::new("debian/control"), control).into_iter().map(|range| &control[range]).collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [" my-app", " debhelper-compat (= 13),", " libmy-app-dev", " my-app", " ${misc:Depends}, my-app-data", " my-app server", " The my-app server."]
+        );
+
+        let changelog = "my-app (1.1-1) unstable; urgency=medium\n\n  * Rename my-app.\n\n -- My App Team <team@my-app.org>  Mon, 01 Jan 2024 00:00:00 +0000\n\n\
+                         my-app (1.0-1) unstable; urgency=low\n";
+        let found = names(Path::new("debian/changelog"), changelog).into_iter().map(|range| &changelog[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-app", "my-app"]);
+        assert!(!is_package_file(Path::new("docs/changelog")));
+    }
+}
@@ -0,0 +1,379 @@
+// DEFLATE (RFC 1951) compression and decompression and the CRC-32 checksum used by gzip and zip
+
+// Base lengths and extra bits of the length codes 257..285
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+
+// Base distances and extra bits of the distance codes 0..29
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+// Order in which the code length code lengths of a dynamic block are stored
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+// How many earlier positions with the same hash are tried when looking for a match
+const MAX_CHAIN: usize = 64;
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut table = [0u32; 256];
+    for (i, entry) in table.iter_mut().enumerate() {
+        let mut c = i as u32;
+        for _ in 0..8 {
+            c = if c & 1 != 0 { 0xEDB88320 ^ (c >> 1) } else { c >> 1 };
+        }
+        *entry = c;
+    }
+    !data.iter().fold(!0u32, |crc, &b| table[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8))
+}
+
+// Decompress raw DEFLATE data, returning the data and the number of input bytes used
+pub fn inflate(input: &[u8]) -> Result<(Vec<u8>, usize), String> {
+    let mut reader = BitReader { input, pos: 0, bit: 0 };
+    let mut out = vec![];
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                reader.align();
+                let header = reader.bytes(4)?;
+                let len = u16::from_le_bytes([header[0], header[1]]);
+                let nlen = u16::from_le_bytes([header[2], header[3]]);
+                if len != !nlen {
+                    return Err("Invalid stored block length".to_string());
+                }
+                out.extend_from_slice(reader.bytes(len as usize)?);
+            }
+            1 => {
+                let (lengths, distances) = fixed_tables();
+                inflate_block(&mut reader, &mut out, &lengths, &distances)?;
+            }
+            2 => {
+                let (lengths, distances) = dynamic_tables(&mut reader)?;
+                inflate_block(&mut reader, &mut out, &lengths, &distances)?;
+            }
+            _ => return Err("Invalid block type".to_string()),
+        }
+        if last {
+            reader.align();
+            return Ok((out, reader.pos));
+        }
+    }
+}
+
+struct BitReader<'a> {
+    input: &'a [u8],
+    pos: usize,
+    bit: u8,
+}
+
+impl<'a> BitReader<'a> {
+    fn bits(&mut self, count: u8) -> Result<u32, String> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = *self.input.get(self.pos).ok_or("Unexpected end of compressed data")?;
+            value |= (((byte >> self.bit) & 1) as u32) << i;
+            self.bit += 1;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.pos += 1;
+            }
+        }
+        Ok(value)
+    }
+
+    fn align(&mut self) {
+        if self.bit > 0 {
+            self.bit = 0;
+            self.pos += 1;
+        }
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+        let bytes = self.input.get(self.pos..self.pos + count).ok_or("Unexpected end of compressed data")?;
+        self.pos += count;
+        Ok(bytes)
+    }
+}
+
+// Canonical Huffman code, the number of codes of every length and the symbols ordered by code
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 16];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+        let mut offsets = [0u16; 16];
+        for i in 1..15 {
+            offsets[i + 1] = offsets[i] + counts[i];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length > 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Self { counts, symbols }
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, String> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for length in 1..16 {
+            code |= reader.bits(1)? as i32;
+            let count = self.counts[length] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err("Invalid Huffman code".to_string())
+    }
+}
+
+fn fixed_lengths() -> ([u8; 288], [u8; 30]) {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    (lengths, [5; 30])
+}
+
+fn fixed_tables() -> (Huffman, Huffman) {
+    let (lengths, distances) = fixed_lengths();
+    (Huffman::new(&lengths), Huffman::new(&distances))
+}
+
+fn dynamic_tables(reader: &mut BitReader) -> Result<(Huffman, Huffman), String> {
+    let literal_count = reader.bits(5)? as usize + 257;
+    let distance_count = reader.bits(5)? as usize + 1;
+    let code_length_count = reader.bits(4)? as usize + 4;
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[index] = reader.bits(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths);
+
+    let mut lengths = vec![];
+    while lengths.len() < literal_count + distance_count {
+        let (value, repeat) = match code_length_code.decode(reader)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => (*lengths.last().ok_or("Repeated code length without a previous one")?, 3 + reader.bits(2)?),
+            17 => (0, 3 + reader.bits(3)?),
+            18 => (0, 11 + reader.bits(7)?),
+            _ => return Err("Invalid code length code".to_string()),
+        };
+        lengths.extend(std::iter::repeat_n(value, repeat as usize));
+    }
+    if lengths.len() > literal_count + distance_count {
+        return Err("Too many code lengths".to_string());
+    }
+    Ok((Huffman::new(&lengths[..literal_count]), Huffman::new(&lengths[literal_count..])))
+}
+
+fn inflate_block(reader: &mut BitReader, out: &mut Vec<u8>, lengths: &Huffman, distances: &Huffman) -> Result<(), String> {
+    loop {
+        let symbol = lengths.decode(reader)? as usize;
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            257..=285 => {
+                let index = symbol - 257;
+                let length = LENGTH_BASE[index] as usize + reader.bits(LENGTH_EXTRA[index])? as usize;
+                let index = distances.decode(reader)? as usize;
+                if index >= 30 {
+                    return Err("Invalid distance code".to_string());
+                }
+                let distance = DIST_BASE[index] as usize + reader.bits(DIST_EXTRA[index])? as usize;
+                if distance > out.len() {
+                    return Err("Distance before the start of the data".to_string());
+                }
+                let start = out.len() - distance;
+                for i in 0..length {
+                    out.push(out[start + i]);
+                }
+            }
+            _ => return Err("Invalid length code".to_string()),
+        }
+    }
+}
+
+// Compress data to raw DEFLATE in a single block with the fixed Huffman codes
+pub fn deflate(data: &[u8]) -> Vec<u8> {
+    let (literal_lengths, _) = fixed_lengths();
+    let literal_codes = canonical_codes(&literal_lengths);
+    let mut writer = BitWriter::default();
+    writer.bits(1, 1); // Last block
+    writer.bits(1, 2); // Fixed Huffman codes
+
+    let hash = |i: usize| (((data[i] as usize) << 10) ^ ((data[i + 1] as usize) << 5) ^ data[i + 2] as usize) & 0x7FFF;
+    let mut head = vec![usize::MAX; 0x8000];
+    let mut previous = vec![usize::MAX; data.len()];
+    let insert = |i: usize, head: &mut [usize], previous: &mut [usize]| {
+        if i + MIN_MATCH <= data.len() {
+            let h = hash(i);
+            previous[i] = head[h];
+            head[h] = i;
+        }
+    };
+
+    let mut i = 0;
+    while i < data.len() {
+        let (mut best_length, mut best_distance) = (0, 0);
+        if i + MIN_MATCH <= data.len() {
+            let mut candidate = head[hash(i)];
+            let mut chain = 0;
+            while candidate != usize::MAX && i - candidate <= WINDOW_SIZE && chain < MAX_CHAIN {
+                let max = (data.len() - i).min(MAX_MATCH);
+                let length = (0..max).take_while(|&k| data[candidate + k] == data[i + k]).count();
+                if length > best_length {
+                    (best_length, best_distance) = (length, i - candidate);
+                    if length == max {
+                        break;
+                    }
+                }
+                candidate = previous[candidate];
+                chain += 1;
+            }
+        }
+
+        if best_length >= MIN_MATCH {
+            let index = LENGTH_BASE.iter().rposition(|&base| base as usize <= best_length).unwrap();
+            let (code, length) = literal_codes[257 + index];
+            writer.huffman(code, length);
+            writer.bits((best_length - LENGTH_BASE[index] as usize) as u32, LENGTH_EXTRA[index]);
+            let index = DIST_BASE.iter().rposition(|&base| base as usize <= best_distance).unwrap();
+            writer.huffman(index as u16, 5);
+            writer.bits((best_distance - DIST_BASE[index] as usize) as u32, DIST_EXTRA[index]);
+            for k in i..i + best_length {
+                insert(k, &mut head, &mut previous);
+            }
+            i += best_length;
+        } else {
+            let (code, length) = literal_codes[data[i] as usize];
+            writer.huffman(code, length);
+            insert(i, &mut head, &mut previous);
+            i += 1;
+        }
+    }
+    let (code, length) = literal_codes[256];
+    writer.huffman(code, length);
+    writer.finish()
+}
+
+// Codes and their lengths of a canonical Huffman code with the given code lengths
+fn canonical_codes(lengths: &[u8]) -> Vec<(u16, u8)> {
+    let mut counts = [0u16; 16];
+    for &length in lengths {
+        counts[length as usize] += 1;
+    }
+    counts[0] = 0;
+    let mut next = [0u16; 16];
+    let mut code = 0;
+    for bits in 1..16 {
+        code = (code + counts[bits - 1]) << 1;
+        next[bits] = code;
+    }
+    lengths
+        .iter()
+        .map(|&length| {
+            let code = next[length as usize];
+            next[length as usize] += 1;
+            (code, length)
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    current: u32,
+    count: u8,
+}
+
+impl BitWriter {
+    // Write a value with its least significant bit first
+    fn bits(&mut self, value: u32, count: u8) {
+        for i in 0..count {
+            self.current |= ((value >> i) & 1) << self.count;
+            self.count += 1;
+            if self.count == 8 {
+                self.out.push(self.current as u8);
+                self.current = 0;
+                self.count = 0;
+            }
+        }
+    }
+
+    // Huffman codes are written with their most significant bit first
+    fn huffman(&mut self, code: u16, length: u8) {
+        for i in (0..length).rev() {
+            self.bits(((code >> i) & 1) as u32, 1);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.current as u8);
+        }
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let text = "use my_project::MyProject;\n".repeat(100) + "é😀 and some unique text at the end";
+        for data in [&b""[..], b"a", b"abcabcabcabcabc", text.as_bytes()] {
+            let compressed = deflate(data);
+            assert_eq!(inflate(&compressed).unwrap(), (data.to_vec(), compressed.len()));
+        }
+        assert!(deflate(text.as_bytes()).len() < text.len() / 10);
+    }
+
+    #[test]
+    fn test_inflate() {
+        // Raw DEFLATE data written by zlib, with fixed and dynamic Huffman codes
+        let compressed = [0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x90, 0x00];
+        assert_eq!(inflate(&compressed).unwrap().0, b"hello hello hello");
+        let compressed = [
+            0x15, 0x8e, 0xc9, 0x0d, 0x00, 0x40, 0x08, 0x02, 0x6b, 0x95, 0x43, 0xe8, 0xbf, 0x82, 0x75, 0x4d, 0x4c, 0x7c,
+            0x0c, 0x23, 0x84, 0xd1, 0x36, 0x42, 0x27, 0x99, 0x5a, 0xd8, 0xb9, 0x89, 0x32, 0x6a, 0xb5, 0x52, 0x3d, 0x01,
+            0x8d, 0x8d, 0xec, 0x1e, 0xa3, 0x84, 0xbb, 0x28, 0x98, 0xed, 0x74, 0x1c, 0x52, 0x23, 0x65, 0xf7, 0xb3, 0xbc,
+            0x68, 0x57, 0xe9, 0x66, 0xe7, 0x00, 0x91, 0xf0, 0x00, 0x9f, 0x94, 0xc1, 0xbd, 0xa5, 0x79, 0xae, 0x6d, 0x71,
+            0xe9, 0x2f, 0x86, 0xff, 0xc3, 0x0b, 0x0f, 0xaf, 0x4c, 0xef, 0xda, 0x8c, 0x4f, 0x6b, 0xc0, 0x66, 0xcc, 0xb9,
+            0x3e, 0xbc, 0x5e, 0x0b, 0x1d, 0x80, 0xb8, 0xb3, 0xf1, 0x50, 0xcb, 0x13, 0x3c,
+        ];
+        let expected = "cbebhhhgdbhaggahedbfaaaagdgadhhdfddheagbcebfgdeehgahdggcffbhbcgfhahaegccdaddgffheagcdgahfdghfgfafh\
+                        adccbeabbahaedebcfebcceceehfhhbaegfgdebedgadagcachgdhdagfgaecdaebbeecgecaadhcagdfbdgdhbgehafgeacdfcfgd";
+        assert_eq!(inflate(&compressed).unwrap().0, expected.as_bytes());
+        // A stored block
+        assert_eq!(inflate(&[0x01, 0x02, 0x00, 0xfd, 0xff, b'h', b'i']).unwrap().0, b"hi");
+        assert!(inflate(&[0x07]).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use std::ops::Range;
+use std::path::Path;
+
+// The instructions of a Dockerfile whose whole line is metadata or variables, like
+// `LABEL org.opencontainers.image.title="old-name"` or `ENV OLD_NAME_HOME=/opt/old-name`
+const METADATA: [&str; 3] = ["LABEL", "ARG", "ENV"];
+
+// The commands that build an image from a Dockerfile
+const BUILD_COMMANDS: [&str; 4] = ["docker build", "docker buildx build", "docker image build", "podman build"];
+
+// The commands of the container engines, which may run, push or pull a built image
+const CLIENTS: [&str; 2] = ["docker ", "podman "];
+
+// Whether the file is a Dockerfile, like "Dockerfile", "Dockerfile.dev", "app.Dockerfile" or the
+// "Containerfile" of Podman
+pub fn is_dockerfile(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| {
+        let name = name.to_string_lossy();
+        name == "Dockerfile" || name == "Containerfile" || name.starts_with("Dockerfile.") || name.to_lowercase().ends_with(".dockerfile")
+    })
+}
+
+// The ranges of a Dockerfile the name is renamed in: the LABEL, ARG and ENV instructions with
+// their continuation lines, the names of the build stages in `FROM ... AS old-name` and the
+// references to them in `FROM old-name` and `COPY --from=old-name`. The images pulled from a
+// registry and the commands run are left out.
+pub fn names(text: &str) -> Vec<Range<usize>> {
+    let instructions = instructions(text);
+    let stages = instructions
+        .iter()
+        .filter(|(instruction, _)| instruction.eq_ignore_ascii_case("FROM"))
+        .filter_map(|(_, arguments)| stage_name(text, arguments.clone()))
+        .map(|range| text[range].to_lowercase())
+        .collect::<Vec<_>>();
+    let is_stage = |range: &Range<usize>| stages.contains(&text[range.clone()].to_lowercase());
+    let mut ranges = vec![];
+    for (instruction, arguments) in instructions {
+        match instruction.to_ascii_uppercase().as_str() {
+            instruction if METADATA.contains(&instruction) => ranges.push(arguments),
+            "FROM" => {
+                let words = words(text, arguments.clone()).into_iter().filter(|word| !text[word.clone()].starts_with("--")).collect::<Vec<_>>();
+                ranges.extend(words.first().filter(|image| is_stage(image)).cloned());
+                ranges.extend(stage_name(text, arguments));
+            }
+            "COPY" | "ADD" => {
+                let from = words(text, arguments).into_iter().find(|word| text[word.clone()].starts_with("--from="));
+                ranges.extend(from.map(|word| word.start + "--from=".len()..word.end).filter(is_stage));
+            }
+            _ => {}
+        }
+    }
+    ranges
+}
+
+// The image references of the scripts whose commands are otherwise left alone: the tags and
+// targets given to the commands building an image, like old-name:1.0 and old-name-dev in
+// `docker build -t old-name:1.0 --target old-name-dev .`, and the images built under these tags in
+// the other docker and podman commands, like `docker push old-name:1.0` or `docker run old-name`
+pub fn image_references(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let Some(command) = BUILD_COMMANDS.iter().filter_map(|command| line.find(command).map(|at| at + command.len())).min() else {
+            continue;
+        };
+        let words = words(line, command..line.len());
+        for (index, word) in words.iter().enumerate() {
+            let option = &line[word.clone()];
+            if let Some(value) = ["--tag=", "--target="].iter().find_map(|prefix| option.strip_prefix(prefix)) {
+                ranges.push(start + word.end - value.len()..start + word.end);
+            } else if matches!(option, "-t" | "--tag" | "--target")
+                && let Some(value) = words.get(index + 1)
+            {
+                ranges.push(start + value.start..start + value.end);
+            }
+        }
+    }
+    let images = ranges.iter().map(|range| repository(&text[range.clone()])).collect::<Vec<_>>();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let Some(command) = CLIENTS.iter().filter_map(|client| line.find(client).map(|at| at + client.len())).min() else {
+            continue;
+        };
+        for word in words(line, command..line.len()) {
+            let range = start + word.start..start + word.end;
+            if images.contains(&repository(&line[word])) && !ranges.contains(&range) {
+                ranges.push(range);
+            }
+        }
+    }
+    ranges.sort_by_key(|range| range.start);
+    ranges
+}
+
+// The repository of an image reference, without the tag or digest, like old-name in old-name:1.0
+fn repository(image: &str) -> &str {
+    let image = image.split_once('@').map_or(image, |(repository, _)| repository);
+    match image.rfind(':') {
+        Some(at) if !image[at..].contains('/') => &image[..at],
+        _ => image,
+    }
+}
+
+// The targets of the make commands run by the RUN instructions of a Dockerfile, like old-name in
+// `RUN make old-name`, which are renamed with the rules of the Makefile
+pub fn make_targets(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    for (instruction, arguments) in instructions(text) {
+        if !instruction.eq_ignore_ascii_case("RUN") {
+            continue;
+        }
+        let mut make = false;
+        for word in words(text, arguments) {
+            match &text[word.clone()] {
+                "make" | "gmake" => make = true,
+                "&&" | "||" | ";" | "|" => make = false,
+                argument if make && !argument.starts_with('-') => ranges.push(word),
+                _ => {}
+            }
+        }
+    }
+    ranges
+}
+
+// The instructions of a Dockerfile with the range of their arguments, continuation lines included
+fn instructions(text: &str) -> Vec<(&str, Range<usize>)> {
+    let mut instructions: Vec<(&str, Range<usize>)> = vec![];
+    let mut continued = false;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+        let trimmed = content.trim_start();
+        if continued {
+            if let Some((_, arguments)) = instructions.last_mut()
+                && !trimmed.starts_with('#')
+            {
+                *arguments = arguments.start..start + content.len();
+            }
+        } else if !trimmed.is_empty() && !trimmed.starts_with('#') {
+            let indent = content.len() - trimmed.len();
+            let instruction = trimmed.split_whitespace().next().unwrap_or_default();
+            instructions.push((instruction, start + indent + instruction.len()..start + content.len()));
+        }
+        continued = content.ends_with('\\') || (continued && (trimmed.is_empty() || trimmed.starts_with('#')));
+    }
+    instructions
+}
+
+// The range of the name of the stage in the arguments of a FROM instruction, after "AS"
+fn stage_name(text: &str, arguments: Range<usize>) -> Option<Range<usize>> {
+    let words = words(text, arguments);
+    let position = words.iter().position(|word| text[word.clone()].eq_ignore_ascii_case("AS"))?;
+    words.get(position + 1).cloned()
+}
+
+// The ranges of the words separated by whitespace and line continuations in a range of the text
+fn words(text: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let mut words = vec![];
+    let mut start = None;
+    for (at, c) in text[range.clone()].char_indices().map(|(at, c)| (range.start + at, c)) {
+        match (c.is_whitespace() || c == '\\', start) {
+            (true, Some(word_start)) => {
+                words.push(word_start..at);
+                start = None;
+            }
+            (false, None) => start = Some(at),
+            _ => {}
+        }
+    }
+    words.extend(start.map(|word_start| word_start..range.end));
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        let text = "# my-app\nFROM acme/my-app-base:1.0 AS my-app-build\nARG MY_APP_VERSION=1.0\nRUN make my-app\n\n\
+                    FROM --platform=linux/amd64 my-app-build AS my-app\nLABEL org.opencontainers.image.title=\"my-app\" \\\n      \
+                    org.opencontainers.image.source=\"https://github.com/acme/my-app\"\nCOPY --from=my-app-build /src/my-app /usr/bin/\n";
+        let found = names(text).into_iter().map(|range| &text[range]).collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                "my-app-build",
+                " MY_APP_VERSION=1.0",
+                "my-app-build",
+                "my-app",
+                " org.opencontainers.image.title=\"my-app\" \\\n      org.opencontainers.image.source=\"https://github.com/acme/my-app\"",
+                "my-app-build"
+            ]
+        );
+
+        let script = "docker build -t my-app:1.0 --target=my-app-dev .\ndocker run --rm my-app\ndocker push my-app:1.0\n\
+                      docker pull acme/my-app:1.0\n";
+        let found = image_references(script).into_iter().map(|range| &script[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-app:1.0", "my-app-dev", "my-app", "my-app:1.0"]);
+        let dockerfile = "FROM alpine\nRUN make my-app && cp my-app /usr/bin/\nRUN make -j4 \\\n    my-app-docs\n";
+        let found = make_targets(dockerfile).into_iter().map(|range| &dockerfile[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-app", "my-app-docs"]);
+        assert!(is_dockerfile(Path::new("docker/Dockerfile.dev")));
+        assert!(is_dockerfile(Path::new("app.Dockerfile")));
+        assert!(!is_dockerfile(Path::new("docker-compose.yml")));
+    }
+}
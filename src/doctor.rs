@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::{CaseInfo, Context, NormalizedName, SEPARATORS, transform_text};
+
+// Something in the project that the rename may get wrong
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub kind: Kind,
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    UnhandledCase, // The old name written in a case style that isn't renamed, e.g. "myProject"
+    PartialWord,   // The old name inside a longer word, e.g. "myprojects", which is renamed as well
+    Binary,        // The old name in a file that can't be read as text and is copied unchanged
+    Collision,     // Two files or directories that get the same new name
+    NewNameExists, // The new name already occurs, so it can't be told apart from renamed text
+}
+
+impl Kind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kind::UnhandledCase => "unhandled_case",
+            Kind::PartialWord => "partial_word",
+            Kind::Binary => "binary",
+            Kind::Collision => "collision",
+            Kind::NewNameExists => "new_name_exists",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Kind::UnhandledCase => "The old name is written in a case style that is not renamed",
+            Kind::PartialWord => "The old name is part of a longer word, which is renamed as well",
+            Kind::Binary => "The old name occurs in a binary file, which is copied unchanged",
+            Kind::Collision => "Two files or directories are renamed to the same name",
+            Kind::NewNameExists => "The new name already occurs and can't be told apart from renamed text",
+        }
+    }
+
+    pub const ALL: [Kind; 5] = [Kind::UnhandledCase, Kind::PartialWord, Kind::Binary, Kind::Collision, Kind::NewNameExists];
+}
+
+// Analyse the project of the context without changing anything
+pub fn diagnose(ctx: &Context) -> Vec<Finding> {
+    let doctor = Doctor {
+        ctx,
+        old_variants: variants(&ctx.old_name),
+        new_variants: variants(&ctx.new_name),
+    };
+    let mut findings = vec![];
+    if ctx.input.is_dir() {
+        doctor.scan_directory(&ctx.input, &mut findings);
+    } else {
+        doctor.scan_file(&ctx.input, &mut findings);
+    }
+    findings
+}
+
+// Occurrences of the old name in the text in case styles that aren't renamed, with their line
+pub fn unhandled_cases(text: &str, old_name: &NormalizedName) -> Vec<(usize, String)> {
+    let old_variants = variants(old_name);
+    let mut found = vec![];
+    for (index, line) in text.lines().enumerate() {
+        for (start, end) in find_loose(line, &old_name.parts) {
+            if !old_variants.iter().any(|variant| *variant == line[start..end]) {
+                found.push((index + 1, line[start..end].to_string()));
+            }
+        }
+    }
+    found
+}
+
+// The case variants of the name that are replaced
+fn variants(name: &NormalizedName) -> Vec<String> {
+    let mut variants = CaseInfo::all_cases().into_iter().map(|case_info| case_info.convert(name.clone())).collect::<Vec<_>>();
+    if name.has_mixed_separators() {
+        variants.push(name.original.clone());
+    }
+    variants
+}
+
+struct Doctor<'a> {
+    ctx: &'a Context,
+    old_variants: Vec<String>,
+    new_variants: Vec<String>,
+}
+
+impl Doctor<'_> {
+    fn scan_directory(&self, dir: &Path, findings: &mut Vec<Finding>) {
+        let Ok(entries) = dir.read_dir() else {
+            return;
+        };
+        let mut paths = entries.flatten().map(|entry| entry.path()).collect::<Vec<_>>();
+        paths.sort();
+
+        let mut new_names: HashMap<String, String> = HashMap::new();
+        for path in &paths {
+            let old_file_name = path.file_name().unwrap().to_string_lossy().to_string();
+            self.scan_text(path, None, &old_file_name, findings);
+            let (new_file_name, _) = transform_text(&old_file_name, &self.ctx.old_name, &self.ctx.new_name);
+            if let Some(other) = new_names.get(&new_file_name) {
+                findings.push(Finding {
+                    kind: Kind::Collision,
+                    path: path.clone(),
+                    line: None,
+                    message: format!("{other} and {old_file_name} are both renamed to {new_file_name}"),
+                });
+            } else {
+                new_names.insert(new_file_name, old_file_name);
+            }
+        }
+        for path in paths {
+            if path.is_dir() {
+                self.scan_directory(&path, findings);
+            } else {
+                self.scan_file(&path, findings);
+            }
+        }
+    }
+
+    fn scan_file(&self, path: &Path, findings: &mut Vec<Finding>) {
+        let Ok(bytes) = std::fs::read(path) else {
+            return;
+        };
+        match std::str::from_utf8(&bytes) {
+            Ok(content) if crate::mime::detect(&bytes).text => {
+                for (index, line) in content.lines().enumerate() {
+                    self.scan_text(path, Some(index + 1), line, findings);
+                }
+            }
+            _ => {
+                if let Some(variant) = self.old_variants.iter().find(|variant| contains_bytes(&bytes, variant.as_bytes())) {
+                    findings.push(Finding {
+                        kind: Kind::Binary,
+                        path: path.to_path_buf(),
+                        line: None,
+                        message: format!("{variant} occurs in a binary file, which is copied unchanged"),
+                    });
+                }
+            }
+        }
+    }
+
+    // Check a file name or a line of a file
+    fn scan_text(&self, path: &Path, line: Option<usize>, text: &str, findings: &mut Vec<Finding>) {
+        let mut finding = |kind, message| {
+            findings.push(Finding { kind, path: path.to_path_buf(), line, message });
+        };
+        for (start, end) in find_loose(text, &self.ctx.old_name.parts) {
+            let found = &text[start..end];
+            if !self.old_variants.iter().any(|variant| variant == found) {
+                finding(Kind::UnhandledCase, format!("{found} is written in a case style that is not renamed"));
+            } else if is_partial_word(text, start, end) {
+                finding(Kind::PartialWord, format!("{found} is part of a longer word, which is renamed as well"));
+            }
+        }
+        if let Some(variant) = self.new_variants.iter().find(|variant| text.contains(variant.as_str())) {
+            finding(Kind::NewNameExists, format!("{variant} already occurs and can't be told apart from renamed text"));
+        }
+    }
+}
+
+// Byte ranges of the parts of a name in the text, ignoring case and with at most one separator
+// between the parts
+fn find_loose(text: &str, parts: &[String]) -> Vec<(usize, usize)> {
+    let mut found = vec![];
+    let mut start = 0;
+    while start < text.len() {
+        match match_at(text, start, parts) {
+            Some(end) => {
+                found.push((start, end));
+                start = end;
+            }
+            None => start += text[start..].chars().next().unwrap().len_utf8(),
+        }
+    }
+    found
+}
+
+fn match_at(text: &str, start: usize, parts: &[String]) -> Option<usize> {
+    let mut chars = text[start..].char_indices().peekable();
+    for (index, part) in parts.iter().enumerate() {
+        if index > 0 && chars.peek().is_some_and(|(_, c)| SEPARATORS.contains(c)) {
+            chars.next();
+        }
+        for expected in part.chars() {
+            let (_, c) = chars.next()?;
+            if !c.to_lowercase().eq(expected.to_lowercase()) {
+                return None;
+            }
+        }
+    }
+    Some(chars.peek().map_or(text.len(), |(offset, _)| start + offset))
+}
+
+// Whether the match continues a word before or after it. A capital letter starts a new word, e.g.
+// "MyProjectService" is not a partial match but "myprojects" and "MY_PROJECTS" are.
+fn is_partial_word(text: &str, start: usize, end: usize) -> bool {
+    let found = &text[start..end];
+    let (first, last) = (found.chars().next().unwrap(), found.chars().next_back().unwrap());
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    before.is_some_and(|c| c.is_alphanumeric() && !(c.is_lowercase() && first.is_uppercase()))
+        || after.is_some_and(|c| c.is_alphanumeric() && !(c.is_uppercase() && last.is_lowercase()))
+}
+
+fn contains_bytes(haystack: &[u8], needle: &[u8]) -> bool {
+    !needle.is_empty() && haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{Output, Verbosity};
+
+    fn parts(name: &str) -> Vec<String> {
+        CaseInfo::detect(name).1.parts
+    }
+
+    #[test]
+    fn test_find_loose() {
+        let text = "myProject my-project MY__PROJECT xmyprojectx";
+        let found = find_loose(text, &parts("my-project")).into_iter().map(|(start, end)| &text[start..end]).collect::<Vec<_>>();
+        assert_eq!(found, vec!["myProject", "my-project", "myproject"]);
+    }
+
+    #[test]
+    fn test_unhandled_cases() {
+        let text = "my-project\nlet x = myProject + MyProject;\nMY_project";
+        let found = unhandled_cases(text, &CaseInfo::detect("my-project").1);
+        assert_eq!(found, vec![(2, "myProject".to_string()), (3, "MY_project".to_string())]);
+    }
+
+    #[test]
+    fn test_is_partial_word() {
+        let partial = |text: &str, found: &str| {
+            let start = text.find(found).unwrap();
+            is_partial_word(text, start, start + found.len())
+        };
+        assert!(!partial("use my_project;", "my_project"));
+        assert!(!partial("MyProjectService", "MyProject"));
+        assert!(!partial("newMyProject", "MyProject"));
+        assert!(partial("myprojects", "myproject"));
+        assert!(partial("MY_PROJECTS", "MY_PROJECT"));
+        assert!(partial("premyproject", "myproject"));
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-doctor-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/a.js"), "const x = myProject;\nconst y = myprojects;\n").unwrap();
+        std::fs::write(dir.join("my-project/logo.bin"), b"\xff\xfemy-project").unwrap();
+        std::fs::write(dir.join("my-project/my-project.txt"), "").unwrap();
+        std::fs::write(dir.join("my-project/new-project.txt"), "").unwrap();
+
+        let out = Output::new(Verbosity::Quiet, Default::default(), Default::default());
+        let ctx = Context::new(&dir.join("my-project"), "new-project", out);
+        let findings = diagnose(&ctx)
+            .into_iter()
+            .map(|finding| (finding.kind, finding.path.file_name().unwrap().to_string_lossy().to_string(), finding.line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            findings,
+            vec![
+                (Kind::NewNameExists, "new-project.txt".to_string(), None),
+                (Kind::Collision, "new-project.txt".to_string(), None),
+                (Kind::UnhandledCase, "a.js".to_string(), Some(1)),
+                (Kind::PartialWord, "a.js".to_string(), Some(2)),
+                (Kind::Binary, "logo.bin".to_string(), None),
+            ]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
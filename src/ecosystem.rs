@@ -0,0 +1,181 @@
+use clap::{Arg, ArgAction, ArgMatches, Command};
+
+// A build system, package manager or platform whose naming conventions a rename can follow, each
+// enabled with a flag of its own like --maven
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ecosystem {
+    RustCrates,
+    PythonPackages,
+    RubyGems,
+    Elixir,
+    SwiftPackages,
+    CSharpNamespaces,
+    Composer,
+    Homebrew,
+    Maven,
+    Gradle,
+    Nix,
+    Bazel,
+    CppPackages,
+    Meson,
+    Makefiles,
+    Docker,
+    Kubernetes,
+    Systemd,
+    Debian,
+    Rpm,
+    Flatpak,
+    Electron,
+    Tauri,
+    ReactNative,
+    Wordpress,
+    Vscode,
+}
+
+// Every ecosystem with the name of its option in plans, its flag and the help of the flag, in the
+// order of --help
+const ECOSYSTEMS: [(Ecosystem, &str, &str, &str); 26] = [
+    (Ecosystem::RustCrates, "rust_crates", "rust-crates", "Also rename the references to the crate in Rust code \
+        (\"use\", \"extern crate\" and paths), with the hyphens of the package name mapped to underscores. Catches the \
+        crate name when the old name mixes separators, e.g. \"my-project_rs\". Also renames the feature names, target \
+        names and environment variables derived from the package name, even with --replace-in"),
+    (Ecosystem::PythonPackages, "python_packages", "python-packages", "Rename a Python project with its distribution \
+        name and import name kept apart: the distribution name in pyproject.toml and importlib.metadata calls, e.g. \
+        \"new-project\", and the import name in imports, scripts, entry points and package lists, e.g. \
+        \"new_project\""),
+    (Ecosystem::RubyGems, "ruby_gems", "ruby-gems", "Rename a Ruby gem following the RubyGems naming conventions: the \
+        gem name in the gemspec, the required paths and the constants, where the gem \"old-name\" is required as \
+        \"old/name\" and defines Old::Name"),
+    (Ecosystem::Elixir, "elixir", "elixir", "Rename an Elixir project following the Mix conventions: the application \
+        atom, e.g. \":new_name\" in mix.exs and \"config :new_name\", and the aliases of its modules, e.g. \
+        \"NewName.Repo\" and \"NewNameWeb\""),
+    (Ecosystem::SwiftPackages, "swift_packages", "swift-packages", "Rename a Swift package and its modules: the \
+        package name, target names and paths in Package.swift, e.g. \"new-name\", \"NewName\" and \
+        \"Tests/NewNameTests\", and the modules of import statements, e.g. \"import NewName\""),
+    (Ecosystem::CSharpNamespaces, "csharp_namespaces", "csharp-namespaces", "Rename the namespaces named after the \
+        project in the namespace declarations and using directives of C# sources and Razor pages, e.g. \"namespace \
+        NewName.Core;\", even where --replace-in or --identifier-boundaries leave them out"),
+    (Ecosystem::Composer, "composer", "composer", "Keep the composer.lock of a PHP package in sync with its renamed \
+        composer.json: the content hash of the lock is recomputed, so Composer doesn't report the lock as outdated"),
+    (Ecosystem::Homebrew, "homebrew", "homebrew", "Rename a Homebrew formula with the class Homebrew names after it, \
+        e.g. \"class NewName < Formula\" for the formula \"new-name\" and NewNameAT2 for \"new-name@2\""),
+    (Ecosystem::Maven, "maven", "maven", "Only rename the Maven coordinates in POMs: the groupId, artifactId and name \
+        of the project, its parent reference, its modules and its dependencies, so descriptions, URLs and plugin \
+        configuration keep the old name. With --workspace this also applies to the POMs of the other modules of the \
+        reactor"),
+    (Ecosystem::Gradle, "gradle", "gradle", "Only rename the project names in Gradle build scripts: \
+        \"rootProject.name\", the included projects, project dependencies like project(\":name\") and the archive base \
+        names, in the Groovy and Kotlin DSL. With --workspace this also applies to the scripts of the other projects \
+        of the build"),
+    (Ecosystem::Nix, "nix", "nix", "Only rename the names of the inputs in the flake.lock of a Nix flake, so it still \
+        matches the inputs of the renamed flake.nix while the locked sources stay as they are"),
+    (Ecosystem::Bazel, "bazel", "bazel", "In Bazel files, rename the names and labels like \"@new_name//new_name:lib\" \
+        but not the sources of external repositories, like their urls, strip_prefix and hashes"),
+    (Ecosystem::CppPackages, "cpp_packages", "cpp-packages", "In conanfile.py, conanfile.txt and vcpkg.json, only \
+        rename the name of the package and the names of the packages it requires or depends on"),
+    (Ecosystem::Meson, "meson", "meson", "In meson.build and the wrap files of subprojects, only rename the names of \
+        the project, the targets, the subprojects and the dependencies"),
+    (Ecosystem::Makefiles, "makefiles", "makefiles", "In Makefiles, only rename the targets, the variables, the \
+        included files and the variable references, not the shell commands of the recipes"),
+    (Ecosystem::Docker, "docker", "docker", "In Dockerfiles, only rename the LABEL, ARG and ENV instructions and the \
+        build stages, and with --makefiles also the make targets of RUN and the images built in the recipes"),
+    (Ecosystem::Kubernetes, "kubernetes", "kubernetes", "In Kubernetes manifests, only rename the names of the \
+        resources, the app labels and selectors and the references to Services, so the resources keep selecting each \
+        other"),
+    (Ecosystem::Systemd, "systemd", "systemd", "In systemd unit files, only rename the Description, the Exec commands \
+        and the references to other units, not the users and directories of the services"),
+    (Ecosystem::Debian, "debian", "debian", "In debian/control, only rename the package, relationship and Description \
+        fields, and in debian/changelog only the package names of the entries"),
+    (Ecosystem::Rpm, "rpm", "rpm", "In RPM spec files, rename everything but the Source, Patch and URL tags and the \
+        entries of the %changelog"),
+    (Ecosystem::Flatpak, "flatpak", "flatpak", "Rename the reverse-DNS application IDs of Flatpak manifests and of the \
+        apps of snapcraft.yaml, e.g. \"com.example.NewName\", wherever they occur, files named after them included. \
+        The reversed domain follows --old-domain and --new-domain"),
+    (Ecosystem::Electron, "electron", "electron", "Rename the reverse-DNS application ID of an Electron app, the appId \
+        of electron-builder or the appBundleId of Electron Forge, like --flatpak does"),
+    (Ecosystem::Tauri, "tauri", "tauri", "Rename the reverse-DNS identifier of a Tauri app, in tauri.conf.json or \
+        Tauri.toml, like --flatpak does"),
+    (Ecosystem::ReactNative, "react_native", "react-native", "Rename the reverse-DNS application IDs of a React Native \
+        app, the Android applicationId and namespace and the iOS bundle identifier, like --flatpak does, and move the \
+        Java and Kotlin package directories along"),
+    (Ecosystem::Wordpress, "wordpress", "wordpress", "Rename the text domain of a WordPress plugin or theme, from the \
+        Text Domain of its header to the slug of the new name, where it is used as one, also when it isn't a case \
+        variant of the old name, and the main PHP file and the translation files named after it"),
+    (Ecosystem::Vscode, "vscode", "vscode", "Rename the IDs a VS Code extension contributes, its commands, \
+        configuration properties and views, and the configuration section, also when they are prefixed with the old \
+        name in camelCase like myExt.doThing"),
+];
+
+impl Ecosystem {
+    pub fn all() -> impl Iterator<Item = Ecosystem> {
+        ECOSYSTEMS.iter().map(|(ecosystem, ..)| *ecosystem)
+    }
+
+    // Name of the option in plans and of the argument, like "react_native"
+    pub fn name(self) -> &'static str {
+        ECOSYSTEMS.iter().find(|(ecosystem, ..)| *ecosystem == self).map(|(_, name, ..)| *name).unwrap()
+    }
+}
+
+// The ecosystems enabled for a run, as a set of bits indexed by the variants
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ecosystems(u32);
+
+impl Ecosystems {
+    pub fn contains(self, ecosystem: Ecosystem) -> bool {
+        self.0 & 1 << ecosystem as u32 != 0
+    }
+
+    pub fn insert(&mut self, ecosystem: Ecosystem) {
+        self.0 |= 1 << ecosystem as u32;
+    }
+}
+
+impl FromIterator<Ecosystem> for Ecosystems {
+    fn from_iter<I: IntoIterator<Item = Ecosystem>>(iter: I) -> Self {
+        let mut ecosystems = Self::default();
+        for ecosystem in iter {
+            ecosystems.insert(ecosystem);
+        }
+        ecosystems
+    }
+}
+
+// One flag per ecosystem, e.g. --react-native
+impl clap::Args for Ecosystems {
+    fn augment_args(cmd: Command) -> Command {
+        ECOSYSTEMS.iter().fold(cmd, |cmd, (_, name, flag, help)| {
+            cmd.arg(Arg::new(*name).long(*flag).action(ArgAction::SetTrue).help(*help).conflicts_with("names_only"))
+        })
+    }
+
+    fn augment_args_for_update(cmd: Command) -> Command {
+        Self::augment_args(cmd)
+    }
+}
+
+impl clap::FromArgMatches for Ecosystems {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        Ok(Ecosystem::all().filter(|ecosystem| matches.get_flag(ecosystem.name())).collect())
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
+        *self = Self::from_arg_matches(matches)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ecosystems() {
+        let ecosystems = [Ecosystem::Maven, Ecosystem::Vscode].into_iter().collect::<Ecosystems>();
+        assert!(ecosystems.contains(Ecosystem::Maven));
+        assert!(!ecosystems.contains(Ecosystem::Gradle));
+        assert_eq!(Ecosystem::all().filter(|ecosystem| ecosystems.contains(*ecosystem)).count(), 2);
+        assert_eq!(Ecosystem::all().count(), ECOSYSTEMS.len());
+        assert_eq!(Ecosystem::ReactNative.name(), "react_native");
+    }
+}
@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use crate::{flatpak, json, yaml};
+
+// The reverse-DNS application IDs of an Electron app: the `appId` of the electron-builder config,
+// in the "build" of package.json or in electron-builder.json or .yml, and the `appBundleId` of the
+// packager config of Electron Forge in package.json
+pub fn app_ids(path: &Path, text: &str) -> Vec<String> {
+    let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let values = match name.as_str() {
+        "package.json" | "electron-builder.json" => json::string_values(text).unwrap_or_default(),
+        "electron-builder.yml" | "electron-builder.yaml" => yaml::values(text),
+        _ => vec![],
+    };
+    let package = name == "package.json";
+    let mut ids = vec![];
+    for (path, range) in values {
+        let declared = match path.as_slice() {
+            [key] => !package && key == "appId",
+            [build, key] => package && build == "build" && key == "appId",
+            [config, forge, packager, key] => package && config == "config" && forge == "forge" && packager == "packagerConfig" && key == "appBundleId",
+            _ => false,
+        };
+        let id = text[range].to_string();
+        if declared && flatpak::is_app_id(&id) && !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_ids() {
+        let package = r#"{"name": "my-app", "productName": "My App", "build": {"appId": "com.example.myApp", "mac": {"appId": "x"}},
+            "config": {"forge": {"packagerConfig": {"appBundleId": "com.example.my-app"}}}}"#;
+        assert_eq!(app_ids(Path::new("package.json"), package), ["com.example.myApp", "com.example.my-app"]);
+        assert_eq!(app_ids(Path::new("electron-builder.yml"), "appId: com.example.MyApp\nproductName: My App\n"), ["com.example.MyApp"]);
+        assert!(app_ids(Path::new("tsconfig.json"), r#"{"appId": "com.example.MyApp"}"#).is_empty());
+    }
+}
@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use crate::{json, yaml};
+
+// The reverse-DNS application IDs a manifest declares: the `app-id` or `id` of a Flatpak manifest,
+// which is named after it like "com.example.OldName.yml", and the `common-id` of the apps of a
+// snapcraft.yaml, the ID of their AppStream metadata
+pub fn app_ids(path: &Path, text: &str) -> Vec<String> {
+    let extension = path.extension().map(|extension| extension.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let values = match extension.as_str() {
+        "json" => json::string_values(text).unwrap_or_default().into_iter().map(|(path, range)| (path, text[range].to_string())).collect(),
+        "yml" | "yaml" => yaml::values(text).into_iter().map(|(path, range)| (path, unquote(&text[range]).to_string())).collect(),
+        _ => vec![],
+    };
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let snapcraft = path.file_name().is_some_and(|name| name == "snapcraft.yaml" || name == ".snapcraft.yaml");
+    let mut ids = vec![];
+    for (path, value) in values {
+        let declared = match path.as_slice() {
+            [key] => (key == "app-id" || key == "id") && value == stem,
+            [apps, _, key] => snapcraft && apps == "apps" && key == "common-id",
+            _ => false,
+        };
+        if declared && is_app_id(&value) && !ids.contains(&value) {
+            ids.push(value);
+        }
+    }
+    ids
+}
+
+// Whether the text is a reverse-DNS ID like "com.example.OldName", with at least three elements of
+// letters, digits, underscores and hyphens
+pub fn is_app_id(id: &str) -> bool {
+    let elements = id.split('.').collect::<Vec<_>>();
+    elements.len() >= 3
+        && elements.iter().all(|element| !element.is_empty() && element.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+}
+
+// The domain in the reverse order of an ID, "example.com" is "com.example"
+pub fn reverse_domain(domain: &str) -> String {
+    domain.split('.').rev().collect::<Vec<_>>().join(".")
+}
+
+fn unquote(value: &str) -> &str {
+    value.strip_prefix(['"', '\'']).and_then(|value| value.strip_suffix(['"', '\''])).unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_ids() {
+        let manifest = "app-id: com.example.MyApp\nruntime: org.gnome.Platform\ncommand: my-app\n";
+        assert_eq!(app_ids(Path::new("com.example.MyApp.yml"), manifest), ["com.example.MyApp"]);
+        assert!(app_ids(Path::new("flatpak.yml"), manifest).is_empty());
+
+        let manifest = r#"{"id": "io.github.acme.my_app", "modules": [{"name": "my-app"}]}"#;
+        assert_eq!(app_ids(Path::new("io.github.acme.my_app.json"), manifest), ["io.github.acme.my_app"]);
+
+        let snapcraft = "name: my-app\napps:\n  my-app:\n    command: bin/my-app\n    common-id: 'com.example.MyApp'\n";
+        assert_eq!(app_ids(Path::new("snap/snapcraft.yaml"), snapcraft), ["com.example.MyApp"]);
+        assert_eq!(reverse_domain("example.co.uk"), "uk.co.example");
+        assert!(!is_app_id("example.com"));
+    }
+}
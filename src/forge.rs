@@ -0,0 +1,173 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::json::{self, Value};
+
+// A git hosting service whose API can open pull requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forge {
+    GitHub,
+    // Merge requests, as GitLab calls them
+    GitLab,
+}
+
+impl Forge {
+    // Environment variables the API token is read from, in order
+    fn token_variables(&self) -> &'static [&'static str] {
+        match self {
+            Forge::GitHub => &["GITHUB_TOKEN", "GH_TOKEN"],
+            Forge::GitLab => &["GITLAB_TOKEN"],
+        }
+    }
+}
+
+// A repository on a forge, from the URL of a git remote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repository {
+    pub forge: Forge,
+    pub host: String,
+    // Owner and name, e.g. "org/repo", or the group path and name on GitLab
+    pub path: String,
+}
+
+impl Repository {
+    // Parse "git@github.com:org/repo.git", "ssh://git@host:22/org/repo" or
+    // "https://host/group/sub/repo.git". The forge is told from the host name.
+    pub fn from_remote(url: &str) -> Option<Self> {
+        let (host, path) = match url.split_once("://") {
+            Some((_, rest)) => rest.split_once('/')?,
+            None => url.split_once(':')?,
+        };
+        let host = host.rsplit('@').next()?.split(':').next()?.to_ascii_lowercase();
+        let path = path.trim_end_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path).trim_matches('/');
+        let forge = if host.contains("github") {
+            Forge::GitHub
+        } else if host.contains("gitlab") {
+            Forge::GitLab
+        } else {
+            return None;
+        };
+        (!host.is_empty() && path.contains('/')).then(|| Self { forge, host, path: path.to_string() })
+    }
+
+    // URL, headers and body of the API request that opens the pull request
+    fn request(&self, pull_request: &PullRequest, token: &str) -> (String, Vec<String>, Value) {
+        let object = |entries: Vec<(&str, &str)>| {
+            Value::Object(entries.into_iter().map(|(key, value)| (key.to_string(), value.into())).collect())
+        };
+        match self.forge {
+            Forge::GitHub => {
+                // GitHub Enterprise serves the API under the host itself
+                let api = if self.host == "github.com" { "https://api.github.com".to_string() } else { format!("https://{}/api/v3", self.host) };
+                (
+                    format!("{api}/repos/{}/pulls", self.path),
+                    vec![format!("Authorization: Bearer {token}"), "Accept: application/vnd.github+json".to_string()],
+                    object(vec![
+                        ("title", pull_request.title),
+                        ("body", pull_request.body),
+                        ("head", pull_request.head),
+                        ("base", pull_request.base),
+                    ]),
+                )
+            }
+            Forge::GitLab => (
+                format!("https://{}/api/v4/projects/{}/merge_requests", self.host, self.path.replace('/', "%2F")),
+                vec![format!("PRIVATE-TOKEN: {token}")],
+                object(vec![
+                    ("title", pull_request.title),
+                    ("description", pull_request.body),
+                    ("source_branch", pull_request.head),
+                    ("target_branch", pull_request.base),
+                ]),
+            ),
+        }
+    }
+}
+
+pub struct PullRequest<'a> {
+    pub title: &'a str,
+    pub body: &'a str,
+    // The branch with the changes and the branch they are merged into
+    pub head: &'a str,
+    pub base: &'a str,
+}
+
+// Open the pull request with the API of the forge and return its web URL. The token is read from
+// the environment. curl sends the request, with the token passed on its standard input so it
+// doesn't show up in the process list.
+pub fn open(repository: &Repository, pull_request: &PullRequest) -> Result<String, String> {
+    let variables = repository.forge.token_variables();
+    let token = variables
+        .iter()
+        .find_map(|variable| std::env::var(variable).ok().filter(|token| !token.is_empty()))
+        .ok_or_else(|| format!("Set {} to a token that can open pull requests", variables.join(" or ")))?;
+    let (url, headers, body) = repository.request(pull_request, &token);
+
+    let mut config = format!("url = {}\nrequest = \"POST\"\n", curl_quote(&url));
+    for header in headers.iter().chain(&["Content-Type: application/json".to_string()]) {
+        config.push_str(&format!("header = {}\n", curl_quote(header)));
+    }
+    config.push_str(&format!("data-binary = {}\n", curl_quote(&body.to_string())));
+
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail-with-body", "--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {e}"))?;
+    child.stdin.take().unwrap().write_all(config.as_bytes()).map_err(|e| format!("Failed to run curl: {e}"))?;
+    let output = child.wait_with_output().map_err(|e| format!("Failed to run curl: {e}"))?;
+    let response = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        return Err(format!("{url} failed: {} {}", String::from_utf8_lossy(&output.stderr).trim(), response.trim()));
+    }
+    let response = json::parse(&response).map_err(|e| format!("Invalid response from {url}: {e}"))?;
+    response
+        .get("html_url")
+        .or_else(|| response.get("web_url"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| format!("The response from {url} has no URL of the pull request"))
+}
+
+// A string in a curl config file
+fn curl_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n").replace('\r', "\\r").replace('\t', "\\t"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_remote() {
+        let repository = |forge, host: &str, path: &str| Some(Repository { forge, host: host.to_string(), path: path.to_string() });
+        assert_eq!(Repository::from_remote("git@github.com:org/repo.git"), repository(Forge::GitHub, "github.com", "org/repo"));
+        assert_eq!(Repository::from_remote("https://github.com/org/repo"), repository(Forge::GitHub, "github.com", "org/repo"));
+        assert_eq!(
+            Repository::from_remote("ssh://git@gitlab.example.com:2222/group/sub/repo.git"),
+            repository(Forge::GitLab, "gitlab.example.com", "group/sub/repo")
+        );
+        assert_eq!(Repository::from_remote("https://bitbucket.org/org/repo.git"), None);
+        assert_eq!(Repository::from_remote("/srv/git/repo.git"), None);
+    }
+
+    #[test]
+    fn test_request() {
+        let pull_request = PullRequest { title: "Rename", body: "Renamed \"a\"", head: "rename/b", base: "main" };
+        let gitlab = Repository { forge: Forge::GitLab, host: "gitlab.com".to_string(), path: "group/repo".to_string() };
+        let (url, headers, body) = gitlab.request(&pull_request, "secret");
+        assert_eq!(url, "https://gitlab.com/api/v4/projects/group%2Frepo/merge_requests");
+        assert_eq!(headers, ["PRIVATE-TOKEN: secret"]);
+        assert_eq!(
+            body.to_string(),
+            r#"{"title":"Rename","description":"Renamed \"a\"","source_branch":"rename/b","target_branch":"main"}"#
+        );
+
+        let github = Repository { forge: Forge::GitHub, host: "github.com".to_string(), path: "org/repo".to_string() };
+        assert_eq!(github.request(&pull_request, "secret").0, "https://api.github.com/repos/org/repo/pulls");
+        assert_eq!(curl_quote(r#"{"a":"\"b\n"}"#), r#""{\"a\":\"\\\"b\\n\"}""#);
+    }
+}
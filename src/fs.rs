@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::transfer::{self, Reflink};
+
+/// The file operations a rename reads the project and writes the output with, so it can run against
+/// something other than the disk, e.g. in memory for tests and embedding applications
+pub trait Filesystem: Send + Sync {
+    /// Whether a file or directory is at the path
+    fn exists(&self, path: &Path) -> bool;
+    /// Whether a directory is at the path
+    fn is_dir(&self, path: &Path) -> bool;
+    /// Paths of the entries of a directory, sorted
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>>;
+    /// The content of a file
+    fn read(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Create or replace a file with the content
+    fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
+    /// Copy a file, replacing the one at the destination
+    fn copy(&self, from: &Path, to: &Path) -> Result<()>;
+    /// Create a directory and its missing parents
+    fn create_dir_all(&self, path: &Path) -> Result<()>;
+    /// Remove a file
+    fn remove_file(&self, path: &Path) -> Result<()>;
+    /// Remove a file or a directory with everything in it, if it exists
+    fn remove_all(&self, path: &Path) -> Result<()>;
+    /// Move a file or directory into place, replacing nothing
+    fn rename(&self, from: &Path, to: &Path) -> Result<()>;
+    /// Whether a symbolic link is at the path
+    fn is_symlink(&self, _path: &Path) -> bool {
+        false
+    }
+    /// The path made absolute against the working directory, as paths of the filesystem are compared
+    fn absolute(&self, path: &Path) -> Result<PathBuf> {
+        Ok(path.to_path_buf())
+    }
+    /// Whether this is the real disk, which run locks, checkpoints and backups are written to
+    fn is_disk(&self) -> bool {
+        false
+    }
+}
+
+/// The real filesystem
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Disk {
+    /// How files without content changes are copied
+    pub reflink: Reflink,
+}
+
+impl Filesystem for Disk {
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let mut paths = path.read_dir()?.map(|entry| entry.map(|entry| entry.path())).collect::<Result<Vec<_>>>()?;
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+
+    fn write(&self, path: &Path, content: &[u8]) -> Result<()> {
+        std::fs::write(path, content)
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        transfer::copy_file(from, to, self.reflink)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> Result<()> {
+        std::fs::create_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<()> {
+        std::fs::remove_file(path)
+    }
+
+    fn remove_all(&self, path: &Path) -> Result<()> {
+        if path.is_dir() {
+            std::fs::remove_dir_all(path)
+        } else if path.exists() {
+            std::fs::remove_file(path)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        transfer::move_path(from, to)
+    }
+
+    fn is_symlink(&self, path: &Path) -> bool {
+        path.is_symlink()
+    }
+
+    fn absolute(&self, path: &Path) -> Result<PathBuf> {
+        std::path::absolute(path)
+    }
+
+    fn is_disk(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Entry {
+    File(Vec<u8>),
+    Directory,
+}
+
+/// A filesystem that only exists in memory. Parent directories are created implicitly.
+#[derive(Debug, Default)]
+pub struct Memory {
+    entries: Mutex<BTreeMap<PathBuf, Entry>>,
+}
+
+impl Memory {
+    /// An empty filesystem
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file, e.g. to set up a project
+    pub fn with_file(self, path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> Self {
+        self.write(path.as_ref(), content.as_ref()).unwrap();
+        self
+    }
+
+    /// Every file with its content, sorted by path
+    pub fn files(&self) -> Vec<(PathBuf, Vec<u8>)> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .filter_map(|(path, entry)| match entry {
+                Entry::File(content) => Some((path.clone(), content.clone())),
+                Entry::Directory => None,
+            })
+            .collect()
+    }
+
+    fn add_parents(entries: &mut BTreeMap<PathBuf, Entry>, path: &Path) -> Result<()> {
+        for parent in path.ancestors().skip(1).filter(|parent| !parent.as_os_str().is_empty()) {
+            match entries.get(parent) {
+                Some(Entry::File(_)) => return Err(Error::new(ErrorKind::NotADirectory, parent.display().to_string())),
+                Some(Entry::Directory) => break,
+                None => {
+                    entries.insert(parent.to_path_buf(), Entry::Directory);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn not_found(path: &Path) -> Error {
+    Error::new(ErrorKind::NotFound, format!("{} does not exist", path.display()))
+}
+
+impl Filesystem for Memory {
+    fn exists(&self, path: &Path) -> bool {
+        self.entries.lock().unwrap().contains_key(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.entries.lock().unwrap().get(path) == Some(&Entry::Directory)
+    }
+
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let entries = self.entries.lock().unwrap();
+        if entries.get(path) != Some(&Entry::Directory) {
+            return Err(not_found(path));
+        }
+        Ok(entries.keys().filter(|child| child.parent() == Some(path)).cloned().collect())
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        match self.entries.lock().unwrap().get(path) {
+            Some(Entry::File(content)) => Ok(content.clone()),
+            Some(Entry::Directory) => Err(Error::new(ErrorKind::IsADirectory, path.display().to_string())),
+            None => Err(not_found(path)),
+        }
+    }
+
+    fn write(&self, path: &Path, content: &[u8]) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.get(path) == Some(&Entry::Directory) {
+            return Err(Error::new(ErrorKind::IsADirectory, path.display().to_string()));
+        }
+        Self::add_parents(&mut entries, path)?;
+        entries.insert(path.to_path_buf(), Entry::File(content.to_vec()));
+        Ok(())
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        let content = self.read(from)?;
+        self.write(to, &content)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(path) {
+            Some(Entry::Directory) => Ok(()),
+            Some(Entry::File(_)) => Err(Error::new(ErrorKind::AlreadyExists, path.display().to_string())),
+            None => {
+                Self::add_parents(&mut entries, path)?;
+                entries.insert(path.to_path_buf(), Entry::Directory);
+                Ok(())
+            }
+        }
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(path) {
+            Some(Entry::File(_)) => {
+                entries.remove(path);
+                Ok(())
+            }
+            Some(Entry::Directory) => Err(Error::new(ErrorKind::IsADirectory, path.display().to_string())),
+            None => Err(not_found(path)),
+        }
+    }
+
+    fn remove_all(&self, path: &Path) -> Result<()> {
+        self.entries.lock().unwrap().retain(|entry, _| !entry.starts_with(path));
+        Ok(())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        if !entries.contains_key(from) {
+            return Err(not_found(from));
+        }
+        if entries.contains_key(to) {
+            return Err(Error::new(ErrorKind::AlreadyExists, to.display().to_string()));
+        }
+        Self::add_parents(&mut entries, to)?;
+        let moved = entries.keys().filter(|entry| entry.starts_with(from)).cloned().collect::<Vec<_>>();
+        for path in moved {
+            let entry = entries.remove(&path).unwrap();
+            entries.insert(to.join(path.strip_prefix(from).unwrap()), entry);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory() {
+        let fs = Memory::new().with_file("a/b/c.txt", "c").with_file("a/d.txt", "d");
+        assert!(fs.is_dir(Path::new("a/b")));
+        assert_eq!(fs.read_dir(Path::new("a")).unwrap(), vec![PathBuf::from("a/b"), PathBuf::from("a/d.txt")]);
+        assert!(fs.write(Path::new("a/d.txt/e"), b"").is_err());
+
+        fs.rename(Path::new("a"), Path::new("x/y")).unwrap();
+        assert!(!fs.exists(Path::new("a")));
+        assert_eq!(fs.read(Path::new("x/y/b/c.txt")).unwrap(), b"c");
+        fs.copy(Path::new("x/y/d.txt"), Path::new("z.txt")).unwrap();
+        fs.remove_all(Path::new("x")).unwrap();
+        assert_eq!(fs.files(), vec![(PathBuf::from("z.txt"), b"d".to_vec())]);
+    }
+}
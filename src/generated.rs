@@ -0,0 +1,33 @@
+// Markers code generators put in the header of the files they write, lower case
+const MARKERS: [&str; 5] = ["@generated", "do not edit", "auto-generated", "autogenerated", "automatically generated"];
+
+// Only the header is looked at, the markers also occur in the code of the generators themselves
+const HEADER_LINES: usize = 5;
+
+// Whether the file was written by a code generator, like "// Code generated by protoc-gen-go. DO NOT
+// EDIT." or "# @generated by pip-compile". Such files are regenerated from their sources, so edits
+// to them are churn that the next regeneration reverts.
+pub fn is_generated(text: &str) -> bool {
+    text.lines().take(HEADER_LINES).any(|line| {
+        let line = line.to_ascii_lowercase();
+        MARKERS.iter().any(|marker| line.contains(marker))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_generated() {
+        for text in [
+            "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage my_project",
+            "#!/bin/sh\n# @generated by pip-compile\n",
+            "<?xml version=\"1.0\"?>\n<!-- <auto-generated> This code was generated by a tool. </auto-generated> -->",
+        ] {
+            assert!(is_generated(text), "{text}");
+        }
+        assert!(!is_generated("fn main() {}\n"));
+        assert!(!is_generated("1\n2\n3\n4\n5\n// DO NOT EDIT\n"));
+    }
+}
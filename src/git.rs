@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Run git in the directory and return what it printed
+pub fn git(directory: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(directory)
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// The files in the directory that git tracks, including those of submodules, and the directories
+// that contain them
+pub fn tracked_paths(directory: &Path) -> Result<HashSet<PathBuf>, String> {
+    let files = git(directory, &["ls-files", "--cached", "--recurse-submodules", "-z"])?;
+    let mut paths = HashSet::new();
+    for file in files.split('\0').filter(|file| !file.is_empty()) {
+        for ancestor in Path::new(file).ancestors().filter(|ancestor| !ancestor.as_os_str().is_empty()) {
+            if !paths.insert(directory.join(ancestor)) {
+                break;
+            }
+        }
+    }
+    Ok(paths)
+}
+
+// Create a branch at the current commit of the work tree of the path and switch to it
+pub fn create_branch(path: &Path, branch: &str) -> Result<(), String> {
+    git(&directory(&[path])?, &["switch", "--quiet", "--create", branch])?;
+    Ok(())
+}
+
+// The branch checked out in the work tree of the path
+pub fn current_branch(path: &Path) -> Result<String, String> {
+    Ok(git(&directory(&[path])?, &["rev-parse", "--abbrev-ref", "HEAD"])?.trim().to_string())
+}
+
+// URL of the "origin" remote of the work tree of the path
+pub fn origin_url(path: &Path) -> Result<String, String> {
+    Ok(git(&directory(&[path])?, &["remote", "get-url", "origin"])?.trim().to_string())
+}
+
+// Push the checked out branch to "origin" and track it
+pub fn push(path: &Path) -> Result<(), String> {
+    git(&directory(&[path])?, &["push", "--quiet", "--set-upstream", "origin", "HEAD"])?;
+    Ok(())
+}
+
+// Stage the removal of the old paths and the addition of the new ones, like "git add --all" limited
+// to them, so git detects the renames
+pub fn stage(paths: &[&Path]) -> Result<(), String> {
+    with_paths(paths, &["add", "--all", "--"])
+}
+
+// Commit the changes of the paths, and only of them
+pub fn commit(paths: &[&Path], message: &str) -> Result<(), String> {
+    with_paths(paths, &["commit", "--quiet", "--message", message, "--"])
+}
+
+fn with_paths(paths: &[&Path], args: &[&str]) -> Result<(), String> {
+    let absolute = paths.iter().map(std::path::absolute).collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?;
+    let absolute = absolute.iter().map(|path| path.to_string_lossy()).collect::<Vec<_>>();
+    let mut args = args.to_vec();
+    args.extend(absolute.iter().map(|path| path.as_ref()));
+    git(&directory(paths)?, &args)?;
+    Ok(())
+}
+
+// A directory in the work tree to run git in: the first of the paths that is a directory, or the
+// parent of a file. Old paths of a rename are gone.
+fn directory(paths: &[&Path]) -> Result<PathBuf, String> {
+    paths
+        .iter()
+        .find_map(|path| {
+            let path = std::path::absolute(path).ok()?;
+            if path.is_dir() { Some(path) } else { path.parent().filter(|parent| parent.is_dir()).map(Path::to_path_buf) }
+        })
+        .ok_or_else(|| "None of the paths is in a work tree".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracked_paths() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-git-tracked-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src/bin")).unwrap();
+        std::fs::write(dir.join("src/bin/main.rs"), "").unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "").unwrap();
+        std::fs::write(dir.join("scratch.txt"), "").unwrap();
+        git(&dir, &["init", "--quiet"]).unwrap();
+        git(&dir, &["add", "src"]).unwrap();
+
+        let mut paths = tracked_paths(&dir).unwrap().into_iter().collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(paths, ["src", "src/bin", "src/bin/main.rs", "src/lib.rs"].map(|path| dir.join(path)));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::fs::Filesystem;
+use crate::glob;
+
+// A line of a .gitattributes file that sets the text attribute
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    pattern: String,
+    // Some(false) for "binary" and "-text", Some(true) for "text", None for "text=auto" and "!text"
+    text: Option<bool>,
+}
+
+// The text attributes of the .gitattributes files of a project, which say whether git treats a file
+// as text or binary. The files are read once per directory when a file in it is asked about.
+#[derive(Debug, Default)]
+pub struct Attributes {
+    // Rules by the directory of their .gitattributes file, relative to the project
+    rules: HashMap<PathBuf, Vec<Rule>>,
+}
+
+impl Attributes {
+    // Whether the file at the path relative to the project root is marked as text or as binary, or
+    // None when its content decides. Like in git, deeper files and later lines take precedence.
+    pub fn text(&mut self, fs: &dyn Filesystem, root: &Path, relative: &Path) -> Option<bool> {
+        let mut text = None;
+        let directories = relative.ancestors().skip(1).collect::<Vec<_>>();
+        for directory in directories.into_iter().rev() {
+            let rules = self.rules.entry(directory.to_path_buf()).or_insert_with(|| {
+                fs.read(&root.join(directory).join(".gitattributes"))
+                    .ok()
+                    .map(|content| parse(&String::from_utf8_lossy(&content)))
+                    .unwrap_or_default()
+            });
+            let Ok(path) = relative.strip_prefix(directory) else {
+                continue;
+            };
+            for rule in rules.iter().filter(|rule| glob::matches(&rule.pattern, path)) {
+                text = rule.text;
+            }
+        }
+        text
+    }
+}
+
+fn parse(content: &str) -> Vec<Rule> {
+    let mut rules = vec![];
+    for line in content.lines() {
+        let mut words = line.split_whitespace();
+        let Some(pattern) = words.next().filter(|pattern| !pattern.starts_with('#')) else {
+            continue;
+        };
+        let mut text = None;
+        let mut set = false;
+        for attribute in words {
+            match attribute {
+                "binary" | "-text" => (text, set) = (Some(false), true),
+                "text" => (text, set) = (Some(true), true),
+                "text=auto" | "!text" => (text, set) = (None, true),
+                _ => {}
+            }
+        }
+        if set {
+            rules.push(Rule { pattern: pattern.to_string(), text });
+        }
+    }
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::Memory;
+
+    #[test]
+    fn test_text() {
+        let fs = Memory::new()
+            .with_file("p/.gitattributes", "# comment\n* text=auto\n*.dat binary\n*.txt -text\ndocs/*.txt text\n")
+            .with_file("p/vendor/.gitattributes", "*.dat text\n");
+        let mut attributes = Attributes::default();
+        let mut text = |path: &str| attributes.text(&fs, Path::new("p"), Path::new(path));
+        assert_eq!(text("a.rs"), None);
+        assert_eq!(text("data/a.dat"), Some(false));
+        assert_eq!(text("notes.txt"), Some(false));
+        assert_eq!(text("docs/notes.txt"), Some(true));
+        assert_eq!(text("vendor/a.dat"), Some(true));
+    }
+}
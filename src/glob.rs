@@ -0,0 +1,53 @@
+use std::path::Path;
+
+// Whether the path relative to the project matches the glob pattern. "*" matches within a single
+// path component, "**" across components and "?" a single character. Like in .gitignore, a pattern
+// without a "/" matches any file or directory with that name, e.g. "*.lock" or "fixtures".
+pub fn matches(pattern: &str, relative: &Path) -> bool {
+    let path = relative.components().map(|component| component.as_os_str().to_string_lossy()).collect::<Vec<_>>();
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    if pattern.contains('/') {
+        matches_text(pattern.trim_start_matches('/').as_bytes(), path.join("/").as_bytes())
+    } else {
+        path.last().is_some_and(|name| matches_text(pattern.as_bytes(), name.as_bytes()))
+    }
+}
+
+fn matches_text(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            // "**/" also matches no directory at all
+            let rest_after_slash = rest.strip_prefix(b"/").unwrap_or(rest);
+            (0..=text.len()).any(|skip| matches_text(rest, &text[skip..]) || matches_text(rest_after_slash, &text[skip..]))
+        }
+        [b'*', rest @ ..] => {
+            let component = text.iter().position(|&c| c == b'/').unwrap_or(text.len());
+            (0..=component).any(|skip| matches_text(rest, &text[skip..]))
+        }
+        [b'?', rest @ ..] => text.first().is_some_and(|&c| c != b'/') && matches_text(rest, &text[1..]),
+        [c, rest @ ..] => text.first() == Some(c) && matches_text(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let matches = |pattern: &str, path: &str| matches(pattern, Path::new(path));
+        assert!(matches("*.lock", "Cargo.lock"));
+        assert!(matches("*.lock", "web/package.lock"));
+        assert!(!matches("*.lock", "Cargo.lock.txt"));
+        assert!(matches("fixtures", "tests/fixtures"));
+        assert!(matches("docs/*.md", "docs/a.md"));
+        assert!(!matches("docs/*.md", "docs/api/a.md"));
+        assert!(matches("docs/**/*.md", "docs/api/a.md"));
+        assert!(matches("docs/**/*.md", "docs/a.md"));
+        assert!(matches("/vendor/", "vendor"));
+        assert!(matches("**/generated", "src/api/generated"));
+        assert!(matches("file?.txt", "file1.txt"));
+        assert!(!matches("a?b", "a/b"));
+    }
+}
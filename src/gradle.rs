@@ -0,0 +1,94 @@
+use std::ops::Range;
+use std::path::Path;
+
+use crate::syntax::{self, Language, Node};
+
+// Identifiers that start a statement naming the projects of a build or the archives they produce,
+// besides "rootProject.name"
+const STATEMENTS: [&str; 6] = ["include", "includeFlat", "project", "archivesName", "archivesBaseName", "baseName"];
+
+// Whether the file is a Gradle build script in the Groovy or Kotlin DSL, like "settings.gradle" or
+// "build.gradle.kts"
+pub fn is_script(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| {
+        let name = name.to_string_lossy();
+        name.ends_with(".gradle") || name.ends_with(".gradle.kts")
+    })
+}
+
+// The ranges of the string literals, without their quotes, that name projects in a build script:
+// `rootProject.name = "..."`, `include(":a", ":b")` or `include ':a', ':b'`, project dependencies
+// like `project(":a")` and the archive base names. Comments and every other string are left out.
+pub fn references(text: &str) -> Vec<Range<usize>> {
+    let spans = syntax::spans(text, Language::CLike);
+    let token = |index: usize| &text[spans[index].range.clone()];
+    let mut ranges = vec![];
+    let mut statement = false;
+    let mut depth = 0usize;
+    // The last character of the statement that isn't whitespace, a "," continues it on the next line
+    let mut last = ' ';
+    for (index, span) in spans.iter().enumerate() {
+        match span.node {
+            Node::Identifier if !statement => {
+                let name = token(index);
+                statement = match name {
+                    "project" => spans.get(index + 1).is_some_and(|_| token(index + 1).starts_with('(')),
+                    "name" => index >= 2 && token(index - 1) == "." && token(index - 2) == "rootProject",
+                    _ => STATEMENTS.contains(&name),
+                };
+                depth = 0;
+                last = ' ';
+            }
+            Node::String if statement => {
+                let literal = token(index);
+                if literal.len() >= 2 {
+                    ranges.push(span.range.start + 1..span.range.end - 1);
+                }
+                last = '"';
+            }
+            Node::Other if statement => {
+                for c in token(index).chars() {
+                    match c {
+                        '(' | '[' => depth += 1,
+                        ')' | ']' => depth = depth.saturating_sub(1),
+                        _ => {}
+                    }
+                    let ends = match c {
+                        ')' | '}' | ';' => depth == 0,
+                        '\n' => depth == 0 && last != ',',
+                        _ => false,
+                    };
+                    if ends {
+                        statement = false;
+                        break;
+                    }
+                    if !c.is_whitespace() {
+                        last = c;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_references() {
+        let settings = "// my-app\nrootProject.name = 'my-app'\ninclude ':my-app-core',\n        ':my-app-cli'\nprintln \"my-app\"\n";
+        let found = references(settings).into_iter().map(|range| &settings[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-app", ":my-app-core", ":my-app-cli"]);
+
+        let build = "description = \"my-app\"\ndependencies {\n    implementation(project(\":my-app-core\"))\n    \
+                     implementation(\"org.my-app:lib:1.0\")\n}\nbase { archivesName.set(\"my-app\") }\nproject.version = \"my-app\"\n";
+        let found = references(build).into_iter().map(|range| &build[range]).collect::<Vec<_>>();
+        assert_eq!(found, [":my-app-core", "my-app"]);
+        assert!(is_script(Path::new("app/build.gradle.kts")));
+        assert!(is_script(Path::new("settings.gradle")));
+        assert!(!is_script(Path::new("gradle.properties")));
+    }
+}
@@ -0,0 +1,87 @@
+use std::ops::Range;
+
+use crate::structured::Located;
+
+// The values of an INI file like platformio.ini with the section and key leading to them, e.g.
+// ["env:my-app", "board"], in the order they occur. The name of a section header is a value of its
+// own with the path [section], so it can be renamed like the others. The lines of a value continued
+// on indented lines, like the build_flags of PlatformIO, are values of the same key. Comments
+// starting with ";" or "#" on their own line or with "; " after a value are left out, and so are
+// the spaces around the values.
+pub fn values(text: &str) -> Vec<Located> {
+    let mut values = vec![];
+    let mut section: Option<String> = None;
+    // The path of the last key, whose value continues on indented lines
+    let mut continued: Option<Vec<String>> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+        let trimmed = content.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with([';', '#']) {
+            continue;
+        }
+        let indent = content.len() - trimmed.len();
+        if indent > 0
+            && let Some(path) = &continued
+        {
+            values.push((path.clone(), value(start + indent, trimmed)));
+        } else if let Some(header) = trimmed.strip_prefix('[').and_then(|header| header.split_once(']')).map(|(name, _)| name) {
+            let name_start = start + indent + 1 + (header.len() - header.trim_start().len());
+            let name = header.trim();
+            values.push((vec![name.to_string()], name_start..name_start + name.len()));
+            section = Some(name.to_string());
+            continued = None;
+        } else if let Some(separator) = trimmed.find(['=', ':']) {
+            let key = trimmed[..separator].trim();
+            let path = section.iter().cloned().chain(std::iter::once(key.to_string())).collect::<Vec<_>>();
+            let rest = &trimmed[separator + 1..];
+            let value_start = start + indent + separator + 1 + (rest.len() - rest.trim_start().len());
+            let range = value(value_start, rest.trim_start());
+            if !range.is_empty() {
+                values.push((path.clone(), range));
+            }
+            continued = Some(path);
+        }
+    }
+    values
+}
+
+// The range of a value starting at the offset, without an inline comment and trailing spaces
+fn value(start: usize, value: &str) -> Range<usize> {
+    let value = value.trim_end_matches(['\n', '\r']);
+    let value = &value[..value.find(" ;").or_else(|| value.find("\t;")).unwrap_or(value.len())];
+    start..start + value.trim_end().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values() {
+        let text = "; my-app\n\
+                    [platformio]\n\
+                    default_envs = my-app\n\n\
+                    [env:my-app]\n\
+                    platform = ststm32 ; the my-app board\n\
+                    board_build.ldscript = my_app.ld\n\
+                    build_flags =\n    -D MY_APP\n    # -D MY_APP_DEBUG\n    -D NAME=my-app\n\
+                    [env:my-app-debug]\n\
+                    extends = env:my-app\n";
+        let found = values(text).into_iter().map(|(path, range)| (path.join("."), &text[range])).collect::<Vec<_>>();
+        let expected = [
+            ("platformio", "platformio"),
+            ("platformio.default_envs", "my-app"),
+            ("env:my-app", "env:my-app"),
+            ("env:my-app.platform", "ststm32"),
+            ("env:my-app.board_build.ldscript", "my_app.ld"),
+            ("env:my-app.build_flags", "-D MY_APP"),
+            ("env:my-app.build_flags", "-D NAME=my-app"),
+            ("env:my-app-debug", "env:my-app-debug"),
+            ("env:my-app-debug.extends", "env:my-app"),
+        ];
+        assert_eq!(found, expected.map(|(path, value)| (path.to_string(), value)));
+    }
+}
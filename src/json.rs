@@ -0,0 +1,401 @@
+use std::fmt;
+
+use crate::structured::Located;
+
+// A parsed JSON value. Objects keep the order of their keys.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    // Value of a key if this is an object
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Self {
+        Value::Number(n as f64)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+// Compact serialization of the value
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Number(n) if n.is_finite() && n.fract() == 0.0 && n.abs() < 1e15 => write!(f, "{}", *n as i64),
+            Value::Number(n) if n.is_finite() => write!(f, "{n}"),
+            Value::Number(_) => write!(f, "null"),
+            Value::String(s) => write!(f, "{}", string(s)),
+            Value::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{value}", string(key))?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+// Quote and escape a string for JSON
+pub fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// Error while parsing JSON, with the byte offset where it occurred
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub offset: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub fn parse(input: &str) -> Result<Value, ParseError> {
+    let mut parser = Parser { input: input.as_bytes(), pos: 0, strings: None, keys: vec![] };
+    parser.document()
+}
+
+// The string values of a JSON document with the keys and array indexes leading to them, and the
+// range of their text between the quotes, escapes included, in the order they occur
+pub fn string_values(input: &str) -> Result<Vec<Located>, ParseError> {
+    let mut parser = Parser { input: input.as_bytes(), pos: 0, strings: Some((vec![], vec![])), keys: vec![] };
+    parser.document()?;
+    Ok(parser.strings.map(|(_, strings)| strings).unwrap_or_default())
+}
+
+// The keys of the objects of a JSON document with the path leading to them including the key
+// itself, e.g. ["nodes", "nixpkgs"], and the range of their text between the quotes, in the order
+// they occur
+pub fn keys(input: &str) -> Result<Vec<Located>, ParseError> {
+    let mut parser = Parser { input: input.as_bytes(), pos: 0, strings: Some((vec![], vec![])), keys: vec![] };
+    parser.document()?;
+    Ok(parser.keys)
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+    // The path to the current value and the string values found so far, when they are collected
+    strings: Option<(Vec<String>, Vec<Located>)>,
+    // The keys found so far, while the string values are collected
+    keys: Vec<Located>,
+}
+
+impl Parser<'_> {
+    fn document(&mut self) -> Result<Value, ParseError> {
+        let value = self.value()?;
+        self.whitespace();
+        if self.pos != self.input.len() {
+            return Err(self.error("Unexpected trailing characters"));
+        }
+        Ok(value)
+    }
+
+    // Enter the value of a key or array index while the string values are collected
+    fn enter(&mut self, segment: impl FnOnce() -> String) {
+        if let Some((path, _)) = &mut self.strings {
+            path.push(segment());
+        }
+    }
+
+    fn leave(&mut self) {
+        if let Some((path, _)) = &mut self.strings {
+            path.pop();
+        }
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            message: message.to_string(),
+            offset: self.pos,
+        }
+    }
+
+    fn whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.input.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), ParseError> {
+        if self.input[self.pos..].starts_with(literal.as_bytes()) {
+            self.pos += literal.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{literal}'")))
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        self.whitespace();
+        match self.input.get(self.pos) {
+            Some(b'n') => self.expect("null").map(|_| Value::Null),
+            Some(b't') => self.expect("true").map(|_| Value::Bool(true)),
+            Some(b'f') => self.expect("false").map(|_| Value::Bool(false)),
+            Some(b'"') => {
+                let start = self.pos;
+                let string = self.string()?;
+                if let Some((path, strings)) = &mut self.strings {
+                    strings.push((path.clone(), start + 1..self.pos - 1));
+                }
+                Ok(Value::String(string))
+            }
+            Some(b'[') => self.array(),
+            Some(b'{') => self.object(),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("Unexpected character")),
+            None => Err(self.error("Unexpected end of input")),
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, ParseError> {
+        self.pos += 1;
+        let mut items = vec![];
+        self.whitespace();
+        if self.input.get(self.pos) == Some(&b']') {
+            self.pos += 1;
+            return Ok(Value::Array(items));
+        }
+        loop {
+            let index = items.len();
+            self.enter(|| index.to_string());
+            items.push(self.value()?);
+            self.leave();
+            self.whitespace();
+            match self.input.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error("Expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, ParseError> {
+        self.pos += 1;
+        let mut entries = vec![];
+        self.whitespace();
+        if self.input.get(self.pos) == Some(&b'}') {
+            self.pos += 1;
+            return Ok(Value::Object(entries));
+        }
+        loop {
+            self.whitespace();
+            if self.input.get(self.pos) != Some(&b'"') {
+                return Err(self.error("Expected a string key"));
+            }
+            let start = self.pos;
+            let key = self.string()?;
+            let end = self.pos;
+            self.whitespace();
+            self.expect(":")?;
+            self.enter(|| key.clone());
+            if let Some((path, _)) = &self.strings {
+                self.keys.push((path.clone(), start + 1..end - 1));
+            }
+            let value = self.value()?;
+            self.leave();
+            entries.push((key, value));
+            self.whitespace();
+            match self.input.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Value::Object(entries));
+                }
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, ParseError> {
+        let start = self.pos;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.input.get(self.pos) {
+            self.pos += 1;
+        }
+        let text = std::str::from_utf8(&self.input[start..self.pos]).unwrap();
+        text.parse().map(Value::Number).map_err(|_| ParseError {
+            message: "Invalid number".to_string(),
+            offset: start,
+        })
+    }
+
+    fn hex4(&mut self) -> Result<u32, ParseError> {
+        let hex = self.input.get(self.pos..self.pos + 4).ok_or_else(|| self.error("Invalid unicode escape"))?;
+        let hex = std::str::from_utf8(hex).map_err(|_| self.error("Invalid unicode escape"))?;
+        let code = u32::from_str_radix(hex, 16).map_err(|_| self.error("Invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(&b) = self.input.get(self.pos) {
+                if b == b'"' || b == b'\\' {
+                    break;
+                }
+                self.pos += 1;
+            }
+            // The input is a str and the loop only stops at ASCII characters, so this is valid UTF-8
+            out.push_str(std::str::from_utf8(&self.input[start..self.pos]).unwrap());
+            match self.input.get(self.pos) {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let escape = *self.input.get(self.pos).ok_or_else(|| self.error("Unterminated string"))?;
+                    self.pos += 1;
+                    match escape {
+                        b'"' => out.push('"'),
+                        b'\\' => out.push('\\'),
+                        b'/' => out.push('/'),
+                        b'b' => out.push('\u{8}'),
+                        b'f' => out.push('\u{c}'),
+                        b'n' => out.push('\n'),
+                        b'r' => out.push('\r'),
+                        b't' => out.push('\t'),
+                        b'u' => {
+                            let mut code = self.hex4()?;
+                            // Combine surrogate pairs
+                            if (0xD800..0xDC00).contains(&code) && self.input[self.pos..].starts_with(b"\\u") {
+                                self.pos += 2;
+                                let low = self.hex4()?;
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                            }
+                            out.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                        }
+                        _ => return Err(self.error("Invalid escape")),
+                    }
+                }
+                _ => return Err(self.error("Unterminated string")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let value = parse(r#" {"name": "my-project", "tags": [1, 2.5, -3e2, true, null], "nested": {"a": "é😀\n"}} "#).unwrap();
+        assert_eq!(value.get("name").and_then(Value::as_str), Some("my-project"));
+        assert_eq!(
+            value.get("tags"),
+            Some(&Value::Array(vec![
+                Value::Number(1.0),
+                Value::Number(2.5),
+                Value::Number(-300.0),
+                Value::Bool(true),
+                Value::Null
+            ]))
+        );
+        assert_eq!(value.get("nested").and_then(|v| v.get("a")).and_then(Value::as_str), Some("é😀\n"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse("{\"a\" 1}").unwrap_err().offset, 5);
+        assert!(parse("[1, 2").is_err());
+        assert!(parse("\"unterminated").is_err());
+        assert!(parse("{} {}").is_err());
+    }
+
+    #[test]
+    fn test_string_values() {
+        let input = r#"{"name": "my-project", "keywords": ["a", 1, "b\"c"], "repository": {"url": "x"}}"#;
+        let values = string_values(input).unwrap();
+        let values = values.iter().map(|(path, range)| (path.join("/"), &input[range.clone()])).collect::<Vec<_>>();
+        let values = values.iter().map(|(path, value)| (path.as_str(), *value)).collect::<Vec<_>>();
+        assert_eq!(values, [("name", "my-project"), ("keywords/0", "a"), ("keywords/2", "b\\\"c"), ("repository/url", "x")]);
+        let keys = keys(input).unwrap().into_iter().map(|(path, range)| (path.join("/"), &input[range])).collect::<Vec<_>>();
+        let keys = keys.iter().map(|(path, key)| (path.as_str(), *key)).collect::<Vec<_>>();
+        assert_eq!(keys, [("name", "name"), ("keywords", "keywords"), ("repository", "repository"), ("repository/url", "url")]);
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        let input = r#"{"b":[1,2.5,"x\"y"],"a":{"c":null,"d":false}}"#;
+        assert_eq!(parse(input).unwrap().to_string(), input);
+    }
+}
@@ -0,0 +1,56 @@
+use std::ops::Range;
+use std::path::Path;
+
+use crate::yaml;
+
+// The labels that name the application a resource belongs to, the ones selectors match on
+const APP_LABELS: [&str; 4] = ["app", "app.kubernetes.io/name", "app.kubernetes.io/instance", "app.kubernetes.io/part-of"];
+
+// The keys of label maps: the labels of resources and pod templates, the selector of a Service and
+// the matchLabels of the other selectors
+const LABEL_MAPS: [&str; 3] = ["labels", "selector", "matchLabels"];
+
+// The ends of the paths referring to other resources by name: the backends of Ingresses, the
+// governing Service of a StatefulSet, the target of an autoscaler and the service account of pods
+const REFERENCES: [&[&str]; 4] = [&["service", "name"], &["serviceName"], &["scaleTargetRef", "name"], &["serviceAccountName"]];
+
+// The ranges of the names in the documents of a Kubernetes manifest, or None if the file isn't one,
+// a YAML file without the apiVersion and kind of a resource: the metadata.name of the resources,
+// the values of the app labels wherever labels are set or selected, and the names of the Services,
+// workloads and service accounts referred to. The images, the namespaces and everything else are
+// left out, so the renamed resources keep selecting each other.
+pub fn names(path: &Path, text: &str) -> Option<Vec<Range<usize>>> {
+    if !path.extension().is_some_and(|extension| extension == "yaml" || extension == "yml") {
+        return None;
+    }
+    let values = yaml::values(text);
+    let root = |key: &str| values.iter().any(|(path, _)| path.len() == 1 && path[0] == key);
+    if !root("apiVersion") || !root("kind") {
+        return None;
+    }
+    let is_name = |path: &[String]| {
+        let label = path.len() >= 2 && APP_LABELS.contains(&path[path.len() - 1].as_str()) && LABEL_MAPS.contains(&path[path.len() - 2].as_str());
+        let reference = REFERENCES.iter().any(|end| path.len() >= end.len() && path[path.len() - end.len()..].iter().zip(end.iter()).all(|(a, b)| a == b));
+        path == ["metadata", "name"] || label || reference
+    };
+    Some(values.into_iter().filter(|(path, _)| is_name(path)).map(|(_, range)| range).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        let text = "apiVersion: apps/v1\nkind: Deployment\nmetadata:\n  name: my-app\n  namespace: my-app\n  labels:\n    \
+                    app.kubernetes.io/name: my-app\n    tier: my-app\nspec:\n  selector:\n    matchLabels:\n      app: my-app\n  \
+                    template:\n    metadata:\n      labels:\n        app: my-app\n    spec:\n      serviceAccountName: my-app\n      \
+                    containers:\n        - name: my-app\n          image: acme/my-app:1.0\n---\n\
+                    apiVersion: networking.k8s.io/v1\nkind: Ingress\nmetadata:\n  name: my-app\nspec:\n  rules:\n    - http:\n        \
+                    paths:\n          - path: /\n            backend:\n              service:\n                name: my-app\n";
+        let found = names(Path::new("k8s/app.yaml"), text).unwrap().into_iter().map(|range| &text[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-app"; 7]);
+        assert_eq!(names(Path::new("ci.yml"), "name: my-app\non: push\n"), None);
+        assert_eq!(names(Path::new("app.json"), text), None);
+    }
+}
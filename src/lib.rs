@@ -0,0 +1,3868 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use clap::{CommandFactory, Parser, Subcommand};
+
+use completions::Shell;
+use output::{ColorChoice, Event, LogFormat, Output, Verbosity};
+use ecosystem::{Ecosystem, Ecosystems};
+use checkpoint::Checkpoint;
+use lock::RunLock;
+use plugin::Plugin;
+use transfer::Reflink;
+use watch::Change;
+
+mod archive;
+mod arduino;
+mod backup;
+mod bazel;
+mod checkpoint;
+mod completions;
+mod composer;
+mod config;
+mod copyright;
+mod cpp_packages;
+mod debian;
+mod deflate;
+mod docker;
+mod doctor;
+mod ecosystem;
+mod electron;
+mod flatpak;
+mod forge;
+pub mod fs;
+mod generated;
+mod git;
+mod gitattributes;
+mod glob;
+mod gradle;
+mod ini;
+mod json;
+mod kubernetes;
+mod lock;
+mod makefile;
+mod maven;
+mod md5;
+mod meson;
+mod mime;
+mod nix;
+mod npm;
+mod output;
+mod patch;
+mod placeholder;
+mod plan;
+mod plugin;
+mod react_native;
+mod remote;
+pub mod renamer;
+pub mod replace;
+mod report;
+mod regex;
+mod reserved;
+mod rpm;
+mod sarif;
+mod secret;
+mod segment;
+mod serve;
+mod sha256;
+mod solution;
+mod structured;
+mod syntax;
+mod systemd;
+mod tar;
+mod tauri;
+mod toml;
+mod transfer;
+mod trash;
+mod uuid;
+mod verify;
+mod vscode;
+mod watch;
+mod wordpress;
+mod workspace;
+mod xml;
+mod yaml;
+
+pub const SEPARATORS : [char; 5] = [' ', '_', '-', '.', '/'];
+
+// Exit status of a run that had nothing to do, because the project already has the new name or the
+// output is already up to date
+const EXIT_NOTHING_TO_DO: i32 = 3;
+
+// Message of the commit of --git-commit without a template
+const DEFAULT_COMMIT_MESSAGE: &str = "Rename {{old_name}} to {{new_name}}\n\n{{summary}}";
+
+// Directories of third party code checked into a project, which is copied unchanged like --exclude
+// unless --include-vendored is given. Matched like exclude patterns.
+const VENDORED_DIRECTORIES: [&str; 10] = [
+    "vendor",
+    "third_party",
+    "third-party",
+    "node_modules",
+    "bower_components",
+    "Pods",
+    "Carthage/Checkouts",
+    ".yarn/cache",
+    ".yarn/unplugged",
+    ".bundle",
+];
+
+/// This program renames a project directory and all occurrences of the project name in the files
+/// and directories. It also renames the files and directories to match the new project name.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    args: Option<Args>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print a shell completion script.
+    /// Example: "project-renamer completions bash > /etc/bash_completion.d/project-renamer"
+    Completions {
+        /// Shell to generate the completion script for.
+        shell: Shell,
+    },
+    /// Serve a JSON-RPC 2.0 API over HTTP with the methods "plan", "apply" and "verify".
+    /// Requests must have Content-Type: application/json, and requests from browsers are refused.
+    /// The API has no authentication, so it only listens on loopback addresses.
+    /// Example: "project-renamer serve --listen 127.0.0.1:7070"
+    Serve {
+        /// Address to listen on, a loopback address like 127.0.0.1 or [::1].
+        #[arg(long, default_value = "127.0.0.1:7070")]
+        listen: String,
+        /// Only print errors.
+        #[arg(short, long, conflicts_with = "verbose")]
+        quiet: bool,
+        /// Print every request.
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+        /// Format of the printed output, "json" prints one structured event per line.
+        #[arg(long, value_enum, default_value_t = LogFormat::Pretty)]
+        log_format: LogFormat,
+    },
+    /// Clone a git repository, rename it, commit the rename and optionally push it to a new remote.
+    /// The original remote is kept as "upstream".
+    /// Example: "project-renamer remote --from https://github.com/org/old-service.git --name new-service"
+    Remote {
+        /// URL of the repository to clone.
+        #[arg(long, value_name = "URL")]
+        from: String,
+        /// New name of the project.
+        #[arg(short, long)]
+        name: String,
+        /// URL of the new remote "origin" the renamed repository is pushed to.
+        #[arg(long, value_name = "URL")]
+        push_to: Option<String>,
+        /// Directory the repository is cloned into.
+        #[arg(long, default_value = ".", value_hint = clap::ValueHint::DirPath)]
+        directory: PathBuf,
+        /// Only print errors.
+        #[arg(short, long, conflicts_with = "verbose")]
+        quiet: bool,
+        /// Print every file action, repeat (-vv) to also print every substitution.
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+    },
+    /// List the files and directories whose name or content still contains any case variant of a
+    /// name, and exit with status 1 if there are any. Useful as a CI check after a rename.
+    /// Example: "project-renamer verify --name old-project path/to/new-project"
+    Verify {
+        /// Name that must not occur anymore, usually the old name of the project.
+        #[arg(short, long)]
+        name: String,
+        /// Project directory or file to scan.
+        #[arg(value_hint = clap::ValueHint::AnyPath)]
+        path: PathBuf,
+        /// Also write the occurrences as a SARIF report, e.g. for GitHub code scanning.
+        #[arg(long, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+        sarif: Option<PathBuf>,
+        /// Only print errors.
+        #[arg(short, long)]
+        quiet: bool,
+        /// Format of the printed output, "json" prints one structured event per line.
+        #[arg(long, value_enum, default_value_t = LogFormat::Pretty)]
+        log_format: LogFormat,
+    },
+    /// Execute exactly the operations of a plan written with "--dry-run --report json", refusing to
+    /// run if any file of the project changed since the plan was made.
+    /// Example: "project-renamer apply plan.json"
+    Apply {
+        /// Plan file to execute.
+        #[arg(value_hint = clap::ValueHint::FilePath)]
+        plan: PathBuf,
+        /// Only print errors.
+        #[arg(short, long, conflicts_with = "verbose")]
+        quiet: bool,
+        /// Print every file action, repeat (-vv) to also print every substitution.
+        #[arg(short, long, action = clap::ArgAction::Count)]
+        verbose: u8,
+        /// Format of the printed output, "json" prints one structured event per line.
+        #[arg(long, value_enum, default_value_t = LogFormat::Pretty)]
+        log_format: LogFormat,
+    },
+    /// Report what a rename may get wrong without changing anything: the old name in case styles
+    /// that aren't renamed or inside longer words, in binary files, and new names that collide.
+    /// Example: "project-renamer doctor --input path/to/old-project --name new-project"
+    Doctor {
+        /// New name of the project.
+        #[arg(short, long)]
+        name: String,
+        /// Input path to the project.
+        #[arg(short, long, value_hint = clap::ValueHint::AnyPath)]
+        input: PathBuf,
+        /// Also write the findings as a SARIF report, e.g. for GitHub code scanning.
+        #[arg(long, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+        sarif: Option<PathBuf>,
+        /// Only print errors.
+        #[arg(short, long)]
+        quiet: bool,
+        /// Format of the printed output, "json" prints one structured event per line.
+        #[arg(long, value_enum, default_value_t = LogFormat::Pretty)]
+        log_format: LogFormat,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+#[command(group(clap::ArgGroup::new("in_place").args(["move_input", "keep_root"]).multiple(true)))]
+struct Args {
+    /// New name of the project. "${VAR}" is replaced with the environment variable VAR, "{{date}}"
+    /// with the current UTC date and "{{user}}" with the current user. A scoped npm package name
+    /// like "@acme/new-project" names the project "new-project" and moves the package to the scope.
+    /// Example: "new-project"
+    // clap leaves the group of Args empty because of the flattened ecosystems, and the group tells
+    // a rename from a subcommand, so the name every rename has joins it
+    #[arg(short, long, group = "Args")]
+    name: String,
+    /// Input path to the project.
+    /// Example: "path/to/old-project"
+    #[arg(short, long, value_hint = clap::ValueHint::DirPath)]
+    input: PathBuf,
+    /// Path the renamed project is written to, next to the input with the new name by default.
+    /// Example: "/mnt/projects/new-project"
+    #[arg(short, long, value_hint = clap::ValueHint::AnyPath, conflicts_with = "keep_root")]
+    output: Option<PathBuf>,
+    /// Exit with a non-zero status if no file or directory was renamed and no content was
+    /// substituted, also when the run has nothing to do. Useful in scripts to detect that the old
+    /// name was detected incorrectly.
+    #[arg(long)]
+    fail_if_no_matches: bool,
+    /// Only print errors.
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Print every file action, repeat (-vv) to also print every substitution.
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+    /// Format of the printed output, "json" prints one structured event per line.
+    #[arg(long, value_enum, default_value_t = LogFormat::Pretty)]
+    log_format: LogFormat,
+    /// When to color the output. "auto" colors when printing to a terminal and NO_COLOR is not set.
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+    /// After renaming, keep watching the input and mirror every change into the output with the
+    /// same transformations applied. Runs until interrupted.
+    #[arg(long)]
+    watch: bool,
+    /// Plugin command that can add replacements or veto changes for every text file, can be
+    /// repeated. Plugins are executables run as subprocesses, not WASM modules or shared libraries:
+    /// the plugin reads one JSON request per line on stdin and answers each on stdout within 30
+    /// seconds.
+    /// Example: "python3 house-rules.py"
+    #[arg(long, value_name = "COMMAND")]
+    plugin: Vec<String>,
+    /// Words that keep their canonical capitalization in capitalised case variants, can be
+    /// repeated or comma separated.
+    /// Example: "AWS,SDK" renames "aws-sdk-tool" to "AWSSDKTool" instead of "AwsSdkTool"
+    #[arg(long, value_name = "WORD", value_delimiter = ',')]
+    acronym: Vec<String>,
+    /// Parts of an old name without separators, comma separated. By default they are detected
+    /// from how the name is written in the project.
+    /// Example: "my,project" for a project directory named "myproject"
+    #[arg(long, value_name = "PARTS", value_delimiter = ',')]
+    old_parts: Vec<String>,
+    /// Escape renamed files and directories whose names are unusable on Windows, like "CON" or
+    /// "aux.rs", by appending an underscore. By default the rename is aborted before any file is
+    /// written.
+    #[arg(long)]
+    escape_reserved_names: bool,
+    /// Directory where the output is staged before it is moved into place, next to the output by
+    /// default. It may be on another filesystem, in which case the output is copied instead.
+    #[arg(long, value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+    temp_dir: Option<PathBuf>,
+    /// Clone files without content changes with a reflink (copy-on-write) instead of copying
+    /// them. Supported on Btrfs, XFS and APFS, "auto" copies when cloning isn't possible.
+    #[arg(long, value_enum, default_value_t = Reflink::Auto)]
+    reflink: Reflink,
+    /// Continue a failed or interrupted run, keeping the files it already wrote instead of
+    /// starting over.
+    #[arg(long)]
+    resume: bool,
+    /// Remove the original project once it was renamed successfully, so it is renamed in place.
+    #[arg(long = "move", conflicts_with = "watch")]
+    move_input: bool,
+    /// Move the original project to the trash instead of deleting it permanently.
+    #[arg(long, requires = "in_place")]
+    trash: bool,
+    /// With --move or --keep-root, write a tar archive of the original project named
+    /// "<old-name>-backup.tar" next to it before renaming. Paths ignored by git are left out. The
+    /// archive is streamed to disk uncompressed, so a large project isn't held in memory.
+    #[arg(long, requires = "in_place")]
+    backup: bool,
+    /// Also rename the paths and contents of the members of zip, tar and tar.gz archives in the
+    /// project, and repack them.
+    #[arg(long)]
+    archives: bool,
+    /// The input is a member of a Cargo, npm or pnpm workspace, a Maven reactor or a Gradle build:
+    /// also update the dependency declarations, member lists and TypeScript and Jest path aliases
+    /// in the rest of the workspace that reference it.
+    #[arg(long)]
+    workspace: bool,
+    /// Directory containing the input, e.g. the repository root, whose other files also get the
+    /// old name replaced in their content, so references to the renamed project elsewhere in the
+    /// repository are updated in the same run.
+    /// Example: "--input repo/packages/old-lib --scope repo"
+    #[arg(long, value_name = "DIR", value_hint = clap::ValueHint::DirPath, conflicts_with = "workspace")]
+    scope: Option<PathBuf>,
+    /// Glob pattern of files and directories relative to the input that are copied unchanged,
+    /// without renaming them or their content, can be repeated. A pattern without a "/" matches
+    /// any file or directory with that name.
+    /// Example: "tests/fixtures/**" or "*.lock"
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+    /// Also rename the vendored third party code in directories like "vendor", "third_party",
+    /// "node_modules", "Pods" or ".yarn/cache", which is copied unchanged by default.
+    #[arg(long)]
+    include_vendored: bool,
+    /// Only rename and copy the files git tracks, so ignored build output and local scratch files
+    /// are left out of the renamed project. The input must be in a git work tree.
+    #[arg(long)]
+    tracked_only: bool,
+    /// Stage the rename in the git index once it is done in place, so git shows the renamed files
+    /// as renames ready to be committed.
+    #[arg(long, requires = "in_place", conflicts_with = "dry_run")]
+    git_add: bool,
+    /// Create a branch and switch to it before renaming in place, so the rename lands on a fresh
+    /// branch. "{{old_name}}" and "{{new_name}}" are replaced with the names.
+    /// Example: "rename/{{new_name}}"
+    #[arg(long, value_name = "BRANCH", requires = "in_place", conflicts_with = "dry_run")]
+    git_branch: Option<String>,
+    /// Stage and commit the in-place rename with a message from this template. "{{old_name}}",
+    /// "{{new_name}}", "{{renamed}}", "{{substitutions}}" and "{{summary}}" are replaced, and so
+    /// are the placeholders of the name. Without a template the message is "Rename <old> to
+    /// <new>" with a summary of the changes.
+    /// Example: "chore: rename {{old_name}} to {{new_name}}"
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        num_args = 0..=1,
+        default_missing_value = DEFAULT_COMMIT_MESSAGE,
+        requires = "in_place",
+        conflicts_with = "dry_run"
+    )]
+    git_commit: Option<String>,
+    /// Push the branch of --git-branch to "origin" after committing and open a pull request for it
+    /// with the rename report as the description. The forge is told from the URL of "origin",
+    /// GitHub with a token in GITHUB_TOKEN or GH_TOKEN, GitLab with one in GITLAB_TOKEN.
+    #[arg(long, requires_all = ["git_branch", "git_commit"])]
+    open_pr: bool,
+    /// JSON file with replacement rules for the files matching glob patterns, ".project-renamer.json"
+    /// in the input directory by default. See the README for the format.
+    #[arg(long, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    config: Option<PathBuf>,
+    /// Kinds of tokens the name is replaced in, for source code in a recognized language, e.g.
+    /// "code" for identifiers only or "strings,comments" for the user-facing text only. Other
+    /// files are replaced in as a whole. Rules in the config file take precedence.
+    #[arg(long, value_enum, value_name = "TOKENS", value_delimiter = ',', conflicts_with = "names_only")]
+    replace_in: Vec<syntax::ReplaceIn>,
+    /// Regular expression replaced in the content of every text file along with the old name, as
+    /// "pattern=>replacement" with "$1" for the groups, can be repeated. The replacement can contain
+    /// the placeholders of --name.
+    /// Example: "old\.example\.com/v(\d+)=>new.example.com/v$1"
+    #[arg(long, value_name = "RULE", value_parser = regex::parse_rule, conflicts_with = "names_only")]
+    replace_regex: Vec<(regex::Regex, String)>,
+    /// Old name of the organization or namespace the project belongs to, e.g. "acme" in
+    /// "github.com/acme/project", "@acme/project" or "com.acme.project". Its case variants are
+    /// replaced with the ones of --new-org in the same pass as the project name.
+    #[arg(long, value_name = "NAME", requires = "new_org", conflicts_with = "names_only")]
+    old_org: Option<String>,
+    /// New name of the organization, see --old-org.
+    #[arg(long, value_name = "NAME", requires = "old_org")]
+    new_org: Option<String>,
+    /// Old domain of the company, replaced in email addresses, URLs and subdomains in the content of
+    /// every text file, e.g. "acme.com" in "maintainers@acme.com" or "https://api.acme.com".
+    #[arg(long, value_name = "DOMAIN", value_parser = parse_domain, requires = "new_domain", conflicts_with = "names_only")]
+    old_domain: Option<String>,
+    /// New domain, see --old-domain.
+    #[arg(long, value_name = "DOMAIN", value_parser = parse_domain, requires = "old_domain")]
+    new_domain: Option<String>,
+    /// Copyright holder to update in the copyright lines of license headers (SPDX, Apache and MIT
+    /// style) and license files, as "OLD=NEW". Copyright lines of other holders are left alone.
+    /// Example: "Acme Inc.=Globex LLC"
+    #[arg(long, value_name = "OLD=NEW", value_parser = parse_also, conflicts_with = "names_only")]
+    copyright_holder: Option<(String, String)>,
+    /// Also extend the year range of the updated copyright lines to the current year, e.g.
+    /// "2019-2023" or "2019" to "2019-2025".
+    #[arg(long, requires = "copyright_holder")]
+    copyright_years: bool,
+    /// Glob pattern of files whose UUIDs are replaced with fresh ones, e.g. Visual Studio solutions
+    /// and projects or installer manifests that need GUIDs unique to the project. Every distinct
+    /// UUID gets the same fresh one in all matching files. Can be repeated.
+    /// Example: "*.sln" or "*.csproj"
+    #[arg(long, value_name = "GLOB", conflicts_with = "names_only")]
+    regen_uuids: Vec<String>,
+    /// Give the projects of Visual Studio solutions fresh GUIDs, so a fork can be loaded next to
+    /// the original. The GUIDs identifying projects are replaced consistently in the solutions and
+    /// in the project references of the projects, while the GUIDs of the project types stay.
+    #[arg(long, conflicts_with = "names_only")]
+    regen_vs_guids: bool,
+    /// Extra exact text replaced in file names and contents in the same pass as the old name, e.g.
+    /// an old codename or a ticket prefix, can be repeated. NEW can contain the placeholders of
+    /// --name.
+    /// Example: "--also ACME-=NOVA-"
+    #[arg(long, value_name = "OLD=NEW", value_parser = parse_also, conflicts_with = "names_only")]
+    also: Vec<(String, String)>,
+    /// Only replace the old name where it isn't part of a longer identifier, i.e. not preceded or
+    /// followed by a letter, digit or underscore, so "core" isn't replaced inside "score_counter".
+    #[arg(long)]
+    identifier_boundaries: bool,
+    // The flags of the ecosystems whose naming conventions are followed, like --maven
+    #[command(flatten)]
+    ecosystems: Ecosystems,
+    /// Also change the content of files that may hold secrets or key material, like ".env",
+    /// "*.pem", "id_rsa" or keystores. Without it they are renamed but their content is copied
+    /// unchanged, with a warning.
+    #[arg(long)]
+    rewrite_secrets: bool,
+    /// Extensions of files whose content is renamed as text whatever it looks like, can be
+    /// repeated or comma separated.
+    /// Example: "dat,tpl"
+    #[arg(long, value_name = "EXT", value_delimiter = ',', conflicts_with = "names_only")]
+    treat_as_text: Vec<String>,
+    /// Extensions of files that are copied unchanged as binary, even when their content looks like
+    /// text, can be repeated or comma separated. Takes precedence over .gitattributes.
+    /// Example: "snap,golden"
+    #[arg(long, value_name = "EXT", value_delimiter = ',', conflicts_with = "names_only")]
+    treat_as_binary: Vec<String>,
+    /// Also change the content of files marked as generated, like "@generated" or "DO NOT EDIT" in
+    /// their first lines. Without it they are renamed but their content is copied unchanged, with
+    /// a note in the report, because the next regeneration reverts the changes.
+    #[arg(long, conflicts_with = "names_only")]
+    rewrite_generated: bool,
+    /// Only rename files and directories, without opening or changing the content of any file.
+    #[arg(long, conflicts_with_all = ["archives", "workspace", "scope", "plugin"])]
+    names_only: bool,
+    /// Rename the files, directories and contents of the project but keep the name of its top-level
+    /// directory. The project is replaced by the renamed one.
+    #[arg(long, conflicts_with = "watch")]
+    keep_root: bool,
+    /// What to do with files that already exist in the output and differ from the renamed ones:
+    /// "keep" them and warn, "overwrite" them, or "fail" before anything is written.
+    #[arg(long, value_enum, default_value_t = Merge::Keep)]
+    merge: Merge,
+    /// Re-run into the output of a previous run, rewriting the files whose content is stale and
+    /// reporting them. The same as "--merge overwrite".
+    #[arg(long, conflicts_with = "merge")]
+    sync: bool,
+    /// Only plan the rename and print what would be done, without writing anything.
+    #[arg(long, conflicts_with = "watch")]
+    dry_run: bool,
+    /// Print a report of every operation of the run, e.g. "csv" for reviewing a plan in a
+    /// spreadsheet, or "json" for a plan that the "apply" command executes. Replaces the other output unless it is written to a file with --report-output.
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    report: Option<report::Format>,
+    /// File the report is written to instead of printing it.
+    #[arg(long, value_name = "FILE", value_hint = clap::ValueHint::FilePath, requires = "report")]
+    report_output: Option<PathBuf>,
+    /// Write the renames and content changes as a unified diff that "git apply" can apply, with
+    /// paths relative to the project. Combine with --dry-run to only create the patch.
+    #[arg(long, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    emit_patch: Option<PathBuf>,
+}
+
+// Counts of the changes made during a run
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Stats {
+    renamed: usize,
+    substitutions: usize,
+    // Existing output files that were rewritten because their content was stale
+    refreshed: usize,
+    // The run stopped before writing anything because it had nothing to do
+    nothing_to_do: bool,
+}
+
+/// A single text replaced in a file, e.g. a case variant of the old name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Substitution {
+    /// The text that was replaced
+    pub from: String,
+    /// The text it was replaced with
+    pub to: String,
+    /// How often it was replaced
+    pub count: usize,
+}
+
+/// What is done with a single file or directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Create a directory in the output
+    CreateDirectory,
+    /// Write the file with the old name replaced in its content
+    Write,
+    /// Copy a file that can't be read as text
+    Copy,
+    /// Leave an existing output file untouched
+    Skip,
+}
+
+impl Action {
+    /// The name of the action in reports and plans, like "create_directory"
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::CreateDirectory => "create_directory",
+            Action::Write => "write",
+            Action::Copy => "copy",
+            Action::Skip => "skip",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [Action::CreateDirectory, Action::Write, Action::Copy, Action::Skip].into_iter().find(|action| action.name() == name)
+    }
+}
+
+// Old and new name of the organization, matched like the project name
+fn org_names(old_org: &str, new_org: &str, acronyms: &[String]) -> (NormalizedName, NormalizedName) {
+    (CaseInfo::detect(old_org).1.with_acronyms(acronyms), CaseInfo::detect(new_org).1.with_acronyms(acronyms))
+}
+
+// A domain of --old-domain or --new-domain, like "example.com"
+fn parse_domain(value: &str) -> Result<String, String> {
+    let labels = value.split('.').collect::<Vec<_>>();
+    let valid = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty() && !label.starts_with('-') && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if valid { Ok(value.to_string()) } else { Err(format!("Expected a domain like \"example.com\", got \"{value}\"")) }
+}
+
+// An extra replacement of --also, "OLD=NEW"
+fn parse_also(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((old, new)) if !old.is_empty() => Ok((old.to_string(), new.to_string())),
+        _ => Err(format!("Expected \"OLD=NEW\", got \"{value}\"")),
+    }
+}
+
+// How files that already exist in the output are handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+enum Merge {
+    // Keep the existing file, warning if it differs from the renamed one
+    #[default]
+    Keep,
+    // Replace the existing file with the renamed one
+    Overwrite,
+    // Abort before anything is written if any existing file differs
+    Fail,
+}
+
+/// A single operation of a rename
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    pub action: Action,
+    /// The file or directory in the input
+    pub input: PathBuf,
+    /// Where it is written in the output
+    pub output: PathBuf,
+    /// How many replacements were made in its content
+    pub substitutions: usize,
+    /// Why the content was copied unchanged although it is text, e.g. a generated file
+    pub note: Option<String>,
+}
+
+// State shared while traversing the project
+struct Context {
+    input: PathBuf,
+    output: PathBuf,
+    old_name: NormalizedName,
+    new_name: NormalizedName,
+    out: Output,
+    stats: Stats,
+    // Only record the operations, without changing anything on disk
+    dry_run: bool,
+    operations: Vec<Operation>,
+    // Replacers of the content that run after the built-in ones: the plugins, then the library ones
+    replacers: Vec<Box<dyn replace::Replacer>>,
+    // Escape new names that are unusable on Windows instead of keeping them
+    escape_reserved_names: bool,
+    // Directory for staging the output instead of next to it
+    temp_dir: Option<PathBuf>,
+    // Where the project is read from and the output written to
+    fs: Arc<dyn fs::Filesystem>,
+    // Continue the previous run from its checkpoint
+    resume: bool,
+    checkpoint: Option<Checkpoint>,
+    // Remove the input once the output is in place, to the trash if requested
+    move_input: bool,
+    trash: bool,
+    // Archive the input before it is removed
+    backup: bool,
+    // Rename inside archives found in the project
+    archives: bool,
+    // Update the references to the renamed member in the rest of its workspace
+    workspace: bool,
+    // Update the references to the renamed project in every file of this directory
+    scope: Option<PathBuf>,
+    // Copy every file unchanged, only renaming the paths
+    names_only: bool,
+    // Glob patterns of paths that are copied without renaming them or their content
+    exclude: Vec<String>,
+    // Rename vendored directories like any other
+    include_vendored: bool,
+    // The files git tracks and their directories, the only paths that are renamed and copied
+    tracked: Option<HashSet<PathBuf>>,
+    // Replacement rules for files matching glob patterns
+    config: config::Config,
+    // The kinds of tokens of source code that are replaced in, everywhere when None
+    nodes: Option<Vec<syntax::Node>>,
+    // The ecosystems whose naming conventions are followed
+    ecosystems: Ecosystems,
+    // Old and new npm package name when the new name moves the package to another scope
+    npm_package: Option<(String, String)>,
+    // Only match the old name where it isn't part of a longer identifier
+    identifier_boundaries: bool,
+    // Change the content of files that may hold secrets
+    rewrite_secrets: bool,
+    // Whether the repository marks files as text or binary
+    gitattributes: gitattributes::Attributes,
+    // Change the content of files written by code generators
+    rewrite_generated: bool,
+    // Lower case extensions, without the dot, of files that are always text or always binary
+    treat_as_text: Vec<String>,
+    treat_as_binary: Vec<String>,
+    // Old and new name of the organization, replaced along with the old name
+    org: Option<(NormalizedName, NormalizedName)>,
+    // Old and new domain, replaced in the content
+    domain: Option<(String, String)>,
+    // Old and new copyright holder in license headers, and the year to extend their ranges to
+    copyright: Option<(String, String)>,
+    copyright_year: Option<u32>,
+    // Glob patterns of files whose UUIDs are regenerated, and the fresh UUIDs given out so far
+    regen_uuids: Vec<String>,
+    uuids: uuid::Regenerator,
+    // Regenerate the GUIDs of the projects of Visual Studio solutions, found before the traversal
+    regen_vs_guids: bool,
+    project_guids: HashSet<String>,
+    // The application IDs of Flatpak and Snapcraft manifests and of Electron, Tauri and React Native
+    // apps, found before the traversal
+    app_ids: Vec<(String, String)>,
+    // The package directories of a renamed application ID, with the number of directories of the
+    // old package and the path of the new one in the source root
+    package_dirs: HashMap<PathBuf, (usize, PathBuf)>,
+    // The text domain of a WordPress plugin or theme, the old and the new one, found before the
+    // traversal
+    text_domain: Option<(String, String)>,
+    // The IDs contributed by a VS Code extension and its configuration section, found before the
+    // traversal
+    extension_ids: Vec<(String, String)>,
+    // Exact text replaced along with the old name
+    also: Vec<(String, String)>,
+    // Regular expressions replaced in every text file, before the ones of the config
+    replace_regex: Vec<(regex::Regex, String)>,
+    // Rule for files that already exist in the output, and the ones found to differ
+    merge: Merge,
+    conflicts: Vec<PathBuf>,
+    // The files written, collected for a patch
+    patch: Option<Vec<patch::Change>>,
+    // Receives the events of the run that a library user can observe
+    observer: Option<renamer::Observer>,
+}
+
+impl Context {
+    // Detect the old name from the input path and derive the output path from the new name
+    fn new(input: &Path, name: &str, out: Output) -> Self {
+        Self::with_fs(input, name, out, Arc::new(fs::Disk::default()))
+    }
+
+    fn with_fs(input: &Path, name: &str, out: Output, fs: Arc<dyn fs::Filesystem>) -> Self {
+        let input_file_name = input.file_name().unwrap().to_string_lossy().to_string();
+        let new_name = CaseInfo::detect(name).1;
+        let is_file = fs.exists(input) && !fs.is_dir(input);
+
+        // The old name of a single file is its file name without the extension
+        let mut old_name = if is_file {
+            CaseInfo::detect(&input.file_stem().unwrap().to_string_lossy()).1
+        } else {
+            CaseInfo::detect(&input_file_name).1
+        };
+
+        // An old name without separators can only be matched as a whole, look for the parts it is
+        // made of in the project
+        if old_name.parts.len() == 1
+            && fs.is_disk()
+            && let Some(parts) = segment::segment_from_project(input, &old_name.parts[0], new_name.parts.len())
+        {
+            out.emit(Event::OldNameParts { parts: &parts.join(", ") });
+            old_name.parts = parts;
+        }
+
+        // A single file is renamed by replacing the old name in the full file name
+        let output = if is_file {
+            let (output_file_name, _) = transform_text(&input_file_name, &old_name, &new_name);
+            input.parent().unwrap().join(output_file_name)
+        } else {
+            input.parent().unwrap().join(name)
+        };
+
+        Self {
+            input: input.to_path_buf(),
+            output,
+            old_name,
+            new_name,
+            out,
+            stats: Stats::default(),
+            dry_run: false,
+            operations: vec![],
+            replacers: vec![],
+            escape_reserved_names: false,
+            temp_dir: None,
+            fs,
+            resume: false,
+            checkpoint: None,
+            move_input: false,
+            trash: false,
+            backup: false,
+            archives: false,
+            workspace: false,
+            scope: None,
+            names_only: false,
+            exclude: vec![],
+            include_vendored: false,
+            tracked: None,
+            config: config::Config::default(),
+            nodes: None,
+            ecosystems: Ecosystems::default(),
+            npm_package: None,
+            identifier_boundaries: false,
+            rewrite_secrets: false,
+            gitattributes: gitattributes::Attributes::default(),
+            rewrite_generated: false,
+            treat_as_text: vec![],
+            treat_as_binary: vec![],
+            org: None,
+            domain: None,
+            copyright: None,
+            copyright_year: None,
+            regen_uuids: vec![],
+            uuids: uuid::Regenerator::default(),
+            regen_vs_guids: false,
+            project_guids: HashSet::new(),
+            package_dirs: HashMap::new(),
+            text_domain: None,
+            extension_ids: vec![],
+            app_ids: vec![],
+            also: vec![],
+            replace_regex: vec![],
+            merge: Merge::Keep,
+            conflicts: vec![],
+            patch: None,
+            observer: None,
+        }
+    }
+
+    // Print the event and pass it on to the observer
+    fn emit(&self, event: Event) {
+        if let Some(observer) = &self.observer
+            && let Some(event) = renamer::RenameEvent::from_event(&event)
+        {
+            observer(&event);
+        }
+        self.out.emit(event);
+    }
+
+    fn error(&self, message: &str) {
+        self.emit(Event::Error { message });
+    }
+
+    // Whether the path in the input or one of its parent directories matches an exclude pattern or
+    // is a vendored directory
+    fn is_excluded(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.input) else {
+            return false;
+        };
+        let vendored = if self.include_vendored { &[][..] } else { &VENDORED_DIRECTORIES[..] };
+        let patterns = self.exclude.iter().map(String::as_str).chain(vendored.iter().copied());
+        relative
+            .ancestors()
+            .filter(|ancestor| !ancestor.as_os_str().is_empty())
+            .any(|ancestor| patterns.clone().any(|pattern| glob::matches(pattern, ancestor)))
+    }
+
+    // Whether the file is text or binary by --treat-as-text and --treat-as-binary, or None when
+    // that is up to its content. Extensions may have several parts, like "min.js".
+    fn treat_as(&self, path: &Path) -> Option<bool> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        let matches = |extensions: &[String]| extensions.iter().any(|extension| name.ends_with(&format!(".{extension}")));
+        if matches(&self.treat_as_binary) {
+            Some(false)
+        } else if matches(&self.treat_as_text) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    // Replace the case variants of the old name with the new name, matching the way the run is
+    // configured to
+    fn transform_text(&self, input: &str) -> (String, Vec<Substitution>) {
+        let cases = CaseInfo::all_cases();
+        transform_text_cases(input, &self.old_name, &self.new_name, &cases, self.identifier_boundaries, self.org.as_slice(), &self.literals())
+    }
+
+    // The exact texts replaced along with the old name, the ones of --also, the application IDs and
+    // the IDs of a VS Code extension
+    fn literals(&self) -> Vec<(String, String)> {
+        self.also.iter().chain(&self.app_ids).chain(&self.extension_ids).cloned().collect()
+    }
+
+    fn record(&mut self, action: Action, input: &Path, output: &Path, substitutions: usize) {
+        self.operations.push(Operation {
+            action,
+            input: input.to_path_buf(),
+            output: output.to_path_buf(),
+            substitutions,
+            note: None,
+        });
+    }
+
+    // Add a note to the operation that was just recorded
+    fn note(&mut self, note: &str) {
+        if let Some(operation) = self.operations.last_mut() {
+            operation.note = Some(note.to_string());
+        }
+    }
+}
+
+// Entry point of the command line program
+pub fn run_cli() {
+    let cli = Cli::parse();
+    let args = match (cli.command, cli.args) {
+        (Some(Command::Completions { shell }), _) => {
+            print!("{}", completions::generate(shell, &Cli::command()));
+            return;
+        }
+        (Some(Command::Serve { listen, quiet, verbose, log_format }), _) => {
+            let out = Output::new(Verbosity::from_flags(quiet, verbose), log_format, ColorChoice::Auto);
+            if let Err(e) = serve::serve(&listen, out) {
+                out.error(&format!("Failed to serve on {listen}: {e}"));
+                std::process::exit(1);
+            }
+            return;
+        }
+        (Some(Command::Remote { from, name, push_to, directory, quiet, verbose }), _) => {
+            let out = Output::new(Verbosity::from_flags(quiet, verbose), LogFormat::Pretty, ColorChoice::Auto);
+            if let Err(e) = remote::remote(&from, &name, push_to.as_deref(), &directory, out) {
+                out.error(&e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        (Some(Command::Verify { name, path, sarif, quiet, log_format }), _) => {
+            let out = Output::new(Verbosity::from_flags(quiet, 0), log_format, ColorChoice::Auto);
+            if !path.exists() {
+                out.error(&format!("Path does not exist: {}", path.display()));
+                std::process::exit(1);
+            }
+            let occurrences = verify::find_occurrences(&path, &CaseInfo::detect(&name).1);
+            for occurrence in &occurrences {
+                out.emit(Event::Occurrence { path: &occurrence.path, line: occurrence.line, text: &occurrence.text });
+            }
+            if let Some(sarif) = &sarif {
+                let message = format!("{name} is left");
+                let rules = [sarif::Rule { id: "leftover_name", description: "The old name of the project is left" }];
+                let results = occurrences
+                    .iter()
+                    .map(|occurrence| sarif::Result {
+                        rule_id: "leftover_name",
+                        level: "error",
+                        message: &message,
+                        path: &occurrence.path,
+                        line: occurrence.line,
+                    })
+                    .collect::<Vec<_>>();
+                write_sarif(sarif, &sarif::report(&rules, &results), out);
+            }
+            out.emit(Event::VerifySummary { name: &name, occurrences: occurrences.len(), path: &path });
+            if !occurrences.is_empty() {
+                std::process::exit(1);
+            }
+            return;
+        }
+        (Some(Command::Apply { plan, quiet, verbose, log_format }), _) => {
+            let out = Output::new(Verbosity::from_flags(quiet, verbose), log_format, ColorChoice::Auto);
+            if let Err(e) = plan::apply(&plan, out) {
+                out.error(&e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        (Some(Command::Doctor { name, input, sarif, quiet, log_format }), _) => {
+            let out = Output::new(Verbosity::from_flags(quiet, 0), log_format, ColorChoice::Auto);
+            if !input.exists() {
+                out.error(&format!("Input path does not exist: {}", input.display()));
+                std::process::exit(1);
+            }
+            let ctx = Context::new(&input, npm::split_scope(&name).1, out);
+            let findings = doctor::diagnose(&ctx);
+            for finding in &findings {
+                out.emit(Event::Finding {
+                    kind: finding.kind.name(),
+                    path: &finding.path,
+                    line: finding.line,
+                    message: &finding.message,
+                });
+            }
+            if let Some(sarif) = &sarif {
+                let rules = doctor::Kind::ALL.map(|kind| sarif::Rule { id: kind.name(), description: kind.description() });
+                let results = findings
+                    .iter()
+                    .map(|finding| sarif::Result {
+                        rule_id: finding.kind.name(),
+                        level: "warning",
+                        message: &finding.message,
+                        path: &finding.path,
+                        line: finding.line,
+                    })
+                    .collect::<Vec<_>>();
+                write_sarif(sarif, &sarif::report(&rules, &results), out);
+            }
+            out.emit(Event::DoctorSummary { findings: findings.len(), path: &input });
+            return;
+        }
+        (None, Some(args)) => args,
+        (None, None) => unreachable!("clap requires the rename arguments without a subcommand"),
+    };
+    let fail_if_no_matches = args.fail_if_no_matches;
+    let out = Output::new(Verbosity::from_flags(args.quiet, args.verbose), args.log_format, args.color);
+    let stats = start(args);
+
+    if let Some(status) = exit_status(stats, fail_if_no_matches) {
+        if status == 1 {
+            out.error("No occurrences of the project name were found, nothing was renamed.");
+        }
+        std::process::exit(status);
+    }
+}
+
+// The exit status of a run that didn't succeed with the default status. --fail-if-no-matches wins
+// over the status of a run with nothing to do, which renamed nothing either.
+fn exit_status(stats: Stats, fail_if_no_matches: bool) -> Option<i32> {
+    if fail_if_no_matches && stats.renamed == 0 && stats.substitutions == 0 {
+        Some(1)
+    } else if stats.nothing_to_do {
+        Some(EXIT_NOTHING_TO_DO)
+    } else {
+        None
+    }
+}
+
+fn write_sarif(path: &Path, report: &json::Value, out: Output) {
+    if let Err(e) = std::fs::write(path, format!("{report}\n")) {
+        out.error(&format!("Failed to write the SARIF report {}: {e}", path.display()));
+        std::process::exit(1);
+    }
+}
+
+fn start(args: Args) -> Stats {
+    // A report printed to stdout replaces the other output, errors are still printed to stderr
+    let verbosity = if args.report.is_some() && args.report_output.is_none() {
+        Verbosity::Quiet
+    } else {
+        Verbosity::from_flags(args.quiet, args.verbose)
+    };
+    let out = Output::new(verbosity, args.log_format, args.color);
+    // Placeholders in the new name and the replacements are expanded once for the whole run
+    let expand = |text: &str| {
+        placeholder::expand(text).unwrap_or_else(|e| {
+            out.error(&e);
+            std::process::exit(1);
+        })
+    };
+    // The scope of a scoped npm package name like "@acme/new-lib" isn't part of the project name
+    let name = expand(&args.name);
+    let mut ctx = Context::new(&args.input, npm::split_scope(&name).1, out);
+    ctx.npm_package = npm::renamed_package(npm::package_name(&args.input).as_deref(), &ctx.old_name.original, &name);
+    if !args.old_parts.is_empty() {
+        ctx.old_name.parts = args.old_parts.iter().map(|part| part.to_lowercase()).collect();
+    }
+    ctx.old_name = ctx.old_name.with_acronyms(&args.acronym);
+    ctx.new_name = ctx.new_name.with_acronyms(&args.acronym);
+    ctx.escape_reserved_names = args.escape_reserved_names;
+    ctx.fs = Arc::new(fs::Disk { reflink: args.reflink });
+    ctx.resume = args.resume;
+    ctx.move_input = args.move_input;
+    ctx.trash = args.trash;
+    ctx.backup = args.backup;
+    ctx.archives = args.archives;
+    ctx.workspace = args.workspace;
+    ctx.names_only = args.names_only;
+    ctx.exclude = args.exclude.clone();
+    ctx.include_vendored = args.include_vendored;
+    if args.tracked_only {
+        match git::tracked_paths(&ctx.input) {
+            Ok(tracked) => ctx.tracked = Some(tracked),
+            Err(e) => {
+                ctx.error(&format!("--tracked-only needs the input in a git work tree: {e}"));
+                std::process::exit(1);
+            }
+        }
+    }
+    let config_path = args.config.clone().or_else(|| Some(ctx.input.join(config::FILE_NAME)).filter(|path| path.is_file()));
+    if let Some(path) = config_path {
+        match config::Config::load(&path) {
+            Ok(config) => ctx.config = config,
+            Err(e) => {
+                ctx.error(&e);
+                std::process::exit(1);
+            }
+        }
+    }
+    ctx.nodes = syntax::ReplaceIn::nodes(&args.replace_in);
+    ctx.ecosystems = args.ecosystems;
+    ctx.identifier_boundaries = args.identifier_boundaries;
+    if let (Some(old_org), Some(new_org)) = (&args.old_org, &args.new_org) {
+        ctx.org = Some(org_names(old_org, &expand(new_org), &args.acronym));
+    }
+    ctx.rewrite_secrets = args.rewrite_secrets;
+    ctx.rewrite_generated = args.rewrite_generated;
+    let extensions = |extensions: &[String]| {
+        extensions.iter().map(|extension| extension.trim_start_matches('.').to_lowercase()).collect::<Vec<_>>()
+    };
+    ctx.treat_as_text = extensions(&args.treat_as_text);
+    ctx.treat_as_binary = extensions(&args.treat_as_binary);
+    if let Some(extension) = ctx.treat_as_text.iter().find(|extension| ctx.treat_as_binary.contains(extension)) {
+        ctx.error(&format!("The extension {extension} can't be treated as both text and binary"));
+        std::process::exit(1);
+    }
+    ctx.domain = args.old_domain.clone().zip(args.new_domain.clone());
+    ctx.copyright = args.copyright_holder.as_ref().map(|(old, new)| (old.clone(), expand(new)));
+    if args.copyright_years {
+        ctx.copyright_year = output::timestamp(std::time::SystemTime::now())[..4].parse().ok();
+    }
+    ctx.regen_uuids = args.regen_uuids.clone();
+    ctx.regen_vs_guids = args.regen_vs_guids;
+    ctx.also = args.also.iter().map(|(from, to)| (from.clone(), expand(to))).collect();
+    ctx.replace_regex = args.replace_regex.iter().map(|(regex, replacement)| (regex.clone(), expand(replacement))).collect();
+    ctx.merge = if args.sync { Merge::Overwrite } else { args.merge };
+    ctx.dry_run = args.dry_run;
+    if args.keep_root {
+        ctx.output = ctx.input.clone();
+    }
+    if let Some(output) = &args.output {
+        // The output can't be written into the project that is being traversed
+        if let (Ok(input), Ok(absolute)) = (std::path::absolute(&ctx.input), std::path::absolute(output))
+            && absolute.starts_with(&input)
+            && absolute != input
+        {
+            ctx.error(&format!("The output {} is inside the input", output.display()));
+            std::process::exit(1);
+        }
+        if let Some(parent) = output.parent().filter(|parent| !parent.as_os_str().is_empty())
+            && let Err(e) = std::fs::create_dir_all(parent)
+        {
+            ctx.error(&format!("Failed to create {}: {e}", parent.display()));
+            std::process::exit(1);
+        }
+        ctx.output = output.clone();
+    }
+    if ctx.workspace && workspace::find_root(ctx.fs.as_ref(), &ctx.input).is_none() {
+        ctx.error(&format!("{} is not in a Cargo, npm or pnpm workspace, a Maven reactor or a Gradle build", ctx.input.display()));
+        std::process::exit(1);
+    }
+    if let Some(scope) = &args.scope {
+        let inside = match (std::path::absolute(scope), std::path::absolute(&ctx.input)) {
+            (Ok(scope), Ok(input)) => scope.is_dir() && input.starts_with(&scope) && input != scope,
+            _ => false,
+        };
+        if !inside {
+            ctx.error(&format!("The input must be inside the scope directory {}", scope.display()));
+            std::process::exit(1);
+        }
+        ctx.scope = Some(scope.clone());
+    }
+    if let Some(temp_dir) = &args.temp_dir {
+        if !temp_dir.is_dir() {
+            ctx.error(&format!("Temporary directory does not exist: {}", temp_dir.display()));
+            std::process::exit(1);
+        }
+        ctx.temp_dir = Some(temp_dir.clone());
+    }
+    if ctx.escape_reserved_names && !args.keep_root && args.output.is_none() {
+        let output_file_name = ctx.output.file_name().unwrap().to_string_lossy().to_string();
+        ctx.output.set_file_name(escape_reserved_name(output_file_name, &ctx));
+    } else {
+        // Check all new names before anything is written
+        let reserved_names = find_reserved_names(&ctx);
+        for (path, reason) in &reserved_names {
+            ctx.error(&format!("New name {reason}: {}", path.display()));
+        }
+        if !reserved_names.is_empty() {
+            ctx.error("Nothing was written, use --escape-reserved-names to escape these names.");
+            std::process::exit(1);
+        }
+    }
+    for command in &args.plugin {
+        match Plugin::spawn(command) {
+            Ok(plugin) => ctx.replacers.push(Box::new(plugin)),
+            Err(e) => {
+                ctx.error(&format!("Failed to start plugin {command}: {e}"));
+                std::process::exit(1);
+            }
+        }
+    }
+    // Paths in the patch are relative to the directory the references are updated in, or to the
+    // project itself
+    if args.emit_patch.is_some() {
+        ctx.patch = Some(vec![]);
+    }
+    let references_root = ctx.scope.clone().or_else(|| ctx.workspace.then(|| workspace::find_root(ctx.fs.as_ref(), &ctx.input)).flatten());
+    let patch_roots = match references_root {
+        Some(root) => (root.clone(), root),
+        None if ctx.fs.is_dir(&ctx.input) => (ctx.input.clone(), ctx.output.clone()),
+        None => (ctx.input.parent().unwrap().to_path_buf(), ctx.output.parent().unwrap().to_path_buf()),
+    };
+    if !args.watch
+        && let Some(reason) = nothing_to_do(&mut ctx)
+    {
+        ctx.emit(Event::NothingToDo { reason });
+        ctx.stats.nothing_to_do = true;
+        return ctx.stats;
+    }
+    let names = [("old_name", ctx.old_name.original.clone()), ("new_name", ctx.new_name.original.clone())];
+    let names = names.iter().map(|(name, value)| (*name, value.as_str())).collect::<Vec<_>>();
+    // The pull request is opened against the branch the rename started from
+    let pull_request_base = args.open_pr.then(|| git::current_branch(&ctx.input));
+    let branch = args.git_branch.as_deref().map(|branch| placeholder::fill(&expand(branch), &names));
+    if let Some(branch) = &branch
+        && let Err(e) = git::create_branch(&ctx.input, branch)
+    {
+        ctx.error(&format!("Failed to create the branch: {e}"));
+        std::process::exit(1);
+    }
+    let commit_template = args.git_commit.as_deref().map(expand);
+    if let Err(message) = run(&mut ctx) {
+        ctx.error(&message);
+        std::process::exit(1);
+    }
+    if args.git_add || commit_template.is_some() {
+        let paths = if ctx.input == ctx.output { vec![ctx.input.as_path()] } else { vec![ctx.input.as_path(), ctx.output.as_path()] };
+        if let Err(e) = git::stage(&paths) {
+            ctx.error(&format!("Failed to stage the rename: {e}"));
+            std::process::exit(1);
+        }
+        if let Some(template) = &commit_template {
+            let (renamed, substitutions) = (ctx.stats.renamed.to_string(), ctx.stats.substitutions.to_string());
+            let summary = format!("Renamed {renamed} files and directories and made {substitutions} substitutions.");
+            let mut values = names.clone();
+            values.extend([("renamed", renamed.as_str()), ("substitutions", substitutions.as_str()), ("summary", summary.as_str())]);
+            let message = placeholder::fill(template, &values);
+            if let Err(e) = git::commit(&paths, &message) {
+                ctx.error(&format!("Failed to commit the rename: {e}"));
+                std::process::exit(1);
+            }
+            if let (Some(base), Some(head)) = (pull_request_base, &branch) {
+                let title = message.lines().next().unwrap_or_default();
+                match open_pull_request(&ctx, base, head, title) {
+                    Ok(url) => ctx.emit(Event::PullRequestOpened { url: &url }),
+                    Err(e) => {
+                        ctx.error(&format!("Failed to open the pull request: {e}"));
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+    }
+    if let Some(path) = &args.emit_patch {
+        let patch = patch::render(ctx.patch.as_deref().unwrap_or_default(), &patch_roots.0, &patch_roots.1);
+        if let Err(e) = std::fs::write(path, patch) {
+            ctx.error(&format!("Failed to write the patch {}: {e}", path.display()));
+            std::process::exit(1);
+        }
+    }
+    if let Some(format) = args.report {
+        let report = report::render(format, &ctx);
+        match &args.report_output {
+            Some(path) => {
+                if let Err(e) = std::fs::write(path, report) {
+                    ctx.error(&format!("Failed to write the report {}: {e}", path.display()));
+                    std::process::exit(1);
+                }
+            }
+            None => print!("{report}"),
+        }
+    }
+
+    if args.watch {
+        let (input, output) = (ctx.input.clone(), ctx.output.clone());
+        watch(&input, &output, &mut ctx);
+    }
+    ctx.stats
+}
+
+// Push the committed rename and open a pull request for it on the forge of "origin"
+fn open_pull_request(ctx: &Context, base: Result<String, String>, head: &str, title: &str) -> Result<String, String> {
+    let base = base?;
+    let url = git::origin_url(&ctx.output)?;
+    let repository = forge::Repository::from_remote(&url).ok_or_else(|| format!("{url} is not on GitHub or GitLab"))?;
+    git::push(&ctx.output)?;
+    let body = report::markdown(ctx);
+    forge::open(&repository, &forge::PullRequest { title, body: &body, head, base: &base })
+}
+
+// Rename the project described by the context
+fn run(ctx: &mut Context) -> Result<(), String> {
+    // Build a new output under a temporary name and only move it into place once every file was
+    // written, so a failed or interrupted run never leaves a half-complete output behind. An output
+    // that already exists is completed in place.
+    //
+    // On a case-insensitive filesystem a rename that only changes the case of the name, like
+    // "MyProject" to "Myproject", makes the output the same entry as the input, and so does keeping
+    // the name of the project. It is staged as well and replaces the input afterwards.
+    let replaces_input = ctx.output == ctx.input || is_case_only_rename(&ctx.input, &ctx.output);
+    let staged = replaces_input || !ctx.fs.exists(&ctx.output);
+    let output = if staged { temporary_path(&ctx.output, ctx.temp_dir.as_deref()) } else { ctx.output.clone() };
+
+    // Only one run at a time may write the output
+    let on_disk = !ctx.dry_run && ctx.fs.is_disk();
+    let _lock = if on_disk { Some(RunLock::acquire(&lock_path(&ctx.output))?) } else { None };
+
+    if staged && !ctx.dry_run && !ctx.resume {
+        let _ = ctx.fs.remove_all(&output);
+    }
+    // The files written to the staged output are recorded in a checkpoint next to it, so a failed
+    // run can be resumed. An output that is completed in place already skips existing files.
+    if staged && on_disk {
+        let checkpoint_path = checkpoint_path(&output);
+        let checkpoint = Checkpoint::open(&checkpoint_path, &ctx.input, ctx.resume)
+            .map_err(|e| format!("Failed to open the checkpoint {}: {e}", checkpoint_path.display()))?;
+        ctx.checkpoint = Some(checkpoint);
+    }
+    let _hint = ResumeHint(ctx.out);
+
+    // An existing output is merged with the renamed project, all conflicts are found up front so
+    // nothing is written if any of them aborts the run
+    if !staged && ctx.merge == Merge::Fail {
+        let conflicts = find_conflicts(ctx)?;
+        if !conflicts.is_empty() {
+            let list = conflicts.iter().map(|path| format!("\n  {}", path.display())).collect::<String>();
+            return Err(format!("Files in {} differ from the renamed project, nothing was written:{list}", ctx.output.display()));
+        }
+    }
+
+    if ctx.backup && on_disk {
+        let archive = backup_path(&ctx.input);
+        if ctx.fs.exists(&archive) {
+            return Err(format!("Backup {} already exists, move it away first", archive.display()));
+        }
+        let entries = backup::create_backup(&ctx.input, &archive)
+            .map_err(|e| format!("Failed to write the backup {}: {e}", archive.display()))?;
+        ctx.emit(Event::BackupCreated { path: &archive, entries });
+    }
+
+    // The references to a project may come before the project in the traversal
+    if ctx.regen_vs_guids {
+        ctx.project_guids = find_project_guids(ctx);
+    }
+    // The files named after an application ID may come before its manifest
+    if APP_IDS.iter().any(|(ecosystem, _)| ctx.ecosystems.contains(*ecosystem)) {
+        ctx.app_ids = find_app_ids(ctx);
+    }
+    if ctx.ecosystems.contains(Ecosystem::ReactNative) {
+        ctx.package_dirs = find_package_dirs(ctx);
+    }
+    // The header declaring the text domain may come after the files using it
+    if ctx.ecosystems.contains(Ecosystem::Wordpress) {
+        ctx.text_domain = find_text_domain(ctx);
+    }
+    // The sources using the IDs may come before the manifest contributing them
+    if ctx.ecosystems.contains(Ecosystem::Vscode) {
+        ctx.extension_ids = find_extension_ids(ctx);
+    }
+
+    // Recursively traverse the project directory
+    if let Err(e) = traverse_directory(ctx.input.clone(), output.clone(), ctx) {
+        let hint = if staged && on_disk { format!("\n{RESUME_HINT}") } else { String::new() };
+        return Err(format!("{e}{hint}"));
+    }
+
+    if staged {
+        for operation in &mut ctx.operations {
+            unstage(&mut operation.output, &output, &ctx.output);
+        }
+        for change in ctx.patch.iter_mut().flatten() {
+            unstage(&mut change.to, &output, &ctx.output);
+        }
+        if !ctx.dry_run && ctx.fs.exists(&output) {
+            // The output can only take the place of the input once the input is gone
+            if replaces_input {
+                remove_input(ctx)?;
+            }
+            ctx.fs
+                .rename(&output, &ctx.output)
+                .map_err(|e| format!("Failed to move {} into place: {e}", output.display()))?;
+            if ctx.move_input && !replaces_input {
+                remove_input(ctx)?;
+            }
+        }
+        if let Some(checkpoint) = ctx.checkpoint.take() {
+            let _ = checkpoint.remove();
+        }
+    }
+
+    update_references(ctx)?;
+
+    ctx.emit(Event::Summary {
+        renamed: ctx.stats.renamed,
+        substitutions: ctx.stats.substitutions,
+        refreshed: ctx.stats.refreshed,
+        output: &ctx.output,
+    });
+    Ok(())
+}
+
+// Map a path in the staged output to its final path
+fn unstage(path: &mut PathBuf, staged: &Path, output: &Path) {
+    if let Ok(relative) = path.strip_prefix(staged) {
+        *path = if relative.as_os_str().is_empty() { output.to_path_buf() } else { output.join(relative) };
+    }
+}
+
+// Replace the old name in place in the files outside the project that may reference it, every file
+// in the scope or the manifests and configurations of the workspace
+fn update_references(ctx: &mut Context) -> Result<(), String> {
+    let backup = backup_path(&ctx.input);
+    let skip = [ctx.input.as_path(), ctx.output.as_path(), backup.as_path()];
+    let files = match (&ctx.scope, ctx.workspace) {
+        (Some(scope), _) => workspace::files(ctx.fs.as_ref(), scope, &skip),
+        (None, true) => match workspace::find_root(ctx.fs.as_ref(), &ctx.input) {
+            Some(root) => workspace::reference_files(ctx.fs.as_ref(), &root, &skip),
+            None => vec![],
+        },
+        (None, false) => return Ok(()),
+    };
+    for path in files {
+        let Some(content) = ctx.fs.read(&path).ok().and_then(|content| String::from_utf8(content).ok()) else {
+            continue;
+        };
+        let (renamed, mut substitutions) = match &ctx.npm_package {
+            Some((old, new)) => replace::NpmPackage::new(old, new).rename(&content),
+            None => (content.clone(), vec![]),
+        };
+        let (new_content, found) = if (ctx.ecosystems.contains(Ecosystem::Maven) && maven::is_pom(&path)) || (ctx.ecosystems.contains(Ecosystem::Gradle) && gradle::is_script(&path)) {
+            build_file_references(ctx, &path, &renamed)
+        } else {
+            ctx.transform_text(&renamed)
+        };
+        substitutions.extend(found);
+        let count = substitutions.iter().map(|s| s.count).sum();
+        if count == 0 {
+            continue;
+        }
+        ctx.emit(Event::ContentRewritten { path: &path, substitutions: count });
+        for substitution in &substitutions {
+            ctx.emit(Event::Substitution {
+                path: &path,
+                from: &substitution.from,
+                to: &substitution.to,
+                count: substitution.count,
+            });
+        }
+        ctx.stats.substitutions += count;
+        ctx.record(Action::Write, &path, &path, count);
+        if let Some(changes) = &mut ctx.patch {
+            changes.push(patch::Change {
+                from: path.clone(),
+                to: path.clone(),
+                content: Some((content.clone().into_bytes(), new_content.clone().into_bytes())),
+            });
+        }
+        if !ctx.dry_run {
+            ctx.fs.write(&path, new_content.as_bytes()).map_err(|e| format!("Failed to update {}: {e}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+// Replace the old name in the coordinates of a POM or the project names of a Gradle build script of
+// another module
+fn build_file_references(ctx: &Context, path: &Path, content: &str) -> (String, Vec<Substitution>) {
+    let keys = maven::is_pom(path).then(maven::keys);
+    let (old_name, new_name) = (ctx.old_name.parts.join("-"), ctx.new_name.parts.join("-"));
+    let file = replace::File { path, original: content, text: content, old_name: &old_name, new_name: &new_name };
+    let mut case_variants = replace::CaseVariants::new(&ctx.old_name, &ctx.new_name).with_other_names(ctx.org.as_slice()).with_keys(keys.as_deref()).with_ecosystems(ctx.ecosystems);
+    match replace::Replacer::replace(&mut case_variants, &file) {
+        Ok(replace::Edits::Replaced { text, substitutions }) => (text, substitutions),
+        _ => (content.to_string(), vec![]),
+    }
+}
+
+// Files in the output that already exist and differ from the renamed ones
+fn find_conflicts(ctx: &mut Context) -> Result<Vec<PathBuf>, String> {
+    Ok(silent_dry_run(ctx)?.1)
+}
+
+// Why the rename would not change anything, if it wouldn't
+fn nothing_to_do(ctx: &mut Context) -> Option<&'static str> {
+    if ctx.output == ctx.input && ctx.old_name.parts == ctx.new_name.parts {
+        return Some("the project already has the new name");
+    }
+    // A previous run wrote the output and the input didn't change since
+    if ctx.fs.exists(&ctx.output) && !ctx.move_input && !is_case_only_rename(&ctx.input, &ctx.output) {
+        // A dry run that fails leaves the error to the rename itself
+        let Ok((operations, conflicts)) = silent_dry_run(ctx) else {
+            return None;
+        };
+        if conflicts.is_empty() && operations.iter().all(|operation| operation.action == Action::Skip) {
+            return Some("the output is already up to date");
+        }
+    }
+    None
+}
+
+// The operations and conflicts of the rename, found without printing or writing anything
+fn silent_dry_run(ctx: &mut Context) -> Result<(Vec<Operation>, Vec<PathBuf>), String> {
+    let (out, dry_run, stats) = (ctx.out, ctx.dry_run, ctx.stats);
+    let operations = std::mem::take(&mut ctx.operations);
+    ctx.out = Output::new(Verbosity::Quiet, LogFormat::Pretty, ColorChoice::Never);
+    ctx.dry_run = true;
+    let result = traverse_directory(ctx.input.clone(), ctx.output.clone(), ctx);
+    let found = std::mem::replace(&mut ctx.operations, operations);
+    (ctx.out, ctx.dry_run, ctx.stats) = (out, dry_run, stats);
+    let conflicts = std::mem::take(&mut ctx.conflicts);
+    result.map(|()| (found, conflicts))
+}
+
+// Delete the input or move it to the trash
+fn remove_input(ctx: &Context) -> Result<(), String> {
+    if ctx.trash {
+        let target = trash::trash(&ctx.input).map_err(|e| format!("Failed to move {} to the trash: {e}", ctx.input.display()))?;
+        ctx.emit(Event::FileTrashed { path: &ctx.input, to: &target });
+        return Ok(());
+    }
+    ctx.emit(Event::FileRemoved { path: &ctx.input });
+    ctx.fs.remove_all(&ctx.input).map_err(|e| format!("Failed to remove {}: {e}", ctx.input.display()))
+}
+
+// Check if the output only differs from the input in case and the filesystem treats them as the
+// same entry
+fn is_case_only_rename(input: &Path, output: &Path) -> bool {
+    let (Some(input_name), Some(output_name)) = (input.file_name(), output.file_name()) else {
+        return false;
+    };
+    let (input_name, output_name) = (input_name.to_string_lossy(), output_name.to_string_lossy());
+    input_name != output_name && input_name.to_lowercase() == output_name.to_lowercase() && same_entry(input, output)
+}
+
+#[cfg(unix)]
+fn same_entry(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (std::fs::symlink_metadata(a), std::fs::symlink_metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_entry(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+const RESUME_HINT: &str = "The rename failed, run it again with --resume to continue where it stopped.";
+
+// Points to --resume when the run panics while writing the output, a failure that is returned
+// carries the hint in its message
+struct ResumeHint(Output);
+
+impl Drop for ResumeHint {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.0.error(RESUME_HINT);
+        }
+    }
+}
+
+fn backup_path(input: &Path) -> PathBuf {
+    input.with_file_name(format!("{}-backup.tar", input.file_name().unwrap().to_string_lossy()))
+}
+
+// Hidden lock file next to the output
+fn lock_path(output: &Path) -> PathBuf {
+    output.with_file_name(format!(".{}.project-renamer.lock", output.file_name().unwrap().to_string_lossy()))
+}
+
+fn checkpoint_path(staged: &Path) -> PathBuf {
+    let mut path = staged.as_os_str().to_owned();
+    path.push(".checkpoint");
+    PathBuf::from(path)
+}
+
+// Hidden path for staging the path in the temporary directory, or next to it without one. It is
+// the same for every run so an interrupted run can be resumed.
+fn temporary_path(path: &Path, temp_dir: Option<&Path>) -> PathBuf {
+    let name = format!(".{}.project-renamer", path.file_name().unwrap().to_string_lossy());
+    match temp_dir {
+        Some(temp_dir) => temp_dir.join(name),
+        None => path.with_file_name(name),
+    }
+}
+
+// Keep the output in sync with the input by applying the rename to every change in the input
+fn watch(input: &Path, output: &Path, ctx: &mut Context) -> ! {
+    let mut previous = watch::snapshot(input);
+    loop {
+        std::thread::sleep(watch::POLL_INTERVAL);
+        let current = watch::snapshot(input);
+        for change in watch::diff(&previous, &current) {
+            // A change that fails to sync is reported, the next changes are still synced
+            if let Err(e) = sync_change(input, output, &change, ctx) {
+                ctx.error(&e);
+            }
+        }
+        previous = current;
+    }
+}
+
+// Apply a single change in the input to the output. An entry that is gone by the time it is synced,
+// like the temporary file of an editor, is skipped.
+fn sync_change(input: &Path, output: &Path, change: &Change, ctx: &mut Context) -> Result<(), String> {
+    match change {
+        Change::Removed(relative) => {
+            let target = renamed_path(output, relative, ctx);
+            if ctx.fs.exists(&target) {
+                ctx.emit(Event::FileRemoved { path: &target });
+                ctx.fs.remove_all(&target).map_err(|e| format!("Failed to remove {}: {e}", target.display()))?;
+            }
+        }
+        Change::Modified(relative) => {
+            let source = if relative.as_os_str().is_empty() { input.to_path_buf() } else { input.join(relative) };
+            let target = renamed_path(output, relative, ctx);
+            if ctx.fs.is_dir(&source) {
+                if !ctx.fs.exists(&target) {
+                    ctx.emit(Event::DirectoryCreated { path: &target });
+                    ctx.fs.create_dir_all(&target).map_err(|e| format!("Failed to create {}: {e}", target.display()))?;
+                }
+            } else if ctx.fs.exists(&source) {
+                if ctx.fs.exists(&target) {
+                    ctx.fs.remove_file(&target).map_err(|e| format!("Failed to remove {}: {e}", target.display()))?;
+                }
+                if let Some(parent) = target.parent() {
+                    ctx.fs.create_dir_all(parent).map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+                }
+                rename_file(&source, &target, ctx)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Path in the output for a path relative to the input, with every component renamed and the
+// package directories moved
+fn renamed_path(output: &Path, relative: &Path, ctx: &Context) -> PathBuf {
+    let (mut path, mut input) = (output.to_path_buf(), ctx.input.clone());
+    for component in relative.components() {
+        input.push(component);
+        let name = component.as_os_str().to_string_lossy();
+        if ctx.is_excluded(&input) {
+            path.push(name.as_ref());
+        } else {
+            path.push(rename_entry(&name, ctx).0);
+        }
+        if let Some((depth, package)) = ctx.package_dirs.get(&input)
+            && let Some(root) = path.ancestors().nth(*depth)
+        {
+            path = root.join(package);
+        }
+    }
+    path
+}
+
+// New name of a file or directory in the project
+fn rename_entry(name: &str, ctx: &Context) -> (String, Vec<Substitution>) {
+    if let Some((old, new)) = &ctx.text_domain
+        && let Some(new_name) = wordpress::renamed_file(name, old, new)
+    {
+        return (escape_reserved_name(new_name, ctx), vec![Substitution { from: old.clone(), to: new.clone(), count: 1 }]);
+    }
+    let (new_name, substitutions) = ctx.transform_text(name);
+    if substitutions.is_empty() {
+        return (new_name, substitutions);
+    }
+    (escape_reserved_name(new_name, ctx), substitutions)
+}
+
+fn escape_reserved_name(name: String, ctx: &Context) -> String {
+    if !ctx.escape_reserved_names || reserved::problem(&name).is_none() {
+        return name;
+    }
+    let escaped = reserved::escape(&name);
+    ctx.emit(Event::ReservedNameEscaped { name: &name, escaped: &escaped });
+    escaped
+}
+
+// Output paths of renamed files and directories whose new name is unusable on Windows, with the
+// reason. Names that were already in the input are left alone.
+fn find_reserved_names(ctx: &Context) -> Vec<(PathBuf, &'static str)> {
+    fn visit(input: &Path, output: &Path, ctx: &Context, found: &mut Vec<(PathBuf, &'static str)>) {
+        let Ok(paths) = ctx.fs.read_dir(input) else {
+            return;
+        };
+        for path in paths {
+            // Excluded paths keep their names
+            if ctx.is_excluded(&path) {
+                continue;
+            }
+            let old_file_name = path.file_name().unwrap().to_string_lossy().to_string();
+            let (new_file_name, substitutions) = ctx.transform_text(&old_file_name);
+            let output_path = output.join(&new_file_name);
+            if !substitutions.is_empty()
+                && let Some(reason) = reserved::problem(&new_file_name)
+            {
+                found.push((output_path.clone(), reason));
+            }
+            visit(&path, &output_path, ctx, found);
+        }
+    }
+
+    let mut found = vec![];
+    let output_file_name = ctx.output.file_name().unwrap().to_string_lossy();
+    if let Some(reason) = reserved::problem(&output_file_name) {
+        found.push((ctx.output.clone(), reason));
+    }
+    visit(&ctx.input, &ctx.output, ctx, &mut found);
+    found
+}
+
+// The lower case GUIDs identifying the projects of the Visual Studio solutions and projects in the
+// input, besides the excluded ones
+fn find_project_guids(ctx: &Context) -> HashSet<String> {
+    fn visit(path: &Path, ctx: &Context, found: &mut HashSet<String>) {
+        if ctx.is_excluded(path) {
+            return;
+        }
+        if ctx.fs.is_dir(path) {
+            for path in ctx.fs.read_dir(path).unwrap_or_default() {
+                visit(&path, ctx, found);
+            }
+        } else if solution::is_solution_file(path)
+            && let Some(text) = ctx.fs.read(path).ok().and_then(|bytes| String::from_utf8(bytes).ok())
+        {
+            found.extend(solution::project_guids(path, &text));
+        }
+    }
+
+    let mut found = HashSet::new();
+    visit(&ctx.input, ctx, &mut found);
+    found
+}
+
+// The ecosystems with reverse-DNS application IDs, and the IDs each finds in a file
+type AppIds = fn(&Path, &str) -> Vec<String>;
+
+const APP_IDS: [(Ecosystem, AppIds); 4] = [
+    (Ecosystem::Flatpak, flatpak::app_ids),
+    (Ecosystem::Electron, electron::app_ids),
+    (Ecosystem::Tauri, tauri::app_ids),
+    (Ecosystem::ReactNative, react_native::app_ids),
+];
+
+// The application IDs of the Flatpak and Snapcraft manifests and of the Electron, Tauri and React
+// Native apps in the input, besides the excluded ones, with the IDs they are renamed to
+fn find_app_ids(ctx: &Context) -> Vec<(String, String)> {
+    fn visit(path: &Path, ctx: &Context, found: &mut Vec<String>) {
+        if ctx.is_excluded(path) {
+            return;
+        }
+        if ctx.fs.is_dir(path) {
+            for path in ctx.fs.read_dir(path).unwrap_or_default() {
+                visit(&path, ctx, found);
+            }
+        } else if let Some(text) = ctx.fs.read(path).ok().and_then(|bytes| String::from_utf8(bytes).ok()) {
+            for (_, app_ids) in APP_IDS.iter().filter(|(ecosystem, _)| ctx.ecosystems.contains(*ecosystem)) {
+                let ids = app_ids(path, &text).into_iter().filter(|id| !found.contains(id)).collect::<Vec<_>>();
+                found.extend(ids);
+            }
+        }
+    }
+
+    let mut found = vec![];
+    visit(&ctx.input, ctx, &mut found);
+    found
+        .into_iter()
+        .filter_map(|old| {
+            let new = renamed_app_id(&old, ctx);
+            (new != old).then_some((old, new))
+        })
+        .collect()
+}
+
+// The text domain declared by the plugin or theme in the input, in the header of a PHP file or the
+// style.css at its root, with the slug of the new name it is renamed to
+fn find_text_domain(ctx: &Context) -> Option<(String, String)> {
+    let new = wordpress::slug(&ctx.new_name);
+    ctx.fs.read_dir(&ctx.input).unwrap_or_default().into_iter().filter(|path| !ctx.is_excluded(path)).find_map(|path| {
+        let text = ctx.fs.read(&path).ok().and_then(|bytes| String::from_utf8(bytes).ok())?;
+        wordpress::text_domain(&path, &text).filter(|old| *old != new).map(|old| (old, new.clone()))
+    })
+}
+
+// The directories of the Java and Kotlin packages named after a renamed application ID, like
+// "android/app/src/main/java/com/example/myapp", which move to the package of the new ID
+fn find_package_dirs(ctx: &Context) -> HashMap<PathBuf, (usize, PathBuf)> {
+    fn visit(path: &Path, ctx: &Context, found: &mut HashMap<PathBuf, (usize, PathBuf)>) {
+        if ctx.is_excluded(path) || !ctx.fs.is_dir(path) {
+            return;
+        }
+        if react_native::is_source_root(path) {
+            for (old, new) in &ctx.app_ids {
+                let dir = old.split('.').fold(path.to_path_buf(), |dir, element| dir.join(element));
+                if ctx.fs.is_dir(&dir) {
+                    found.insert(dir, (old.split('.').count(), new.split('.').collect()));
+                }
+            }
+        }
+        for path in ctx.fs.read_dir(path).unwrap_or_default() {
+            visit(&path, ctx, found);
+        }
+    }
+
+    let mut found = HashMap::new();
+    visit(&ctx.input, ctx, &mut found);
+    found
+}
+
+// Whether everything in the directory moves to the directory of another package, so it is left out
+// of the output. The directories of "com/example" do when "com/example/myapp" moves.
+fn is_moved_away(path: &Path, ctx: &Context) -> bool {
+    if ctx.package_dirs.contains_key(path) {
+        return true;
+    }
+    if !ctx.package_dirs.keys().any(|dir| dir.starts_with(path)) {
+        return false;
+    }
+    let paths = ctx.fs.read_dir(path).unwrap_or_default();
+    !paths.is_empty() && paths.iter().all(|path| is_moved_away(path, ctx))
+}
+
+// The application ID renamed: the reversed old domain replaced by the new one, and the project
+// name in it replaced in its case, camelCase included, which isn't a case variant, e.g.
+// "com.example.oldName" is "io.example.newName"
+fn renamed_app_id(id: &str, ctx: &Context) -> String {
+    let (prefix, element) = id.rsplit_once('.').unwrap_or(("", id));
+    let mut prefix = prefix.to_string();
+    if let Some((old, new)) = &ctx.domain {
+        let (old, new) = (flatpak::reverse_domain(old), flatpak::reverse_domain(new));
+        let lower = prefix.to_ascii_lowercase();
+        if lower == old.to_ascii_lowercase() || lower.starts_with(&format!("{}.", old.to_ascii_lowercase())) {
+            prefix = format!("{new}{}", &prefix[old.len()..]);
+        }
+    }
+    let prefix = ctx.transform_text(&prefix).0;
+    let element = if element == camel_case(&ctx.old_name) { camel_case(&ctx.new_name) } else { ctx.transform_text(element).0 };
+    format!("{prefix}.{element}")
+}
+
+// The IDs contributed by the VS Code extensions in the input, besides the excluded ones, with the
+// IDs they are renamed to, and their configuration sections, quoted like in
+// "getConfiguration('oldName')". The name of the extension they are prefixed with is renamed in its
+// case, camelCase included, which isn't a case variant.
+fn find_extension_ids(ctx: &Context) -> Vec<(String, String)> {
+    fn visit(path: &Path, ctx: &Context, found: &mut Vec<String>) {
+        if ctx.is_excluded(path) {
+            return;
+        }
+        if ctx.fs.is_dir(path) {
+            for path in ctx.fs.read_dir(path).unwrap_or_default() {
+                visit(&path, ctx, found);
+            }
+        } else if let Some(text) = ctx.fs.read(path).ok().and_then(|bytes| String::from_utf8(bytes).ok()) {
+            found.extend(vscode::contributed_ids(path, &text).into_iter().filter(|id| !found.contains(id)).collect::<Vec<_>>());
+        }
+    }
+
+    let mut found = vec![];
+    visit(&ctx.input, ctx, &mut found);
+    let mut renamed: Vec<(String, String)> = vec![];
+    for id in found {
+        let Some((prefix, rest)) = id.split_once('.') else {
+            continue;
+        };
+        let new_prefix = if prefix == camel_case(&ctx.old_name) { camel_case(&ctx.new_name) } else { ctx.transform_text(prefix).0 };
+        if new_prefix == prefix {
+            continue;
+        }
+        renamed.push((id.clone(), format!("{new_prefix}.{rest}")));
+        for quote in ['\'', '"', '`'] {
+            let section = (format!("{quote}{prefix}{quote}"), format!("{quote}{new_prefix}{quote}"));
+            if !renamed.contains(&section) {
+                renamed.push(section);
+            }
+        }
+    }
+    renamed
+}
+
+// The name in camelCase, like "oldName"
+fn camel_case(name: &NormalizedName) -> String {
+    name.parts
+        .iter()
+        .enumerate()
+        .map(|(index, part)| match name.acronym(part) {
+            _ if index == 0 => part.to_lowercase(),
+            Some(acronym) => acronym.to_string(),
+            None => capitalise(part),
+        })
+        .collect()
+}
+
+// Recursively traverse the directory and
+// - Renames all file and directory names
+// - Opens files as text and renames all occurrences of the project name
+fn traverse_directory(input: PathBuf, output: PathBuf, ctx: &mut Context) -> Result<(), String> {
+    // Check if the path is a directory
+    if ctx.fs.is_dir(&input) {
+        let paths = ctx.fs.read_dir(&input).map_err(|e| format!("Failed to read {}: {e}", input.display()))?;
+
+        // Create the output directory if it doesn't exist and something stays in it
+        if !paths.is_empty() && !paths.iter().all(|path| is_moved_away(path, ctx)) && !ctx.fs.exists(&output) {
+            ctx.emit(Event::DirectoryCreated { path: &output });
+            ctx.record(Action::CreateDirectory, &input, &output, 0);
+            if !ctx.dry_run {
+                ctx.fs.create_dir_all(&output).map_err(|e| format!("Failed to create {}: {e}", output.display()))?;
+            }
+        }
+
+        // Iterate over the entries in the directory
+        for path in paths {
+            // Untracked files are left behind
+            if ctx.tracked.as_ref().is_some_and(|tracked| !tracked.contains(&path)) {
+                continue;
+            }
+            let old_file_name = path.file_name().unwrap().to_string_lossy().to_string();
+            let (mut new_file_name, substitutions) =
+                if ctx.is_excluded(&path) { (old_file_name.clone(), vec![]) } else { rename_entry(&old_file_name, ctx) };
+            // The primary file of an Arduino sketch follows the name of its folder, whatever its case.
+            // The output of the project is staged under another name.
+            let new_dir = if input == ctx.input { &ctx.output } else { &output };
+            let sketch = (!ctx.is_excluded(&path))
+                .then(|| arduino::primary_sketch(&input, &old_file_name, new_dir))
+                .flatten()
+                .filter(|sketch| *sketch != new_file_name);
+            if let Some(sketch) = &sketch {
+                new_file_name = sketch.clone();
+            }
+            if !substitutions.is_empty() || sketch.is_some() {
+                ctx.emit(Event::FileRenamed { from: &old_file_name, to: &new_file_name });
+                ctx.stats.renamed += 1;
+            }
+            let mut output_path = output.join(&new_file_name);
+            // A package directory moves to the new package, from the source root it is in
+            if let Some((depth, package)) = ctx.package_dirs.get(&path)
+                && let Some(root) = output.ancestors().nth(depth - 1)
+            {
+                output_path = root.join(package);
+            }
+
+            traverse_directory(path, output_path, ctx)?;
+        }
+    } else if ctx.checkpoint.as_ref().is_some_and(|checkpoint| checkpoint.is_done(&input)) {
+        // Already written by the run that is resumed
+        ctx.emit(Event::FileSkipped { path: &output });
+        ctx.record(Action::Skip, &input, &output, 0);
+    } else {
+        // A file that isn't in the checkpoint may have been written partially
+        if ctx.checkpoint.is_some() && ctx.fs.exists(&output) {
+            ctx.fs.remove_file(&output).map_err(|e| format!("Failed to remove {}: {e}", output.display()))?;
+        }
+        // If the path is a file, rename it
+        rename_file(&input, &output, ctx)?;
+        if let Some(checkpoint) = &mut ctx.checkpoint
+            && let Err(e) = checkpoint.mark_done(&input)
+        {
+            ctx.error(&format!("Failed to update the checkpoint: {e}"));
+        }
+    }
+    Ok(())
+}
+
+// Rename the file and all occurrences of the project name in the file
+fn rename_file(input: &Path, output: &Path, ctx: &mut Context) -> Result<(), String> {
+    // The content stays byte-identical, only the path is renamed
+    let relative = input.strip_prefix(&ctx.input).unwrap_or(input);
+    if ctx.names_only || ctx.is_excluded(input) || ctx.config.for_file(relative).skip {
+        return write_output(input, output, None, Action::Copy, 0, ctx);
+    }
+
+    // Rewriting credentials breaks them, and key material gets corrupted
+    if !ctx.rewrite_secrets && secret::is_secret(input) {
+        ctx.emit(Event::SecretSkipped { path: input });
+        return write_output(input, output, None, Action::Copy, 0, ctx);
+    }
+
+    if ctx.archives
+        && let Some(kind) = archive::Kind::from_path(input)
+        && rename_archive(input, output, kind, ctx)?
+    {
+        return Ok(());
+    }
+
+    // The file is binary by --treat-as-binary or by the repository owner in .gitattributes, it is
+    // copied as it is
+    let treat_as = ctx.treat_as(input);
+    if treat_as.or_else(|| ctx.gitattributes.text(ctx.fs.as_ref(), &ctx.input, relative)) == Some(false) {
+        return write_output(input, output, None, Action::Copy, 0, ctx);
+    }
+
+    // Open the file and rename all occurrences of the project name, unless its content is binary
+    // whatever its extension says
+    if let Some(content) =
+        ctx.fs.read(input).ok().filter(|bytes| treat_as == Some(true) || mime::detect(bytes).text).and_then(|bytes| String::from_utf8(bytes).ok())
+    {
+        // Generated files are regenerated from their sources, which is where the rename belongs
+        if !ctx.rewrite_generated && generated::is_generated(&content) {
+            ctx.emit(Event::GeneratedSkipped { path: input });
+            write_output(input, output, None, Action::Copy, 0, ctx)?;
+            ctx.note("generated file, content unchanged");
+            return Ok(());
+        }
+        // Occurrences that aren't replaced would otherwise only be noticed when the project breaks
+        for (line, found) in doctor::unhandled_cases(&content, &ctx.old_name) {
+            ctx.emit(Event::Finding {
+                kind: doctor::Kind::UnhandledCase.name(),
+                path: input,
+                line: Some(line),
+                message: &format!("{found} is written in a case style that is not renamed"),
+            });
+        }
+        let Some((mut new_content, mut substitutions)) = run_replacers(input, &content, ctx)? else {
+            return write_output(input, output, None, Action::Copy, 0, ctx);
+        };
+        if ctx.ecosystems.contains(Ecosystem::Composer)
+            && input.file_name().is_some_and(|name| name == "composer.lock")
+            && let Some(substitution) = update_content_hash(input, &mut new_content, ctx)
+        {
+            substitutions.push(substitution);
+        }
+        let count = substitutions.iter().map(|s| s.count).sum();
+        ctx.emit(Event::ContentRewritten { path: input, substitutions: count });
+        for substitution in &substitutions {
+            ctx.emit(Event::Substitution {
+                path: input,
+                from: &substitution.from,
+                to: &substitution.to,
+                count: substitution.count,
+            });
+        }
+        ctx.stats.substitutions += count;
+
+        // Nothing changed, so the file can be cloned
+        let new_content = (!substitutions.is_empty()).then_some(new_content.as_bytes());
+        write_output(input, output, new_content, Action::Write, count, ctx)
+    } else {
+        ctx.emit(Event::FileCopied { path: input });
+        write_output(input, output, None, Action::Copy, 0, ctx)
+    }
+}
+
+// Write the new content of a file to the output, or a copy of the input without one. An output
+// file that already exists is handled by the merge rule.
+fn write_output(input: &Path, output: &Path, content: Option<&[u8]>, action: Action, count: usize, ctx: &mut Context) -> Result<(), String> {
+    if ctx.fs.exists(output) {
+        let expected = content.map_or_else(|| ctx.fs.read(input).ok(), |content| Some(content.to_vec()));
+        if expected.is_some() && ctx.fs.read(output).ok() == expected {
+            ctx.emit(Event::FileSkipped { path: output });
+            ctx.record(Action::Skip, input, output, count);
+            return Ok(());
+        }
+        if ctx.merge != Merge::Overwrite {
+            ctx.emit(Event::FileConflict { path: output });
+            ctx.record(Action::Skip, input, output, count);
+            ctx.conflicts.push(output.to_path_buf());
+            return Ok(());
+        }
+        ctx.emit(Event::FileOverwritten { path: output });
+        ctx.stats.refreshed += 1;
+        if !ctx.dry_run {
+            ctx.fs.remove_file(output).map_err(|e| format!("Failed to remove {}: {e}", output.display()))?;
+        }
+    } else {
+        ctx.emit(Event::FileCreated { path: output });
+    }
+    ctx.record(action, input, output, count);
+    if let Some(changes) = &mut ctx.patch {
+        changes.push(patch::Change {
+            from: input.to_path_buf(),
+            to: output.to_path_buf(),
+            content: content.map(|content| (ctx.fs.read(input).unwrap_or_default(), content.to_vec())),
+        });
+    }
+    if ctx.dry_run {
+        return Ok(());
+    }
+    match content {
+        Some(content) => ctx.fs.write(output, content).map_err(|e| format!("Failed to write {}: {e}", output.display())),
+        None => copy_file(input, output, ctx),
+    }
+}
+
+fn copy_file(input: &Path, output: &Path, ctx: &Context) -> Result<(), String> {
+    ctx.fs.copy(input, output).map_err(|e| format!("Failed to copy {} to {}: {e}", input.display(), output.display()))
+}
+
+// Rename the paths and contents of the members of an archive and repack it. Returns false if the
+// archive can't be read, so it is handled like any other file.
+fn rename_archive(input: &Path, output: &Path, kind: archive::Kind, ctx: &mut Context) -> Result<bool, String> {
+    let Ok(bytes) = ctx.fs.read(input) else {
+        return Ok(false);
+    };
+    let rewritten = archive::rewrite(kind, &bytes, |name, data| {
+        let mut count = 0;
+        let renamed = name
+            .split('/')
+            .map(|component| {
+                let (new_component, substitutions) = rename_entry(component, ctx);
+                count += substitutions.iter().map(|s| s.count).sum::<usize>();
+                new_component
+            })
+            .collect::<Vec<_>>();
+        *name = renamed.join("/");
+        if mime::detect(data).text
+            && let Ok(content) = std::str::from_utf8(data)
+        {
+            let (new_content, substitutions) = ctx.transform_text(content);
+            count += substitutions.iter().map(|s| s.count).sum::<usize>();
+            *data = new_content.into_bytes();
+        }
+        count
+    });
+    let (new_bytes, count) = match rewritten {
+        Ok(rewritten) => rewritten,
+        Err(e) => {
+            ctx.error(&format!("Failed to read the archive {}, copying it unchanged: {e}", input.display()));
+            return Ok(false);
+        }
+    };
+    if count == 0 {
+        return Ok(false);
+    }
+
+    ctx.emit(Event::ContentRewritten { path: input, substitutions: count });
+    ctx.stats.substitutions += count;
+    write_output(input, output, Some(&new_bytes), Action::Write, count, ctx)?;
+    Ok(true)
+}
+
+// Pass the content through the built-in replacer and then every other one, making their
+// replacements in turn. Returns None if a replacer vetoed any change to the file.
+fn run_replacers(input: &Path, content: &str, ctx: &mut Context) -> Result<Option<(String, Vec<Substitution>)>, String> {
+    let path = input.strip_prefix(&ctx.input).unwrap_or(input);
+    let old_name = ctx.old_name.parts.join("-");
+    let new_name = ctx.new_name.parts.join("-");
+    let rules = ctx.config.for_file(path);
+    let maven_keys = (ctx.ecosystems.contains(Ecosystem::Maven) && maven::is_pom(path)).then(maven::keys);
+    let literals = ctx.literals();
+    let mut case_variants = replace::CaseVariants::new(&ctx.old_name, &ctx.new_name)
+        .with_cases(rules.cases)
+        .with_nodes(rules.nodes.or(ctx.nodes.as_deref()))
+        .with_keys(rules.keys.or(maven_keys.as_deref()))
+        .with_ecosystems(ctx.ecosystems)
+        .with_identifier_boundaries(ctx.identifier_boundaries)
+        .with_other_names(ctx.org.as_slice())
+        .with_literals(&literals);
+    let mut rust_crates = ctx.ecosystems.contains(Ecosystem::RustCrates).then(|| replace::RustCrates::new(&ctx.old_name, &ctx.new_name));
+    let mut domains = ctx.domain.as_ref().map(|(old, new)| replace::Domains::new(old, new));
+    let mut copyright = ctx.copyright.as_ref().map(|(old, new)| replace::Copyright::new(old, new, ctx.copyright_year));
+    let project_guids = ctx.regen_vs_guids.then_some(&ctx.project_guids);
+    let mut uuids = (!ctx.regen_uuids.is_empty() || ctx.regen_vs_guids)
+        .then(|| replace::Uuids::new(&ctx.regen_uuids, &mut ctx.uuids).with_project_guids(project_guids));
+    let mut regexes = replace::Regexes::new(ctx.replace_regex.iter().chain(rules.regex).collect());
+    let mut npm_package = ctx.npm_package.as_ref().map(|(old, new)| replace::NpmPackage::new(old, new));
+    let mut ecosystems = replace::ecosystem_replacers(ctx.ecosystems, &ctx.old_name, &ctx.new_name);
+    let mut text_domains = ctx.text_domain.as_ref().map(|(old, new)| replace::TextDomains::new(old, new));
+    let replacers = npm_package
+        .as_mut()
+        .map(|replacer| replacer as &mut dyn replace::Replacer)
+        .into_iter()
+        .chain(ecosystems.iter_mut().map(|replacer| replacer.as_mut() as &mut dyn replace::Replacer))
+        .chain(text_domains.as_mut().map(|replacer| replacer as &mut dyn replace::Replacer))
+        .chain(std::iter::once(&mut case_variants as &mut dyn replace::Replacer))
+        .chain(rust_crates.as_mut().map(|replacer| replacer as &mut dyn replace::Replacer))
+        .chain(domains.as_mut().map(|replacer| replacer as &mut dyn replace::Replacer))
+        .chain(copyright.as_mut().map(|replacer| replacer as &mut dyn replace::Replacer))
+        .chain(uuids.as_mut().map(|replacer| replacer as &mut dyn replace::Replacer))
+        .chain(std::iter::once(&mut regexes as &mut dyn replace::Replacer))
+        .chain(ctx.replacers.iter_mut().map(|replacer| replacer.as_mut() as &mut dyn replace::Replacer));
+
+    let mut text = content.to_string();
+    let mut substitutions = vec![];
+    for replacer in replacers {
+        let file = replace::File { path, original: content, text: &text, old_name: &old_name, new_name: &new_name };
+        match replacer.replace(&file) {
+            Ok(replace::Edits::Replaced { text: new_text, substitutions: made }) => {
+                text = new_text;
+                substitutions.extend(made);
+            }
+            Ok(replace::Edits::Veto) => {
+                ctx.out.emit(Event::PluginVeto { path: input, plugin: replacer.name() });
+                return Ok(None);
+            }
+            Err(e) => return Err(format!("Replacer {} failed on {}: {e}", replacer.name(), input.display())),
+        }
+    }
+    Ok(Some((text, substitutions)))
+}
+
+// Replace the content hash of a composer.lock with the hash of the renamed composer.json next to
+// it. A lock whose hash doesn't match the composer.json before the rename was already outdated and
+// is left for Composer to update.
+fn update_content_hash(input: &Path, lock: &mut String, ctx: &mut Context) -> Option<Substitution> {
+    let composer_json = input.with_file_name("composer.json");
+    let content = String::from_utf8(ctx.fs.read(&composer_json).ok()?).ok()?;
+    let range = composer::lock_hash(lock)?;
+    let old_hash = lock[range.clone()].to_string();
+    if composer::content_hash(&content)? != old_hash {
+        return None;
+    }
+    let (renamed, _) = run_replacers(&composer_json, &content, ctx).ok().flatten()?;
+    let new_hash = composer::content_hash(&renamed)?;
+    if new_hash == old_hash {
+        return None;
+    }
+    lock.replace_range(range, &new_hash);
+    Some(Substitution { from: old_hash, to: new_hash, count: 1 })
+}
+
+// Replace all case variants of the old name with the new name, returning the new text and the
+// substitutions made
+fn transform_text(input: &str, old_name: &NormalizedName, new_name: &NormalizedName) -> (String, Vec<Substitution>) {
+    transform_text_cases(input, old_name, new_name, &CaseInfo::all_cases(), false, &[], &[])
+}
+
+// Replace the given case variants of the old name with the new name, and of the other names (e.g.
+// the organization) and the extra literal replacements in the same pass
+fn transform_text_cases(
+    input: &str,
+    old_name: &NormalizedName,
+    new_name: &NormalizedName,
+    cases: &[CaseInfo],
+    identifier_boundaries: bool,
+    other_names: &[(NormalizedName, NormalizedName)],
+    literals: &[(String, String)],
+) -> (String, Vec<Substitution>) {
+    let mut variants: Vec<(String, String)> = vec![];
+    for (old_name, new_name) in std::iter::once((old_name, new_name)).chain(other_names.iter().map(|(old, new)| (old, new))) {
+        for case_info in cases {
+            let search_for = case_info.convert(old_name.clone());
+            // Names with a single part look the same with every separator, the first variant wins
+            if !search_for.is_empty() && !variants.iter().any(|(from, _)| *from == search_for) {
+                variants.push((search_for, case_info.convert(new_name.clone())));
+            }
+        }
+        // A mixed separator name isn't one of the generated variants, replace it as it was given
+        if old_name.has_mixed_separators() {
+            variants.push((old_name.original.clone(), new_name.original.clone()));
+        }
+    }
+    // Literals are matched exactly, without identifier boundaries
+    let case_variants = variants.len();
+    for (from, to) in literals {
+        if !from.is_empty() && !variants.iter().any(|(variant, _)| variant == from) {
+            variants.push((from.clone(), to.clone()));
+        }
+    }
+
+    // Replace in a single pass, preferring the longest variant at every position, so text that was
+    // already replaced is never matched again. Otherwise a new name containing the old name (e.g.
+    // "project" to "my-project") would be replaced over and over.
+    let mut by_length = (0..variants.len()).collect::<Vec<_>>();
+    by_length.sort_by_key(|&index| std::cmp::Reverse(variants[index].0.len()));
+    let first_bytes = variants.iter().map(|(from, _)| from.as_bytes()[0]).collect::<Vec<_>>();
+
+    let mut out = String::with_capacity(input.len());
+    let mut counts = vec![0; variants.len()];
+    let mut copied_until = 0;
+    let mut pos = 0;
+    while pos < input.len() {
+        if first_bytes.contains(&input.as_bytes()[pos]) {
+            let rest = &input[pos..];
+            // With identifier boundaries a variant must not be surrounded by letters, digits or
+            // underscores, so "core" isn't found in "score_counter"
+            let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+            let bounded = |index: usize, len: usize| {
+                !identifier_boundaries
+                    || index >= case_variants
+                    || (!input[..pos].chars().next_back().is_some_and(is_identifier)
+                        && !rest[len..].chars().next().is_some_and(is_identifier))
+            };
+            if let Some(&index) =
+                by_length.iter().find(|&&index| rest.starts_with(&variants[index].0) && bounded(index, variants[index].0.len()))
+            {
+                out.push_str(&input[copied_until..pos]);
+                out.push_str(&variants[index].1);
+                counts[index] += 1;
+                pos += variants[index].0.len();
+                copied_until = pos;
+                continue;
+            }
+        }
+        pos += 1;
+        while !input.is_char_boundary(pos) {
+            pos += 1;
+        }
+    }
+    out.push_str(&input[copied_until..]);
+
+    let substitutions = variants
+        .into_iter()
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .map(|((from, to), count)| Substitution { from, to, count })
+        .collect();
+    (out, substitutions)
+}
+
+// This struct is used to store the case information of the project name
+// It contains the separator and the type of case (capitalise, upper case, lower case)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CaseInfo {
+    separator: Option<char>,
+    part_type: CaseType
+}
+
+// This enum is used to store the type of case (capitalise, upper case, lower case)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CaseType {
+    Capitalise, // My Project
+    UpperCase, // MY PROJECT
+    LowerCase, // my project
+}
+
+impl CaseInfo {
+    // Every combination of a case type and a separator, e.g. for "my project":
+    // - Capitalise: "MyProject", "My Project", "My_Project", "My-Project" (Train-Case), "My.Project", "My/Project"
+    // - UpperCase: "MYPROJECT", "MY PROJECT", "MY_PROJECT", "MY-PROJECT" (SCREAMING-KEBAB), "MY.PROJECT", "MY/PROJECT"
+    // - LowerCase: "myproject", "my project", "my_project", "my-project", "my.project" (dot.case), "my/project" (path/case)
+    fn all_cases() -> Vec<CaseInfo> {
+        let mut cases = vec![];
+        for part_type in [CaseType::Capitalise, CaseType::UpperCase, CaseType::LowerCase].iter() {
+            cases.push(CaseInfo {
+                separator: None,
+                part_type: *part_type,
+            });
+            for separator in SEPARATORS.iter() {
+                cases.push(CaseInfo {
+                    separator: Some(*separator),
+                    part_type: *part_type,
+                });
+            }
+        }
+        cases
+    }
+
+    fn detect(name: &str) -> (Self, NormalizedName) {
+        // Split on all separators at once so mixed names like "my_cool-project" are split into
+        // all their parts, the first separator is used as the separator of the name
+        let separator = name.chars().find(|c| SEPARATORS.contains(c));
+        let parts = name
+            .split(|c| SEPARATORS.contains(&c))
+            .filter(|part| !part.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        // Only letters with a case decide the case type, so digits and scripts without case
+        // (e.g. "project2" or "项目") don't turn a lower or upper case name into a capitalised one
+        let has_upper = parts.iter().any(|s| s.chars().any(char::is_uppercase));
+        let has_lower = parts.iter().any(|s| s.chars().any(char::is_lowercase));
+        let part_type = match (has_upper, has_lower) {
+            (true, false) => CaseType::UpperCase,
+            (false, _) => CaseType::LowerCase,
+            (true, true) => CaseType::Capitalise,
+        };
+
+        (
+            Self {
+                separator,
+                part_type,
+            },
+            NormalizedName {
+                parts: parts.into_iter().map(|s| s.to_lowercase()).collect(),
+                acronyms: vec![],
+                original: name.to_string(),
+            },
+        )
+    }
+
+    fn convert(&self, normalized_name: NormalizedName) -> String {
+        let separator = if let Some(separator) = self.separator {
+            separator.to_string()
+        } else {
+            "".to_string()
+        };
+
+        normalized_name.parts.iter()
+            .map(|part| match self.part_type {
+                CaseType::Capitalise => match normalized_name.acronym(part) {
+                    Some(acronym) => acronym.to_string(),
+                    None => capitalise(part),
+                },
+                CaseType::UpperCase => part.to_uppercase(),
+                CaseType::LowerCase => part.to_lowercase(),
+            })
+            .collect::<Vec<_>>()
+            .join(&separator)
+    }
+}
+
+// Upper case the first character of a word, which may be more than one byte long
+fn capitalise(word: &str) -> String {
+    let mut chars = word.chars();
+    let Some(first) = chars.next() else {
+        return String::new();
+    };
+    // Digraphs have a title case that differs from their upper case, e.g. "ǆungla" -> "ǅungla"
+    let first = match first {
+        'Ǆ' | 'ǅ' | 'ǆ' => "ǅ".to_string(),
+        'Ǉ' | 'ǈ' | 'ǉ' => "ǈ".to_string(),
+        'Ǌ' | 'ǋ' | 'ǌ' => "ǋ".to_string(),
+        'Ǳ' | 'ǲ' | 'ǳ' => "ǲ".to_string(),
+        c => c.to_uppercase().collect(),
+    };
+    first + chars.as_str()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NormalizedName {
+    parts: Vec<String>,
+    // Parts with a canonical capitalization that is kept when capitalising, e.g. "AWS" or "GraphQL"
+    acronyms: Vec<String>,
+    // The name as it was given
+    original: String,
+}
+
+impl NormalizedName {
+    fn with_acronyms(mut self, acronyms: &[String]) -> Self {
+        self.acronyms = acronyms.to_vec();
+        self
+    }
+
+    // Whether the original name uses more than one kind of separator, e.g. "my_cool-project"
+    fn has_mixed_separators(&self) -> bool {
+        let mut separators = self.original.chars().filter(|c| SEPARATORS.contains(c));
+        let first = separators.next();
+        separators.any(|c| Some(c) != first)
+    }
+
+    // Canonical capitalization of the part if it is an acronym
+    fn acronym(&self, part: &str) -> Option<&str> {
+        self.acronyms
+            .iter()
+            .find(|acronym| acronym.to_lowercase() == part)
+            .map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Args {
+        Cli::parse_from(std::iter::once("project-renamer").chain(args.iter().copied())).args.unwrap()
+    }
+
+    #[test]
+    fn test_detect_title_case() {
+        let name = "My Project";
+        let (case_info, normalized_name) = CaseInfo::detect(name);
+        assert_eq!(case_info.separator, Some(' '));
+        assert_eq!(case_info.part_type, CaseType::Capitalise);
+        assert_eq!(normalized_name.parts, vec!["my", "project"]);
+    }
+
+    #[test]
+    fn test_detect_upper_case() {
+        let name = "MY PROJECT";
+        let (case_info, normalized_name) = CaseInfo::detect(name);
+        assert_eq!(case_info.separator, Some(' '));
+        assert_eq!(case_info.part_type, CaseType::UpperCase);
+        assert_eq!(normalized_name.parts, vec!["my", "project"]);
+    }
+
+    #[test]
+    fn test_detect_lower_case() {
+        let name = "my project";
+        let (case_info, normalized_name) = CaseInfo::detect(name);
+        assert_eq!(case_info.separator, Some(' '));
+        assert_eq!(case_info.part_type, CaseType::LowerCase);
+        assert_eq!(normalized_name.parts, vec!["my", "project"]);
+    }
+
+    #[test]
+    fn test_detect_snake_case() {
+        let name = "my_project";
+        let (case_info, normalized_name) = CaseInfo::detect(name);
+        assert_eq!(case_info.separator, Some('_'));
+        assert_eq!(case_info.part_type, CaseType::LowerCase);
+        assert_eq!(normalized_name.parts, vec!["my", "project"]);
+    }
+
+    #[test]
+    fn test_detect_kebab_case() {
+        let name = "my-project";
+        let (case_info, normalized_name) = CaseInfo::detect(name);
+        assert_eq!(case_info.separator, Some('-'));
+        assert_eq!(case_info.part_type, CaseType::LowerCase);
+        assert_eq!(normalized_name.parts, vec!["my", "project"]);
+    }
+
+    #[test]
+    fn test_detect_mumble_case() {
+        let name = "myproject";
+        let (case_info, normalized_name) = CaseInfo::detect(name);
+        assert_eq!(case_info.separator, None);
+        assert_eq!(case_info.part_type, CaseType::LowerCase);
+        assert_eq!(normalized_name.parts, vec!["myproject"]);
+    }
+
+    #[test]
+    fn test_detect_const_case() {
+        let name = "MY_PROJECT";
+        let (case_info, normalized_name) = CaseInfo::detect(name);
+        assert_eq!(case_info.separator, Some('_'));
+        assert_eq!(case_info.part_type, CaseType::UpperCase);
+        assert_eq!(normalized_name.parts, vec!["my", "project"]);
+    }
+
+    #[test]
+    fn test_detect_mixed_separators() {
+        let (case_info, normalized_name) = CaseInfo::detect("my_cool-project");
+        assert_eq!(case_info.separator, Some('_'));
+        assert_eq!(case_info.part_type, CaseType::LowerCase);
+        assert_eq!(normalized_name.parts, vec!["my", "cool", "project"]);
+        assert!(normalized_name.has_mixed_separators());
+        assert!(!CaseInfo::detect("my-cool-project").1.has_mixed_separators());
+    }
+
+    #[test]
+    fn test_transform_text_mixed_separators() {
+        let (_, old_name) = CaseInfo::detect("my_cool-project");
+        let (_, new_name) = CaseInfo::detect("new-project");
+        let (out, _) = transform_text("my_cool-project MyCoolProject my-cool-project MY_COOL_PROJECT", &old_name, &new_name);
+        assert_eq!(out, "new-project NewProject new-project NEW_PROJECT");
+    }
+
+    #[test]
+    fn test_transform_text_identifier_boundaries() {
+        let (_, old_name) = CaseInfo::detect("core");
+        let (_, new_name) = CaseInfo::detect("kernel");
+        let text = "score_counter core core_v2 Core.init() (CORE) coreé";
+        let (out, _) = transform_text_cases(text, &old_name, &new_name, &CaseInfo::all_cases(), true, &[], &[]);
+        assert_eq!(out, "score_counter kernel core_v2 Kernel.init() (KERNEL) coreé");
+        let (out, _) = transform_text_cases(text, &old_name, &new_name, &CaseInfo::all_cases(), false, &[], &[]);
+        assert_eq!(out, "skernel_counter kernel kernel_v2 Kernel.init() (KERNEL) kernelé");
+    }
+
+    #[test]
+    fn test_detect_unicode() {
+        let (case_info, normalized_name) = CaseInfo::detect("ÉTÉ-PROJET");
+        assert_eq!(case_info.part_type, CaseType::UpperCase);
+        assert_eq!(normalized_name.parts, vec!["été", "projet"]);
+
+        assert_eq!(CaseInfo::detect("project2").0.part_type, CaseType::LowerCase);
+        assert_eq!(CaseInfo::detect("PROJECT2").0.part_type, CaseType::UpperCase);
+        assert_eq!(CaseInfo::detect("项目-名称").0.part_type, CaseType::LowerCase);
+    }
+
+    #[test]
+    fn test_convert_unicode() {
+        let (_, normalized_name) = CaseInfo::detect("été-straße");
+        assert_eq!(CaseInfo::detect("My-Project").0.convert(normalized_name.clone()), "Été-Straße");
+        assert_eq!(CaseInfo::detect("MY-PROJECT").0.convert(normalized_name), "ÉTÉ-STRASSE");
+
+        let (_, normalized_name) = CaseInfo::detect("ǆungla 项目");
+        assert_eq!(CaseInfo::detect("My Project").0.convert(normalized_name), "ǅungla 项目");
+    }
+
+    #[test]
+    fn test_convert_title_case() {
+        let name = "my project";
+        let (_, normalized_name) = CaseInfo::detect(name);
+        let new_name = CaseInfo::detect("My Project").0.convert(normalized_name);
+        assert_eq!(new_name, "My Project");
+    }
+
+    #[test]
+    fn test_convert_upper_case() {
+        let name = "my project";
+        let (_, normalized_name) = CaseInfo::detect(name);
+        let new_name = CaseInfo::detect("MY PROJECT").0.convert(normalized_name);
+        assert_eq!(new_name, "MY PROJECT");
+    }
+
+    #[test]
+    fn test_convert_lower_case() {
+        let name = "My Project";
+        let (_, normalized_name) = CaseInfo::detect(name);
+        let new_name = CaseInfo::detect("my project").0.convert(normalized_name);
+        assert_eq!(new_name, "my project");
+    }
+
+    #[test]
+    fn test_convert_snake_case() {
+        let name = "my project";
+        let (_, normalized_name) = CaseInfo::detect(name);
+        let new_name = CaseInfo::detect("my_project").0.convert(normalized_name);
+        assert_eq!(new_name, "my_project");
+    }
+
+    #[test]
+    fn test_convert_kebab_case() {
+        let name = "my project";
+        let (_, normalized_name) = CaseInfo::detect(name);
+        let new_name = CaseInfo::detect("my-project").0.convert(normalized_name);
+        assert_eq!(new_name, "my-project");
+    }
+
+    #[test]
+    fn test_convert_mumble_case() {
+        let name = "my project";
+        let (_, normalized_name) = CaseInfo::detect(name);
+        let new_name = CaseInfo::detect("myproject").0.convert(normalized_name);
+        assert_eq!(new_name, "myproject");
+    }
+
+    #[test]
+    fn test_convert_const_case() {
+        let name = "my project";
+        let (_, normalized_name) = CaseInfo::detect(name);
+        let new_name = CaseInfo::detect("MY_PROJECT").0.convert(normalized_name);
+        assert_eq!(new_name, "MY_PROJECT");
+    }
+
+    #[test]
+    fn test_transform_text_counts_substitutions() {
+        let (_, old_name) = CaseInfo::detect("my-project");
+        let (_, new_name) = CaseInfo::detect("new-project");
+        let (out, substitutions) = transform_text("my-project MY_PROJECT MyProject my-project", &old_name, &new_name);
+        assert_eq!(out, "new-project NEW_PROJECT NewProject new-project");
+        assert_eq!(substitutions.iter().map(|s| s.count).sum::<usize>(), 4);
+        assert!(substitutions.contains(&Substitution {
+            from: "my-project".to_string(),
+            to: "new-project".to_string(),
+            count: 2,
+        }));
+
+        let (out, substitutions) = transform_text("nothing to see here", &old_name, &new_name);
+        assert_eq!(out, "nothing to see here");
+        assert!(substitutions.is_empty());
+    }
+
+    #[test]
+    fn test_transform_text_case_styles() {
+        let (_, old_name) = CaseInfo::detect("old-app");
+        let (_, new_name) = CaseInfo::detect("new-app");
+        let (out, _) = transform_text("Old-App old.app OLD-APP old/app", &old_name, &new_name);
+        assert_eq!(out, "New-App new.app NEW-APP new/app");
+
+        for (name, case_info) in [("My-Project", "My-Project"), ("my.project", "my.project"), ("MY-PROJECT", "MY-PROJECT"), ("my/project", "my/project")] {
+            let (detected, normalized_name) = CaseInfo::detect(name);
+            assert_eq!(normalized_name.parts, vec!["my", "project"]);
+            assert_eq!(detected.convert(normalized_name), case_info);
+        }
+    }
+
+    #[test]
+    fn test_transform_text_new_name_contains_old_name() {
+        let (_, old_name) = CaseInfo::detect("project");
+        let (_, new_name) = CaseInfo::detect("my-project");
+        let (out, substitutions) = transform_text("Project project PROJECT", &old_name, &new_name);
+        assert_eq!(out, "MyProject myproject MYPROJECT");
+        assert_eq!(substitutions.len(), 3);
+
+        let (_, old_name) = CaseInfo::detect("old-app");
+        let (_, new_name) = CaseInfo::detect("old-app-v2");
+        let (out, _) = transform_text("Old-App old.app OLD_APP", &old_name, &new_name);
+        assert_eq!(out, "Old-App-V2 old.app.v2 OLD_APP_V2");
+    }
+
+    #[test]
+    fn test_convert_acronyms() {
+        let acronyms = ["AWS".to_string(), "SDK".to_string(), "GraphQL".to_string()];
+        let (_, name) = CaseInfo::detect("aws-sdk-graphql-tool");
+        let name = name.with_acronyms(&acronyms);
+        assert_eq!(CaseInfo::detect("MyProject").0.convert(name.clone()), "AWSSDKGraphQLTool");
+        assert_eq!(CaseInfo::detect("My-Project").0.convert(name.clone()), "AWS-SDK-GraphQL-Tool");
+        assert_eq!(CaseInfo::detect("MY_PROJECT").0.convert(name.clone()), "AWS_SDK_GRAPHQL_TOOL");
+        assert_eq!(CaseInfo::detect("my-project").0.convert(name), "aws-sdk-graphql-tool");
+    }
+
+    // Create an empty directory in the system temp directory that is unique to the test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("project-renamer-{}-{}", name, std::process::id()));
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_concatenated_old_name() {
+        let dir = temp_dir("concatenated-old-name");
+        std::fs::create_dir_all(dir.join("myproject")).unwrap();
+        std::fs::write(dir.join("myproject/main.rs"), "use my_project::MyProject;\nmyproject").unwrap();
+        start(parse_args(&[
+            "--name", "new-project",
+            "--input", dir.join("myproject").to_str().unwrap(),
+        ]));
+        let content = std::fs::read_to_string(dir.join("new-project/main.rs")).unwrap();
+        assert_eq!(content, "use new_project::NewProject;\nnewproject");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_single_file() {
+        let dir = temp_dir("single-file");
+        std::fs::write(dir.join("my-project.rs"), "use my_project::MyProject;").unwrap();
+        let stats = start(parse_args(&[
+            "--name", "new-project",
+            "--input", dir.join("my-project.rs").to_str().unwrap(),
+        ]));
+        let content = std::fs::read_to_string(dir.join("new-project.rs")).unwrap();
+        assert_eq!(content, "use new_project::NewProject;");
+        assert_eq!(stats.substitutions, 2);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    // A hard link with a name that only differs in case behaves like the same file on a
+    // case-insensitive filesystem
+    #[cfg(unix)]
+    #[test]
+    fn test_case_only_rename() {
+        let dir = temp_dir("case-only");
+        std::fs::write(dir.join("MyProject.txt"), "MyProject").unwrap();
+        std::fs::hard_link(dir.join("MyProject.txt"), dir.join("Myproject.txt")).unwrap();
+        assert!(is_case_only_rename(&dir.join("MyProject.txt"), &dir.join("Myproject.txt")));
+
+        let stats = start(parse_args(&[
+            "--name", "Myproject",
+            "--input", dir.join("MyProject.txt").to_str().unwrap(),
+        ]));
+        assert_eq!(stats.substitutions, 1);
+        assert!(!dir.join("MyProject.txt").exists());
+        assert_eq!(std::fs::read_to_string(dir.join("Myproject.txt")).unwrap(), "Myproject");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        // Staging can happen in another directory
+        std::fs::create_dir(dir.join("staging")).unwrap();
+        std::fs::rename(dir.join("Myproject.txt"), dir.join("MyProject.txt")).unwrap();
+        std::fs::hard_link(dir.join("MyProject.txt"), dir.join("Myproject.txt")).unwrap();
+        start(parse_args(&[
+            "--name", "Myproject",
+            "--input", dir.join("MyProject.txt").to_str().unwrap(),
+            "--temp-dir", dir.join("staging").to_str().unwrap(),
+        ]));
+        assert!(!dir.join("MyProject.txt").exists());
+        assert!(dir.join("Myproject.txt").exists());
+        assert_eq!(std::fs::read_dir(dir.join("staging")).unwrap().count(), 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_staged_output() {
+        let dir = temp_dir("staged");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/my-project.txt"), "my-project").unwrap();
+        start(parse_args(&["--name", "new-project", "--input", dir.join("my-project").to_str().unwrap()]));
+        let mut entries = std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name()).collect::<Vec<_>>();
+        entries.sort();
+        assert_eq!(entries, vec!["my-project", "new-project"]);
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/new-project.txt")).unwrap(), "new-project");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_move() {
+        let dir = temp_dir("move");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/my-project.txt"), "my-project").unwrap();
+        start(parse_args(&["--name", "new-project", "--input", dir.join("my-project").to_str().unwrap(), "--move"]));
+        assert!(!dir.join("my-project").exists());
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/new-project.txt")).unwrap(), "new-project");
+
+        start(parse_args(&["--name", "newer-project", "--input", dir.join("new-project").to_str().unwrap(), "--move", "--backup"]));
+        assert!(!dir.join("new-project").exists());
+        assert!(dir.join("newer-project/newer-project.txt").exists());
+        assert!(std::fs::metadata(dir.join("new-project-backup.tar")).unwrap().len() > 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_workspace() {
+        let dir = temp_dir("workspace");
+        std::fs::create_dir_all(dir.join("crates/my-lib/src")).unwrap();
+        std::fs::create_dir_all(dir.join("crates/app")).unwrap();
+        std::fs::write(dir.join("Cargo.toml"), "[workspace]\nmembers = [\"crates/app\", \"crates/my-lib\"]\n").unwrap();
+        std::fs::write(dir.join("crates/my-lib/Cargo.toml"), "[package]\nname = \"my-lib\"\n").unwrap();
+        std::fs::write(dir.join("crates/my-lib/src/lib.rs"), "").unwrap();
+        std::fs::write(dir.join("crates/app/Cargo.toml"), "[dependencies]\nmy-lib = { path = \"../my-lib\" }\n").unwrap();
+        std::fs::write(dir.join("tsconfig.json"), r#"{"paths": {"@org/my-lib": ["crates/my-lib/src"]}}"#).unwrap();
+
+        start(parse_args(&["--name", "new-lib", "--input", dir.join("crates/my-lib").to_str().unwrap(), "--move", "--workspace"]));
+        assert_eq!(std::fs::read_to_string(dir.join("Cargo.toml")).unwrap(), "[workspace]\nmembers = [\"crates/app\", \"crates/new-lib\"]\n");
+        assert_eq!(std::fs::read_to_string(dir.join("crates/app/Cargo.toml")).unwrap(), "[dependencies]\nnew-lib = { path = \"../new-lib\" }\n");
+        assert_eq!(std::fs::read_to_string(dir.join("tsconfig.json")).unwrap(), r#"{"paths": {"@org/new-lib": ["crates/new-lib/src"]}}"#);
+        assert_eq!(std::fs::read_to_string(dir.join("crates/new-lib/Cargo.toml")).unwrap(), "[package]\nname = \"new-lib\"\n");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_workspace_memory() {
+        use fs::Filesystem;
+
+        let fs = Arc::new(
+            fs::Memory::new()
+                .with_file("ws/Cargo.toml", "[workspace]\nmembers = [\"crates/app\", \"crates/my-lib\"]\n")
+                .with_file("ws/crates/my-lib/Cargo.toml", "[package]\nname = \"my-lib\"\n")
+                .with_file("ws/crates/app/Cargo.toml", "[dependencies]\nmy-lib = { path = \"../my-lib\" }\n"),
+        );
+        let out = Output::new(Verbosity::Quiet, LogFormat::Pretty, ColorChoice::Never);
+        let mut ctx = Context::with_fs(Path::new("ws/crates/my-lib"), "new-lib", out, fs.clone());
+        ctx.workspace = true;
+        ctx.move_input = true;
+        run(&mut ctx).unwrap();
+        assert_eq!(fs.read(Path::new("ws/Cargo.toml")).unwrap(), b"[workspace]\nmembers = [\"crates/app\", \"crates/new-lib\"]\n");
+        assert_eq!(fs.read(Path::new("ws/crates/app/Cargo.toml")).unwrap(), b"[dependencies]\nnew-lib = { path = \"../new-lib\" }\n");
+        assert_eq!(fs.read(Path::new("ws/crates/new-lib/Cargo.toml")).unwrap(), b"[package]\nname = \"new-lib\"\n");
+        assert!(!fs.exists(Path::new("ws/crates/my-lib")));
+    }
+
+    #[test]
+    fn test_maven() {
+        let dir = temp_dir("maven");
+        std::fs::create_dir_all(dir.join("my-lib")).unwrap();
+        std::fs::create_dir_all(dir.join("app")).unwrap();
+        std::fs::write(dir.join("pom.xml"), "<project><modules><module>app</module><module>my-lib</module></modules></project>\n").unwrap();
+        let pom = |body: &str| format!("<project>\n  <parent><artifactId>parent</artifactId></parent>\n{body}</project>\n");
+        std::fs::write(dir.join("my-lib/pom.xml"), pom("  <artifactId>my-lib</artifactId>\n  <description>The my-lib library</description>\n")).unwrap();
+        let app = "  <description>Uses my-lib</description>\n  <dependencies><dependency><artifactId>my-lib</artifactId></dependency></dependencies>\n";
+        std::fs::write(dir.join("app/pom.xml"), pom(app)).unwrap();
+
+        start(parse_args(&["--name", "new-lib", "--input", dir.join("my-lib").to_str().unwrap(), "--move", "--workspace", "--maven"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("pom.xml")).unwrap(),
+            "<project><modules><module>app</module><module>new-lib</module></modules></project>\n"
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("app/pom.xml")).unwrap(), pom(&app.replacen("<artifactId>my-lib", "<artifactId>new-lib", 1)));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-lib/pom.xml")).unwrap(),
+            pom("  <artifactId>new-lib</artifactId>\n  <description>The my-lib library</description>\n")
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_gradle() {
+        let dir = temp_dir("gradle");
+        std::fs::create_dir_all(dir.join("my-app/app")).unwrap();
+        std::fs::write(dir.join("my-app/settings.gradle.kts"), "rootProject.name = \"my-app\"\ninclude(\":app\", \":my-app-core\")\n").unwrap();
+        let build = "description = \"Runs my-app\"\ndependencies {\n    implementation(project(\":my-app-core\"))\n}\n";
+        std::fs::write(dir.join("my-app/app/build.gradle.kts"), build).unwrap();
+
+        start(parse_args(&["--name", "new-app", "--input", dir.join("my-app").to_str().unwrap(), "--gradle"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/settings.gradle.kts")).unwrap(),
+            "rootProject.name = \"new-app\"\ninclude(\":app\", \":new-app-core\")\n"
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("new-app/app/build.gradle.kts")).unwrap(), build.replace(":my-app-core", ":new-app-core"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_nix() {
+        let dir = temp_dir("nix");
+        std::fs::create_dir_all(dir.join("my-app")).unwrap();
+        std::fs::write(dir.join("my-app/flake.nix"), "{\n  inputs.my-app-assets.url = \"github:acme/my-app-assets\";\n}\n").unwrap();
+        let lock = r#"{"nodes": {"my-app-assets": {"locked": {"repo": "my-app-assets"}}, "root": {"inputs": {"my-app-assets": "my-app-assets"}}}}"#;
+        std::fs::write(dir.join("my-app/flake.lock"), lock).unwrap();
+
+        start(parse_args(&["--name", "new-app", "--input", dir.join("my-app").to_str().unwrap(), "--nix"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/flake.nix")).unwrap(),
+            "{\n  inputs.new-app-assets.url = \"github:acme/new-app-assets\";\n}\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/flake.lock")).unwrap(),
+            r#"{"nodes": {"new-app-assets": {"locked": {"repo": "my-app-assets"}}, "root": {"inputs": {"new-app-assets": "new-app-assets"}}}}"#
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_bazel() {
+        let dir = temp_dir("bazel");
+        std::fs::create_dir_all(dir.join("my_app/my_app")).unwrap();
+        let module = "module(name = \"my_app\")\narchive_override(\n    module_name = \"my_app_deps\",\n    \
+                      urls = [\"https://x/my_app_deps.tgz\"],\n    strip_prefix = \"my_app_deps-1.0\",\n)\n";
+        std::fs::write(dir.join("my_app/MODULE.bazel"), module).unwrap();
+        std::fs::write(dir.join("my_app/my_app/BUILD"), "cc_binary(name = \"my_app\", deps = [\"//my_app:lib\", \"@my_app_deps//:x\"])\n").unwrap();
+
+        start(parse_args(&["--name", "new_app", "--input", dir.join("my_app").to_str().unwrap(), "--bazel"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new_app/MODULE.bazel")).unwrap(),
+            module.replace("\"my_app\"", "\"new_app\"").replace("\"my_app_deps\"", "\"new_app_deps\"")
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new_app/new_app/BUILD")).unwrap(),
+            "cc_binary(name = \"new_app\", deps = [\"//new_app:lib\", \"@new_app_deps//:x\"])\n"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cpp_packages() {
+        let dir = temp_dir("cpp_packages");
+        std::fs::create_dir_all(dir.join("my-lib/ports/my-lib")).unwrap();
+        std::fs::write(dir.join("my-lib/conanfile.txt"), "[requires]\nmy-lib-core/1.0\n[generators]\nmy-lib\n").unwrap();
+        let vcpkg = r#"{"name": "my-lib", "homepage": "https://github.com/acme/my-lib", "dependencies": ["my-lib-core"]}"#;
+        std::fs::write(dir.join("my-lib/ports/my-lib/vcpkg.json"), vcpkg).unwrap();
+
+        start(parse_args(&["--name", "new-lib", "--input", dir.join("my-lib").to_str().unwrap(), "--cpp-packages"]));
+        assert_eq!(std::fs::read_to_string(dir.join("new-lib/conanfile.txt")).unwrap(), "[requires]\nnew-lib-core/1.0\n[generators]\nmy-lib\n");
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-lib/ports/new-lib/vcpkg.json")).unwrap(),
+            r#"{"name": "new-lib", "homepage": "https://github.com/acme/my-lib", "dependencies": ["new-lib-core"]}"#
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_meson() {
+        let dir = temp_dir("meson");
+        std::fs::create_dir_all(dir.join("my-app/subprojects")).unwrap();
+        std::fs::write(dir.join("my-app/meson.build"), "project('my-app', 'c')\nmy_app_core = subproject('my-app-core')\n").unwrap();
+        let wrap = "[wrap-git]\nurl = https://github.com/acme/my-app-core.git\n\n[provide]\nmy-app-core = my_app_core_dep\n";
+        std::fs::write(dir.join("my-app/subprojects/my-app-core.wrap"), wrap).unwrap();
+
+        start(parse_args(&["--name", "new-app", "--input", dir.join("my-app").to_str().unwrap(), "--meson"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/meson.build")).unwrap(),
+            "project('new-app', 'c')\nmy_app_core = subproject('new-app-core')\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/subprojects/new-app-core.wrap")).unwrap(),
+            wrap.replace("\nmy-app-core =", "\nnew-app-core =")
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_makefiles() {
+        let dir = temp_dir("makefiles");
+        std::fs::create_dir_all(dir.join("my-app")).unwrap();
+        let makefile = "MY_APP_BIN := my-app\n\nmy-app: main.c\n\t$(CC) -o $(MY_APP_BIN) main.c\n\tcurl -O https://x/my-app.tgz\n";
+        std::fs::write(dir.join("my-app/Makefile"), makefile).unwrap();
+
+        start(parse_args(&["--name", "new-app", "--input", dir.join("my-app").to_str().unwrap(), "--makefiles"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/Makefile")).unwrap(),
+            "NEW_APP_BIN := new-app\n\nnew-app: main.c\n\t$(CC) -o $(NEW_APP_BIN) main.c\n\tcurl -O https://x/my-app.tgz\n"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_docker() {
+        let dir = temp_dir("docker");
+        std::fs::create_dir_all(dir.join("my-app")).unwrap();
+        let dockerfile = "FROM acme/my-app-base AS my-app-build\nRUN make my-app\n\nFROM alpine\nLABEL title=\"my-app\"\n\
+                          COPY --from=my-app-build /my-app /usr/bin/\n";
+        std::fs::write(dir.join("my-app/Dockerfile"), dockerfile).unwrap();
+        std::fs::write(dir.join("my-app/Makefile"), "image:\n\tdocker build -t my-app:latest .\n\tdocker push my-app:latest\n").unwrap();
+
+        start(parse_args(&["--name", "new-app", "--input", dir.join("my-app").to_str().unwrap(), "--docker", "--makefiles"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/Dockerfile")).unwrap(),
+            "FROM acme/my-app-base AS new-app-build\nRUN make new-app\n\nFROM alpine\nLABEL title=\"new-app\"\nCOPY --from=new-app-build /my-app /usr/bin/\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/Makefile")).unwrap(),
+            "image:\n\tdocker build -t new-app:latest .\n\tdocker push new-app:latest\n"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_kubernetes() {
+        let dir = temp_dir("kubernetes");
+        std::fs::create_dir_all(dir.join("my-app/k8s")).unwrap();
+        let service = "apiVersion: v1\nkind: Service\nmetadata:\n  name: my-app\n  annotations:\n    docs: https://my-app.example.com\n\
+                       spec:\n  selector:\n    app: my-app\n";
+        std::fs::write(dir.join("my-app/k8s/service.yaml"), service).unwrap();
+        std::fs::write(dir.join("my-app/ci.yml"), "name: my-app\n").unwrap();
+
+        start(parse_args(&["--name", "new-app", "--input", dir.join("my-app").to_str().unwrap(), "--kubernetes"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/k8s/service.yaml")).unwrap(),
+            service.replace("name: my-app", "name: new-app").replace("app: my-app", "app: new-app")
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("new-app/ci.yml")).unwrap(), "name: new-app\n");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_systemd() {
+        let dir = temp_dir("systemd");
+        std::fs::create_dir_all(dir.join("my-app/deploy")).unwrap();
+        let unit = "[Unit]\nDescription=my-app worker\nAfter=my-app.socket\n\n[Service]\nUser=my-app\nExecStart=/usr/bin/my-app work\n";
+        std::fs::write(dir.join("my-app/deploy/my-app.service"), unit).unwrap();
+        std::fs::write(dir.join("my-app/deploy/my-app.timer"), "[Timer]\nUnit=my-app.service\nOnCalendar=daily\n").unwrap();
+
+        start(parse_args(&["--name", "new-app", "--input", dir.join("my-app").to_str().unwrap(), "--systemd"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/deploy/new-app.service")).unwrap(),
+            "[Unit]\nDescription=new-app worker\nAfter=new-app.socket\n\n[Service]\nUser=my-app\nExecStart=/usr/bin/new-app work\n"
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("new-app/deploy/new-app.timer")).unwrap(), "[Timer]\nUnit=new-app.service\nOnCalendar=daily\n");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_homebrew() {
+        let dir = temp_dir("homebrew");
+        std::fs::create_dir_all(dir.join("my-api/HomebrewFormula")).unwrap();
+        let formula = "class MyApi < Formula\n  homepage \"https://github.com/acme/my-api\"\n\n  \
+                       test do\n    system bin/\"my-api\", \"--version\"\n  end\nend\n";
+        std::fs::write(dir.join("my-api/HomebrewFormula/my-api.rb"), formula).unwrap();
+
+        start(parse_args(&["--name", "new-api", "--input", dir.join("my-api").to_str().unwrap(), "--acronym", "API", "--homebrew"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-api/HomebrewFormula/new-api.rb")).unwrap(),
+            formula.replace("MyApi", "NewApi").replace("my-api", "new-api")
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_debian() {
+        let dir = temp_dir("debian");
+        std::fs::create_dir_all(dir.join("my-app/debian")).unwrap();
+        std::fs::write(dir.join("my-app/debian/control"), "Source: my-app\nVcs-Git: https://x/my-app.git\n\nPackage: my-app\n").unwrap();
+        let changelog = "my-app (1.0-1) unstable; urgency=low\n\n  * Initial my-app release.\n\n -- A <a@my-app.org>  Mon, 01 Jan 2024 00:00:00 +0000\n";
+        std::fs::write(dir.join("my-app/debian/changelog"), changelog).unwrap();
+        std::fs::write(dir.join("my-app/debian/my-app.postinst"), "#!/bin/sh\nsystemctl enable my-app\n").unwrap();
+
+        start(parse_args(&["--name", "new-app", "--input", dir.join("my-app").to_str().unwrap(), "--debian"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/debian/control")).unwrap(),
+            "Source: new-app\nVcs-Git: https://x/my-app.git\n\nPackage: new-app\n"
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("new-app/debian/changelog")).unwrap(), changelog.replacen("my-app", "new-app", 1));
+        assert_eq!(std::fs::read_to_string(dir.join("new-app/debian/new-app.postinst")).unwrap(), "#!/bin/sh\nsystemctl enable new-app\n");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_rpm() {
+        let dir = temp_dir("rpm");
+        std::fs::create_dir_all(dir.join("my-app")).unwrap();
+        let spec = "Name: my-app\nSource0: https://x/my-app-1.0.tar.gz\n\n%files\n%{_unitdir}/my-app.service\n\n\
+                    %changelog\n* Mon Jan 01 2024 A <a@x> - 1.0-1\n- Package my-app\n";
+        std::fs::write(dir.join("my-app/my-app.spec"), spec).unwrap();
+
+        start(parse_args(&["--name", "new-app", "--input", dir.join("my-app").to_str().unwrap(), "--rpm"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/new-app.spec")).unwrap(),
+            spec.replace("Name: my-app", "Name: new-app").replace("/my-app.service", "/new-app.service")
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_flatpak() {
+        let dir = temp_dir("flatpak");
+        std::fs::create_dir_all(dir.join("my-app/data")).unwrap();
+        let manifest = "app-id: com.example.MyApp\ncommand: my-app\nmodules:\n  - name: my-app\n";
+        std::fs::write(dir.join("my-app/com.example.MyApp.yml"), manifest).unwrap();
+        std::fs::write(dir.join("my-app/data/com.example.MyApp.desktop"), "[Desktop Entry]\nIcon=com.example.MyApp\nExec=my-app\n").unwrap();
+        std::fs::write(dir.join("my-app/snapcraft.yaml"), "name: my-app\napps:\n  my-app:\n    common-id: com.example.myApp\n").unwrap();
+
+        let input = dir.join("my-app");
+        start(parse_args(&["--name", "new-app", "--input", input.to_str().unwrap(), "--flatpak", "--old-domain", "example.com", "--new-domain", "globex.io"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/io.globex.NewApp.yml")).unwrap(),
+            "app-id: io.globex.NewApp\ncommand: new-app\nmodules:\n  - name: new-app\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/data/io.globex.NewApp.desktop")).unwrap(),
+            "[Desktop Entry]\nIcon=io.globex.NewApp\nExec=new-app\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/snapcraft.yaml")).unwrap(),
+            "name: new-app\napps:\n  new-app:\n    common-id: io.globex.newApp\n"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_electron() {
+        let dir = temp_dir("electron");
+        std::fs::create_dir_all(dir.join("my-app")).unwrap();
+        let package = r#"{"name": "my-app", "productName": "My App", "build": {"appId": "com.example.myApp", "protocols": [{"schemes": ["myapp"]}]}}"#;
+        std::fs::write(dir.join("my-app/package.json"), package).unwrap();
+        std::fs::write(dir.join("my-app/main.js"), "app.setAppUserModelId('com.example.myApp');\napp.setAsDefaultProtocolClient('myapp');\n").unwrap();
+
+        start(parse_args(&["--name", "new-app", "--input", dir.join("my-app").to_str().unwrap(), "--electron"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/package.json")).unwrap(),
+            r#"{"name": "new-app", "productName": "New App", "build": {"appId": "com.example.newApp", "protocols": [{"schemes": ["newapp"]}]}}"#
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/main.js")).unwrap(),
+            "app.setAppUserModelId('com.example.newApp');\napp.setAsDefaultProtocolClient('newapp');\n"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_tauri() {
+        let dir = temp_dir("tauri");
+        std::fs::create_dir_all(dir.join("my-app/src-tauri/src")).unwrap();
+        let config = r#"{"productName": "My App", "identifier": "com.example.my-app", "app": {"windows": [{"title": "My App"}]}}"#;
+        std::fs::write(dir.join("my-app/src-tauri/tauri.conf.json"), config).unwrap();
+        std::fs::write(dir.join("my-app/src-tauri/Cargo.toml"), "[package]\nname = \"my-app\"\n\n[lib]\nname = \"my_app_lib\"\n").unwrap();
+        std::fs::write(dir.join("my-app/src-tauri/src/main.rs"), "fn main() {\n    my_app_lib::run()\n}\n").unwrap();
+
+        start(parse_args(&["--name", "new-app", "--input", dir.join("my-app").to_str().unwrap(), "--tauri", "--old-domain", "example.com", "--new-domain", "globex.io"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/src-tauri/tauri.conf.json")).unwrap(),
+            r#"{"productName": "New App", "identifier": "io.globex.new-app", "app": {"windows": [{"title": "New App"}]}}"#
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/src-tauri/Cargo.toml")).unwrap(),
+            "[package]\nname = \"new-app\"\n\n[lib]\nname = \"new_app_lib\"\n"
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("new-app/src-tauri/src/main.rs")).unwrap(), "fn main() {\n    new_app_lib::run()\n}\n");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_react_native() {
+        let dir = temp_dir("react_native");
+        let java = dir.join("my-app/android/app/src/main/java");
+        std::fs::create_dir_all(java.join("com/example/myapp")).unwrap();
+        std::fs::create_dir_all(java.join("com/example/shared")).unwrap();
+        std::fs::create_dir_all(dir.join("my-app/android/app/src/debug/java/com/example/myapp")).unwrap();
+        std::fs::create_dir_all(dir.join("my-app/ios/MyApp.xcodeproj")).unwrap();
+        std::fs::write(dir.join("my-app/app.json"), r#"{"name": "MyApp", "displayName": "My App"}"#).unwrap();
+        std::fs::write(dir.join("my-app/android/app/build.gradle"), "android {\n    namespace \"com.example.myapp\"\n}\n").unwrap();
+        std::fs::write(java.join("com/example/myapp/MainActivity.kt"), "package com.example.myapp\n\nimport com.example.shared.Util\n").unwrap();
+        std::fs::write(java.join("com/example/shared/Util.kt"), "package com.example.shared\n").unwrap();
+        std::fs::write(dir.join("my-app/android/app/src/debug/java/com/example/myapp/Flipper.java"), "package com.example.myapp;\n").unwrap();
+        std::fs::write(dir.join("my-app/ios/MyApp.xcodeproj/project.pbxproj"), "PRODUCT_BUNDLE_IDENTIFIER = com.example.MyApp;\n").unwrap();
+
+        let input = dir.join("my-app");
+        start(parse_args(&["--name", "new-app", "--input", input.to_str().unwrap(), "--react-native", "--old-domain", "example.com", "--new-domain", "globex.io"]));
+        let java = dir.join("new-app/android/app/src/main/java");
+        assert_eq!(std::fs::read_to_string(dir.join("new-app/app.json")).unwrap(), r#"{"name": "NewApp", "displayName": "New App"}"#);
+        assert_eq!(std::fs::read_to_string(dir.join("new-app/android/app/build.gradle")).unwrap(), "android {\n    namespace \"io.globex.newapp\"\n}\n");
+        assert_eq!(std::fs::read_to_string(java.join("io/globex/newapp/MainActivity.kt")).unwrap(), "package io.globex.newapp\n\nimport com.example.shared.Util\n");
+        assert_eq!(std::fs::read_to_string(java.join("com/example/shared/Util.kt")).unwrap(), "package com.example.shared\n");
+        assert!(!java.join("com/example/myapp").exists());
+        assert!(!java.join("com/example/newapp").exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-app/android/app/src/debug/java/io/globex/newapp/Flipper.java")).unwrap(),
+            "package io.globex.newapp;\n"
+        );
+        assert!(!dir.join("new-app/android/app/src/debug/java/com").exists());
+        assert_eq!(std::fs::read_to_string(dir.join("new-app/ios/NewApp.xcodeproj/project.pbxproj")).unwrap(), "PRODUCT_BUNDLE_IDENTIFIER = io.globex.NewApp;\n");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_wordpress() {
+        let dir = temp_dir("wordpress");
+        std::fs::create_dir_all(dir.join("my-plugin/languages")).unwrap();
+        let plugin = "<?php\n/**\n * Plugin Name: My Plugin\n * Text Domain: myplug\n */\n\n\
+                      load_plugin_textdomain( 'myplug', false, 'my-plugin/languages' );\nfunction myplug_init() {\n    _e( 'Hello', 'myplug' );\n}\n";
+        std::fs::write(dir.join("my-plugin/myplug.php"), plugin).unwrap();
+        std::fs::write(dir.join("my-plugin/block.json"), r#"{"name": "my-plugin/notice", "textdomain": "myplug"}"#).unwrap();
+        std::fs::write(dir.join("my-plugin/languages/myplug-de_DE.po"), "msgid \"Hello\"\n").unwrap();
+
+        start(parse_args(&["--name", "new-plugin", "--input", dir.join("my-plugin").to_str().unwrap(), "--wordpress"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-plugin/new-plugin.php")).unwrap(),
+            "<?php\n/**\n * Plugin Name: New Plugin\n * Text Domain: new-plugin\n */\n\n\
+             load_plugin_textdomain( 'new-plugin', false, 'new-plugin/languages' );\nfunction myplug_init() {\n    _e( 'Hello', 'new-plugin' );\n}\n"
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("new-plugin/block.json")).unwrap(), r#"{"name": "new-plugin/notice", "textdomain": "new-plugin"}"#);
+        assert!(dir.join("new-plugin/languages/new-plugin-de_DE.po").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_vscode() {
+        let dir = temp_dir("vscode");
+        std::fs::create_dir_all(dir.join("my-ext/src")).unwrap();
+        let manifest = r#"{"name": "my-ext", "displayName": "My Ext", "engines": {"vscode": "^1.80.0"},
+            "activationEvents": ["onCommand:myExt.doThing"],
+            "contributes": {"commands": [{"command": "myExt.doThing", "title": "My Ext: Do Thing"}], "configuration": {"properties": {"myExt.enable": {"type": "boolean"}}}}}"#;
+        std::fs::write(dir.join("my-ext/package.json"), manifest).unwrap();
+        let source = "const myExtension = vscode.workspace.getConfiguration('myExt');\n\
+                      vscode.commands.registerCommand('myExt.doThing', () => myExtension.get('enable'));\n";
+        std::fs::write(dir.join("my-ext/src/extension.ts"), source).unwrap();
+
+        start(parse_args(&["--name", "new-ext", "--input", dir.join("my-ext").to_str().unwrap(), "--vscode"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-ext/package.json")).unwrap(),
+            r#"{"name": "new-ext", "displayName": "New Ext", "engines": {"vscode": "^1.80.0"},
+            "activationEvents": ["onCommand:newExt.doThing"],
+            "contributes": {"commands": [{"command": "newExt.doThing", "title": "New Ext: Do Thing"}], "configuration": {"properties": {"newExt.enable": {"type": "boolean"}}}}}"#
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-ext/src/extension.ts")).unwrap(),
+            "const myExtension = vscode.workspace.getConfiguration('newExt');\n\
+             vscode.commands.registerCommand('newExt.doThing', () => myExtension.get('enable'));\n"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_npm_scope() {
+        let dir = temp_dir("npm-scope");
+        std::fs::create_dir_all(dir.join("old-lib")).unwrap();
+        std::fs::write(dir.join("old-lib/package.json"), r#"{"name": "@acme/old-lib", "main": "old-lib.js"}"#).unwrap();
+        std::fs::write(dir.join("old-lib/old-lib.js"), "require('@acme/old-lib/lib');\nrequire('@acme/old-lib-utils');\n").unwrap();
+
+        let stats = start(parse_args(&["--name", "@globex/new-lib", "--input", dir.join("old-lib").to_str().unwrap()]));
+        assert_eq!(std::fs::read_to_string(dir.join("new-lib/package.json")).unwrap(), r#"{"name": "@globex/new-lib", "main": "new-lib.js"}"#);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-lib/new-lib.js")).unwrap(),
+            "require('@globex/new-lib/lib');\nrequire('@acme/new-lib-utils');\n"
+        );
+        assert_eq!(stats.substitutions, 4);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_composer() {
+        let dir = temp_dir("composer");
+        std::fs::create_dir_all(dir.join("old-lib/src")).unwrap();
+        let composer_json = r#"{"name": "acme/old-lib", "autoload": {"psr-4": {"Acme\\OldLib\\": "src/"}}}"#;
+        std::fs::write(dir.join("old-lib/composer.json"), composer_json).unwrap();
+        let old_hash = composer::content_hash(composer_json).unwrap();
+        std::fs::write(dir.join("old-lib/composer.lock"), format!("{{\n    \"content-hash\": \"{old_hash}\",\n    \"packages\": []\n}}\n")).unwrap();
+        std::fs::write(dir.join("old-lib/src/Client.php"), "<?php\nnamespace Acme\\OldLib;\n").unwrap();
+
+        let stats = start(parse_args(&["--name", "new-lib", "--input", dir.join("old-lib").to_str().unwrap(), "--composer"]));
+        let composer_json = std::fs::read_to_string(dir.join("new-lib/composer.json")).unwrap();
+        assert_eq!(composer_json, r#"{"name": "acme/new-lib", "autoload": {"psr-4": {"Acme\\NewLib\\": "src/"}}}"#);
+        let new_hash = composer::content_hash(&composer_json).unwrap();
+        assert_ne!(new_hash, old_hash);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-lib/composer.lock")).unwrap(),
+            format!("{{\n    \"content-hash\": \"{new_hash}\",\n    \"packages\": []\n}}\n")
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("new-lib/src/Client.php")).unwrap(), "<?php\nnamespace Acme\\NewLib;\n");
+        assert_eq!(stats.substitutions, 4);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_arduino_sketch() {
+        let dir = temp_dir("arduino-sketch");
+        std::fs::create_dir_all(dir.join("MyProject")).unwrap();
+        std::fs::write(dir.join("MyProject/MyProject.ino"), "#include \"MyProject.h\"\n").unwrap();
+        std::fs::write(dir.join("MyProject/MyProject.h"), "#define MY_PROJECT 1\n").unwrap();
+
+        let stats = start(parse_args(&["--name", "new-project", "--input", dir.join("MyProject").to_str().unwrap()]));
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/new-project.ino")).unwrap(), "#include \"NewProject.h\"\n");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/NewProject.h")).unwrap(), "#define NEW_PROJECT 1\n");
+        assert_eq!(stats.renamed, 2);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_scope() {
+        let dir = temp_dir("scope");
+        std::fs::create_dir_all(dir.join("repo/packages/old-lib")).unwrap();
+        std::fs::create_dir_all(dir.join("repo/packages/app")).unwrap();
+        std::fs::create_dir_all(dir.join("repo/.git")).unwrap();
+        std::fs::write(dir.join("repo/packages/old-lib/index.ts"), "export class OldLib {}").unwrap();
+        std::fs::write(dir.join("repo/packages/app/index.ts"), "import { OldLib } from \"@org/old-lib\";").unwrap();
+        std::fs::write(dir.join("repo/.git/config"), "old-lib").unwrap();
+        std::fs::write(dir.join("old-lib.txt"), "old-lib").unwrap();
+
+        let repo = dir.join("repo");
+        start(parse_args(&["--name", "new-lib", "--input", repo.join("packages/old-lib").to_str().unwrap(), "--move", "--scope", repo.to_str().unwrap()]));
+        assert_eq!(std::fs::read_to_string(repo.join("packages/new-lib/index.ts")).unwrap(), "export class NewLib {}");
+        assert_eq!(std::fs::read_to_string(repo.join("packages/app/index.ts")).unwrap(), "import { NewLib } from \"@org/new-lib\";");
+        assert_eq!(std::fs::read_to_string(repo.join(".git/config")).unwrap(), "old-lib");
+        assert_eq!(std::fs::read_to_string(dir.join("old-lib.txt")).unwrap(), "old-lib");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_names_only() {
+        let dir = temp_dir("names-only");
+        std::fs::create_dir_all(dir.join("my-project/my-project-dir")).unwrap();
+        std::fs::write(dir.join("my-project/my-project-dir/my-project.txt"), "my-project").unwrap();
+        let stats = start(parse_args(&["--name", "new-project", "--input", dir.join("my-project").to_str().unwrap(), "--names-only"]));
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/new-project-dir/new-project.txt")).unwrap(), "my-project");
+        assert_eq!(stats, Stats { renamed: 2, substitutions: 0, refreshed: 0, nothing_to_do: false });
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_config() {
+        let dir = temp_dir("config");
+        std::fs::create_dir_all(dir.join("my-project/chart")).unwrap();
+        std::fs::write(dir.join("my-project/Cargo.lock"), "my-project").unwrap();
+        std::fs::write(dir.join("my-project/README.md"), "My Project, my-project").unwrap();
+        std::fs::write(dir.join("my-project/chart/values.yaml"), "image: my-project:v1").unwrap();
+        std::fs::write(
+            dir.join("my-project").join(config::FILE_NAME),
+            r#"{"rules": [
+                {"files": "*.lock", "skip": true},
+                {"files": "*.md", "cases": ["My Project"]},
+                {"files": "*.yaml", "regex": [{"pattern": ":v(\\d+)", "replacement": ":v$1-renamed"}]}
+            ]}"#,
+        )
+        .unwrap();
+        start(parse_args(&["--name", "new-project", "--input", dir.join("my-project").to_str().unwrap()]));
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/Cargo.lock")).unwrap(), "my-project");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/README.md")).unwrap(), "New Project, my-project");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/chart/values.yaml")).unwrap(), "image: new-project:v1-renamed");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_config_keys() {
+        let dir = temp_dir("config-keys");
+        std::fs::create_dir_all(dir.join("my-project/k8s")).unwrap();
+        let manifest = "metadata:\n  name: my-project # service\n  labels:\n    app: \"my-project\"\nspec:\n  image: registry/my-project:1.0\n";
+        std::fs::write(dir.join("my-project/k8s/deploy.yaml"), manifest).unwrap();
+        std::fs::write(
+            dir.join("my-project").join(config::FILE_NAME),
+            r#"{"rules": [{"files": "k8s/*.yaml", "keys": ["metadata.name", "metadata.labels.*"]}]}"#,
+        )
+        .unwrap();
+        let stats = start(parse_args(&["--name", "new-project", "--input", dir.join("my-project").to_str().unwrap()]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-project/k8s/deploy.yaml")).unwrap(),
+            "metadata:\n  name: new-project # service\n  labels:\n    app: \"new-project\"\nspec:\n  image: registry/my-project:1.0\n"
+        );
+        assert_eq!(stats.substitutions, 2);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_replace_in() {
+        let dir = temp_dir("replace-in");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/main.py"), "import my_project  # my_project\nprint('my_project')\n").unwrap();
+        std::fs::write(dir.join("my-project/README.md"), "my_project").unwrap();
+        let input = dir.join("my-project");
+        start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap(), "--replace-in", "strings,comments"]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-project/main.py")).unwrap(),
+            "import my_project  # new_project\nprint('new_project')\n"
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/README.md")).unwrap(), "new_project");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_replace_regex() {
+        let dir = temp_dir("replace-regex");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/README.md"), "my-project at https://docs.example.com/my-project/v2/").unwrap();
+        let input = dir.join("my-project");
+        let rule = r"docs\.example\.com/([\w-]+)/v(\d+)=>docs.example.org/$1/v$2";
+        let stats = start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap(), "--replace-regex", rule]));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-project/README.md")).unwrap(),
+            "new-project at https://docs.example.org/new-project/v2/"
+        );
+        assert_eq!(stats.substitutions, 3);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_also() {
+        let dir = temp_dir("also");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/ACME-12.md"), "my-project is codenamed Falcon, see ACME-12").unwrap();
+        let input = dir.join("my-project");
+        let args = ["--name", "new-project", "--input", input.to_str().unwrap(), "--also", "ACME-=NOVA-", "--also", "Falcon=Eagle"];
+        let stats = start(parse_args(&args));
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/NOVA-12.md")).unwrap(), "new-project is codenamed Eagle, see NOVA-12");
+        assert_eq!(stats, Stats { renamed: 1, substitutions: 3, refreshed: 0, nothing_to_do: false });
+        assert!(parse_also("=x").is_err());
+        assert_eq!(parse_also("a=b=c"), Ok(("a".to_string(), "b=c".to_string())));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let dir = temp_dir("placeholders");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/VERSION"), "my-project built on RELEASE_DATE").unwrap();
+        let input = dir.join("my-project");
+        start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap(), "--also", "RELEASE_DATE={{date}}"]));
+        let date = &output::timestamp(std::time::SystemTime::now())[..10];
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/VERSION")).unwrap(), format!("new-project built on {date}"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_org() {
+        let dir = temp_dir("org");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        let content = "github.com/acme-corp/my-project @acme-corp/my-project com.acme_corp.my_project AcmeCorp";
+        std::fs::write(dir.join("my-project/README.md"), content).unwrap();
+        let input = dir.join("my-project");
+        let args = ["--name", "new-project", "--input", input.to_str().unwrap(), "--old-org", "acme-corp", "--new-org", "globex"];
+        let stats = start(parse_args(&args));
+        assert_eq!(
+            std::fs::read_to_string(dir.join("new-project/README.md")).unwrap(),
+            "github.com/globex/new-project @globex/new-project com.globex.new_project Globex"
+        );
+        assert_eq!(stats.substitutions, 7);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_regen_uuids() {
+        let dir = temp_dir("regen-uuids");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        let uuid = "8BC9CEB8-8B4A-11D0-8D11-00A0C91BC942";
+        std::fs::write(dir.join("my-project/MyProject.sln"), format!("Project(\"{{{uuid}}}\") = \"MyProject\"")).unwrap();
+        std::fs::write(dir.join("my-project/MyProject.csproj"), format!("<ProjectGuid>{uuid}</ProjectGuid>")).unwrap();
+        std::fs::write(dir.join("my-project/notes.txt"), uuid).unwrap();
+        let input = dir.join("my-project");
+        let args = ["--name", "new-project", "--input", input.to_str().unwrap(), "--regen-uuids", "*.sln", "--regen-uuids", "*.csproj"];
+        start(parse_args(&args));
+        let sln = std::fs::read_to_string(dir.join("new-project/NewProject.sln")).unwrap();
+        let fresh = &sln[10..46];
+        assert_ne!(fresh, uuid);
+        assert_eq!(sln, format!("Project(\"{{{fresh}}}\") = \"NewProject\""));
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/NewProject.csproj")).unwrap(), format!("<ProjectGuid>{fresh}</ProjectGuid>"));
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/notes.txt")).unwrap(), uuid);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_regen_vs_guids() {
+        let dir = temp_dir("regen-vs-guids");
+        std::fs::create_dir_all(dir.join("my-project/App")).unwrap();
+        std::fs::create_dir_all(dir.join("my-project/Lib")).unwrap();
+        let (csharp, app, lib) = ("FAE04EC0-301F-11D3-BF4B-00C04F79EFBC", "5E1F3D2A-0B4C-4D6E-8F7A-9B0C1D2E3F4A", "1A2B3C4D-2222-4333-8444-555555555555");
+        let sln = format!(
+            "Project(\"{{{csharp}}}\") = \"App\", \"App\\App.csproj\", \"{{{app}}}\"\nEndProject\n\
+             Project(\"{{{csharp}}}\") = \"Lib\", \"Lib\\Lib.csproj\", \"{{{lib}}}\"\nEndProject\n\
+             \t\t{{{app}}}.Debug|Any CPU.Build.0 = Debug|Any CPU\n"
+        );
+        std::fs::write(dir.join("my-project/MyProject.sln"), sln).unwrap();
+        let reference = format!("<ProjectReference Include=\"..\\Lib\\Lib.csproj\"><Project>{{{}}}</Project></ProjectReference>", lib.to_lowercase());
+        std::fs::write(dir.join("my-project/App/App.csproj"), format!("<Project><ProjectGuid>{{{app}}}</ProjectGuid>{reference}</Project>")).unwrap();
+        std::fs::write(dir.join("my-project/Lib/Lib.csproj"), format!("<Project><ProjectGuid>{{{lib}}}</ProjectGuid></Project>")).unwrap();
+
+        start(parse_args(&["--name", "new-project", "--input", dir.join("my-project").to_str().unwrap(), "--regen-vs-guids"]));
+        let sln = std::fs::read_to_string(dir.join("new-project/NewProject.sln")).unwrap();
+        let app_csproj = std::fs::read_to_string(dir.join("new-project/App/App.csproj")).unwrap();
+        let lib_csproj = std::fs::read_to_string(dir.join("new-project/Lib/Lib.csproj")).unwrap();
+        let (new_app, new_lib) = (&app_csproj[23..59], &lib_csproj[23..59]);
+        assert!(new_app != app && new_lib != lib && new_lib == new_lib.to_uppercase());
+        assert_eq!(sln.matches(csharp).count(), 2);
+        assert_eq!(sln.matches(new_app).count(), 2);
+        assert_eq!(sln.matches(new_lib).count(), 1);
+        assert!(app_csproj.contains(&format!("<Project>{{{}}}</Project>", new_lib.to_lowercase())));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_secrets() {
+        let dir = temp_dir("secrets");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/.env"), "MY_PROJECT_TOKEN=my-project-secret").unwrap();
+        std::fs::write(dir.join("my-project/my-project.pem"), "my-project").unwrap();
+        let input = dir.join("my-project");
+        let stats = start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap()]));
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/.env")).unwrap(), "MY_PROJECT_TOKEN=my-project-secret");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/new-project.pem")).unwrap(), "my-project");
+        assert_eq!(stats.substitutions, 0);
+
+        let output = dir.join("rewritten");
+        start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap(), "-o", output.to_str().unwrap(), "--rewrite-secrets"]));
+        assert_eq!(std::fs::read_to_string(output.join(".env")).unwrap(), "NEW_PROJECT_TOKEN=new-project-secret");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_gitattributes() {
+        let dir = temp_dir("gitattributes");
+        std::fs::create_dir_all(dir.join("my-project/fixtures")).unwrap();
+        std::fs::write(dir.join("my-project/.gitattributes"), "*.snap -text
+fixtures/** binary
+").unwrap();
+        std::fs::write(dir.join("my-project/my-project.snap"), "my-project").unwrap();
+        std::fs::write(dir.join("my-project/fixtures/data.txt"), "my-project").unwrap();
+        std::fs::write(dir.join("my-project/README.md"), "my-project").unwrap();
+        let input = dir.join("my-project");
+        let stats = start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap()]));
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/new-project.snap")).unwrap(), "my-project");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/fixtures/data.txt")).unwrap(), "my-project");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/README.md")).unwrap(), "new-project");
+        assert_eq!(stats.substitutions, 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_content_type() {
+        let dir = temp_dir("content-type");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/cache.txt"), "SQLite format 3\0my-project").unwrap();
+        std::fs::write(dir.join("my-project/logo"), "<svg><title>my-project</title></svg>").unwrap();
+        let input = dir.join("my-project");
+        let stats = start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap()]));
+        assert_eq!(std::fs::read(dir.join("new-project/cache.txt")).unwrap(), b"SQLite format 3\0my-project");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/logo")).unwrap(), "<svg><title>new-project</title></svg>");
+        assert_eq!(stats.substitutions, 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_treat_as() {
+        let dir = temp_dir("treat-as");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/.gitattributes"), "*.snap binary\n").unwrap();
+        std::fs::write(dir.join("my-project/header.DAT"), "\0my-project").unwrap();
+        std::fs::write(dir.join("my-project/app.snap"), "my-project").unwrap();
+        std::fs::write(dir.join("my-project/expected.golden"), "my-project").unwrap();
+        let input = dir.join("my-project");
+        let stats = start(parse_args(&[
+            "--name",
+            "new-project",
+            "--input",
+            input.to_str().unwrap(),
+            "--treat-as-text",
+            ".dat,snap",
+            "--treat-as-binary",
+            "golden",
+        ]));
+        assert_eq!(std::fs::read(dir.join("new-project/header.DAT")).unwrap(), b"\0new-project");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/app.snap")).unwrap(), "new-project");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/expected.golden")).unwrap(), "my-project");
+        assert_eq!(stats.substitutions, 2);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_generated() {
+        let dir = temp_dir("generated");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/api.pb.go"), "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage my_project\n").unwrap();
+        std::fs::write(dir.join("my-project/main.go"), "package my_project\n").unwrap();
+        let input = dir.join("my-project");
+        let report = dir.join("report.csv");
+        let stats = start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap(), "--report", "csv", "--report-output", report.to_str().unwrap()]));
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/api.pb.go")).unwrap(), "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage my_project\n");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/main.go")).unwrap(), "package new_project\n");
+        assert_eq!(stats.substitutions, 1);
+        assert!(std::fs::read_to_string(report).unwrap().contains(",copy,0,\"generated file, content unchanged\"\r\n"));
+
+        let output = dir.join("rewritten");
+        start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap(), "-o", output.to_str().unwrap(), "--rewrite-generated"]));
+        assert_eq!(std::fs::read_to_string(output.join("api.pb.go")).unwrap(), "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage new_project\n");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_vendored() {
+        let dir = temp_dir("vendored");
+        std::fs::create_dir_all(dir.join("my-project/vendor/my-project-utils")).unwrap();
+        std::fs::create_dir_all(dir.join("my-project/ios/Pods")).unwrap();
+        std::fs::write(dir.join("my-project/vendor/my-project-utils/lib.go"), "my-project").unwrap();
+        std::fs::write(dir.join("my-project/ios/Pods/my-project.h"), "my-project").unwrap();
+        std::fs::write(dir.join("my-project/main.go"), "my-project").unwrap();
+        let input = dir.join("my-project");
+        let stats = start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap()]));
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/vendor/my-project-utils/lib.go")).unwrap(), "my-project");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/ios/Pods/my-project.h")).unwrap(), "my-project");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/main.go")).unwrap(), "new-project");
+        assert_eq!(stats.substitutions, 1);
+
+        let output = dir.join("included");
+        start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap(), "-o", output.to_str().unwrap(), "--include-vendored"]));
+        assert_eq!(std::fs::read_to_string(output.join("vendor/new-project-utils/lib.go")).unwrap(), "new-project");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_tracked_only() {
+        let dir = temp_dir("tracked-only");
+        std::fs::create_dir_all(dir.join("my-project/build")).unwrap();
+        std::fs::write(dir.join("my-project/my-project.txt"), "my-project").unwrap();
+        std::fs::write(dir.join("my-project/build/my-project.o"), "my-project").unwrap();
+        std::fs::write(dir.join("my-project/notes.txt"), "my-project").unwrap();
+        let input = dir.join("my-project");
+        git::git(&input, &["init", "--quiet"]).unwrap();
+        git::git(&input, &["add", "my-project.txt"]).unwrap();
+        let output = dir.join("new-project");
+        let stats = start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap(), "-o", output.to_str().unwrap(), "--tracked-only"]));
+        assert_eq!(std::fs::read_to_string(output.join("new-project.txt")).unwrap(), "new-project");
+        assert!(!output.join("notes.txt").exists());
+        assert!(!output.join("build").exists());
+        assert_eq!(stats.renamed, 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_git_add() {
+        let dir = temp_dir("git-add");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/my-project.txt"), format!("my-project\n{}", "unchanged\n".repeat(20))).unwrap();
+        std::fs::write(dir.join("README.md"), "my-project").unwrap();
+        git::git(&dir, &["init", "--quiet"]).unwrap();
+        git::git(&dir, &["add", "--all"]).unwrap();
+        git::git(&dir, &["-c", "user.name=Test", "-c", "user.email=test@example.com", "commit", "--quiet", "-m", "Initial commit"]).unwrap();
+        let input = dir.join("my-project");
+        start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap(), "--move", "--git-add"]));
+        let staged = git::git(&dir, &["diff", "--cached", "--name-status", "-M"]).unwrap();
+        assert!(staged.starts_with("R"), "{staged}");
+        assert!(staged.contains("my-project/my-project.txt\tnew-project/new-project.txt"), "{staged}");
+        assert_eq!(git::git(&dir, &["status", "--porcelain", "--untracked-files=all"]).unwrap().lines().filter(|line| !line.starts_with("R ")).count(), 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_git_commit() {
+        let dir = temp_dir("git-commit");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/my-project.txt"), "my-project").unwrap();
+        git::git(&dir, &["init", "--quiet"]).unwrap();
+        git::git(&dir, &["config", "user.name", "Test"]).unwrap();
+        git::git(&dir, &["config", "user.email", "test@example.com"]).unwrap();
+        git::git(&dir, &["add", "--all"]).unwrap();
+        git::git(&dir, &["commit", "--quiet", "-m", "Initial commit"]).unwrap();
+        let input = dir.join("my-project");
+        start(parse_args(&[
+            "--name",
+            "new-project",
+            "--input",
+            input.to_str().unwrap(),
+            "--move",
+            "--git-branch",
+            "rename/{{new_name}}",
+            "--git-commit",
+        ]));
+        assert_eq!(git::git(&dir, &["rev-parse", "--abbrev-ref", "HEAD"]).unwrap(), "rename/new-project\n");
+        assert_eq!(
+            git::git(&dir, &["log", "-1", "--format=%B"]).unwrap(),
+            "Rename my-project to new-project\n\nRenamed 1 files and directories and made 1 substitutions.\n\n"
+        );
+        assert_eq!(git::git(&dir, &["status", "--porcelain"]).unwrap(), "");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_keep_root() {
+        let dir = temp_dir("keep-root");
+        std::fs::create_dir_all(dir.join("my-project/src")).unwrap();
+        std::fs::write(dir.join("my-project/src/my_project.rs"), "struct MyProject;").unwrap();
+        std::fs::write(dir.join("my-project/README.md"), "# my-project").unwrap();
+        start(parse_args(&["--name", "new-project", "--input", dir.join("my-project").to_str().unwrap(), "--keep-root", "--backup"]));
+        assert_eq!(std::fs::read_to_string(dir.join("my-project/src/new_project.rs")).unwrap(), "struct NewProject;");
+        assert_eq!(std::fs::read_to_string(dir.join("my-project/README.md")).unwrap(), "# new-project");
+        assert!(!dir.join("my-project/src/my_project.rs").exists());
+        assert!(!dir.join("new-project").exists());
+        assert!(dir.join("my-project-backup.tar").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_output() {
+        let dir = temp_dir("output");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/my-project.txt"), "my-project").unwrap();
+        let output = dir.join("elsewhere/renamed");
+        start(parse_args(&["--name", "new-project", "--input", dir.join("my-project").to_str().unwrap(), "-o", output.to_str().unwrap()]));
+        assert_eq!(std::fs::read_to_string(output.join("new-project.txt")).unwrap(), "new-project");
+        assert!(!dir.join("new-project").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_merge() {
+        let dir = temp_dir("merge");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/a.txt"), "my-project").unwrap();
+        std::fs::write(dir.join("my-project/b.txt"), "b").unwrap();
+        std::fs::write(dir.join("my-project/LICENSE"), "MIT").unwrap();
+        std::fs::create_dir_all(dir.join("new-project")).unwrap();
+        std::fs::write(dir.join("new-project/a.txt"), "existing").unwrap();
+        std::fs::write(dir.join("new-project/LICENSE"), "MIT").unwrap();
+        let out = Output::new(Verbosity::Quiet, LogFormat::Pretty, ColorChoice::Never);
+
+        // Nothing is written when a file differs
+        let mut ctx = Context::new(&dir.join("my-project"), "new-project", out);
+        ctx.merge = Merge::Fail;
+        let error = run(&mut ctx).unwrap_err();
+        assert!(error.contains(&dir.join("new-project/a.txt").display().to_string()), "{error}");
+        assert!(!error.contains("LICENSE"), "{error}");
+        assert!(!dir.join("new-project/b.txt").exists());
+
+        // Differing files are kept by default
+        ctx.merge = Merge::Keep;
+        run(&mut ctx).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/a.txt")).unwrap(), "existing");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/b.txt")).unwrap(), "b");
+        assert_eq!(ctx.conflicts, vec![dir.join("new-project/a.txt")]);
+
+        ctx.merge = Merge::Overwrite;
+        run(&mut ctx).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/a.txt")).unwrap(), "new-project");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_sync() {
+        let dir = temp_dir("sync");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/a.txt"), "my-project").unwrap();
+        std::fs::write(dir.join("my-project/b.txt"), "b").unwrap();
+        let input = dir.join("my-project");
+        let args = ["--name", "new-project", "--input", input.to_str().unwrap()];
+        start(parse_args(&args));
+
+        // A file fixed in the input after the first run is stale in the output
+        std::fs::write(dir.join("my-project/a.txt"), "my-project fixed").unwrap();
+        start(parse_args(&args));
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/a.txt")).unwrap(), "new-project");
+        let stats = start(parse_args(&[&args[..], &["--sync"]].concat()));
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/a.txt")).unwrap(), "new-project fixed");
+        assert_eq!(stats.refreshed, 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_nothing_to_do() {
+        let dir = temp_dir("nothing-to-do");
+        std::fs::create_dir_all(dir.join("my-project/src")).unwrap();
+        std::fs::write(dir.join("my-project/src/my_project.rs"), "struct MyProject;").unwrap();
+        let out = Output::new(Verbosity::Quiet, LogFormat::Pretty, ColorChoice::Never);
+
+        let mut ctx = Context::new(&dir.join("my-project"), "My Project", out);
+        ctx.output = ctx.input.clone();
+        assert_eq!(nothing_to_do(&mut ctx), Some("the project already has the new name"));
+
+        let mut ctx = Context::new(&dir.join("my-project"), "new-project", out);
+        assert_eq!(nothing_to_do(&mut ctx), None);
+        run(&mut ctx).unwrap();
+        let mut ctx = Context::new(&dir.join("my-project"), "new-project", out);
+        assert_eq!(nothing_to_do(&mut ctx), Some("the output is already up to date"));
+        assert!(ctx.operations.is_empty());
+
+        std::fs::write(dir.join("my-project/README.md"), "my-project").unwrap();
+        assert_eq!(nothing_to_do(&mut ctx), None);
+
+        // --fail-if-no-matches decides the exit status of a run with nothing to do
+        let stats = start(parse_args(&["--name", "my-project", "--input", dir.join("my-project").to_str().unwrap(), "--keep-root"]));
+        assert!(stats.nothing_to_do);
+        assert_eq!(exit_status(stats, false), Some(EXIT_NOTHING_TO_DO));
+        assert_eq!(exit_status(stats, true), Some(1));
+        assert_eq!(exit_status(Stats { renamed: 1, ..Stats::default() }, true), None);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_dry_run_report() {
+        let dir = temp_dir("report");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/my-project.txt"), "my-project").unwrap();
+        let report = dir.join("plan.csv");
+        let input = dir.join("my-project");
+        start(parse_args(&["--name", "new-project", "--input", input.to_str().unwrap(), "--dry-run", "--report", "csv", "--report-output", report.to_str().unwrap()]));
+        assert!(!dir.join("new-project").exists());
+        let output = dir.join("new-project");
+        let expected = format!(
+            "old_path,new_path,action,substitutions,note\r\n{},{},create_directory,0,\r\n{},{},write,1,\r\n",
+            input.display(),
+            output.display(),
+            input.join("my-project.txt").display(),
+            output.join("new-project.txt").display()
+        );
+        assert_eq!(std::fs::read_to_string(report).unwrap(), expected);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_runs() {
+        let dir = temp_dir("concurrent");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/a.txt"), "my-project").unwrap();
+        let out = Output::new(Verbosity::Quiet, LogFormat::Pretty, ColorChoice::Never);
+
+        let lock = RunLock::acquire(&lock_path(&dir.join("new-project"))).unwrap();
+        let mut ctx = Context::new(&dir.join("my-project"), "new-project", out);
+        assert!(run(&mut ctx).is_err());
+        assert!(!dir.join("new-project").exists());
+
+        // Planning doesn't write anything, so it doesn't need the lock
+        ctx.dry_run = true;
+        assert!(run(&mut ctx).is_ok());
+        drop(lock);
+
+        ctx.dry_run = false;
+        assert!(run(&mut ctx).is_ok());
+        assert!(dir.join("new-project/a.txt").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_resume() {
+        let dir = temp_dir("resume");
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/a.txt"), "my-project").unwrap();
+        std::fs::write(dir.join("my-project/b.txt"), "my-project").unwrap();
+
+        // An interrupted run wrote a.txt completely and b.txt partially
+        let staged = temporary_path(&dir.join("new-project"), None);
+        std::fs::create_dir_all(&staged).unwrap();
+        std::fs::write(staged.join("a.txt"), "written by the previous run").unwrap();
+        std::fs::write(staged.join("b.txt"), "new-pro").unwrap();
+        let mut checkpoint = Checkpoint::open(&checkpoint_path(&staged), &dir.join("my-project"), false).unwrap();
+        checkpoint.mark_done(&dir.join("my-project/a.txt")).unwrap();
+        drop(checkpoint);
+
+        start(parse_args(&["--name", "new-project", "--input", dir.join("my-project").to_str().unwrap(), "--resume"]));
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/a.txt")).unwrap(), "written by the previous run");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/b.txt")).unwrap(), "new-project");
+        assert!(!staged.exists());
+        assert!(!checkpoint_path(&staged).exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_reserved_names() {
+        let dir = temp_dir("reserved");
+        std::fs::create_dir_all(dir.join("my-project/src")).unwrap();
+        std::fs::write(dir.join("my-project/src/my-project.rs"), "mod my_project;").unwrap();
+        std::fs::write(dir.join("my-project/src/con.rs"), "").unwrap();
+        let out = Output::new(Verbosity::Quiet, LogFormat::Pretty, ColorChoice::Never);
+
+        // Only names created by the rename are flagged
+        let ctx = Context::new(&dir.join("my-project"), "aux", out);
+        let reserved_names = find_reserved_names(&ctx).into_iter().map(|(path, _)| path).collect::<Vec<_>>();
+        assert_eq!(reserved_names, vec![dir.join("aux"), dir.join("aux/src/aux.rs")]);
+
+        start(parse_args(&[
+            "--name", "aux",
+            "--input", dir.join("my-project").to_str().unwrap(),
+            "--escape-reserved-names",
+        ]));
+        assert_eq!(std::fs::read_to_string(dir.join("aux_/src/aux_.rs")).unwrap(), "mod aux;");
+        assert!(dir.join("aux_/src/con.rs").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_sync_change() {
+        let dir = temp_dir("sync-change");
+        let input = dir.join("my-project");
+        let output = dir.join("new-project");
+        std::fs::create_dir_all(input.join("my-project-dir")).unwrap();
+        std::fs::create_dir_all(&output).unwrap();
+        let out = Output::new(Verbosity::Quiet, LogFormat::Pretty, ColorChoice::Never);
+        let mut ctx = Context::new(&input, "new-project", out);
+
+        // New and modified files are written with the rename applied
+        std::fs::write(input.join("my-project-dir/my_project.txt"), "MyProject").unwrap();
+        sync_change(&input, &output, &Change::Modified(PathBuf::from("my-project-dir/my_project.txt")), &mut ctx).unwrap();
+        let target = output.join("new-project-dir/new_project.txt");
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "NewProject");
+
+        std::fs::write(input.join("my-project-dir/my_project.txt"), "MY_PROJECT").unwrap();
+        sync_change(&input, &output, &Change::Modified(PathBuf::from("my-project-dir/my_project.txt")), &mut ctx).unwrap();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "NEW_PROJECT");
+
+        // Removed entries are removed from the output
+        sync_change(&input, &output, &Change::Removed(PathBuf::from("my-project-dir")), &mut ctx).unwrap();
+        assert!(!output.join("new-project-dir").exists());
+
+        // Entries that are already gone are skipped
+        sync_change(&input, &output, &Change::Modified(PathBuf::from("my-project-dir/.my_project.txt.swp")), &mut ctx).unwrap();
+        sync_change(&input, &output, &Change::Removed(PathBuf::from("my-project-dir")), &mut ctx).unwrap();
+        assert!(!output.join("new-project-dir").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    // Generate a test project structure with this layout
+    // test-project
+    // ├── test-dir-1
+    // │   ├── test-dir-test-project
+    // │   │   └── test-file-test-project.txt
+    // │   └── test-file-2.txt
+    // └── test-file-1.txt
+    fn gen_test_project() {
+        let test_dir = std::env::current_dir().unwrap().join("test-project");
+        std::fs::create_dir_all(test_dir.join("test-dir-1/test-dir-test-project")).unwrap();
+        std::fs::write(test_dir.join("test-dir-1/test-file-2.txt"), "Test Project").unwrap();
+        std::fs::write(test_dir.join("test-file-1.txt"), "test-project").unwrap();
+        std::fs::write(test_dir.join("test-dir-1/test-dir-test-project/test-file-test-project.txt"), "test_project").unwrap();
+    }
+
+    // Check if there is a project structure with this layout
+    // copied-project
+    // ├── test-dir-1
+    // │   ├── test-dir-copied-project
+    // │   │   └── test-file-copied-project.txt
+    // │   └── copied-file-2.txt
+    // └── copied-file-1.txt
+    fn check_test_project() {
+        let test_dir = std::env::current_dir().unwrap().join("copied-project");
+        assert!(test_dir.exists());
+        assert!(test_dir.join("test-dir-1").exists());
+        assert!(test_dir.join("test-file-1.txt").exists());
+        // Check the contents of test-file-1.txt
+        let content = std::fs::read_to_string(test_dir.join("test-file-1.txt")).unwrap();
+        assert_eq!(content, "copied-project");
+        assert!(test_dir.join("test-dir-1/test-dir-copied-project").exists());
+        assert!(test_dir.join("test-dir-1/test-file-2.txt").exists());
+        let content = std::fs::read_to_string(test_dir.join("test-dir-1/test-file-2.txt")).unwrap();
+        assert_eq!(content, "Copied Project");
+        assert!(test_dir.join("test-dir-1/test-dir-copied-project/test-file-copied-project.txt").exists());
+        let content = std::fs::read_to_string(test_dir.join("test-dir-1/test-dir-copied-project/test-file-copied-project.txt")).unwrap();
+        assert_eq!(content, "copied_project");
+    }
+
+    #[test]
+    fn test_complete() {
+        gen_test_project();
+        let stats = start(parse_args(&[
+            "--name", "copied-project",
+            "--input", std::env::current_dir().unwrap().join("test-project").to_str().unwrap(),
+        ]));
+        check_test_project();
+        assert_eq!(stats, Stats { renamed: 2, substitutions: 3, refreshed: 0, nothing_to_do: false });
+        std::fs::remove_dir_all(std::env::current_dir().unwrap().join("test-project")).unwrap();
+        std::fs::remove_dir_all(std::env::current_dir().unwrap().join("copied-project")).unwrap();
+    }
+}
\ No newline at end of file
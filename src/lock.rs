@@ -0,0 +1,48 @@
+use std::fs::{File, TryLockError};
+use std::path::{Path, PathBuf};
+
+// Advisory lock held while a run writes its output, so a second run writing the same output fails
+// instead of interleaving its writes. The lock is released by the operating system when the
+// process exits, so a crashed run never leaves a stale lock behind.
+pub struct RunLock {
+    path: PathBuf,
+    _file: File,
+}
+
+impl RunLock {
+    pub fn acquire(path: &Path) -> Result<Self, String> {
+        let file = File::create(path).map_err(|e| format!("Failed to create the lock file {}: {e}", path.display()))?;
+        match file.try_lock() {
+            Ok(()) => Ok(Self {
+                path: path.to_path_buf(),
+                _file: file,
+            }),
+            Err(TryLockError::WouldBlock) => Err(format!(
+                "Another rename is already writing this output, wait for it to finish (lock file {})",
+                path.display()
+            )),
+            Err(TryLockError::Error(e)) => Err(format!("Failed to lock {}: {e}", path.display())),
+        }
+    }
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire() {
+        let path = std::env::temp_dir().join(format!("project-renamer-lock-{}", std::process::id()));
+        let lock = RunLock::acquire(&path).unwrap();
+        assert!(RunLock::acquire(&path).is_err());
+        drop(lock);
+        assert!(!path.exists());
+        drop(RunLock::acquire(&path).unwrap());
+    }
+}
@@ -0,0 +1,147 @@
+use std::ops::Range;
+use std::path::Path;
+
+// The directives whose whole line names variables or files, like `ifdef OLD_NAME_DEBUG` or
+// `include old-name.mk`
+const NAMING_DIRECTIVES: [&str; 11] =
+    ["define", "undefine", "ifdef", "ifndef", "include", "-include", "sinclude", "export", "unexport", "override", "private"];
+
+// Whether the file is a Makefile, like "Makefile", "GNUmakefile" or an included "rules.mk"
+pub fn is_makefile(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| matches!(name.to_string_lossy().as_ref(), "Makefile" | "makefile" | "GNUmakefile"))
+        || path.extension().is_some_and(|extension| extension == "mk" || extension == "mak")
+}
+
+// How a logical line of a Makefile, with its continuation lines, is renamed
+#[derive(Clone, Copy, PartialEq)]
+enum Line {
+    // The targets and prerequisites of a rule, an assignment or a directive naming variables or
+    // files: everything up to a comment or the recipe after a ";"
+    Make,
+    // A recipe, the body of a define or a conditional: only the variable references
+    References,
+}
+
+// The ranges of a Makefile the name is renamed in: the targets and prerequisites of the rules, the
+// names and values of the variables, the files included and the names of the variables referenced
+// like `$(OLD_NAME_DIR)` or `${OLD_NAME_DIR}`. The rest of the recipes and the comments are left
+// out, the shell commands of a recipe are run as they are, whatever the project is called.
+pub fn names(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut kind = Line::References;
+    let mut define = false;
+    let mut continued = false;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+        if !continued {
+            let trimmed = content.trim_start();
+            let directive = trimmed.split_whitespace().next().unwrap_or_default();
+            kind = if content.starts_with('\t') || (define && directive != "endef") {
+                Line::References
+            } else if NAMING_DIRECTIVES.contains(&directive) {
+                Line::Make
+            } else if matches!(directive, "ifeq" | "ifneq" | "else" | "endif" | "endef") || trimmed.starts_with('#') {
+                Line::References
+            } else {
+                let top = top_level(trimmed);
+                if top.contains([':', '=']) { Line::Make } else { Line::References }
+            };
+            define = (define && directive != "endef") || directive == "define";
+        }
+        continued = content.ends_with('\\');
+        let mut end = 0;
+        if kind == Line::Make {
+            let rule = top_level(content).find([':', '=']).is_some_and(|at| content[at..].starts_with(':') && !content[at..].starts_with(":="));
+            end = content.find('#').unwrap_or(content.len());
+            if rule {
+                end = end.min(content.find(';').unwrap_or(content.len()));
+            }
+            ranges.push(start..start + end);
+        }
+        ranges.extend(references(&content[end..]).into_iter().map(|range| start + end + range.start..start + end + range.end));
+    }
+    ranges.retain(|range| !range.is_empty());
+    ranges
+}
+
+// The line with the variable references and function calls blanked out, to find the ":" or "=" of
+// a rule or an assignment
+fn top_level(line: &str) -> String {
+    let mut blanked = String::with_capacity(line.len());
+    let mut depth = 0usize;
+    let mut previous = ' ';
+    for c in line.chars() {
+        let inside = match c {
+            '(' | '{' if previous == '$' || depth > 0 => {
+                depth += 1;
+                true
+            }
+            ')' | '}' if depth > 0 => {
+                depth -= 1;
+                true
+            }
+            _ => depth > 0,
+        };
+        // Blanked with as many bytes, so the offsets stay those of the line
+        if inside {
+            blanked.push_str(&" ".repeat(c.len_utf8()));
+        } else {
+            blanked.push(c);
+        }
+        previous = c;
+    }
+    blanked
+}
+
+// The ranges of the names of the variables referenced in a text, like OLD_NAME_DIR in
+// `$(OLD_NAME_DIR)`, `${OLD_NAME_DIR}` or `$(OLD_NAME_SRCS:.c=.o)`, but not an escaped `$$(...)`
+fn references(text: &str) -> Vec<Range<usize>> {
+    let bytes = text.as_bytes();
+    let mut ranges = vec![];
+    let mut pos = 0;
+    while pos + 1 < bytes.len() {
+        if bytes[pos] == b'$' && bytes[pos + 1] == b'$' {
+            pos += 2;
+        } else if bytes[pos] == b'$' && matches!(bytes[pos + 1], b'(' | b'{') {
+            let start = pos + 2;
+            let end = text[start..].find([')', '}', ':', ' ', ',', '=']).map_or(text.len(), |end| start + end);
+            ranges.push(start..end);
+            pos = end;
+        } else {
+            pos += 1;
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        let text = "# my-app\nMY_APP_SRCS := main.c \\\n    my_app.c\nPROJECT ?= my-app # the name\ninclude my-app.mk\n\n\
+                    my-app: $(MY_APP_SRCS:.c=.o) ; echo my-app\n\t$(CC) -o my-app \\\n\t  ${MY_APP_SRCS} $$(my-app)\n\
+                    ifdef MY_APP_DEBUG\nendif\n";
+        let found = names(text).into_iter().map(|range| &text[range]).collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                "MY_APP_SRCS := main.c \\",
+                "    my_app.c",
+                "PROJECT ?= my-app ",
+                "include my-app.mk",
+                "my-app: $(MY_APP_SRCS:.c=.o) ",
+                "CC",
+                "MY_APP_SRCS",
+                "ifdef MY_APP_DEBUG"
+            ]
+        );
+        assert!(is_makefile(Path::new("GNUmakefile")));
+        assert!(is_makefile(Path::new("build/rules.mk")));
+        assert!(!is_makefile(Path::new("CMakeLists.txt")));
+    }
+}
@@ -0,0 +1,51 @@
+use std::path::Path;
+
+// Elements of a POM that name the project, its parent, its modules and its dependencies, the ones
+// the reactor resolves the modules of a build by
+const COORDINATES: [&str; 16] = [
+    "project/groupId",
+    "project/artifactId",
+    "project/name",
+    "project/build/finalName",
+    "project/parent/groupId",
+    "project/parent/artifactId",
+    "project/parent/relativePath",
+    "project/modules/module",
+    "project/profiles/profile/modules/module",
+    "project/dependencies/dependency/groupId",
+    "project/dependencies/dependency/artifactId",
+    "project/dependencyManagement/dependencies/dependency/groupId",
+    "project/dependencyManagement/dependencies/dependency/artifactId",
+    "project/profiles/profile/dependencies/dependency/artifactId",
+    "project/build/plugins/plugin/dependencies/dependency/artifactId",
+    "project/build/pluginManagement/plugins/plugin/dependencies/dependency/artifactId",
+];
+
+// Whether the file is a Maven POM, "pom.xml" or a "*.pom" of a repository
+pub fn is_pom(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == "pom.xml") || path.extension().is_some_and(|extension| extension == "pom")
+}
+
+// The key paths of the coordinates, to replace in with the case variants
+pub fn keys() -> Vec<String> {
+    COORDINATES.iter().map(|key| key.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structured::{self, Format};
+
+    #[test]
+    fn test_keys() {
+        let pom = "<project>\n  <parent><artifactId>my-app-parent</artifactId><relativePath>../my-app-parent</relativePath></parent>\n  \
+                   <artifactId>my-app-core</artifactId>\n  <description>Core of my-app</description>\n  \
+                   <dependencies><dependency><groupId>com.example.myapp</groupId><artifactId>my-app-api</artifactId></dependency></dependencies>\n\
+                   </project>\n";
+        let found = structured::values(pom, Format::Xml, &keys()).into_iter().map(|range| &pom[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-app-parent", "../my-app-parent", "my-app-core", "com.example.myapp", "my-app-api"]);
+        assert!(is_pom(Path::new("modules/core/pom.xml")));
+        assert!(is_pom(Path::new("my-app-1.0.pom")));
+        assert!(!is_pom(Path::new("pom.xml.bak")));
+    }
+}
@@ -0,0 +1,154 @@
+use std::ops::Range;
+use std::path::Path;
+
+use crate::syntax::{self, Language, Node};
+
+// The functions of meson.build whose first argument names the project, a target, a subproject or a
+// dependency
+const FUNCTIONS: [&str; 14] = [
+    "project",
+    "executable",
+    "library",
+    "shared_library",
+    "static_library",
+    "both_libraries",
+    "shared_module",
+    "jar",
+    "custom_target",
+    "run_target",
+    "alias_target",
+    "subproject",
+    "dependency",
+    "override_dependency",
+];
+
+// The keys of the [provide] section of a wrap file whose values list the names it provides, every
+// other key is the name of a dependency itself
+const PROVIDE_LISTS: [&str; 2] = ["dependency_names", "program_names"];
+
+// Whether the file is read by Meson: a "meson.build" or the wrap file of a subproject like
+// "subprojects/zlib.wrap"
+pub fn is_meson_file(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == "meson.build") || path.extension().is_some_and(|extension| extension == "wrap")
+}
+
+// The ranges of the names in a Meson file: in a meson.build the strings of the first argument of
+// `project(...)`, the target functions, `subproject(...)` and `dependency(...)` with the subproject
+// of its fallback, and in a wrap file the names of the [provide] section and the patch_directory.
+// The variables, the urls and the directory an archive unpacks to are left out.
+pub fn names(path: &Path, text: &str) -> Vec<Range<usize>> {
+    if path.file_name().is_some_and(|name| name == "meson.build") { build_names(text) } else { wrap_names(text) }
+}
+
+fn build_names(text: &str) -> Vec<Range<usize>> {
+    let spans = syntax::spans(text, Language::Python);
+    let token = |index: usize| &text[spans[index].range.clone()];
+    let mut ranges = vec![];
+    // The depth of the brackets inside the call being read
+    let mut call: Option<usize> = None;
+    let mut argument = 0;
+    let mut keyword: Option<&str> = None;
+    // The index of the element in a list argument
+    let mut element = 0;
+    let mut depth = 0usize;
+    for (index, span) in spans.iter().enumerate() {
+        let next = spans.get(index + 1).map(|_| token(index + 1).trim_start()).unwrap_or_default();
+        match span.node {
+            Node::Identifier if call.is_none() && next.starts_with('(') && FUNCTIONS.contains(&token(index)) => {
+                call = Some(depth + 1);
+                (argument, keyword) = (0, None);
+            }
+            Node::Identifier if call == Some(depth) && next.starts_with(':') => keyword = Some(token(index)),
+            Node::String if call.is_some() => {
+                let inside = call.unwrap_or_default();
+                let first = match keyword {
+                    None => argument == 0 && depth == inside,
+                    Some("fallback") => depth == inside || (depth == inside + 1 && element == 0),
+                    _ => false,
+                };
+                let literal = token(index);
+                let quote = if literal.starts_with("'''") { 3 } else { 1 };
+                if first && literal.len() >= 2 * quote {
+                    ranges.push(span.range.start + quote..span.range.end - quote);
+                }
+            }
+            Node::Other => {
+                for c in token(index).chars() {
+                    match c {
+                        '(' | '[' | '{' => {
+                            depth += 1;
+                            element = 0;
+                        }
+                        ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                        ',' if call == Some(depth) => {
+                            argument += 1;
+                            keyword = None;
+                        }
+                        ',' => element += 1,
+                        _ => {}
+                    }
+                    if call.is_some_and(|inside| depth < inside) {
+                        call = None;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    ranges
+}
+
+fn wrap_names(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut section = "";
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let trimmed = line.trim();
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|name| name.strip_suffix(']')) {
+            section = name.trim();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let range = |part: &str, at: usize| at + (part.len() - part.trim_start().len())..at + part.trim_end().len();
+        let value_start = start + key.len() + 1;
+        match (section, key.trim()) {
+            ("provide", key) if PROVIDE_LISTS.contains(&key) => {
+                let mut at = value_start;
+                for item in value.split(',') {
+                    ranges.push(range(item, at));
+                    at += item.len() + 1;
+                }
+            }
+            ("provide", key) if !key.starts_with(['#', ';']) => ranges.push(range(key, start)),
+            (section, "patch_directory") if section.starts_with("wrap-") => ranges.push(range(value, value_start)),
+            _ => {}
+        }
+    }
+    ranges.retain(|range| !range.is_empty());
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        let build = "project('my-app', 'c', version: 'my-app')\nmy_app_dep = dependency('my-app-core', fallback: ['my-app-core', 'my_app_core_dep'])\n\
+                     executable('my-app', files('my-app.c'), dependencies: my_app_dep, install_dir: 'my-app')\n\
+                     message('my-app')\nsubproject('my-app-tools')\n";
+        let found = names(Path::new("meson.build"), build).into_iter().map(|range| &build[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-app", "my-app-core", "my-app-core", "my-app", "my-app-tools"]);
+
+        let wrap = "[wrap-file]\ndirectory = my-app-core-1.0\nsource_url = https://x/my-app-core-1.0.tar.gz\npatch_directory = my-app-core\n\n\
+                    [provide]\nmy-app-core = my_app_core_dep\ndependency_names = my-app-core, my-app-extra\n";
+        let found = names(Path::new("subprojects/my-app-core.wrap"), wrap).into_iter().map(|range| &wrap[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-app-core", "my-app-core", "my-app-core", "my-app-extra"]);
+        assert!(is_meson_file(Path::new("src/meson.build")));
+        assert!(!is_meson_file(Path::new("meson_options.txt")));
+    }
+}
@@ -0,0 +1,78 @@
+// Signatures binary formats start with, which may also be valid UTF-8, like SQLite databases
+const SIGNATURES: [(&[u8], &str); 16] = [
+    (b"\x1f\x8b", "application/gzip"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"%PDF-", "application/pdf"),
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"\x7fELF", "application/x-elf"),
+    (b"\0asm", "application/wasm"),
+    (b"\xfd7zXZ\0", "application/x-xz"),
+    (b"\x28\xb5\x2f\xfd", "application/zstd"),
+    (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    (b"SQLite format 3\0", "application/vnd.sqlite3"),
+    (b"\xca\xfe\xba\xbe", "application/java-vm"),
+    (b"\xcf\xfa\xed\xfe", "application/x-mach-binary"),
+    (b"OggS", "application/ogg"),
+];
+
+// Like git, a NUL byte in the first 8000 bytes makes a file binary
+const SNIFF_LEN: usize = 8000;
+
+// The content type of a file, told from its first bytes rather than its extension, which may be
+// missing or misleading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentType {
+    pub mime: &'static str,
+    // Whether the content can be renamed as text
+    pub text: bool,
+}
+
+pub fn detect(bytes: &[u8]) -> ContentType {
+    let binary = |mime| ContentType { mime, text: false };
+    if let Some((_, mime)) = SIGNATURES.iter().find(|(signature, _)| bytes.starts_with(signature)) {
+        return binary(mime);
+    }
+    if bytes[..bytes.len().min(SNIFF_LEN)].contains(&0) {
+        return binary("application/octet-stream");
+    }
+    let Ok(text) = std::str::from_utf8(bytes) else {
+        return binary("application/octet-stream");
+    };
+
+    let start = text.trim_start_matches('\u{feff}').trim_start();
+    let head = start.chars().take(1024).collect::<String>().to_ascii_lowercase();
+    let mime = if head.starts_with("<svg") || (head.starts_with("<?xml") && head.contains("<svg")) {
+        "image/svg+xml"
+    } else if head.starts_with("<!doctype html") || head.starts_with("<html") {
+        "text/html"
+    } else if head.starts_with("<?xml") {
+        "application/xml"
+    } else if head.starts_with(['{', '[']) {
+        "application/json"
+    } else {
+        "text/plain"
+    };
+    ContentType { mime, text: true }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let mime = |bytes: &[u8]| (detect(bytes).mime, detect(bytes).text);
+        assert_eq!(mime(b"\x1f\x8b\x08\0my-project"), ("application/gzip", false));
+        assert_eq!(mime(b"SQLite format 3\0my-project"), ("application/vnd.sqlite3", false));
+        assert_eq!(mime(b"my-project\0\x01"), ("application/octet-stream", false));
+        assert_eq!(mime(b"\xc3\x28"), ("application/octet-stream", false));
+        assert_eq!(mime(b"<?xml version=\"1.0\"?>\n<svg xmlns=\"http://www.w3.org/2000/svg\">"), ("image/svg+xml", true));
+        assert_eq!(mime(b"<?xml version=\"1.0\"?>\n<project>"), ("application/xml", true));
+        assert_eq!(mime("\u{feff}  {\"name\": \"my-project\"}".as_bytes()), ("application/json", true));
+        assert_eq!(mime(b"<!DOCTYPE html>"), ("text/html", true));
+        assert_eq!(mime(b"my-project"), ("text/plain", true));
+    }
+}
@@ -0,0 +1,52 @@
+use std::ops::Range;
+use std::path::Path;
+
+use crate::json;
+use crate::structured;
+
+// Whether the file is the lock file of a Nix flake
+pub fn is_lock(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == "flake.lock")
+}
+
+// The ranges of the names of the inputs in a flake.lock, in order: the names of the nodes, the
+// names of the inputs of every node and the nodes they refer to, or the input path they follow,
+// like ["nixpkgs"]. The locked and original sources of the inputs are left out, they are where the
+// inputs come from whatever they are called.
+pub fn input_names(text: &str) -> Vec<Range<usize>> {
+    let pattern = |pattern: &[&str]| pattern.iter().map(|segment| segment.to_string()).collect::<Vec<_>>();
+    let (nodes, inputs, follows) = (pattern(&["nodes", "*"]), pattern(&["nodes", "*", "inputs", "*"]), pattern(&["nodes", "*", "inputs", "*", "*"]));
+    let keys = json::keys(text).unwrap_or_default().into_iter().filter(|(path, _)| structured::matches(&nodes, path) || structured::matches(&inputs, path));
+    let values = json::string_values(text)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(path, _)| structured::matches(&inputs, path) || structured::matches(&follows, path));
+    let mut ranges = keys.chain(values).map(|(_, range)| range).collect::<Vec<_>>();
+    ranges.sort_by_key(|range| range.start);
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_names() {
+        let text = r#"{
+  "nodes": {
+    "my-app-assets": {
+      "inputs": {"nixpkgs": ["nixpkgs"]},
+      "locked": {"owner": "acme", "repo": "my-app-assets", "type": "github"},
+      "original": {"owner": "acme", "repo": "my-app-assets", "type": "github"}
+    },
+    "nixpkgs": {"locked": {"type": "github"}},
+    "root": {"inputs": {"my-app-assets": "my-app-assets", "nixpkgs": "nixpkgs"}}
+  },
+  "root": "root",
+  "version": 7
+}"#;
+        let found = input_names(text).into_iter().map(|range| &text[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-app-assets", "nixpkgs", "nixpkgs", "nixpkgs", "root", "my-app-assets", "my-app-assets", "nixpkgs", "nixpkgs"]);
+        assert!(is_lock(Path::new("flake.lock")));
+    }
+}
@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use crate::json::{self, Value};
+
+// Split the scope off a scoped npm package name, "@acme/my-lib" into ("@acme", "my-lib"). Other
+// names have no scope.
+pub fn split_scope(name: &str) -> (Option<&str>, &str) {
+    match name.split_once('/') {
+        Some((scope, name)) if scope.len() > 1 && scope.starts_with('@') && !name.is_empty() && !name.contains('/') => (Some(scope), name),
+        _ => (None, name),
+    }
+}
+
+// The name in the package.json of the directory, if it has one
+pub fn package_name(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    json::parse(&content).ok()?.get("name").and_then(Value::as_str).map(str::to_string)
+}
+
+// The full old and new package name when the new name moves the package to another scope, which
+// the case variants of the name don't cover. The old package name is the one in package.json, or
+// else the old name itself.
+pub fn renamed_package(old_package: Option<&str>, old_name: &str, new_name: &str) -> Option<(String, String)> {
+    let old_package = old_package.unwrap_or(old_name);
+    let (Some(new_scope), new_name) = split_scope(new_name) else {
+        return None;
+    };
+    (split_scope(old_package).0 != Some(new_scope)).then(|| (old_package.to_string(), format!("{new_scope}/{new_name}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renamed_package() {
+        assert_eq!(split_scope("@acme/my-lib"), (Some("@acme"), "my-lib"));
+        assert_eq!(split_scope("my-lib"), (None, "my-lib"));
+        assert_eq!(split_scope("@/my-lib"), (None, "@/my-lib"));
+        assert_eq!(split_scope("@acme/my-lib/lib"), (None, "@acme/my-lib/lib"));
+
+        let renamed = |old_package, old_name, new_name| renamed_package(old_package, old_name, new_name);
+        let pair = |old: &str, new: &str| Some((old.to_string(), new.to_string()));
+        assert_eq!(renamed(Some("@acme/my-lib"), "my-lib", "@globex/new-lib"), pair("@acme/my-lib", "@globex/new-lib"));
+        assert_eq!(renamed(None, "my-lib", "@globex/new-lib"), pair("my-lib", "@globex/new-lib"));
+        // The case variants rename the package when the scope stays
+        assert_eq!(renamed(Some("@acme/my-lib"), "my-lib", "@acme/new-lib"), None);
+        assert_eq!(renamed(Some("@acme/my-lib"), "my-lib", "new-lib"), None);
+        assert_eq!(renamed(None, "my-lib", "new-lib"), None);
+    }
+}
@@ -0,0 +1,457 @@
+use std::io::IsTerminal;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::json;
+
+// How much output is printed during a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,   // Only errors
+    Normal,  // A summary at the end of the run
+    Verbose, // Every file and directory action
+    Trace,   // Every substitution made in a file
+}
+
+impl Verbosity {
+    pub fn from_flags(quiet: bool, verbose: u8) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Trace,
+        }
+    }
+}
+
+// How events are formatted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum LogFormat {
+    // Human readable lines
+    #[default]
+    Pretty,
+    // One JSON object per line, for collecting logs in CI and automation
+    Json,
+}
+
+// When to color the printed output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ColorChoice {
+    // Color if printing to a terminal and NO_COLOR is not set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    fn enabled(self, is_terminal: bool, no_color: bool) -> bool {
+        match self {
+            ColorChoice::Auto => is_terminal && !no_color,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+// See https://no-color.org
+fn no_color_env() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+// ANSI escape codes of the colors used for events
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+// Something that happened during a run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event<'a> {
+    DirectoryCreated { path: &'a Path },
+    FileRenamed { from: &'a str, to: &'a str },
+    ContentRewritten { path: &'a Path, substitutions: usize },
+    Substitution { path: &'a Path, from: &'a str, to: &'a str, count: usize },
+    FileCreated { path: &'a Path },
+    FileCopied { path: &'a Path },
+    FileSkipped { path: &'a Path },
+    FileConflict { path: &'a Path },
+    FileOverwritten { path: &'a Path },
+    FileRemoved { path: &'a Path },
+    FileTrashed { path: &'a Path, to: &'a Path },
+    BackupCreated { path: &'a Path, entries: usize },
+    PullRequestOpened { url: &'a str },
+    NothingToDo { reason: &'a str },
+    Occurrence { path: &'a Path, line: Option<usize>, text: &'a str },
+    VerifySummary { name: &'a str, occurrences: usize, path: &'a Path },
+    Finding { kind: &'a str, path: &'a Path, line: Option<usize>, message: &'a str },
+    DoctorSummary { findings: usize, path: &'a Path },
+    PluginVeto { path: &'a Path, plugin: &'a str },
+    SecretSkipped { path: &'a Path },
+    GeneratedSkipped { path: &'a Path },
+    OldNameParts { parts: &'a str },
+    ReservedNameEscaped { name: &'a str, escaped: &'a str },
+    ServerListening { address: &'a str },
+    ServerRequest { method: &'a str },
+    Summary { renamed: usize, substitutions: usize, refreshed: usize, output: &'a Path },
+    Error { message: &'a str },
+}
+
+// A value of a structured event field
+enum Field<'a> {
+    Str(&'a str),
+    Path(&'a Path),
+    Number(usize),
+}
+
+impl Event<'_> {
+    // Name of the event in structured logs
+    fn name(&self) -> &'static str {
+        match self {
+            Event::DirectoryCreated { .. } => "directory_created",
+            Event::FileRenamed { .. } => "file_renamed",
+            Event::ContentRewritten { .. } => "content_rewritten",
+            Event::Substitution { .. } => "substitution",
+            Event::FileCreated { .. } => "file_created",
+            Event::FileCopied { .. } => "file_copied",
+            Event::FileSkipped { .. } => "file_skipped",
+            Event::FileConflict { .. } => "file_conflict",
+            Event::FileOverwritten { .. } => "file_overwritten",
+            Event::FileRemoved { .. } => "file_removed",
+            Event::FileTrashed { .. } => "file_trashed",
+            Event::BackupCreated { .. } => "backup_created",
+            Event::PullRequestOpened { .. } => "pull_request_opened",
+            Event::NothingToDo { .. } => "nothing_to_do",
+            Event::Occurrence { .. } => "occurrence",
+            Event::VerifySummary { .. } => "verify_summary",
+            Event::Finding { .. } => "finding",
+            Event::DoctorSummary { .. } => "doctor_summary",
+            Event::PluginVeto { .. } => "plugin_veto",
+            Event::SecretSkipped { .. } => "secret_skipped",
+            Event::GeneratedSkipped { .. } => "generated_skipped",
+            Event::OldNameParts { .. } => "old_name_parts",
+            Event::ReservedNameEscaped { .. } => "reserved_name_escaped",
+            Event::ServerListening { .. } => "server_listening",
+            Event::ServerRequest { .. } => "server_request",
+            Event::Summary { .. } => "summary",
+            Event::Error { .. } => "error",
+        }
+    }
+
+    // Lowest verbosity at which the event is printed
+    fn verbosity(&self) -> Verbosity {
+        match self {
+            Event::Error { .. } => Verbosity::Quiet,
+            Event::Summary { .. }
+            | Event::ServerListening { .. }
+            | Event::OldNameParts { .. }
+            | Event::ReservedNameEscaped { .. }
+            | Event::FileConflict { .. }
+            | Event::SecretSkipped { .. }
+            | Event::GeneratedSkipped { .. }
+            | Event::FileOverwritten { .. }
+            | Event::NothingToDo { .. }
+            | Event::Occurrence { .. }
+            | Event::VerifySummary { .. }
+            | Event::Finding { .. }
+            | Event::DoctorSummary { .. }
+            | Event::BackupCreated { .. }
+            | Event::PullRequestOpened { .. } => Verbosity::Normal,
+            Event::Substitution { .. } => Verbosity::Trace,
+            _ => Verbosity::Verbose,
+        }
+    }
+
+    fn level(&self) -> &'static str {
+        match self {
+            Event::Error { .. } => "error",
+            Event::ReservedNameEscaped { .. }
+            | Event::FileConflict { .. }
+            | Event::SecretSkipped { .. }
+            | Event::GeneratedSkipped { .. }
+            | Event::Occurrence { .. }
+            | Event::Finding { .. } => "warn",
+            Event::Substitution { .. } => "trace",
+            Event::Summary { .. }
+            | Event::ServerListening { .. }
+            | Event::OldNameParts { .. }
+            | Event::FileOverwritten { .. }
+            | Event::NothingToDo { .. }
+            | Event::VerifySummary { .. }
+            | Event::DoctorSummary { .. }
+            | Event::BackupCreated { .. }
+            | Event::PullRequestOpened { .. } => "info",
+            _ => "debug",
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, Field<'_>)> {
+        match *self {
+            Event::DirectoryCreated { path } => vec![("path", Field::Path(path))],
+            Event::FileRenamed { from, to } => vec![("from", Field::Str(from)), ("to", Field::Str(to))],
+            Event::ContentRewritten { path, substitutions } => {
+                vec![("path", Field::Path(path)), ("substitutions", Field::Number(substitutions))]
+            }
+            Event::Substitution { path, from, to, count } => vec![
+                ("path", Field::Path(path)),
+                ("from", Field::Str(from)),
+                ("to", Field::Str(to)),
+                ("count", Field::Number(count)),
+            ],
+            Event::FileCreated { path } => vec![("path", Field::Path(path))],
+            Event::FileCopied { path } => vec![("path", Field::Path(path))],
+            Event::FileSkipped { path } => vec![("path", Field::Path(path))],
+            Event::FileConflict { path } => vec![("path", Field::Path(path))],
+            Event::FileOverwritten { path } => vec![("path", Field::Path(path))],
+            Event::FileRemoved { path } => vec![("path", Field::Path(path))],
+            Event::FileTrashed { path, to } => vec![("path", Field::Path(path)), ("to", Field::Path(to))],
+            Event::BackupCreated { path, entries } => vec![("path", Field::Path(path)), ("entries", Field::Number(entries))],
+            Event::PullRequestOpened { url } => vec![("url", Field::Str(url))],
+            Event::NothingToDo { reason } => vec![("reason", Field::Str(reason))],
+            Event::Occurrence { path, line, text } => {
+                let mut fields = vec![("path", Field::Path(path))];
+                if let Some(line) = line {
+                    fields.push(("line", Field::Number(line)));
+                }
+                fields.push(("text", Field::Str(text)));
+                fields
+            }
+            Event::VerifySummary { name, occurrences, path } => vec![
+                ("name", Field::Str(name)),
+                ("occurrences", Field::Number(occurrences)),
+                ("path", Field::Path(path)),
+            ],
+            Event::Finding { kind, path, line, message } => {
+                let mut fields = vec![("kind", Field::Str(kind)), ("path", Field::Path(path))];
+                if let Some(line) = line {
+                    fields.push(("line", Field::Number(line)));
+                }
+                fields.push(("message", Field::Str(message)));
+                fields
+            }
+            Event::DoctorSummary { findings, path } => vec![("findings", Field::Number(findings)), ("path", Field::Path(path))],
+            Event::PluginVeto { path, plugin } => vec![("path", Field::Path(path)), ("plugin", Field::Str(plugin))],
+            Event::SecretSkipped { path } => vec![("path", Field::Path(path))],
+            Event::GeneratedSkipped { path } => vec![("path", Field::Path(path))],
+            Event::OldNameParts { parts } => vec![("parts", Field::Str(parts))],
+            Event::ReservedNameEscaped { name, escaped } => vec![("name", Field::Str(name)), ("escaped", Field::Str(escaped))],
+            Event::ServerListening { address } => vec![("address", Field::Str(address))],
+            Event::ServerRequest { method } => vec![("method", Field::Str(method))],
+            Event::Summary { renamed, substitutions, refreshed, output } => vec![
+                ("renamed", Field::Number(renamed)),
+                ("substitutions", Field::Number(substitutions)),
+                ("refreshed", Field::Number(refreshed)),
+                ("output", Field::Path(output)),
+            ],
+            Event::Error { message } => vec![("message", Field::Str(message))],
+        }
+    }
+
+    // Human readable form of the event
+    fn pretty(&self) -> String {
+        match self {
+            Event::DirectoryCreated { path } => format!("Creating directory: {}", path.display()),
+            Event::FileRenamed { from, to } => format!("Renaming: {from} -> {to}"),
+            Event::ContentRewritten { path, .. } => format!("Renaming content of file: {}", path.display()),
+            Event::Substitution { from, to, count, .. } => format!("  {from} -> {to} ({count}x)"),
+            Event::FileCreated { path } => format!("Creating file: {}", path.display()),
+            Event::FileCopied { path } => format!("Failed to read file, doing a simple copy: {}", path.display()),
+            Event::FileSkipped { path } => format!("Skipping existing file: {}", path.display()),
+            Event::FileConflict { path } => format!("Keeping existing file that differs: {}", path.display()),
+            Event::FileOverwritten { path } => format!("Refreshing stale file: {}", path.display()),
+            Event::FileRemoved { path } => format!("Removing: {}", path.display()),
+            Event::FileTrashed { path, to } => format!("Moving to the trash: {} -> {}", path.display(), to.display()),
+            Event::BackupCreated { path, entries } => format!("Backed up {entries} files and directories to: {}", path.display()),
+            Event::PullRequestOpened { url } => format!("Opened a pull request: {url}"),
+            Event::NothingToDo { reason } => format!("Nothing to do, {reason}"),
+            Event::Occurrence { path, line: Some(line), text } => format!("{}:{line}: {text}", path.display()),
+            Event::Occurrence { path, line: None, .. } => path.display().to_string(),
+            Event::VerifySummary { name, occurrences: 0, path } => format!("No occurrences of {name} in {}", path.display()),
+            Event::VerifySummary { name, occurrences, path } => format!("Found {occurrences} occurrences of {name} in {}", path.display()),
+            Event::Finding { path, line: Some(line), message, .. } => format!("{}:{line}: {message}", path.display()),
+            Event::Finding { path, line: None, message, .. } => format!("{}: {message}", path.display()),
+            Event::DoctorSummary { findings: 0, path } => format!("No problems found in {}", path.display()),
+            Event::DoctorSummary { findings, path } => format!("Found {findings} possible problems in {}", path.display()),
+            Event::PluginVeto { path, plugin } => format!("Plugin {plugin} vetoed changes to: {}", path.display()),
+            Event::SecretSkipped { path } => {
+                format!("Copying the content unchanged, the file may hold secrets (see --rewrite-secrets): {}", path.display())
+            }
+            Event::GeneratedSkipped { path } => {
+                format!("Copying the content unchanged, the file is generated (see --rewrite-generated): {}", path.display())
+            }
+            Event::OldNameParts { parts } => format!("Splitting the old name into: {parts}"),
+            Event::ReservedNameEscaped { name, escaped } => format!("Escaping name reserved on Windows: {name} -> {escaped}"),
+            Event::ServerListening { address } => format!("Listening on http://{address}"),
+            Event::ServerRequest { method } => format!("Request: {method}"),
+            Event::Summary { renamed, substitutions, refreshed: 0, output } => format!(
+                "Renamed {renamed} files and directories and made {substitutions} substitutions in {}",
+                output.display()
+            ),
+            Event::Summary { renamed, substitutions, refreshed, output } => format!(
+                "Renamed {renamed} files and directories, made {substitutions} substitutions and refreshed {refreshed} stale files in {}",
+                output.display()
+            ),
+            Event::Error { message } => message.to_string(),
+        }
+    }
+
+    // Color of the event when printed to a terminal
+    fn color(&self) -> Option<&'static str> {
+        match self {
+            Event::DirectoryCreated { .. } | Event::FileCreated { .. } => Some(GREEN),
+            Event::FileRenamed { .. } => Some(CYAN),
+            Event::FileSkipped { .. }
+            | Event::FileConflict { .. }
+            | Event::FileOverwritten { .. }
+            | Event::FileRemoved { .. }
+            | Event::FileTrashed { .. }
+            | Event::PluginVeto { .. }
+            | Event::SecretSkipped { .. }
+            | Event::GeneratedSkipped { .. }
+            | Event::ReservedNameEscaped { .. }
+            | Event::Occurrence { .. }
+            | Event::Finding { .. } => Some(YELLOW),
+            Event::Error { .. } => Some(RED),
+            _ => None,
+        }
+    }
+
+    // Structured form of the event, a single line JSON object
+    fn json(&self) -> String {
+        let mut out = format!(
+            "{{\"timestamp\":\"{}\",\"level\":\"{}\",\"event\":\"{}\"",
+            timestamp(SystemTime::now()),
+            self.level(),
+            self.name()
+        );
+        for (key, value) in self.fields() {
+            let value = match value {
+                Field::Str(s) => json::string(s),
+                Field::Path(p) => json::string(&p.to_string_lossy()),
+                Field::Number(n) => n.to_string(),
+            };
+            out.push_str(&format!(",\"{key}\":{value}"));
+        }
+        out.push('}');
+        out
+    }
+}
+
+// Prints events depending on the configured verbosity and format. This stands in for tracing with
+// a JSON subscriber: neither tracing nor tracing-subscriber is available to the offline build, and
+// a closed set of events gives the JSON lines the same fields in every release.
+#[derive(Debug, Clone, Copy)]
+pub struct Output {
+    verbosity: Verbosity,
+    format: LogFormat,
+    color_stdout: bool,
+    color_stderr: bool,
+}
+
+impl Output {
+    pub fn new(verbosity: Verbosity, format: LogFormat, color: ColorChoice) -> Self {
+        let no_color = no_color_env();
+        Self {
+            verbosity,
+            format,
+            color_stdout: color.enabled(std::io::stdout().is_terminal(), no_color),
+            color_stderr: color.enabled(std::io::stderr().is_terminal(), no_color),
+        }
+    }
+
+    pub fn emit(&self, event: Event) {
+        if self.verbosity < event.verbosity() {
+            return;
+        }
+        let is_error = matches!(event, Event::Error { .. });
+        let colored = if is_error { self.color_stderr } else { self.color_stdout };
+        let line = match (self.format, event.color()) {
+            (LogFormat::Pretty, Some(color)) if colored => format!("{color}{}{RESET}", event.pretty()),
+            (LogFormat::Pretty, _) => event.pretty(),
+            (LogFormat::Json, _) => event.json(),
+        };
+        if is_error {
+            eprintln!("{line}");
+        } else {
+            println!("{line}");
+        }
+    }
+
+    pub fn error(&self, message: &str) {
+        self.emit(Event::Error { message });
+    }
+}
+
+// Format a time as an RFC 3339 UTC timestamp with millisecond precision
+pub fn timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (hour, minute, second) = ((secs / 3600) % 24, (secs / 60) % 60, secs % 60);
+
+    // Convert days since the epoch to a civil date
+    // See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = (secs / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}.{:03}Z",
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_verbosity_from_flags() {
+        assert_eq!(Verbosity::from_flags(true, 2), Verbosity::Quiet);
+        assert_eq!(Verbosity::from_flags(false, 0), Verbosity::Normal);
+        assert_eq!(Verbosity::from_flags(false, 1), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_flags(false, 2), Verbosity::Trace);
+        assert_eq!(Verbosity::from_flags(false, 5), Verbosity::Trace);
+    }
+
+    #[test]
+    fn test_color_choice_enabled() {
+        assert!(ColorChoice::Auto.enabled(true, false));
+        assert!(!ColorChoice::Auto.enabled(false, false));
+        assert!(!ColorChoice::Auto.enabled(true, true));
+        assert!(ColorChoice::Always.enabled(false, true));
+        assert!(!ColorChoice::Never.enabled(true, false));
+    }
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        let time = UNIX_EPOCH + Duration::from_millis(1_709_210_096_123);
+        assert_eq!(timestamp(time), "2024-02-29T12:34:56.123Z");
+    }
+
+    #[test]
+    fn test_event_json() {
+        let event = Event::FileRenamed { from: "my \"project\"", to: "new\\project" };
+        let json = event.json();
+        assert!(json.starts_with("{\"timestamp\":\""));
+        assert!(json.ends_with(
+            "\"level\":\"debug\",\"event\":\"file_renamed\",\"from\":\"my \\\"project\\\"\",\"to\":\"new\\\\project\"}"
+        ));
+    }
+
+    #[test]
+    fn test_occurrence() {
+        let path = Path::new("src/main.rs");
+        assert_eq!(Event::Occurrence { path, line: Some(3), text: "old_project()" }.pretty(), "src/main.rs:3: old_project()");
+        assert_eq!(Event::Occurrence { path, line: None, text: "main.rs" }.pretty(), "src/main.rs");
+        assert!(Event::Occurrence { path, line: None, text: "main.rs" }.json().ends_with("\"path\":\"src/main.rs\",\"text\":\"main.rs\"}"));
+    }
+}
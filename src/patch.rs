@@ -0,0 +1,242 @@
+use std::path::{Path, PathBuf};
+
+// Lines of context around the changes of a hunk, like diff -u
+const CONTEXT: usize = 3;
+
+// A file written by a run: renamed, with its content changed, or both
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    // The old and new content, without a content change
+    pub content: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+// A unified diff of the changes in git's format, which `git apply` applies. Paths are relative to
+// the old and new root, and changes outside of them are left out. Renames get "rename from/to"
+// headers. Content changes of binary files can't be applied without the full git index, so only
+// their renames are included.
+pub fn render(changes: &[Change], old_root: &Path, new_root: &Path) -> String {
+    let absolute = |path: &Path| std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let (old_root, new_root) = (absolute(old_root), absolute(new_root));
+    let mut out = String::new();
+    for change in changes {
+        let (from, to) = (absolute(&change.from), absolute(&change.to));
+        let (Ok(from), Ok(to)) = (from.strip_prefix(&old_root), to.strip_prefix(&new_root)) else {
+            continue;
+        };
+        let (from, to) = (git_path(from), git_path(to));
+        let hunks = match &change.content {
+            Some((old, new)) => match (std::str::from_utf8(old), std::str::from_utf8(new)) {
+                (Ok(old), Ok(new)) => hunks(old, new),
+                _ => String::new(),
+            },
+            None => String::new(),
+        };
+        if from == to && hunks.is_empty() {
+            continue;
+        }
+
+        out.push_str(&format!("diff --git a/{from} b/{to}\n"));
+        if from != to {
+            if hunks.is_empty() {
+                out.push_str("similarity index 100%\n");
+            }
+            out.push_str(&format!("rename from {from}\nrename to {to}\n"));
+        }
+        if !hunks.is_empty() {
+            out.push_str(&format!("--- a/{from}\n+++ b/{to}\n"));
+            out.push_str(&hunks);
+        }
+    }
+    out
+}
+
+fn git_path(path: &Path) -> String {
+    path.components().map(|component| component.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Equal,
+    Delete,
+    Insert,
+}
+
+// The hunks of a unified diff between two texts, empty if they are equal
+fn hunks(old: &str, new: &str) -> String {
+    let old = old.split_inclusive('\n').collect::<Vec<_>>();
+    let new = new.split_inclusive('\n').collect::<Vec<_>>();
+    let edits = diff(&old, &new);
+
+    // Position of every edit in the old and new lines
+    let mut positions = Vec::with_capacity(edits.len());
+    let (mut i, mut j) = (0, 0);
+    for &edit in &edits {
+        positions.push((edit, i, j));
+        match edit {
+            Edit::Equal => (i, j) = (i + 1, j + 1),
+            Edit::Delete => i += 1,
+            Edit::Insert => j += 1,
+        }
+    }
+
+    // Changes closer than twice the context share a hunk
+    let changed = (0..positions.len()).filter(|&index| positions[index].0 != Edit::Equal).collect::<Vec<_>>();
+    let mut groups: Vec<(usize, usize)> = vec![];
+    for &index in &changed {
+        match groups.last_mut() {
+            Some((_, last)) if index - *last <= 2 * CONTEXT + 1 => *last = index,
+            _ => groups.push((index, index)),
+        }
+    }
+
+    let mut out = String::new();
+    for (first, last) in groups {
+        let range = first.saturating_sub(CONTEXT)..(last + CONTEXT + 1).min(positions.len());
+        let (_, old_start, new_start) = positions[range.start];
+        let old_count = positions[range.clone()].iter().filter(|(edit, _, _)| *edit != Edit::Insert).count();
+        let new_count = positions[range.clone()].iter().filter(|(edit, _, _)| *edit != Edit::Delete).count();
+        // An empty range starts at the line before it
+        let old_start = if old_count == 0 { old_start } else { old_start + 1 };
+        let new_start = if new_count == 0 { new_start } else { new_start + 1 };
+        out.push_str(&format!("@@ -{old_start},{old_count} +{new_start},{new_count} @@\n"));
+        for &(edit, i, j) in &positions[range] {
+            let (prefix, line) = match edit {
+                Edit::Equal => (' ', old[i]),
+                Edit::Delete => ('-', old[i]),
+                Edit::Insert => ('+', new[j]),
+            };
+            out.push(prefix);
+            out.push_str(line);
+            if !line.ends_with('\n') {
+                out.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+    }
+    out
+}
+
+// Edit script between the two sequences. Replacing names never adds or removes lines, so sequences
+// of the same length are compared line by line. Others get the shortest edit script.
+fn diff<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Edit> {
+    if a.len() != b.len() {
+        return shortest_edit_script(a, b);
+    }
+    let mut edits = Vec::with_capacity(a.len());
+    let mut changed = 0;
+    for index in 0..=a.len() {
+        if index < a.len() && a[index] != b[index] {
+            changed += 1;
+            continue;
+        }
+        // A run of changed lines is removed before it is added again
+        edits.extend(std::iter::repeat_n(Edit::Delete, changed));
+        edits.extend(std::iter::repeat_n(Edit::Insert, changed));
+        changed = 0;
+        if index < a.len() {
+            edits.push(Edit::Equal);
+        }
+    }
+    edits
+}
+
+// Shortest edit script between the two sequences, with Myers' algorithm
+fn shortest_edit_script<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Edit> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let offset = n + m + 1;
+    let index = |k: isize| (k + offset) as usize;
+    let mut v = vec![0; 2 * offset as usize + 1];
+    // The furthest reaching paths before every step, to walk back from the end
+    let mut trace = vec![];
+    'search: for d in 0..=n + m {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) { v[index(k + 1)] } else { v[index(k - 1)] + 1 };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                (x, y) = (x + 1, y + 1);
+            }
+            v[index(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut edits = vec![];
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let previous_k = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) { k + 1 } else { k - 1 };
+        let previous_x = v[index(previous_k)];
+        let previous_y = previous_x - previous_k;
+        while x > previous_x && y > previous_y {
+            edits.push(Edit::Equal);
+            (x, y) = (x - 1, y - 1);
+        }
+        if d > 0 {
+            if x == previous_x {
+                edits.push(Edit::Insert);
+                y -= 1;
+            } else {
+                edits.push(Edit::Delete);
+                x -= 1;
+            }
+        }
+    }
+    edits.reverse();
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let (a, b) = (['a', 'b', 'c', 'a', 'b', 'b', 'a'], ['c', 'b', 'a', 'b', 'a', 'c']);
+        let edits = diff(&a, &b);
+        assert_eq!(edits.iter().filter(|&&edit| edit != Edit::Equal).count(), 5);
+        use Edit::*;
+        assert_eq!(diff(&['a', 'b', 'c', 'd'], &['a', 'x', 'y', 'd']), vec![Equal, Delete, Delete, Insert, Insert, Equal]);
+        assert_eq!(diff(&['a'], &['a']), vec![Edit::Equal]);
+        assert_eq!(diff::<char>(&[], &['a']), vec![Edit::Insert]);
+    }
+
+    #[test]
+    fn test_hunks() {
+        let old = (1..=10).map(|i| format!("line {i}\n")).collect::<String>();
+        let new = old.replace("line 2\n", "line two\n").replace("line 10\n", "line 10");
+        assert_eq!(
+            hunks(&old, &new),
+            "@@ -1,5 +1,5 @@\n line 1\n-line 2\n+line two\n line 3\n line 4\n line 5\n\
+             @@ -7,4 +7,4 @@\n line 7\n line 8\n line 9\n-line 10\n+line 10\n\\ No newline at end of file\n"
+        );
+        assert_eq!(hunks(&old, &old), "");
+        assert_eq!(hunks("", "a\n"), "@@ -0,0 +1,1 @@\n+a\n");
+    }
+
+    #[test]
+    fn test_render() {
+        let changes = vec![
+            Change {
+                from: PathBuf::from("my-project/src/my_project.rs"),
+                to: PathBuf::from("new-project/src/new_project.rs"),
+                content: Some((b"struct MyProject;\n".to_vec(), b"struct NewProject;\n".to_vec())),
+            },
+            Change { from: PathBuf::from("my-project/my-project.png"), to: PathBuf::from("new-project/new-project.png"), content: None },
+            Change { from: PathBuf::from("my-project/a.txt"), to: PathBuf::from("new-project/a.txt"), content: None },
+        ];
+        assert_eq!(
+            render(&changes, Path::new("my-project"), Path::new("new-project")),
+            "diff --git a/src/my_project.rs b/src/new_project.rs\n\
+             rename from src/my_project.rs\nrename to src/new_project.rs\n\
+             --- a/src/my_project.rs\n+++ b/src/new_project.rs\n\
+             @@ -1,1 +1,1 @@\n-struct MyProject;\n+struct NewProject;\n\
+             diff --git a/my-project.png b/new-project.png\n\
+             similarity index 100%\nrename from my-project.png\nrename to new-project.png\n"
+        );
+    }
+}
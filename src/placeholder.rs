@@ -0,0 +1,76 @@
+use std::time::SystemTime;
+
+use crate::output;
+
+// Expand the placeholders of a new name or replacement: "${VAR}" is the environment variable VAR,
+// "{{date}}" the current UTC date like 2024-02-29 and "{{user}}" the current user. Groups like
+// "${1}" of regex replacements are left alone, and so are unknown "{{...}}".
+pub fn expand(text: &str) -> Result<String, String> {
+    let date = &output::timestamp(SystemTime::now())[..10];
+    expand_with(text, |name| std::env::var(name).ok(), date)
+}
+
+fn expand_with(text: &str, env: impl Fn(&str) -> Option<String>, date: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(['$', '{']) {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("${")
+            && let Some(end) = after.find('}')
+            && is_variable(&after[..end])
+        {
+            let name = &after[..end];
+            out.push_str(&env(name).ok_or_else(|| format!("Environment variable {name} is not set"))?);
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix("{{date}}") {
+            out.push_str(date);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("{{user}}") {
+            let user = env("USER").or_else(|| env("USERNAME")).ok_or("Can't tell the current user, USER is not set")?;
+            out.push_str(&user);
+            rest = after;
+        } else {
+            out.push_str(&rest[..1]);
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+// Replace the named placeholders like "{{new_name}}" of a template with their values
+pub fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    values.iter().fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{{{name}}}}}"), value))
+}
+
+// Names of environment variables, which unlike regex groups don't start with a digit
+fn is_variable(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        let env = |name: &str| match name {
+            "BUILD" => Some("42".to_string()),
+            "USER" => Some("ada".to_string()),
+            _ => None,
+        };
+        let expand = |text| expand_with(text, env, "2024-02-29");
+        assert_eq!(expand("app-${BUILD}").unwrap(), "app-42");
+        assert_eq!(expand("{{user}}-{{date}}").unwrap(), "ada-2024-02-29");
+        assert_eq!(expand("v${1} $$ {{title}} ${ x} {").unwrap(), "v${1} $$ {{title}} ${ x} {");
+        assert_eq!(expand("${MISSING}").unwrap_err(), "Environment variable MISSING is not set");
+        assert!(expand_with("{{user}}", |_| None, "").is_err());
+    }
+
+    #[test]
+    fn test_fill() {
+        let values = [("old_name", "my-project"), ("new_name", "new-project")];
+        assert_eq!(fill("Rename {{old_name}} to {{new_name}} {{other}}", &values), "Rename my-project to new-project {{other}}");
+    }
+}
@@ -0,0 +1,482 @@
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+
+use crate::ecosystem::{Ecosystem, Ecosystems};
+use crate::json::{self, Value};
+use crate::output::{ColorChoice, LogFormat, Output, Verbosity};
+use crate::syntax::Node;
+use crate::{Action, Context, Merge, Operation, config, regex, run, sha256};
+
+// Version of the plan file format, increased when older plans can't be applied anymore
+const VERSION: usize = 1;
+
+// A reviewed rename: the options it was planned with and the operations it consists of
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    pub name: String,
+    pub input: PathBuf,
+    pub output: PathBuf,
+    pub old_parts: Vec<String>,
+    pub acronyms: Vec<String>,
+    pub escape_reserved_names: bool,
+    pub archives: bool,
+    pub workspace: bool,
+    pub scope: Option<PathBuf>,
+    pub names_only: bool,
+    pub exclude: Vec<String>,
+    pub include_vendored: bool,
+    pub tracked_only: bool,
+    // The config file with the replacement rules, read again when the plan is applied
+    pub config: Option<PathBuf>,
+    pub nodes: Option<Vec<Node>>,
+    pub ecosystems: Ecosystems,
+    pub rewrite_secrets: bool,
+    pub rewrite_generated: bool,
+    pub treat_as_text: Vec<String>,
+    pub treat_as_binary: Vec<String>,
+    pub identifier_boundaries: bool,
+    // Old and new name of the organization, as they were given
+    pub org: Option<(String, String)>,
+    pub domain: Option<(String, String)>,
+    // Old and new npm package name when the package moves to another scope
+    pub npm_package: Option<(String, String)>,
+    pub copyright: Option<(String, String)>,
+    // The year copyright ranges are extended to, fixed when the plan is made
+    pub copyright_year: Option<u32>,
+    pub regen_uuids: Vec<String>,
+    pub regen_vs_guids: bool,
+    pub also: Vec<(String, String)>,
+    // The rules of --replace-regex, as they were given
+    pub replace_regex: Vec<String>,
+    pub merge: Merge,
+    pub operations: Vec<PlannedOperation>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedOperation {
+    pub operation: Operation,
+    // SHA-256 of the input file when the plan was made, to detect changes since
+    pub sha256: Option<String>,
+}
+
+impl Plan {
+    // The plan of a (dry) run of the context
+    pub fn from_context(ctx: &Context) -> Self {
+        let operations = ctx
+            .operations
+            .iter()
+            .map(|operation| PlannedOperation {
+                operation: operation.clone(),
+                sha256: std::fs::read(&operation.input).ok().map(|content| sha256::hash(&content)),
+            })
+            .collect();
+        Self {
+            name: ctx.new_name.original.clone(),
+            input: ctx.input.clone(),
+            output: ctx.output.clone(),
+            old_parts: ctx.old_name.parts.clone(),
+            acronyms: ctx.new_name.acronyms.clone(),
+            escape_reserved_names: ctx.escape_reserved_names,
+            archives: ctx.archives,
+            workspace: ctx.workspace,
+            scope: ctx.scope.clone(),
+            names_only: ctx.names_only,
+            exclude: ctx.exclude.clone(),
+            include_vendored: ctx.include_vendored,
+            tracked_only: ctx.tracked.is_some(),
+            config: ctx.config.path.clone(),
+            nodes: ctx.nodes.clone(),
+            ecosystems: ctx.ecosystems,
+            rewrite_secrets: ctx.rewrite_secrets,
+            rewrite_generated: ctx.rewrite_generated,
+            treat_as_text: ctx.treat_as_text.clone(),
+            treat_as_binary: ctx.treat_as_binary.clone(),
+            identifier_boundaries: ctx.identifier_boundaries,
+            org: ctx.org.as_ref().map(|(old, new)| (old.original.clone(), new.original.clone())),
+            domain: ctx.domain.clone(),
+            npm_package: ctx.npm_package.clone(),
+            copyright: ctx.copyright.clone(),
+            copyright_year: ctx.copyright_year,
+            regen_uuids: ctx.regen_uuids.clone(),
+            regen_vs_guids: ctx.regen_vs_guids,
+            also: ctx.also.clone(),
+            replace_regex: ctx.replace_regex.iter().map(regex::format_rule).collect(),
+            merge: ctx.merge,
+            operations,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let path = |path: &Path| Value::from(path.to_string_lossy().as_ref());
+        let strings = |strings: &[String]| Value::Array(strings.iter().map(|s| s.as_str().into()).collect());
+        let operations = self
+            .operations
+            .iter()
+            .map(|planned| {
+                object(vec![
+                    ("action", planned.operation.action.name().into()),
+                    ("input", path(&planned.operation.input)),
+                    ("output", path(&planned.operation.output)),
+                    ("substitutions", planned.operation.substitutions.into()),
+                    ("note", planned.operation.note.as_deref().map_or(Value::Null, Value::from)),
+                    ("sha256", planned.sha256.clone().map_or(Value::Null, Value::from)),
+                ])
+            })
+            .collect();
+        let merge = self.merge.to_possible_value().unwrap();
+        let mut entries = vec![
+            ("version", VERSION.into()),
+            ("name", self.name.as_str().into()),
+            ("input", path(&self.input)),
+            ("output", path(&self.output)),
+            ("old_parts", strings(&self.old_parts)),
+            ("acronyms", strings(&self.acronyms)),
+            ("escape_reserved_names", self.escape_reserved_names.into()),
+            ("archives", self.archives.into()),
+            ("workspace", self.workspace.into()),
+            ("scope", self.scope.as_deref().map_or(Value::Null, path)),
+            ("names_only", self.names_only.into()),
+            ("exclude", strings(&self.exclude)),
+            ("include_vendored", self.include_vendored.into()),
+            ("tracked_only", self.tracked_only.into()),
+            ("config", self.config.as_deref().map_or(Value::Null, path)),
+            (
+                "nodes",
+                self.nodes.as_ref().map_or(Value::Null, |nodes| Value::Array(nodes.iter().map(|node| node.name().into()).collect())),
+            ),
+            ("rewrite_secrets", self.rewrite_secrets.into()),
+            ("rewrite_generated", self.rewrite_generated.into()),
+            ("treat_as_text", strings(&self.treat_as_text)),
+            ("treat_as_binary", strings(&self.treat_as_binary)),
+            ("identifier_boundaries", self.identifier_boundaries.into()),
+            ("old_org", self.org.as_ref().map_or(Value::Null, |(old, _)| old.as_str().into())),
+            ("new_org", self.org.as_ref().map_or(Value::Null, |(_, new)| new.as_str().into())),
+            ("old_domain", self.domain.as_ref().map_or(Value::Null, |(old, _)| old.as_str().into())),
+            ("new_domain", self.domain.as_ref().map_or(Value::Null, |(_, new)| new.as_str().into())),
+            ("old_npm_package", self.npm_package.as_ref().map_or(Value::Null, |(old, _)| old.as_str().into())),
+            ("new_npm_package", self.npm_package.as_ref().map_or(Value::Null, |(_, new)| new.as_str().into())),
+            ("old_copyright_holder", self.copyright.as_ref().map_or(Value::Null, |(old, _)| old.as_str().into())),
+            ("new_copyright_holder", self.copyright.as_ref().map_or(Value::Null, |(_, new)| new.as_str().into())),
+            ("copyright_year", self.copyright_year.map_or(Value::Null, |year| (year as usize).into())),
+            ("regen_uuids", strings(&self.regen_uuids)),
+            ("regen_vs_guids", self.regen_vs_guids.into()),
+            (
+                "also",
+                Value::Array(self.also.iter().map(|(from, to)| object(vec![("from", from.as_str().into()), ("to", to.as_str().into())])).collect()),
+            ),
+            ("replace_regex", strings(&self.replace_regex)),
+            ("merge", merge.get_name().into()),
+            ("operations", Value::Array(operations)),
+        ];
+        // The ecosystems go after the nodes, one flag each
+        let at = entries.iter().position(|(key, _)| *key == "nodes").unwrap() + 1;
+        entries.splice(at..at, Ecosystem::all().map(|ecosystem| (ecosystem.name(), self.ecosystems.contains(ecosystem).into())));
+        object(entries)
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        if usize_field(value, "version")? != VERSION {
+            return Err(format!("Unsupported plan version, expected {VERSION}"));
+        }
+        let operations = match value.get("operations") {
+            Some(Value::Array(operations)) => operations,
+            _ => return Err("Missing array field: operations".to_string()),
+        };
+        let operations = operations
+            .iter()
+            .map(|operation| {
+                let action = str_field(operation, "action")?;
+                let action = Action::from_name(action).ok_or_else(|| format!("Unknown action: {action}"))?;
+                Ok(PlannedOperation {
+                    operation: Operation {
+                        action,
+                        input: PathBuf::from(str_field(operation, "input")?),
+                        output: PathBuf::from(str_field(operation, "output")?),
+                        substitutions: usize_field(operation, "substitutions")?,
+                        note: operation.get("note").and_then(Value::as_str).map(str::to_string),
+                    },
+                    sha256: operation.get("sha256").and_then(Value::as_str).map(str::to_string),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let merge = str_field(value, "merge")?;
+        Ok(Self {
+            name: str_field(value, "name")?.to_string(),
+            input: PathBuf::from(str_field(value, "input")?),
+            output: PathBuf::from(str_field(value, "output")?),
+            old_parts: strings_field(value, "old_parts")?,
+            acronyms: strings_field(value, "acronyms")?,
+            escape_reserved_names: bool_field(value, "escape_reserved_names")?,
+            archives: bool_field(value, "archives")?,
+            workspace: bool_field(value, "workspace")?,
+            scope: value.get("scope").and_then(Value::as_str).map(PathBuf::from),
+            names_only: bool_field(value, "names_only")?,
+            exclude: strings_field(value, "exclude")?,
+            include_vendored: matches!(value.get("include_vendored"), Some(Value::Bool(true))),
+            tracked_only: matches!(value.get("tracked_only"), Some(Value::Bool(true))),
+            config: value.get("config").and_then(Value::as_str).map(PathBuf::from),
+            nodes: match value.get("nodes") {
+                None | Some(Value::Null) => None,
+                Some(_) => Some(
+                    strings_field(value, "nodes")?
+                        .iter()
+                        .map(|node| Node::from_name(node).ok_or_else(|| format!("Unknown node: {node}")))
+                        .collect::<Result<Vec<_>, String>>()?,
+                ),
+            },
+            ecosystems: Ecosystem::all().filter(|ecosystem| matches!(value.get(ecosystem.name()), Some(Value::Bool(true)))).collect(),
+            rewrite_secrets: matches!(value.get("rewrite_secrets"), Some(Value::Bool(true))),
+            rewrite_generated: matches!(value.get("rewrite_generated"), Some(Value::Bool(true))),
+            treat_as_text: match value.get("treat_as_text") {
+                None => vec![],
+                Some(_) => strings_field(value, "treat_as_text")?,
+            },
+            treat_as_binary: match value.get("treat_as_binary") {
+                None => vec![],
+                Some(_) => strings_field(value, "treat_as_binary")?,
+            },
+            identifier_boundaries: matches!(value.get("identifier_boundaries"), Some(Value::Bool(true))),
+            org: match (value.get("old_org").and_then(Value::as_str), value.get("new_org").and_then(Value::as_str)) {
+                (Some(old), Some(new)) => Some((old.to_string(), new.to_string())),
+                _ => None,
+            },
+            domain: match (value.get("old_domain").and_then(Value::as_str), value.get("new_domain").and_then(Value::as_str)) {
+                (Some(old), Some(new)) => Some((old.to_string(), new.to_string())),
+                _ => None,
+            },
+            npm_package: match (value.get("old_npm_package").and_then(Value::as_str), value.get("new_npm_package").and_then(Value::as_str)) {
+                (Some(old), Some(new)) => Some((old.to_string(), new.to_string())),
+                _ => None,
+            },
+            copyright: match (
+                value.get("old_copyright_holder").and_then(Value::as_str),
+                value.get("new_copyright_holder").and_then(Value::as_str),
+            ) {
+                (Some(old), Some(new)) => Some((old.to_string(), new.to_string())),
+                _ => None,
+            },
+            copyright_year: match value.get("copyright_year") {
+                None | Some(Value::Null) => None,
+                Some(_) => Some(usize_field(value, "copyright_year")? as u32),
+            },
+            regen_uuids: match value.get("regen_uuids") {
+                None => vec![],
+                Some(_) => strings_field(value, "regen_uuids")?,
+            },
+            regen_vs_guids: matches!(value.get("regen_vs_guids"), Some(Value::Bool(true))),
+            also: match value.get("also") {
+                None => vec![],
+                Some(Value::Array(items)) => items
+                    .iter()
+                    .map(|item| Ok((str_field(item, "from")?.to_string(), str_field(item, "to")?.to_string())))
+                    .collect::<Result<Vec<_>, String>>()?,
+                Some(_) => return Err("Invalid array field: also".to_string()),
+            },
+            replace_regex: match value.get("replace_regex") {
+                None => vec![],
+                Some(_) => strings_field(value, "replace_regex")?,
+            },
+            merge: Merge::from_str(merge, false).map_err(|_| format!("Unknown merge rule: {merge}"))?,
+            operations,
+        })
+    }
+
+    // A context that renames with the options of the plan
+    fn context(&self, out: Output) -> Result<Context, String> {
+        let mut ctx = Context::new(&self.input, &self.name, out);
+        ctx.old_name.parts = self.old_parts.clone();
+        ctx.old_name = ctx.old_name.with_acronyms(&self.acronyms);
+        ctx.new_name = ctx.new_name.with_acronyms(&self.acronyms);
+        ctx.output = self.output.clone();
+        ctx.escape_reserved_names = self.escape_reserved_names;
+        ctx.archives = self.archives;
+        ctx.workspace = self.workspace;
+        ctx.scope = self.scope.clone();
+        ctx.names_only = self.names_only;
+        ctx.exclude = self.exclude.clone();
+        ctx.include_vendored = self.include_vendored;
+        if self.tracked_only {
+            ctx.tracked = Some(crate::git::tracked_paths(&self.input)?);
+        }
+        ctx.nodes = self.nodes.clone();
+        ctx.ecosystems = self.ecosystems;
+        ctx.rewrite_secrets = self.rewrite_secrets;
+        ctx.rewrite_generated = self.rewrite_generated;
+        ctx.treat_as_text = self.treat_as_text.clone();
+        ctx.treat_as_binary = self.treat_as_binary.clone();
+        ctx.identifier_boundaries = self.identifier_boundaries;
+        ctx.org = self.org.as_ref().map(|(old, new)| crate::org_names(old, new, &self.acronyms));
+        ctx.domain = self.domain.clone();
+        ctx.npm_package = self.npm_package.clone();
+        ctx.copyright = self.copyright.clone();
+        ctx.copyright_year = self.copyright_year;
+        ctx.regen_uuids = self.regen_uuids.clone();
+        ctx.regen_vs_guids = self.regen_vs_guids;
+        ctx.also = self.also.clone();
+        ctx.replace_regex = self.replace_regex.iter().map(|rule| regex::parse_rule(rule)).collect::<Result<_, _>>()?;
+        if let Some(path) = &self.config {
+            ctx.config = config::Config::load(path)?;
+        }
+        ctx.merge = self.merge;
+        Ok(ctx)
+    }
+
+    // The first difference between the project and the plan, if it changed since the plan was made
+    fn find_change(&self) -> Option<String> {
+        for planned in &self.operations {
+            let Some(expected) = &planned.sha256 else {
+                continue;
+            };
+            let path = &planned.operation.input;
+            match std::fs::read(path) {
+                Ok(content) if sha256::hash(&content) == *expected => {}
+                Ok(_) => return Some(format!("{} was changed", path.display())),
+                Err(_) => return Some(format!("{} can't be read anymore", path.display())),
+            }
+        }
+
+        // Files that were added or removed change the operations of the rename
+        let quiet = Output::new(Verbosity::Quiet, LogFormat::Pretty, ColorChoice::Never);
+        let mut ctx = match self.context(quiet) {
+            Ok(ctx) => ctx,
+            Err(e) => return Some(e),
+        };
+        ctx.dry_run = true;
+        if let Err(e) = run(&mut ctx) {
+            return Some(e);
+        }
+        let planned = self.operations.iter().map(|planned| &planned.operation).collect::<Vec<_>>();
+        if let Some(operation) = ctx.operations.iter().find(|operation| !planned.contains(operation)) {
+            return Some(if planned.iter().any(|planned| planned.input == operation.input) {
+                format!("{} would be renamed differently", operation.input.display())
+            } else {
+                format!("{} is new", operation.input.display())
+            });
+        }
+        planned
+            .iter()
+            .find(|planned| !ctx.operations.contains(planned))
+            .map(|operation| format!("{} is gone", operation.input.display()))
+    }
+}
+
+// Execute exactly the operations of a plan file, refusing to if the project changed since the plan
+// was made
+pub fn apply(path: &Path, out: Output) -> Result<(), String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read the plan {}: {e}", path.display()))?;
+    let value = json::parse(&content).map_err(|e| format!("Invalid plan {}: {e}", path.display()))?;
+    let plan = Plan::from_json(&value).map_err(|e| format!("Invalid plan {}: {e}", path.display()))?;
+    if !plan.input.exists() {
+        return Err(format!("Input path does not exist: {}", plan.input.display()));
+    }
+    if let Some(change) = plan.find_change() {
+        return Err(format!("The project changed since the plan was made, {change}. Nothing was written, make a new plan."));
+    }
+    run(&mut plan.context(out)?)
+}
+
+fn str_field<'a>(value: &'a Value, name: &str) -> Result<&'a str, String> {
+    value.get(name).and_then(Value::as_str).ok_or_else(|| format!("Missing string field: {name}"))
+}
+
+fn usize_field(value: &Value, name: &str) -> Result<usize, String> {
+    match value.get(name) {
+        Some(Value::Number(n)) if *n >= 0.0 && n.fract() == 0.0 => Ok(*n as usize),
+        _ => Err(format!("Missing number field: {name}")),
+    }
+}
+
+fn bool_field(value: &Value, name: &str) -> Result<bool, String> {
+    match value.get(name) {
+        Some(Value::Bool(b)) => Ok(*b),
+        _ => Err(format!("Missing boolean field: {name}")),
+    }
+}
+
+fn strings_field(value: &Value, name: &str) -> Result<Vec<String>, String> {
+    match value.get(name) {
+        Some(Value::Array(values)) => values
+            .iter()
+            .map(|value| value.as_str().map(str::to_string).ok_or_else(|| format!("Invalid string in field: {name}")))
+            .collect(),
+        _ => Err(format!("Missing array field: {name}")),
+    }
+}
+
+fn object(entries: Vec<(&str, Value)>) -> Value {
+    Value::Object(entries.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet() -> Output {
+        Output::new(Verbosity::Quiet, Default::default(), Default::default())
+    }
+
+    fn plan(input: &Path) -> Plan {
+        let mut ctx = Context::new(input, "new-project", quiet());
+        ctx.dry_run = true;
+        run(&mut ctx).unwrap();
+        Plan::from_context(&ctx)
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-plan-json-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/my-project.txt"), "my-project").unwrap();
+
+        let mut plan = plan(&dir.join("my-project"));
+        plan.nodes = Some(vec![Node::Identifier, Node::Comment]);
+        plan.ecosystems = Ecosystem::all().collect();
+        plan.rewrite_secrets = true;
+        plan.include_vendored = true;
+        plan.tracked_only = true;
+        plan.rewrite_generated = true;
+        plan.treat_as_text = vec!["dat".to_string()];
+        plan.treat_as_binary = vec!["snap".to_string()];
+        plan.identifier_boundaries = true;
+        plan.org = Some(("acme".to_string(), "Globex".to_string()));
+        plan.domain = Some(("acme.com".to_string(), "globex.io".to_string()));
+        plan.npm_package = Some(("@acme/my-lib".to_string(), "@globex/new-lib".to_string()));
+        plan.copyright = Some(("Acme Inc.".to_string(), "Globex LLC".to_string()));
+        plan.copyright_year = Some(2024);
+        plan.regen_uuids = vec!["*.sln".to_string()];
+        plan.regen_vs_guids = true;
+        plan.also = vec![("ACME-".to_string(), "NOVA-".to_string())];
+        plan.replace_regex = vec!["v(\\d+)=>version $1".to_string()];
+        assert_eq!(plan.operations.len(), 2);
+        assert_eq!(plan.operations[0].sha256, None);
+        assert_eq!(plan.operations[1].sha256, Some(sha256::hash(b"my-project")));
+        let parsed = Plan::from_json(&json::parse(&plan.to_json().to_string()).unwrap()).unwrap();
+        assert_eq!(parsed, plan);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_apply() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-plan-apply-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/my-project.txt"), "my-project").unwrap();
+        let plan_path = dir.join("plan.json");
+        std::fs::write(&plan_path, plan(&dir.join("my-project")).to_json().to_string()).unwrap();
+
+        // A changed file, and a new file, refuse the plan
+        std::fs::write(dir.join("my-project/my-project.txt"), "my-project!").unwrap();
+        let error = apply(&plan_path, quiet()).unwrap_err();
+        assert!(error.contains("my-project.txt was changed"), "{error}");
+        std::fs::write(dir.join("my-project/my-project.txt"), "my-project").unwrap();
+        std::fs::write(dir.join("my-project/b.txt"), "").unwrap();
+        let error = apply(&plan_path, quiet()).unwrap_err();
+        assert!(error.contains("b.txt is new"), "{error}");
+        assert!(!dir.join("new-project").exists());
+
+        std::fs::remove_file(dir.join("my-project/b.txt")).unwrap();
+        apply(&plan_path, quiet()).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/new-project.txt")).unwrap(), "new-project");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
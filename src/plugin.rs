@@ -0,0 +1,205 @@
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+
+use crate::json::{self, Value};
+
+// A plugin is an executable that is started once per run and receives one JSON request per line on
+// stdin for every text file:
+//
+//   {"path": "src/main.rs", "content": "...", "old_name": "old-project", "new_name": "new-project"}
+//
+// It answers every request with one JSON line on stdout, contributing extra replacements that are
+// applied after the built-in ones, or vetoing any change to the file:
+//
+//   {"replacements": [{"from": "old.example.com", "to": "new.example.com"}], "veto": false}
+//
+// A plugin that doesn't read a request and answer it in time is stopped and fails the run.
+pub struct Plugin {
+    command: String,
+    child: Child,
+    // The requests, written to stdin on their own thread so a plugin that stops reading can't block
+    // the run
+    requests: Sender<String>,
+    // The lines of stdout, read on their own thread so a plugin that hangs can't block the run, and
+    // the errors of both threads
+    responses: Receiver<Result<String, String>>,
+    timeout: Duration,
+}
+
+// How long a plugin may take to answer a request
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Answer of a plugin for a single file
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PluginResponse {
+    pub replacements: Vec<(String, String)>,
+    pub veto: bool,
+}
+
+impl Plugin {
+    // Start the plugin. The command is split on whitespace into the program and its arguments.
+    pub fn spawn(command: &str) -> std::io::Result<Self> {
+        let mut parts = command.split_whitespace();
+        let program = parts
+            .next()
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "Empty plugin command"))?;
+        let mut child = Command::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let mut stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let (sender, responses) = mpsc::channel();
+        let (requests, pending) = mpsc::channel::<String>();
+        let errors = sender.clone();
+        std::thread::spawn(move || {
+            // Ends when the plugin closes stdin or the plugin is dropped
+            for request in pending {
+                if let Err(e) = writeln!(stdin, "{request}").and_then(|_| stdin.flush()) {
+                    let _ = errors.send(Err(format!("Failed to send request: {e}")));
+                    break;
+                }
+            }
+        });
+        std::thread::spawn(move || {
+            // Ends when the plugin closes stdout or the plugin is dropped
+            for line in stdout.lines() {
+                if sender.send(line.map_err(|e| format!("Failed to read response: {e}"))).is_err() {
+                    return;
+                }
+            }
+            let _ = sender.send(Err("Plugin exited unexpectedly".to_string()));
+        });
+        Ok(Self {
+            command: command.to_string(),
+            child,
+            requests,
+            responses,
+            timeout: RESPONSE_TIMEOUT,
+        })
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    // Ask the plugin about a single file
+    pub fn process(&mut self, path: &Path, content: &str, old_name: &str, new_name: &str) -> Result<PluginResponse, String> {
+        let request = Value::Object(vec![
+            ("path".to_string(), path.to_string_lossy().as_ref().into()),
+            ("content".to_string(), content.into()),
+            ("old_name".to_string(), old_name.into()),
+            ("new_name".to_string(), new_name.into()),
+        ]);
+        // The timeout covers writing the request as well, which blocks once the plugin stops reading
+        // and the pipe is full
+        self.requests.send(request.to_string()).map_err(|_| "Plugin exited unexpectedly".to_string())?;
+
+        match self.responses.recv_timeout(self.timeout) {
+            Ok(Ok(line)) => parse_response(&line),
+            Ok(Err(e)) => Err(e),
+            Err(RecvTimeoutError::Disconnected) => Err("Plugin exited unexpectedly".to_string()),
+            Err(RecvTimeoutError::Timeout) => {
+                let _ = self.child.kill();
+                let _ = self.child.wait();
+                Err(format!("Plugin didn't answer within {} seconds and was stopped", self.timeout.as_secs()))
+            }
+        }
+    }
+}
+
+impl Drop for Plugin {
+    fn drop(&mut self) {
+        // Stop the plugin once the run is done
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn parse_response(line: &str) -> Result<PluginResponse, String> {
+    let response = json::parse(line).map_err(|e| format!("Invalid response: {e}"))?;
+    let mut replacements = vec![];
+    if let Some(value) = response.get("replacements") {
+        let Value::Array(items) = value else {
+            return Err("Invalid response: replacements must be an array".to_string());
+        };
+        for item in items {
+            match (item.get("from").and_then(Value::as_str), item.get("to").and_then(Value::as_str)) {
+                (Some(from), Some(to)) if !from.is_empty() => replacements.push((from.to_string(), to.to_string())),
+                _ => return Err("Invalid response: replacements need non-empty 'from' and a 'to'".to_string()),
+            }
+        }
+    }
+    let veto = matches!(response.get("veto"), Some(Value::Bool(true)));
+    Ok(PluginResponse { replacements, veto })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(
+            parse_response(r#"{"replacements":[{"from":"a","to":"b"}]}"#),
+            Ok(PluginResponse {
+                replacements: vec![("a".to_string(), "b".to_string())],
+                veto: false,
+            })
+        );
+        assert_eq!(
+            parse_response(r#"{"veto":true}"#),
+            Ok(PluginResponse {
+                replacements: vec![],
+                veto: true,
+            })
+        );
+        assert!(parse_response(r#"{"replacements":[{"from":"","to":"b"}]}"#).is_err());
+        assert!(parse_response("not json").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_process() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-plugin-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("plugin.sh");
+        std::fs::write(
+            &script,
+            r#"while read -r line; do
+    case "$line" in
+        *keep*) echo '{"veto":true}' ;;
+        *) echo '{"replacements":[{"from":"acme.com","to":"example.com"}]}' ;;
+    esac
+done
+"#,
+        )
+        .unwrap();
+
+        let mut plugin = Plugin::spawn(&format!("sh {}", script.display())).unwrap();
+        let response = plugin.process(Path::new("a.txt"), "acme.com", "old", "new").unwrap();
+        assert_eq!(response.replacements, vec![("acme.com".to_string(), "example.com".to_string())]);
+        let response = plugin.process(Path::new("keep.txt"), "acme.com", "old", "new").unwrap();
+        assert!(response.veto);
+        drop(plugin);
+
+        // A plugin that hangs is stopped
+        let mut plugin = Plugin::spawn("sleep 60").unwrap();
+        plugin.timeout = Duration::from_millis(100);
+        let error = plugin.process(Path::new("a.txt"), "acme.com", "old", "new").unwrap_err();
+        assert!(error.starts_with("Plugin didn't answer within"), "{error}");
+        assert!(plugin.child.try_wait().unwrap().is_some());
+
+        // So is a plugin that never reads a request larger than the pipe
+        let mut plugin = Plugin::spawn("sleep 60").unwrap();
+        plugin.timeout = Duration::from_millis(100);
+        let error = plugin.process(Path::new("a.txt"), &"acme.com ".repeat(1 << 17), "old", "new").unwrap_err();
+        assert!(error.starts_with("Plugin didn't answer within"), "{error}");
+        assert!(plugin.child.try_wait().unwrap().is_some());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use crate::{flatpak, xml};
+
+// The settings of the Android build and the iOS project naming the application ID
+const ID_SETTINGS: [&str; 3] = ["applicationId", "namespace", "PRODUCT_BUNDLE_IDENTIFIER"];
+
+// The reverse-DNS application IDs of a React Native app: the `applicationId` and `namespace` of the
+// Android build.gradle, the `package` of its AndroidManifest.xml and the PRODUCT_BUNDLE_IDENTIFIER
+// of the Xcode project. IDs made of build settings, like the
+// "org.reactjs.native.example.$(PRODUCT_NAME:rfc1034identifier)" of the template, are left out.
+pub fn app_ids(path: &Path, text: &str) -> Vec<String> {
+    let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let values: Vec<&str> = match name.as_str() {
+        "build.gradle" | "build.gradle.kts" | "project.pbxproj" => text.lines().filter_map(setting).collect(),
+        "AndroidManifest.xml" => xml::values(text)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(path, _)| path.len() == 2 && path[0] == "manifest" && path[1] == "@package")
+            .map(|(_, range)| &text[range])
+            .collect(),
+        _ => vec![],
+    };
+    let mut ids = vec![];
+    for id in values {
+        if flatpak::is_app_id(id) && !ids.iter().any(|found| found == id) {
+            ids.push(id.to_string());
+        }
+    }
+    ids
+}
+
+// The value of a line setting the application ID, like `applicationId "com.example.myapp"`,
+// `namespace = "com.example.myapp"` or `PRODUCT_BUNDLE_IDENTIFIER = com.example.MyApp;`
+fn setting(line: &str) -> Option<&str> {
+    let value = ID_SETTINGS.iter().find_map(|key| line.trim().strip_prefix(key))?;
+    // Another setting starting with the key, like applicationIdSuffix
+    if !value.starts_with([' ', '\t', '=', '"', '\'']) {
+        return None;
+    }
+    let value = value.trim_start();
+    let value = value.strip_prefix('=').unwrap_or(value).trim().trim_end_matches(';');
+    Some(value.trim_matches(['"', '\'']))
+}
+
+// Whether the directory is a root of Java or Kotlin sources, whose packages are directories
+pub fn is_source_root(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == "java" || name == "kotlin")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_ids() {
+        let gradle = "android {\n    namespace \"com.example.myapp\"\n    defaultConfig {\n        applicationId = 'com.example.myapp.dev'\n        \
+                      applicationIdSuffix \".debug\"\n    }\n}\n";
+        assert_eq!(app_ids(Path::new("android/app/build.gradle"), gradle), ["com.example.myapp", "com.example.myapp.dev"]);
+        let manifest = r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.myapp"></manifest>"#;
+        assert_eq!(app_ids(Path::new("android/app/src/main/AndroidManifest.xml"), manifest), ["com.example.myapp"]);
+        let project = "\t\t\t\tPRODUCT_BUNDLE_IDENTIFIER = \"org.reactjs.native.example.$(PRODUCT_NAME:rfc1034identifier)\";\n\
+                       \t\t\t\tPRODUCT_BUNDLE_IDENTIFIER = com.example.MyApp;\n";
+        assert_eq!(app_ids(Path::new("ios/MyApp.xcodeproj/project.pbxproj"), project), ["com.example.MyApp"]);
+        assert!(is_source_root(Path::new("android/app/src/main/java")));
+    }
+}
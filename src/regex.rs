@@ -0,0 +1,496 @@
+// A small backtracking regular expression engine for user supplied replacement rules. It supports
+// literals, ".", classes like "[a-z_]" and "[^/]", the escapes \d \w \s \D \W \S \b \B, anchors "^"
+// and "$", groups "(...)" and "(?:...)", alternation "|", the quantifiers "*",
+// "+", "?", "{n}", "{n,}" and "{n,m}" (lazy with a "?" suffix) and the flag "(?i)" at the start to
+// ignore case. The anchors match at the start and end of every line.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Char(char),
+    // Any character except a line break
+    Any,
+    Class { items: Vec<ClassItem>, negated: bool },
+    Start,
+    End,
+    WordBoundary { negated: bool },
+    Group { index: Option<usize>, alternatives: Vec<Vec<Node>> },
+    Repeat { node: Box<Node>, min: usize, max: Option<usize>, greedy: bool },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+impl ClassItem {
+    fn matches(&self, c: char, ignore_case: bool) -> bool {
+        match *self {
+            ClassItem::Range(from, to) => {
+                let in_range = |c: char| from <= c && c <= to;
+                in_range(c) || (ignore_case && (c.to_lowercase().any(in_range) || c.to_uppercase().any(in_range)))
+            }
+            ClassItem::Digit(negated) => c.is_ascii_digit() != negated,
+            ClassItem::Word(negated) => is_word(c) != negated,
+            ClassItem::Space(negated) => c.is_whitespace() != negated,
+        }
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regex {
+    pattern: String,
+    nodes: Vec<Node>,
+    groups: usize,
+    ignore_case: bool,
+}
+
+// Start and end of a match and of every group in it, as byte offsets
+pub type Captures = Vec<Option<(usize, usize)>>;
+
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        let (ignore_case, rest) = match pattern.strip_prefix("(?i)") {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+        let mut parser = Parser { chars: rest.chars().collect(), pos: 0, groups: 0 };
+        let alternatives = parser.alternatives()?;
+        if parser.pos < parser.chars.len() {
+            return Err(format!("Unmatched ) in regex {pattern}"));
+        }
+        Ok(Self {
+            pattern: pattern.to_string(),
+            nodes: vec![Node::Group { index: Some(0), alternatives }],
+            groups: parser.groups + 1,
+            ignore_case,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    // Every non-overlapping match from left to right
+    pub fn captures_iter(&self, text: &str) -> Vec<Captures> {
+        let chars = text.chars().collect::<Vec<_>>();
+        let mut offsets = text.char_indices().map(|(offset, _)| offset).collect::<Vec<_>>();
+        offsets.push(text.len());
+        let matcher = Matcher { chars: &chars, ignore_case: self.ignore_case };
+
+        let mut found = vec![];
+        let mut start = 0;
+        while start <= chars.len() {
+            let mut captures = vec![None; self.groups];
+            let mut end = None;
+            matcher.match_seq(&self.nodes, start, &mut captures, &mut |pos, _| {
+                end = Some(pos);
+                true
+            });
+            match end {
+                Some(end) => {
+                    found.push(captures.iter().map(|group| group.map(|(from, to)| (offsets[from], offsets[to]))).collect());
+                    start = if end == start { end + 1 } else { end };
+                }
+                None => start += 1,
+            }
+        }
+        found
+    }
+
+    // Replace every match, "$1" or "${1}" in the replacement is the text of a group, "$0" the whole
+    // match and "$$" a dollar sign. Returns the new text and the number of matches.
+    pub fn replace_all(&self, text: &str, replacement: &str) -> (String, usize) {
+        let matches = self.captures_iter(text);
+        let mut out = String::with_capacity(text.len());
+        let mut copied_until = 0;
+        for captures in &matches {
+            let (start, end) = captures[0].unwrap();
+            out.push_str(&text[copied_until..start]);
+            expand(replacement, captures, text, &mut out);
+            copied_until = end;
+        }
+        out.push_str(&text[copied_until..]);
+        (out, matches.len())
+    }
+}
+
+// A replacement rule from the command line, "pattern=>replacement"
+pub fn parse_rule(rule: &str) -> Result<(Regex, String), String> {
+    let Some((pattern, replacement)) = rule.split_once("=>") else {
+        return Err(format!("Expected \"pattern=>replacement\", got \"{rule}\""));
+    };
+    Ok((Regex::new(pattern)?, replacement.to_string()))
+}
+
+// The command line form of a rule
+pub fn format_rule((regex, replacement): &(Regex, String)) -> String {
+    format!("{}=>{replacement}", regex.as_str())
+}
+
+fn expand(replacement: &str, captures: &Captures, text: &str, out: &mut String) {
+    let chars = replacement.chars().collect::<Vec<_>>();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '$' || i + 1 == chars.len() {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let (digits, next) = if chars[i + 1] == '{' {
+            match chars[i + 2..].iter().position(|&c| c == '}') {
+                Some(length) => (chars[i + 2..i + 2 + length].iter().collect::<String>(), i + 3 + length),
+                None => (String::new(), i + 1),
+            }
+        } else {
+            let length = chars[i + 1..].iter().take_while(|c| c.is_ascii_digit()).count();
+            (chars[i + 1..i + 1 + length].iter().collect(), i + 1 + length)
+        };
+        match digits.parse::<usize>() {
+            Ok(group) => {
+                if let Some(Some((start, end))) = captures.get(group) {
+                    out.push_str(&text[*start..*end]);
+                }
+                i = next;
+            }
+            Err(_) if chars[i + 1] == '$' => {
+                out.push('$');
+                i += 2;
+            }
+            Err(_) => {
+                out.push('$');
+                i += 1;
+            }
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    groups: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn alternatives(&mut self) -> Result<Vec<Vec<Node>>, String> {
+        let mut alternatives = vec![self.sequence()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            alternatives.push(self.sequence()?);
+        }
+        Ok(alternatives)
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Node>, String> {
+        let mut nodes = vec![];
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+        Ok(nodes)
+    }
+
+    fn atom(&mut self) -> Result<Node, String> {
+        let c = self.chars[self.pos];
+        self.pos += 1;
+        Ok(match c {
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '[' => self.class()?,
+            '(' => {
+                let index = if self.chars[self.pos..].starts_with(&['?', ':']) {
+                    self.pos += 2;
+                    None
+                } else {
+                    self.groups += 1;
+                    Some(self.groups)
+                };
+                let alternatives = self.alternatives()?;
+                if self.peek() != Some(')') {
+                    return Err("Unclosed ( in regex".to_string());
+                }
+                self.pos += 1;
+                Node::Group { index, alternatives }
+            }
+            '\\' => match self.escape()? {
+                Escape::Char(c) => Node::Char(c),
+                Escape::Class(item) => Node::Class { items: vec![item], negated: false },
+                Escape::WordBoundary(negated) => Node::WordBoundary { negated },
+            },
+            '*' | '+' | '?' => return Err(format!("Nothing to repeat before {c} in regex")),
+            c => Node::Char(c),
+        })
+    }
+
+    fn escape(&mut self) -> Result<Escape, String> {
+        let c = self.peek().ok_or("Trailing \\ in regex")?;
+        self.pos += 1;
+        Ok(match c {
+            'd' | 'D' => Escape::Class(ClassItem::Digit(c == 'D')),
+            'w' | 'W' => Escape::Class(ClassItem::Word(c == 'W')),
+            's' | 'S' => Escape::Class(ClassItem::Space(c == 'S')),
+            'b' | 'B' => Escape::WordBoundary(c == 'B'),
+            'n' => Escape::Char('\n'),
+            'r' => Escape::Char('\r'),
+            't' => Escape::Char('\t'),
+            c if c.is_alphanumeric() => return Err(format!("Unknown escape \\{c} in regex")),
+            c => Escape::Char(c),
+        })
+    }
+
+    fn class(&mut self) -> Result<Node, String> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+        let mut items = vec![];
+        loop {
+            let c = self.peek().ok_or("Unclosed [ in regex")?;
+            self.pos += 1;
+            // A "]" right after the opening bracket is a literal
+            if c == ']' && !items.is_empty() {
+                break;
+            }
+            let from = match c {
+                '\\' => match self.escape()? {
+                    Escape::Char(c) => c,
+                    Escape::Class(item) => {
+                        items.push(item);
+                        continue;
+                    }
+                    Escape::WordBoundary(_) => return Err("\\b can't be used in a class in regex".to_string()),
+                },
+                c => c,
+            };
+            if self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|&c| c != ']') {
+                self.pos += 1;
+                let mut to = self.chars[self.pos];
+                self.pos += 1;
+                if to == '\\' {
+                    match self.escape()? {
+                        Escape::Char(c) => to = c,
+                        _ => return Err("Invalid range in regex".to_string()),
+                    }
+                }
+                if to < from {
+                    return Err(format!("Invalid range {from}-{to} in regex"));
+                }
+                items.push(ClassItem::Range(from, to));
+            } else {
+                items.push(ClassItem::Range(from, from));
+            }
+        }
+        Ok(Node::Class { items, negated })
+    }
+
+    fn quantified(&mut self, atom: Node) -> Result<Node, String> {
+        let (min, max) = match self.peek() {
+            Some('{') => match self.braces() {
+                Some(bounds) => bounds,
+                None => return Ok(atom),
+            },
+            Some(c @ ('*' | '+' | '?')) => {
+                self.pos += 1;
+                (usize::from(c == '+'), if c == '?' { Some(1) } else { None })
+            }
+            _ => return Ok(atom),
+        };
+        if matches!(atom, Node::Start | Node::End | Node::WordBoundary { .. }) {
+            return Err("Nothing to repeat in regex".to_string());
+        }
+        let greedy = self.peek() != Some('?');
+        if !greedy {
+            self.pos += 1;
+        }
+        Ok(Node::Repeat { node: Box::new(atom), min, max, greedy })
+    }
+
+    // The bounds of "{n}", "{n,}" or "{n,m}", or None if the brace is a literal
+    fn braces(&mut self) -> Option<(usize, Option<usize>)> {
+        let length = self.chars[self.pos..].iter().position(|&c| c == '}')?;
+        let inner = self.chars[self.pos + 1..self.pos + length].iter().collect::<String>();
+        let bounds = match inner.split_once(',') {
+            None => {
+                let n = inner.parse().ok()?;
+                (n, Some(n))
+            }
+            Some((min, "")) => (min.parse().ok()?, None),
+            Some((min, max)) => (min.parse().ok()?, Some(max.parse().ok()?)),
+        };
+        self.pos += length + 1;
+        Some(bounds)
+    }
+}
+
+enum Escape {
+    Char(char),
+    Class(ClassItem),
+    WordBoundary(bool),
+}
+
+struct Matcher<'a> {
+    chars: &'a [char],
+    ignore_case: bool,
+}
+
+type Continuation<'k> = dyn FnMut(usize, &mut Vec<Option<(usize, usize)>>) -> bool + 'k;
+
+impl Matcher<'_> {
+    fn is_word_at(&self, pos: usize) -> bool {
+        self.chars.get(pos).is_some_and(|&c| is_word(c))
+    }
+
+    // Match the nodes at the position and call the continuation with every possible end, most
+    // preferred first, until it accepts one
+    fn match_seq(&self, nodes: &[Node], pos: usize, captures: &mut Vec<Option<(usize, usize)>>, k: &mut Continuation) -> bool {
+        let Some((node, rest)) = nodes.split_first() else {
+            return k(pos, captures);
+        };
+        let current = self.chars.get(pos).copied();
+        match node {
+            Node::Char(expected) => {
+                let matches = current.is_some_and(|c| {
+                    c == *expected || (self.ignore_case && c.to_lowercase().eq(expected.to_lowercase()))
+                });
+                matches && self.match_seq(rest, pos + 1, captures, k)
+            }
+            Node::Any => current.is_some_and(|c| c != '\n') && self.match_seq(rest, pos + 1, captures, k),
+            Node::Class { items, negated } => {
+                let matches = current.is_some_and(|c| items.iter().any(|item| item.matches(c, self.ignore_case)) != *negated);
+                matches && self.match_seq(rest, pos + 1, captures, k)
+            }
+            Node::Start => (pos == 0 || self.chars[pos - 1] == '\n') && self.match_seq(rest, pos, captures, k),
+            Node::End => current.is_none_or(|c| c == '\n') && self.match_seq(rest, pos, captures, k),
+            Node::WordBoundary { negated } => {
+                let boundary = (pos > 0 && self.is_word_at(pos - 1)) != self.is_word_at(pos);
+                boundary != *negated && self.match_seq(rest, pos, captures, k)
+            }
+            Node::Group { index, alternatives } => alternatives.iter().any(|alternative| {
+                let index = *index;
+                self.match_seq(alternative, pos, captures, &mut |end, captures| {
+                    let saved = index.map(|index| captures[index]);
+                    if let Some(index) = index {
+                        captures[index] = Some((pos, end));
+                    }
+                    if self.match_seq(rest, end, captures, k) {
+                        return true;
+                    }
+                    if let (Some(index), Some(saved)) = (index, saved) {
+                        captures[index] = saved;
+                    }
+                    false
+                })
+            }),
+            Node::Repeat { node, min, max, greedy } => {
+                let repeat = Repeat { node, min: *min, max: *max, greedy: *greedy };
+                self.match_repeat(&repeat, 0, pos, rest, captures, k)
+            }
+        }
+    }
+
+    fn match_repeat(
+        &self,
+        repeat: &Repeat,
+        count: usize,
+        pos: usize,
+        rest: &[Node],
+        captures: &mut Vec<Option<(usize, usize)>>,
+        k: &mut Continuation,
+    ) -> bool {
+        let done = count >= repeat.min;
+        if done && !repeat.greedy && self.match_seq(rest, pos, captures, k) {
+            return true;
+        }
+        if repeat.max.is_none_or(|max| count < max) {
+            let more = self.match_seq(std::slice::from_ref(repeat.node), pos, captures, &mut |end, captures| {
+                // A repetition that matched nothing can't make progress
+                if end == pos && done {
+                    return false;
+                }
+                self.match_repeat(repeat, count + 1, end, rest, captures, k)
+            });
+            if more {
+                return true;
+            }
+        }
+        done && repeat.greedy && self.match_seq(rest, pos, captures, k)
+    }
+}
+
+struct Repeat<'a> {
+    node: &'a Node,
+    min: usize,
+    max: Option<usize>,
+    greedy: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(pattern: &str, text: &str) -> Vec<String> {
+        let regex = Regex::new(pattern).unwrap();
+        regex.captures_iter(text).iter().map(|captures| captures[0].map(|(start, end)| text[start..end].to_string()).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_matches() {
+        assert_eq!(find("a+", "caaat aa"), vec!["aaa", "aa"]);
+        assert_eq!(find("colou?r", "color colour"), vec!["color", "colour"]);
+        assert_eq!(find(r"\d{2,3}", "1 12 1234"), vec!["12", "123"]);
+        assert_eq!(find(r"\bcore\b", "core score_counter core_x (core)"), vec!["core", "core"]);
+        assert_eq!(find("[^/]+", "a/bc"), vec!["a", "bc"]);
+        assert_eq!(find("(?i)myproject", "MyProject myproject"), vec!["MyProject", "myproject"]);
+        assert_eq!(find("a.*?b", "axbxb"), vec!["axb"]);
+        assert_eq!(find("a.*b", "axbxb"), vec!["axbxb"]);
+        assert_eq!(find("cat|dog", "dog cat"), vec!["dog", "cat"]);
+        assert_eq!(find("^a", "aa"), vec!["a"]);
+        assert_eq!(find("^v=.*$", "x\nv=1\nv=2"), vec!["v=1", "v=2"]);
+        assert_eq!(find("x*", "ab"), vec!["", "", ""]);
+        assert_eq!(find("é+", "aéé"), vec!["éé"]);
+        assert_eq!(find("(a|ab)(c|bcd)(d*)", "abcd"), vec!["abcd"]);
+    }
+
+    #[test]
+    fn test_replace_all() {
+        let regex = Regex::new(r"https://old\.example\.com/v(\d+)/").unwrap();
+        let (text, count) = regex.replace_all("see https://old.example.com/v2/api and https://old.example.com/v10/", "https://new.example.com/api/v$1/");
+        assert_eq!(text, "see https://new.example.com/api/v2/api and https://new.example.com/api/v10/");
+        assert_eq!(count, 2);
+        let regex = Regex::new(r"(\w+)@(\w+)").unwrap();
+        assert_eq!(regex.replace_all("a@b", "${2}$$$1").0, "b$a");
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let rule = parse_rule(r"https://old\.example\.com/v(\d+)=>https://new.example.com/v$1").unwrap();
+        assert_eq!(rule.0.replace_all("see https://old.example.com/v2/", &rule.1).0, "see https://new.example.com/v2/");
+        assert_eq!(format_rule(&rule), r"https://old\.example\.com/v(\d+)=>https://new.example.com/v$1");
+        assert!(parse_rule("no arrow").is_err());
+        assert!(parse_rule("(=>x").is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(Regex::new("(a").is_err());
+        assert!(Regex::new("a)").is_err());
+        assert!(Regex::new("[a").is_err());
+        assert!(Regex::new("*a").is_err());
+        assert!(Regex::new(r"\q").is_err());
+        assert!(Regex::new("[z-a]").is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::{Path, PathBuf};
+
+use crate::git::git;
+use crate::output::Output;
+use crate::{Context, run, transfer};
+
+// Clone the repository into the directory, rename it in place and commit the rename. The original
+// remote is kept as "upstream", and with a push URL it becomes the new "origin" the rename is
+// pushed to. Returns the path of the renamed clone.
+pub fn remote(from: &str, name: &str, push_to: Option<&str>, directory: &Path, out: Output) -> Result<PathBuf, String> {
+    let repo_name = repo_name(from).ok_or_else(|| format!("Can't derive a directory name from {from}"))?;
+    let clone = directory.join(&repo_name);
+    if clone.exists() {
+        return Err(format!("{} already exists", clone.display()));
+    }
+    git(directory, &["clone", from, &repo_name])?;
+
+    // Keep the repository itself out of the rename, so refs and config that mention the old name
+    // stay intact
+    let git_dir = directory.join(format!(".{repo_name}.git.project-renamer"));
+    transfer::move_path(&clone.join(".git"), &git_dir).map_err(|e| format!("Failed to move the git directory aside: {e}"))?;
+    let mut ctx = Context::new(&clone, name, out);
+    ctx.move_input = true;
+    let result = run(&mut ctx);
+    let target = if result.is_ok() { ctx.output.clone() } else { clone };
+    transfer::move_path(&git_dir, &target.join(".git")).map_err(|e| format!("Failed to restore the git directory: {e}"))?;
+    result?;
+
+    git(&target, &["remote", "rename", "origin", "upstream"])?;
+    if let Some(push_to) = push_to {
+        git(&target, &["remote", "add", "origin", push_to])?;
+    }
+    git(&target, &["add", "--all"])?;
+    let message = format!("Rename {repo_name} to {}", target.file_name().unwrap().to_string_lossy());
+    git(&target, &["commit", "--quiet", "--message", &message])?;
+    if push_to.is_some() {
+        git(&target, &["push", "--set-upstream", "origin", "HEAD"])?;
+    }
+    Ok(target)
+}
+
+// Name of the directory git clones a repository into, e.g. "repo" for
+// "git@github.com:org/repo.git"
+fn repo_name(url: &str) -> Option<String> {
+    let url = url.trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url).trim_end_matches('/');
+    let name = url.rsplit(['/', ':', '\\']).next()?;
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::Verbosity;
+    use std::process::Command;
+
+    #[test]
+    fn test_repo_name() {
+        assert_eq!(repo_name("https://github.com/org/my-service.git"), Some("my-service".to_string()));
+        assert_eq!(repo_name("git@github.com:org/my-service.git"), Some("my-service".to_string()));
+        assert_eq!(repo_name("/srv/git/my-service/"), Some("my-service".to_string()));
+        assert_eq!(repo_name("my-service"), Some("my-service".to_string()));
+        assert_eq!(repo_name("https://example.com/"), Some("example.com".to_string()));
+        assert_eq!(repo_name(""), None);
+    }
+
+    // Needs git with a configured user to commit
+    #[test]
+    fn test_remote() {
+        let configured = Command::new("git")
+            .args(["config", "user.email"])
+            .current_dir(std::env::temp_dir())
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if !configured {
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("project-renamer-remote-{}", std::process::id()));
+        let source = dir.join("source/my-service");
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::write(source.join("my_service.rs"), "struct MyService;").unwrap();
+        git(&source, &["init", "--quiet"]).unwrap();
+        git(&source, &["add", "--all"]).unwrap();
+        git(&source, &["commit", "--quiet", "--message", "Initial commit"]).unwrap();
+        git(&dir, &["init", "--quiet", "--bare", "pushed.git"]).unwrap();
+
+        let out = Output::new(Verbosity::Quiet, Default::default(), Default::default());
+        let source_url = source.to_string_lossy().to_string();
+        let pushed_url = dir.join("pushed.git").to_string_lossy().to_string();
+        let target = remote(&source_url, "new-service", Some(&pushed_url), &dir, out).unwrap();
+
+        assert_eq!(target, dir.join("new-service"));
+        assert!(!dir.join("my-service").exists());
+        assert_eq!(std::fs::read_to_string(target.join("new_service.rs")).unwrap(), "struct NewService;");
+        let log = Command::new("git").args(["log", "--format=%s", "HEAD"]).current_dir(dir.join("pushed.git")).output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&log.stdout), "Rename my-service to new-service\nInitial commit\n");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,409 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::fs::{Disk, Filesystem};
+use crate::output::{Event, Output, Verbosity};
+use crate::replace::Replacer;
+use crate::{Context, Operation, find_reserved_names, run};
+
+/// Callback that receives the events of a run
+pub type Observer = Arc<dyn Fn(&RenameEvent) + Send + Sync>;
+
+/// Renames a project from an application, with the same options as the command line:
+///
+/// ```no_run
+/// use project_renamer::renamer::Renamer;
+///
+/// let report = Renamer::builder().input("path/to/old-project").new_name("new-project").dry_run(true).build()?.run()?;
+/// # Ok::<(), String>(())
+/// ```
+#[derive(Clone)]
+pub struct Renamer {
+    options: RenamerBuilder,
+}
+
+/// The options of a Renamer, checked by build
+#[derive(Clone, Default)]
+pub struct RenamerBuilder {
+    input: Option<PathBuf>,
+    new_name: Option<String>,
+    output: Option<PathBuf>,
+    exclude: Vec<String>,
+    dry_run: bool,
+    acronyms: Vec<String>,
+    old_parts: Vec<String>,
+    escape_reserved_names: bool,
+    archives: bool,
+    names_only: bool,
+    observer: Option<Observer>,
+    fs: Option<Arc<dyn Filesystem>>,
+    replacers: Vec<Arc<Mutex<dyn Replacer>>>,
+}
+
+/// An event during a run, for progress displays and logging in the application
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameEvent {
+    /// A file or directory got a new name
+    FileRenamed { from: String, to: String },
+    /// The old name was replaced in the content of a file
+    ContentChanged { path: PathBuf, substitutions: usize },
+    /// An existing output file was left untouched
+    Skipped { path: PathBuf },
+    /// An error that was reported during the run
+    Error { message: String },
+}
+
+impl RenameEvent {
+    pub(crate) fn from_event(event: &Event) -> Option<Self> {
+        match *event {
+            Event::FileRenamed { from, to } => Some(RenameEvent::FileRenamed { from: from.to_string(), to: to.to_string() }),
+            Event::ContentRewritten { path, substitutions } => {
+                Some(RenameEvent::ContentChanged { path: path.to_path_buf(), substitutions })
+            }
+            Event::FileSkipped { path } | Event::FileConflict { path } => Some(RenameEvent::Skipped { path: path.to_path_buf() }),
+            Event::Error { message } => Some(RenameEvent::Error { message: message.to_string() }),
+            _ => None,
+        }
+    }
+}
+
+/// What a run did, or would do in a dry run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameReport {
+    /// Where the renamed project is written
+    pub output: PathBuf,
+    /// How many files and directories got a new name
+    pub renamed: usize,
+    /// How many replacements were made in the contents
+    pub substitutions: usize,
+    /// How many existing output files were overwritten
+    pub refreshed: usize,
+    /// Every operation of the run, in order
+    pub operations: Vec<Operation>,
+}
+
+impl Renamer {
+    /// Start setting the options of a rename
+    pub fn builder() -> RenamerBuilder {
+        RenamerBuilder::default()
+    }
+
+    /// Rename the project, or only plan it in a dry run. A file that can't be read, written or
+    /// passed through a replacer ends the run with an error.
+    pub fn run(&self) -> Result<RenameReport, String> {
+        let options = &self.options;
+        let (Some(input), Some(new_name)) = (&options.input, &options.new_name) else {
+            unreachable!("build checks the required options");
+        };
+        let out = Output::new(Verbosity::Quiet, Default::default(), Default::default());
+        let fs = options.fs.clone().unwrap_or_else(|| Arc::new(Disk::default()));
+        let mut ctx = Context::with_fs(input, new_name, out, fs);
+        if !options.old_parts.is_empty() {
+            ctx.old_name.parts = options.old_parts.iter().map(|part| part.to_lowercase()).collect();
+        }
+        ctx.old_name = ctx.old_name.with_acronyms(&options.acronyms);
+        ctx.new_name = ctx.new_name.with_acronyms(&options.acronyms);
+        if let Some(output) = &options.output {
+            ctx.output = output.clone();
+        }
+        ctx.exclude = options.exclude.clone();
+        ctx.dry_run = options.dry_run;
+        ctx.escape_reserved_names = options.escape_reserved_names;
+        ctx.archives = options.archives;
+        ctx.names_only = options.names_only;
+        ctx.observer = options.observer.clone();
+        for replacer in &options.replacers {
+            ctx.replacers.push(Box::new(replacer.clone()));
+        }
+        if !ctx.escape_reserved_names
+            && let Some((path, reason)) = find_reserved_names(&ctx).first()
+        {
+            let message = format!("New name {reason}: {}", path.display());
+            ctx.error(&message);
+            return Err(message);
+        }
+        if let Err(message) = run(&mut ctx) {
+            ctx.error(&message);
+            return Err(message);
+        }
+        Ok(RenameReport {
+            output: ctx.output,
+            renamed: ctx.stats.renamed,
+            substitutions: ctx.stats.substitutions,
+            refreshed: ctx.stats.refreshed,
+            operations: ctx.operations,
+        })
+    }
+}
+
+impl RenamerBuilder {
+    /// Path to the project directory or file
+    pub fn input(mut self, input: impl AsRef<Path>) -> Self {
+        self.input = Some(input.as_ref().to_path_buf());
+        self
+    }
+
+    /// The new name of the project, required
+    pub fn new_name(mut self, new_name: impl Into<String>) -> Self {
+        self.new_name = Some(new_name.into());
+        self
+    }
+
+    /// Where the renamed project is written, next to the input with the new name by default
+    pub fn output(mut self, output: impl AsRef<Path>) -> Self {
+        self.output = Some(output.as_ref().to_path_buf());
+        self
+    }
+
+    /// Glob pattern of paths that are copied unchanged, can be called repeatedly
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Only plan the rename, the report lists the operations without anything being written
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// A part of the names that keeps its capitalization, like "API", can be called repeatedly
+    pub fn acronym(mut self, acronym: impl Into<String>) -> Self {
+        self.acronyms.push(acronym.into());
+        self
+    }
+
+    /// The parts of the old name, when they can't be told from the name of the input
+    pub fn old_parts<S: Into<String>>(mut self, parts: impl IntoIterator<Item = S>) -> Self {
+        self.old_parts = parts.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Escape new names that are unusable on Windows instead of failing
+    pub fn escape_reserved_names(mut self, escape_reserved_names: bool) -> Self {
+        self.escape_reserved_names = escape_reserved_names;
+        self
+    }
+
+    /// Also rename the members of zip and tar archives
+    pub fn archives(mut self, archives: bool) -> Self {
+        self.archives = archives;
+        self
+    }
+
+    /// Only rename files and directories, copying their contents unchanged
+    pub fn names_only(mut self, names_only: bool) -> Self {
+        self.names_only = names_only;
+        self
+    }
+
+    /// Call the callback with every event of the run, e.g. to show progress
+    pub fn on_event(mut self, callback: impl Fn(&RenameEvent) + Send + Sync + 'static) -> Self {
+        self.observer = Some(Arc::new(callback));
+        self
+    }
+
+    /// Add a replacer that runs after the built-in replacements and the replacers added before
+    pub fn replacer(mut self, replacer: impl Replacer + 'static) -> Self {
+        self.replacers.push(Arc::new(Mutex::new(replacer)));
+        self
+    }
+
+    /// Read the project from and write the output to this filesystem instead of the disk, e.g. an
+    /// in-memory fs::Memory
+    pub fn filesystem(mut self, fs: Arc<dyn Filesystem>) -> Self {
+        self.fs = Some(fs);
+        self
+    }
+
+    /// Check the options, the input must exist and the new name must be given
+    pub fn build(self) -> Result<Renamer, String> {
+        let Some(input) = &self.input else {
+            return Err("The input path is required".to_string());
+        };
+        if !self.fs.as_ref().map_or_else(|| input.exists(), |fs| fs.exists(input)) {
+            return Err(format!("Input path does not exist: {}", input.display()));
+        }
+        if input.file_name().is_none() {
+            return Err(format!("Input path has no name: {}", input.display()));
+        }
+        if self.new_name.as_deref().is_none_or(|name| name.trim().is_empty()) {
+            return Err("The new name is required".to_string());
+        }
+        if let Some(output) = &self.output
+            && let (Ok(input), Ok(output)) = (std::path::absolute(input), std::path::absolute(output))
+            && output.starts_with(&input)
+            && output != input
+        {
+            return Err(format!("The output {} is inside the input", output.display()));
+        }
+        Ok(Renamer { options: self })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Action;
+
+    #[test]
+    fn test_build_errors() {
+        assert!(Renamer::builder().new_name("new-project").build().is_err());
+        assert!(Renamer::builder().input("/does/not/exist").new_name("new-project").build().is_err());
+        assert!(Renamer::builder().input(std::env::temp_dir()).new_name(" ").build().is_err());
+    }
+
+    #[test]
+    fn test_on_event() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-renamer-events-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/my-project.txt"), "my-project").unwrap();
+
+        let events = Arc::new(std::sync::Mutex::new(vec![]));
+        let received = events.clone();
+        Renamer::builder()
+            .input(dir.join("my-project"))
+            .new_name("new-project")
+            .dry_run(true)
+            .on_event(move |event| received.lock().unwrap().push(event.clone()))
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                RenameEvent::FileRenamed { from: "my-project.txt".to_string(), to: "new-project.txt".to_string() },
+                RenameEvent::ContentChanged { path: dir.join("my-project/my-project.txt"), substitutions: 1 },
+            ]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_memory_filesystem() {
+        let fs = Arc::new(crate::fs::Memory::new().with_file("my-project/src/my_project.rs", "struct MyProject;"));
+        let report = Renamer::builder()
+            .input("my-project")
+            .new_name("new-project")
+            .filesystem(fs.clone())
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!((report.renamed, report.substitutions), (1, 1));
+        assert_eq!(
+            fs.files(),
+            vec![
+                (PathBuf::from("my-project/src/my_project.rs"), b"struct MyProject;".to_vec()),
+                (PathBuf::from("new-project/src/new_project.rs"), b"struct NewProject;".to_vec()),
+            ]
+        );
+    }
+
+    // A memory filesystem whose disk is full
+    struct Full(crate::fs::Memory);
+
+    impl Filesystem for Full {
+        fn exists(&self, path: &Path) -> bool {
+            self.0.exists(path)
+        }
+
+        fn is_dir(&self, path: &Path) -> bool {
+            self.0.is_dir(path)
+        }
+
+        fn read_dir(&self, path: &Path) -> std::io::Result<Vec<PathBuf>> {
+            self.0.read_dir(path)
+        }
+
+        fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+            self.0.read(path)
+        }
+
+        fn write(&self, _: &Path, _: &[u8]) -> std::io::Result<()> {
+            Err(std::io::Error::new(std::io::ErrorKind::StorageFull, "no space left"))
+        }
+
+        fn copy(&self, _: &Path, _: &Path) -> std::io::Result<()> {
+            Err(std::io::Error::new(std::io::ErrorKind::StorageFull, "no space left"))
+        }
+
+        fn create_dir_all(&self, path: &Path) -> std::io::Result<()> {
+            self.0.create_dir_all(path)
+        }
+
+        fn remove_file(&self, path: &Path) -> std::io::Result<()> {
+            self.0.remove_file(path)
+        }
+
+        fn remove_all(&self, path: &Path) -> std::io::Result<()> {
+            self.0.remove_all(path)
+        }
+
+        fn rename(&self, from: &Path, to: &Path) -> std::io::Result<()> {
+            self.0.rename(from, to)
+        }
+    }
+
+    #[test]
+    fn test_failed_write() {
+        let fs = Arc::new(Full(crate::fs::Memory::new().with_file("my-project/my-project.txt", "my-project")));
+        let result = Renamer::builder().input("my-project").new_name("new-project").filesystem(fs.clone()).build().unwrap().run();
+        let error = result.unwrap_err();
+        assert!(error.starts_with("Failed to write .new-project.project-renamer/new-project.txt: no space left"), "{error}");
+        assert!(!fs.exists(Path::new("new-project")));
+    }
+
+    struct Domain;
+
+    impl Replacer for Domain {
+        fn name(&self) -> &str {
+            "domain"
+        }
+
+        fn replace(&mut self, file: &crate::replace::File) -> Result<crate::replace::Edits, String> {
+            let count = file.text.matches("old.example.com").count();
+            let substitutions = vec![crate::Substitution { from: "old.example.com".to_string(), to: "new.example.com".to_string(), count }];
+            let text = file.text.replace("old.example.com", "new.example.com");
+            Ok(crate::replace::Edits::Replaced { text, substitutions: if count > 0 { substitutions } else { vec![] } })
+        }
+    }
+
+    #[test]
+    fn test_replacer() {
+        let fs = Arc::new(crate::fs::Memory::new().with_file("my-project/a.txt", "my-project at old.example.com"));
+        let report = Renamer::builder()
+            .input("my-project")
+            .new_name("new-project")
+            .filesystem(fs.clone())
+            .replacer(Domain)
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!(report.substitutions, 2);
+        assert_eq!(fs.read(Path::new("new-project/a.txt")).unwrap(), b"new-project at new.example.com");
+    }
+
+    #[test]
+    fn test_run() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-renamer-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("my-project/fixtures")).unwrap();
+        std::fs::write(dir.join("my-project/my-project.txt"), "my-project").unwrap();
+        std::fs::write(dir.join("my-project/fixtures/my-project.txt"), "my-project").unwrap();
+
+        let renamer = Renamer::builder().input(dir.join("my-project")).new_name("new-project").exclude("fixtures");
+        let report = renamer.clone().dry_run(true).build().unwrap().run().unwrap();
+        assert_eq!(report.output, dir.join("new-project"));
+        assert_eq!((report.renamed, report.substitutions), (1, 1));
+        assert!(report.operations.iter().any(|operation| operation.action == Action::Copy
+            && operation.output == dir.join("new-project/fixtures/my-project.txt")));
+        assert!(!dir.join("new-project").exists());
+
+        assert_eq!(renamer.build().unwrap().run().unwrap().renamed, 1);
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/new-project.txt")).unwrap(), "new-project");
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/fixtures/my-project.txt")).unwrap(), "my-project");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
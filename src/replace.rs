@@ -0,0 +1,1595 @@
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use syn::Token;
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+
+use crate::{bazel, copyright, cpp_packages, debian, docker, glob, gradle, kubernetes, makefile, meson, nix, rpm, solution, systemd};
+use crate::ecosystem::{Ecosystem, Ecosystems};
+use crate::plugin::Plugin;
+use crate::regex::Regex;
+use crate::{structured, toml};
+use crate::syntax::{self, Language, Node};
+use crate::uuid::Regenerator;
+use crate::{CaseInfo, CaseType, NormalizedName, Substitution, capitalise, transform_text_cases};
+
+/// A text file passed through the replacers
+pub struct File<'a> {
+    /// Path relative to the project
+    pub path: &'a Path,
+    /// The content before any replacement
+    pub original: &'a str,
+    /// The content with the replacements of the previous replacers made
+    pub text: &'a str,
+    /// Old and new name of the project, with their parts joined by "-"
+    pub old_name: &'a str,
+    pub new_name: &'a str,
+}
+
+/// What a replacer does to a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edits {
+    /// The new text, with every replacement made
+    Replaced { text: String, substitutions: Vec<Substitution> },
+    /// Keep the file unchanged, discarding the replacements of every replacer
+    Veto,
+}
+
+/// A step of the pipeline that the content of every text file is passed through. The steps run in
+/// this order, each on the text the previous one returned:
+///
+/// 1. The renames of the names an ecosystem spells its own way: the npm package that moves to
+///    another scope, the Python package, Ruby gem, Elixir application, Swift package, C# namespace
+///    and Homebrew formula, and the WordPress text domain.
+/// 2. The case variants of the name.
+/// 3. The other built-in replacements: the Rust crate references, the domain, the copyright
+///    holder, the regenerated UUIDs and the regex replacements.
+/// 4. The plugins of the command line.
+/// 5. The replacers of a library user, in the order they were added to the renamer.
+pub trait Replacer: Send {
+    /// Name of the replacer in messages, e.g. the plugin command
+    fn name(&self) -> &str;
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String>;
+}
+
+// The replacers of the ecosystems with naming conventions of their own, in the order they run
+type NewReplacer = for<'a> fn(&'a NormalizedName, &'a NormalizedName) -> Box<dyn Replacer + 'a>;
+
+const REPLACERS: [(Ecosystem, NewReplacer); 6] = [
+    (Ecosystem::PythonPackages, |old, new| Box::new(PythonPackages::new(old, new))),
+    (Ecosystem::RubyGems, |old, new| Box::new(RubyGems::new(old, new))),
+    (Ecosystem::Elixir, |old, new| Box::new(ElixirApps::new(old, new))),
+    (Ecosystem::SwiftPackages, |old, new| Box::new(SwiftPackages::new(old, new))),
+    (Ecosystem::CSharpNamespaces, |old, new| Box::new(CSharpNamespaces::new(old, new))),
+    (Ecosystem::Homebrew, |old, new| Box::new(HomebrewFormulae::new(old, new))),
+];
+
+// The replacers of the enabled ecosystems, which run before the case variants
+pub(crate) fn ecosystem_replacers<'a>(ecosystems: Ecosystems, old_name: &'a NormalizedName, new_name: &'a NormalizedName) -> Vec<Box<dyn Replacer + 'a>> {
+    REPLACERS.iter().filter(|(ecosystem, _)| ecosystems.contains(*ecosystem)).map(|(_, new)| new(old_name, new_name)).collect()
+}
+
+/// Replaces every case variant of the old name with the same case variant of the new name
+pub struct CaseVariants<'a> {
+    old_name: &'a NormalizedName,
+    new_name: &'a NormalizedName,
+    /// Only these case styles, every one when None
+    cases: Option<&'a [CaseInfo]>,
+    /// Only in these kinds of tokens of source code in a recognized language, anywhere when None
+    nodes: Option<&'a [Node]>,
+    /// Only where the name isn't part of a longer identifier
+    identifier_boundaries: bool,
+    /// Other names whose case variants are replaced in the same pass, e.g. the organization
+    other_names: &'a [(NormalizedName, NormalizedName)],
+    /// Exact text replaced in the same pass
+    literals: &'a [(String, String)],
+    /// Only in the values at these key paths of a data file like YAML, anywhere when None
+    keys: Option<&'a [String]>,
+    /// The ecosystems whose files are only renamed in the names they declare, e.g. the project names
+    /// of Gradle build scripts
+    ecosystems: Ecosystems,
+}
+
+impl<'a> CaseVariants<'a> {
+    pub(crate) fn new(old_name: &'a NormalizedName, new_name: &'a NormalizedName) -> Self {
+        Self {
+            old_name,
+            new_name,
+            cases: None,
+            nodes: None,
+            identifier_boundaries: false,
+            other_names: &[],
+            literals: &[],
+            keys: None,
+            ecosystems: Ecosystems::default(),
+        }
+    }
+
+    pub(crate) fn with_cases(mut self, cases: Option<&'a [CaseInfo]>) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    pub(crate) fn with_identifier_boundaries(mut self, identifier_boundaries: bool) -> Self {
+        self.identifier_boundaries = identifier_boundaries;
+        self
+    }
+
+    pub(crate) fn with_other_names(mut self, other_names: &'a [(NormalizedName, NormalizedName)]) -> Self {
+        self.other_names = other_names;
+        self
+    }
+
+    pub(crate) fn with_literals(mut self, literals: &'a [(String, String)]) -> Self {
+        self.literals = literals;
+        self
+    }
+
+    pub(crate) fn with_nodes(mut self, nodes: Option<&'a [Node]>) -> Self {
+        self.nodes = nodes;
+        self
+    }
+
+    pub(crate) fn with_keys(mut self, keys: Option<&'a [String]>) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    pub(crate) fn with_ecosystems(mut self, ecosystems: Ecosystems) -> Self {
+        self.ecosystems = ecosystems;
+        self
+    }
+
+    // Replace the case variants only in the ranges of the text, which are in order
+    fn replace_in(&self, text: &str, ranges: impl IntoIterator<Item = Range<usize>>, cases: &[CaseInfo]) -> Edits {
+        let mut out = String::with_capacity(text.len());
+        let mut substitutions: Vec<Substitution> = vec![];
+        let mut copied_until = 0;
+        for range in ranges {
+            out.push_str(&text[copied_until..range.start]);
+            let (replaced, found) =
+                transform_text_cases(&text[range.clone()], self.old_name, self.new_name, cases, self.identifier_boundaries, self.other_names, self.literals);
+            out.push_str(&replaced);
+            copied_until = range.end;
+            merge(&mut substitutions, found);
+        }
+        out.push_str(&text[copied_until..]);
+        Edits::Replaced { text: out, substitutions }
+    }
+}
+
+// The ranges each ecosystem renames in the files it knows, given the enabled ecosystems, None for
+// other files. The first ecosystem that knows a file decides.
+type Names = fn(&Path, &str, Ecosystems) -> Option<Vec<Range<usize>>>;
+
+const NAMES: [(Ecosystem, Names); 11] = [
+    (Ecosystem::Gradle, |path, text, _| gradle::is_script(path).then(|| gradle::references(text))),
+    (Ecosystem::Nix, |path, text, _| nix::is_lock(path).then(|| nix::input_names(text))),
+    (Ecosystem::Bazel, |path, text, _| bazel::is_build_file(path).then(|| bazel::names(text))),
+    (Ecosystem::CppPackages, |path, text, _| cpp_packages::is_manifest(path).then(|| cpp_packages::names(path, text))),
+    (Ecosystem::Meson, |path, text, _| meson::is_meson_file(path).then(|| meson::names(path, text))),
+    (Ecosystem::Makefiles, |path, text, ecosystems| {
+        let ranges = makefile::is_makefile(path).then(|| makefile::names(text))?;
+        Some(if ecosystems.contains(Ecosystem::Docker) { union(ranges, docker::image_references(text)) } else { ranges })
+    }),
+    (Ecosystem::Docker, |path, text, ecosystems| {
+        let ranges = docker::is_dockerfile(path).then(|| docker::names(text))?;
+        Some(if ecosystems.contains(Ecosystem::Makefiles) { union(ranges, docker::make_targets(text)) } else { ranges })
+    }),
+    (Ecosystem::Kubernetes, |path, text, _| kubernetes::names(path, text)),
+    (Ecosystem::Systemd, |path, text, _| systemd::is_unit_file(path).then(|| systemd::names(text))),
+    (Ecosystem::Debian, |path, text, _| debian::is_package_file(path).then(|| debian::names(path, text))),
+    (Ecosystem::Rpm, |path, text, _| rpm::is_spec(path).then(|| rpm::names(text))),
+];
+
+impl Replacer for CaseVariants<'_> {
+    fn name(&self) -> &str {
+        "case variants"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        let cases = self.cases.map_or_else(CaseInfo::all_cases, <[CaseInfo]>::to_vec);
+        // Only the values at the key paths, none in a file whose format isn't known
+        if let Some(keys) = self.keys {
+            let ranges = structured::Format::detect(file.path).map_or_else(Vec::new, |format| structured::values(file.text, format, keys));
+            return Ok(self.replace_in(file.text, ranges, &cases));
+        }
+        for (ecosystem, names) in NAMES {
+            if self.ecosystems.contains(ecosystem)
+                && let Some(ranges) = names(file.path, file.text, self.ecosystems)
+            {
+                return Ok(self.replace_in(file.text, ranges, &cases));
+            }
+        }
+        let (Some(nodes), Some(language)) = (self.nodes, Language::detect(file.path)) else {
+            let (text, substitutions) = transform_text_cases(file.text, self.old_name, self.new_name, &cases, self.identifier_boundaries, self.other_names, self.literals);
+            return Ok(Edits::Replaced { text, substitutions });
+        };
+        let spans = syntax::spans(file.text, language);
+        Ok(self.replace_in(file.text, spans.into_iter().filter(|span| nodes.contains(&span.node)).map(|span| span.range), &cases))
+    }
+}
+
+/// Replaces regular expressions with their replacements, in order
+pub struct Regexes<'a> {
+    rules: Vec<&'a (Regex, String)>,
+}
+
+impl<'a> Regexes<'a> {
+    pub(crate) fn new(rules: Vec<&'a (Regex, String)>) -> Self {
+        Self { rules }
+    }
+}
+
+impl Replacer for Regexes<'_> {
+    fn name(&self) -> &str {
+        "regex"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        let mut text = file.text.to_string();
+        let mut substitutions = vec![];
+        for (regex, replacement) in &self.rules {
+            let (new_text, count) = regex.replace_all(&text, replacement);
+            if count > 0 {
+                text = new_text;
+                substitutions.push(Substitution { from: regex.as_str().to_string(), to: replacement.clone(), count });
+            }
+        }
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+/// Renames the references to the crate in Rust code: "use old_name::", "extern crate old_name" and
+/// paths like "old_name::module::Item". The crate is referred to by its package name with hyphens
+/// mapped to underscores, which isn't one of the case variants when the name mixes separators.
+/// The names Cargo derives from the package are renamed too, also where the case variants are
+/// limited to identifiers: the features in `feature = "..."` and the environment variables read
+/// with env!, option_env! and std::env::var like "OLD_NAME_LOG", and in Cargo.toml the package,
+/// library and target names and the features. Rust files are parsed with syn, and the ones that
+/// don't parse, like templates, are left to the case variants.
+pub struct RustCrates<'a> {
+    old_name: &'a NormalizedName,
+    new_name: &'a NormalizedName,
+    old_crate: String,
+    new_crate: String,
+}
+
+// Values of Cargo.toml named after the package
+const CARGO_VALUES: [&str; 7] = ["package.name", "lib.name", "bin.*.name", "example.*.name", "test.*.name", "bench.*.name", "features.*.*"];
+
+impl<'a> RustCrates<'a> {
+    pub(crate) fn new(old_name: &'a NormalizedName, new_name: &'a NormalizedName) -> Self {
+        Self { old_name, new_name, old_crate: crate_name(old_name), new_crate: crate_name(new_name) }
+    }
+
+    // The ranges of the names and features of a Cargo.toml, in order
+    fn cargo_names(text: &str) -> Vec<Range<usize>> {
+        let patterns = CARGO_VALUES.map(|pattern| pattern.split('.').map(str::to_string).collect::<Vec<_>>());
+        let features = ["features".to_string(), "*".to_string()];
+        let values = toml::values(text).unwrap_or_default().into_iter().filter(|(path, _)| patterns.iter().any(|pattern| structured::matches(pattern, path)));
+        let keys = toml::keys(text).unwrap_or_default().into_iter().filter(|(path, _)| structured::matches(&features, path));
+        let mut ranges = values.chain(keys).map(|(_, range)| range).collect::<Vec<_>>();
+        ranges.sort_by_key(|range| range.start);
+        ranges
+    }
+}
+
+// The name of the crate in Rust code, for a package name like "my-project" or "My Project"
+fn crate_name(name: &NormalizedName) -> String {
+    if !name.original.is_empty() && name.original.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        name.original.replace('-', "_")
+    } else {
+        CaseInfo { separator: Some('_'), part_type: CaseType::LowerCase }.convert(name.clone())
+    }
+}
+
+impl Replacer for RustCrates<'_> {
+    fn name(&self) -> &str {
+        "rust crates"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        let cases = CaseInfo::all_cases();
+        if file.path.file_name().is_some_and(|name| name == "Cargo.toml") {
+            return Ok(CaseVariants::new(self.old_name, self.new_name).replace_in(file.text, Self::cargo_names(file.text), &cases));
+        }
+        if Language::detect(file.path) != Some(Language::Rust) {
+            return Ok(Edits::Replaced { text: file.text.to_string(), substitutions: vec![] });
+        }
+        let mut references = CrateReferences { old_crate: &self.old_crate, crates: vec![], derived: vec![] };
+        if let Some(parsed) = parse_rust(file.text) {
+            references.visit_file(&parsed);
+        }
+        proc_macro2::extra::invalidate_current_thread_spans();
+        if self.old_crate == self.new_crate {
+            references.crates.clear();
+        }
+        let mut ranges = references.crates.iter().map(|range| (range.clone(), true)).chain(references.derived.iter().map(|range| (range.clone(), false))).collect::<Vec<_>>();
+        ranges.sort_by_key(|(range, _)| range.start);
+        ranges.dedup_by_key(|(range, _)| range.start);
+
+        let mut text = String::with_capacity(file.text.len());
+        let mut substitutions = vec![];
+        let mut copied_until = 0;
+        for (range, is_crate) in ranges {
+            text.push_str(&file.text[copied_until..range.start]);
+            if is_crate {
+                text.push_str(&self.new_crate);
+            } else {
+                let (replaced, found) = transform_text_cases(&file.text[range.clone()], self.old_name, self.new_name, &cases, false, &[], &[]);
+                text.push_str(&replaced);
+                merge(&mut substitutions, found);
+            }
+            copied_until = range.end;
+        }
+        text.push_str(&file.text[copied_until..]);
+        let count = references.crates.len();
+        if count > 0 {
+            merge(&mut substitutions, vec![Substitution { from: self.old_crate.clone(), to: self.new_crate.clone(), count }]);
+        }
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+// Parse a Rust file, with a shebang or a byte order mark blanked out so the byte offsets of the
+// spans stay those of the text. None for a file that isn't valid Rust, e.g. a template.
+fn parse_rust(text: &str) -> Option<syn::File> {
+    let mut source = text.to_string();
+    if source.starts_with('\u{feff}') {
+        source.replace_range(..3, "   ");
+    }
+    let rest = source.trim_start();
+    if rest.starts_with("#!") && !rest.starts_with("#![") {
+        let start = source.len() - rest.len();
+        let end = source[start..].find('\n').map_or(source.len(), |end| start + end);
+        source.replace_range(start..end, &" ".repeat(end - start));
+    }
+    syn::parse_file(&source).ok()
+}
+
+// The references to a crate and the string literals of the names Cargo derives from its package in
+// the syntax tree of a Rust file. The bodies of macros are parsed as expressions or statements
+// where they are ones, like the arguments of println! or vec!.
+struct CrateReferences<'a> {
+    old_crate: &'a str,
+    crates: Vec<Range<usize>>,
+    derived: Vec<Range<usize>>,
+}
+
+impl CrateReferences<'_> {
+    // The crate at the root of a use item, like "use old_name::{a, b}" or "use {old_name::a, c}"
+    fn use_root(&mut self, tree: &syn::UseTree) {
+        match tree {
+            syn::UseTree::Path(syn::UsePath { ident, .. }) | syn::UseTree::Name(syn::UseName { ident }) | syn::UseTree::Rename(syn::UseRename { ident, .. })
+                if ident == self.old_crate =>
+            {
+                self.crates.push(ident.span().byte_range());
+            }
+            syn::UseTree::Group(group) => group.items.iter().for_each(|tree| self.use_root(tree)),
+            _ => {}
+        }
+    }
+
+    fn string(&mut self, expr: &syn::Expr) {
+        if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) = expr {
+            self.derived.push(lit.span().byte_range());
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CrateReferences<'_> {
+    fn visit_item_use(&mut self, item: &'ast syn::ItemUse) {
+        self.use_root(&item.tree);
+        visit::visit_item_use(self, item);
+    }
+
+    fn visit_item_extern_crate(&mut self, item: &'ast syn::ItemExternCrate) {
+        if item.ident == self.old_crate {
+            self.crates.push(item.ident.span().byte_range());
+        }
+        visit::visit_item_extern_crate(self, item);
+    }
+
+    // Paths like old_name::module::Item, but not a variable named like the crate
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        if (path.segments.len() > 1 || path.leading_colon.is_some())
+            && let Some(first) = path.segments.first()
+            && first.ident == self.old_crate
+        {
+            self.crates.push(first.ident.span().byte_range());
+        }
+        visit::visit_path(self, path);
+    }
+
+    // The features of cfg and cfg_attr, like `feature = "old-name-tls"`
+    fn visit_meta(&mut self, meta: &'ast syn::Meta) {
+        match meta {
+            syn::Meta::NameValue(name_value) if name_value.path.is_ident("feature") => self.string(&name_value.value),
+            syn::Meta::List(list) => {
+                if let Ok(nested) = list.parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated) {
+                    nested.iter().for_each(|meta| self.visit_meta(meta));
+                }
+            }
+            _ => {}
+        }
+        visit::visit_meta(self, meta);
+    }
+
+    // The environment variables read with std::env::var and the like
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(function) = call.func.as_ref()
+            && function.path.segments.last().is_some_and(|segment| matches!(segment.ident.to_string().as_str(), "var" | "var_os" | "set_var" | "remove_var"))
+            && let Some(name) = call.args.first()
+        {
+            self.string(name);
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        let name = mac.path.segments.last().map(|segment| segment.ident.to_string()).unwrap_or_default();
+        if name == "cfg" {
+            if let Ok(meta) = mac.parse_body::<syn::Meta>() {
+                self.visit_meta(&meta);
+            }
+        } else if let Ok(args) = mac.parse_body_with(Punctuated::<syn::Expr, Token![,]>::parse_terminated) {
+            if matches!(name.as_str(), "env" | "option_env")
+                && let Some(name) = args.first()
+            {
+                self.string(name);
+            }
+            args.iter().for_each(|expr| self.visit_expr(expr));
+        } else if let Ok(stmts) = mac.parse_body_with(syn::Block::parse_within) {
+            stmts.iter().for_each(|stmt| self.visit_stmt(stmt));
+        }
+        visit::visit_macro(self, mac);
+    }
+}
+
+// The ranges covered by either of two lists of ranges, in order and without overlaps
+fn union(mut ranges: Vec<Range<usize>>, other: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.extend(other);
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+// Add the substitutions to the ones already made, counting the same replacement once
+fn merge(substitutions: &mut Vec<Substitution>, found: Vec<Substitution>) {
+    for substitution in found {
+        match substitutions.iter_mut().find(|s| s.from == substitution.from && s.to == substitution.to) {
+            Some(existing) => existing.count += substitution.count,
+            None => substitutions.push(substitution),
+        }
+    }
+}
+
+/// Renames a Python project, keeping its distribution name and its import name apart: the
+/// distribution name in pyproject.toml and importlib.metadata calls, e.g. "old-name", and the import
+/// name of the package in imports, scripts and entry points, e.g. "old_name". The import name has
+/// the separators of the name mapped to underscores, which isn't one of the case variants when the
+/// name mixes them, and the distribution name is compared normalized like pip does, so
+/// "Old.Name" and "old_name" are the same distribution.
+pub struct PythonPackages<'a> {
+    old_name: &'a NormalizedName,
+    new_name: &'a NormalizedName,
+    old_module: String,
+    new_module: String,
+    new_distribution: String,
+}
+
+// Values of pyproject.toml with the distribution name, for Poetry and standard metadata that
+// Hatch, PDM, uv and Flit read
+const DISTRIBUTION_NAMES: [&str; 2] = ["project.name", "tool.poetry.name"];
+
+// Values of pyproject.toml that refer to the package by its import name
+const MODULE_REFERENCES: [&str; 11] = [
+    "project.scripts.*",
+    "project.gui-scripts.*",
+    "project.entry-points.*.*",
+    "tool.poetry.scripts.*",
+    "tool.poetry.packages.*.include",
+    "tool.hatch.build.targets.wheel.packages.*",
+    "tool.hatch.build.targets.sdist.include.*",
+    "tool.pdm.build.includes.*",
+    "tool.setuptools.packages.*",
+    "tool.setuptools.packages.find.include.*",
+    "tool.flit.module.name",
+];
+
+// Tables of pyproject.toml whose keys are the names of scripts
+const SCRIPT_NAMES: [&str; 3] = ["project.scripts.*", "project.gui-scripts.*", "tool.poetry.scripts.*"];
+
+impl<'a> PythonPackages<'a> {
+    pub(crate) fn new(old_name: &'a NormalizedName, new_name: &'a NormalizedName) -> Self {
+        Self {
+            old_name,
+            new_name,
+            old_module: CaseInfo { separator: Some('_'), part_type: CaseType::LowerCase }.convert(old_name.clone()),
+            new_module: CaseInfo { separator: Some('_'), part_type: CaseType::LowerCase }.convert(new_name.clone()),
+            new_distribution: CaseInfo { separator: Some('-'), part_type: CaseType::LowerCase }.convert(new_name.clone()),
+        }
+    }
+
+    fn is_distribution(&self, text: &str) -> bool {
+        normalize_distribution(text) == normalize_distribution(&self.old_name.parts.join("-"))
+    }
+
+    // The text with the distribution name replaced if it is the old one
+    fn distribution(&self, text: &str, substitutions: &mut Vec<Substitution>) -> String {
+        if !self.is_distribution(text) {
+            return text.to_string();
+        }
+        merge(substitutions, vec![Substitution { from: text.to_string(), to: self.new_distribution.clone(), count: 1 }]);
+        self.new_distribution.clone()
+    }
+
+    // The text with the import name replaced where it is a whole dotted name part, like in
+    // "old_name.cli:main", and the case variants replaced in the rest
+    fn module(&self, text: &str, substitutions: &mut Vec<Substitution>) -> String {
+        let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+        let mut out = String::with_capacity(text.len());
+        let mut copied_until = 0;
+        let mut count = 0;
+        for (start, _) in text.match_indices(&self.old_module) {
+            let end = start + self.old_module.len();
+            if start >= copied_until && !text[..start].ends_with(is_identifier) && !text[end..].starts_with(is_identifier) {
+                let (replaced, found) = transform_text_cases(&text[copied_until..start], self.old_name, self.new_name, &CaseInfo::all_cases(), false, &[], &[]);
+                out.push_str(&replaced);
+                merge(substitutions, found);
+                out.push_str(&self.new_module);
+                copied_until = end;
+                count += 1;
+            }
+        }
+        let (replaced, found) = transform_text_cases(&text[copied_until..], self.old_name, self.new_name, &CaseInfo::all_cases(), false, &[], &[]);
+        out.push_str(&replaced);
+        merge(substitutions, found);
+        if count > 0 {
+            merge(substitutions, vec![Substitution { from: self.old_module.clone(), to: self.new_module.clone(), count }]);
+        }
+        out
+    }
+
+    fn pyproject(&self, text: &str) -> Edits {
+        let patterns = |patterns: &[&str]| patterns.iter().map(|pattern| pattern.split('.').map(str::to_string).collect::<Vec<_>>()).collect::<Vec<_>>();
+        let (distributions, modules, scripts) = (patterns(&DISTRIBUTION_NAMES), patterns(&MODULE_REFERENCES), patterns(&SCRIPT_NAMES));
+        let matching = |patterns: &[Vec<String>], path: &[String]| patterns.iter().any(|pattern| structured::matches(pattern, path));
+        let mut ranges = toml::values(text)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(path, range)| {
+                let distribution = matching(&distributions, &path);
+                (distribution || matching(&modules, &path)).then_some((range, distribution))
+            })
+            // A script named like the project is named like the distribution
+            .chain(
+                toml::keys(text)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|(path, _)| matching(&scripts, path))
+                    .map(|(_, range)| (range.clone(), self.is_distribution(&text[range]))),
+            )
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|(range, _)| range.start);
+
+        let mut out = String::with_capacity(text.len());
+        let mut substitutions = vec![];
+        let mut copied_until = 0;
+        for (range, distribution) in ranges {
+            out.push_str(&text[copied_until..range.start]);
+            let value = &text[range.clone()];
+            out.push_str(&if distribution { self.distribution(value, &mut substitutions) } else { self.module(value, &mut substitutions) });
+            copied_until = range.end;
+        }
+        out.push_str(&text[copied_until..]);
+        Edits::Replaced { text: out, substitutions }
+    }
+}
+
+// A distribution name as pip compares them (PEP 503): lower case, with runs of "-", "_" and "."
+// as a single "-"
+fn normalize_distribution(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for part in name.split(['-', '_', '.']).filter(|part| !part.is_empty()) {
+        if !normalized.is_empty() {
+            normalized.push('-');
+        }
+        normalized.push_str(&part.to_lowercase());
+    }
+    normalized
+}
+
+impl Replacer for PythonPackages<'_> {
+    fn name(&self) -> &str {
+        "python packages"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        if file.path.file_name().is_some_and(|name| name == "pyproject.toml") {
+            return Ok(self.pyproject(file.text));
+        }
+        if Language::detect(file.path) != Some(Language::Python) {
+            return Ok(Edits::Replaced { text: file.text.to_string(), substitutions: vec![] });
+        }
+        let spans = syntax::spans(file.text, Language::Python);
+        let token = |index: usize| &file.text[spans[index].range.clone()];
+        // The identifier before the span at the index, if only whitespace is in between
+        let previous = |index: usize| {
+            (index >= 2 && token(index - 1).trim().is_empty() && spans[index - 2].node == Node::Identifier)
+                .then(|| token(index - 2))
+        };
+
+        let mut text = String::with_capacity(file.text.len());
+        let mut substitutions = vec![];
+        let mut count = 0;
+        for (index, span) in spans.iter().enumerate() {
+            // "import old_name", "from old_name.cli import main" and "old_name.attribute"
+            let is_import = span.node == Node::Identifier
+                && self.old_module != self.new_module
+                && token(index) == self.old_module
+                && (spans.get(index + 1).is_some_and(|_| token(index + 1).starts_with('.'))
+                    || matches!(previous(index), Some("import" | "from")));
+            // importlib.metadata.version("old-name") and the like
+            let is_distribution = span.node == Node::String
+                && index >= 2
+                && token(index - 1) == "("
+                && matches!(token(index - 2), "version" | "distribution" | "metadata" | "requires" | "files");
+            if is_import {
+                text.push_str(&self.new_module);
+                count += 1;
+            } else if is_distribution && token(index).len() >= 2 {
+                let literal = token(index);
+                let quote = &literal[..1];
+                let inner = literal.trim_matches(|c| c == '"' || c == '\'');
+                text.push_str(&format!("{quote}{}{quote}", self.distribution(inner, &mut substitutions)));
+            } else {
+                text.push_str(token(index));
+            }
+        }
+        if count > 0 {
+            merge(&mut substitutions, vec![Substitution { from: self.old_module.clone(), to: self.new_module.clone(), count }]);
+        }
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+/// Renames a Ruby gem following the RubyGems naming conventions, where an underscore joins words and
+/// a hyphen nests: the gem "old-name" is required as "old/name" and defines the constant Old::Name,
+/// which aren't case variants of the name. The gem name in `spec.name = "..."` of the gemspec, the
+/// required paths and the constants are renamed, with the new ones following the same conventions.
+pub struct RubyGems {
+    old_gem: String,
+    new_gem: String,
+}
+
+impl RubyGems {
+    pub(crate) fn new(old_name: &NormalizedName, new_name: &NormalizedName) -> Self {
+        Self { old_gem: gem_name(old_name), new_gem: gem_name(new_name) }
+    }
+}
+
+// Replace the occurrences of the old text whose text before and after it is accepted, e.g. where
+// it isn't part of a longer name
+fn replace_words(text: &str, old: &str, new: &str, before: impl Fn(&str) -> bool, after: impl Fn(&str) -> bool) -> (String, usize) {
+    let mut out = String::with_capacity(text.len());
+    let mut copied_until = 0;
+    let mut count = 0;
+    for (start, _) in text.match_indices(old) {
+        let end = start + old.len();
+        if start >= copied_until && before(&text[..start]) && after(&text[end..]) {
+            out.push_str(&text[copied_until..start]);
+            out.push_str(new);
+            copied_until = end;
+            count += 1;
+        }
+    }
+    out.push_str(&text[copied_until..]);
+    (out, count)
+}
+
+// The name of the gem, for a name like "old-name" or "Old Name"
+fn gem_name(name: &NormalizedName) -> String {
+    if !name.original.is_empty() && name.original.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_') {
+        name.original.clone()
+    } else {
+        CaseInfo { separator: Some('_'), part_type: CaseType::LowerCase }.convert(name.clone())
+    }
+}
+
+// The path a gem is required by, "old-name" is "old/name"
+fn gem_path(gem: &str) -> String {
+    gem.replace('-', "/")
+}
+
+// The constant of a gem, "old_name" is OldName and "old-name" is Old::Name
+fn gem_constant(gem: &str) -> String {
+    gem.split('-').map(|segment| segment.split('_').map(capitalise).collect::<String>()).collect::<Vec<_>>().join("::")
+}
+
+impl Replacer for RubyGems {
+    fn name(&self) -> &str {
+        "ruby gems"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        let name = file.path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let is_ruby = Language::detect(file.path) == Some(Language::Ruby) || name.ends_with(".gemspec") || matches!(name.as_str(), "Gemfile" | "Rakefile");
+        if !is_ruby || self.old_gem == self.new_gem {
+            return Ok(Edits::Replaced { text: file.text.to_string(), substitutions: vec![] });
+        }
+        let spans = syntax::spans(file.text, Language::Ruby);
+        let token = |index: usize| &file.text[spans[index].range.clone()];
+        let (old_path, new_path) = (gem_path(&self.old_gem), gem_path(&self.new_gem));
+
+        // The gem name of the gemspec and the paths of `require "old/name/version"`
+        let mut text = String::with_capacity(file.text.len());
+        let mut renamed = 0;
+        let mut required = 0;
+        for (index, span) in spans.iter().enumerate() {
+            let literal = token(index);
+            let inner = literal.get(1..literal.len().saturating_sub(1)).unwrap_or_default();
+            let after = |index: usize| {
+                index >= 2 && matches!(token(index - 1).trim(), "" | "(" | "=") && spans[index - 2].node == Node::Identifier
+            };
+            if span.node == Node::String && after(index) && token(index - 2) == "name" && inner == self.old_gem {
+                text.push_str(&format!("{0}{1}{0}", &literal[..1], self.new_gem));
+                renamed += 1;
+            } else if span.node == Node::String
+                && after(index)
+                && token(index - 2) == "require"
+                && (inner == old_path || inner.starts_with(&format!("{old_path}/")))
+            {
+                text.push_str(&format!("{0}{1}{2}{0}", &literal[..1], new_path, &inner[old_path.len()..]));
+                required += 1;
+            } else {
+                text.push_str(literal);
+            }
+        }
+
+        let (old_constant, new_constant) = (gem_constant(&self.old_gem), gem_constant(&self.new_gem));
+        let is_part = |c: char| c.is_alphanumeric() || c == '_';
+        let (text, constants) = replace_words(&text, &old_constant, &new_constant, |before| !before.ends_with(is_part), |after| !after.starts_with(is_part));
+        let substitutions = [(self.old_gem.clone(), self.new_gem.clone(), renamed), (old_path, new_path, required), (old_constant, new_constant, constants)]
+            .into_iter()
+            .filter(|(_, _, count)| *count > 0)
+            .map(|(from, to, count)| Substitution { from, to, count })
+            .collect();
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+/// Renames an Elixir project following the Mix conventions, where the application is a snake_case
+/// atom and its modules are under the alias Macro.camelize makes of it: the application :old_name
+/// defines OldName. The atoms of the application, like `app: :old_name` in mix.exs and `config
+/// :old_name`, and the aliases of its modules, like `OldName.Repo` or `OldNameWeb` of Phoenix, are
+/// renamed to the new name with the same conventions, whatever separators the new name was given
+/// with.
+pub struct ElixirApps {
+    old_app: String,
+    new_app: String,
+}
+
+impl ElixirApps {
+    pub(crate) fn new(old_name: &NormalizedName, new_name: &NormalizedName) -> Self {
+        let app = |name: &NormalizedName| CaseInfo { separator: Some('_'), part_type: CaseType::LowerCase }.convert(name.clone());
+        Self { old_app: app(old_name), new_app: app(new_name) }
+    }
+}
+
+// The alias of the modules of an application, "old_name" is OldName
+fn elixir_alias(app: &str) -> String {
+    app.split('_').map(capitalise).collect()
+}
+
+impl Replacer for ElixirApps {
+    fn name(&self) -> &str {
+        "elixir apps"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        let extension = file.path.extension().map(|extension| extension.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let is_elixir = matches!(extension.as_str(), "ex" | "exs" | "eex" | "heex" | "leex");
+        if !is_elixir || self.old_app == self.new_app {
+            return Ok(Edits::Replaced { text: file.text.to_string(), substitutions: vec![] });
+        }
+        // Atoms of the application and of others named after it, like :old_name_web, but not
+        // :old_names or the second colon of a "::" type spec
+        let (old_atom, new_atom) = (format!(":{}", self.old_app), format!(":{}", self.new_app));
+        let is_part = |c: char| c.is_alphanumeric() || c == '_';
+        let (text, atoms) = replace_words(
+            file.text,
+            &old_atom,
+            &new_atom,
+            |before| !before.ends_with(|c: char| is_part(c) || c == ':'),
+            |after| !after.starts_with(char::is_alphanumeric),
+        );
+        // Aliases that start with the alias of the application, like OldName.Repo and OldNameWeb,
+        // but not OldNames or the nested Other.OldName
+        let (old_alias, new_alias) = (elixir_alias(&self.old_app), elixir_alias(&self.new_app));
+        let (text, aliases) = replace_words(
+            &text,
+            &old_alias,
+            &new_alias,
+            |before| !before.ends_with(|c: char| is_part(c) || c == '.'),
+            |after| !after.starts_with(|c: char| c.is_lowercase() || c.is_numeric() || c == '_'),
+        );
+        let substitutions = [(old_atom, new_atom, atoms), (old_alias, new_alias, aliases)]
+            .into_iter()
+            .filter(|(_, _, count)| *count > 0)
+            .map(|(from, to, count)| Substitution { from, to, count })
+            .collect();
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+// The kinds of declarations an import can be restricted to in Swift, like `import struct Module.Type`
+const SWIFT_IMPORT_KINDS: [&str; 8] = ["typealias", "struct", "class", "enum", "protocol", "let", "var", "func"];
+
+/// Renames a Swift package and its modules, where the package keeps the name it was given and its
+/// targets are named after it in PascalCase: the package "old-name" has the targets OldName and
+/// OldNameTests in Sources/OldName and Tests/OldNameTests. The string literals of Package.swift
+/// naming the package, a target or its path, and the modules of `import` statements, like
+/// `@testable import OldNameCore`, are renamed to the new package and modules.
+pub struct SwiftPackages {
+    old_package: String,
+    new_package: String,
+    old_module: String,
+    new_module: String,
+}
+
+impl SwiftPackages {
+    pub(crate) fn new(old_name: &NormalizedName, new_name: &NormalizedName) -> Self {
+        let module = |name: &NormalizedName| CaseInfo { separator: None, part_type: CaseType::Capitalise }.convert(name.clone());
+        Self {
+            old_package: old_name.original.clone(),
+            new_package: new_name.original.clone(),
+            old_module: module(old_name),
+            new_module: module(new_name),
+        }
+    }
+
+    // The new name of a module that is the old module or starts with it, like OldNameTests
+    fn module(&self, name: &str) -> Option<String> {
+        let rest = name.strip_prefix(&self.old_module)?;
+        (rest.is_empty() || rest.starts_with(char::is_uppercase)).then(|| format!("{}{rest}", self.new_module))
+    }
+}
+
+impl Replacer for SwiftPackages {
+    fn name(&self) -> &str {
+        "swift packages"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        let name = file.path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        // Package@swift-5.9.swift is the manifest for that version of Swift
+        let is_manifest = name == "Package.swift" || (name.starts_with("Package@swift") && name.ends_with(".swift"));
+        if !name.ends_with(".swift") || (self.old_package == self.new_package && self.old_module == self.new_module) {
+            return Ok(Edits::Replaced { text: file.text.to_string(), substitutions: vec![] });
+        }
+        let mut text = String::with_capacity(file.text.len());
+        let mut packages = 0;
+        let mut modules = 0;
+        let mut import = false;
+        for span in syntax::spans(file.text, Language::CLike) {
+            let token = &file.text[span.range];
+            match span.node {
+                // Names and paths in the manifest, like "old-name", "OldNameTests" or "Sources/OldName"
+                Node::String if is_manifest && token.len() >= 2 => {
+                    let mut segments = vec![];
+                    for segment in token[1..token.len() - 1].split('/') {
+                        if segment == self.old_package {
+                            segments.push(self.new_package.clone());
+                            packages += 1;
+                        } else if let Some(module) = self.module(segment) {
+                            segments.push(module);
+                            modules += 1;
+                        } else {
+                            segments.push(segment.to_string());
+                        }
+                    }
+                    text.push_str(&format!("{0}{1}{0}", &token[..1], segments.join("/")));
+                }
+                Node::Identifier if import && SWIFT_IMPORT_KINDS.contains(&token) => text.push_str(token),
+                Node::Identifier if import => {
+                    match self.module(token) {
+                        Some(module) => {
+                            text.push_str(&module);
+                            modules += 1;
+                        }
+                        None => text.push_str(token),
+                    }
+                    import = false;
+                }
+                Node::Identifier => {
+                    import = token == "import";
+                    text.push_str(token);
+                }
+                Node::Other if import && token.trim().is_empty() => text.push_str(token),
+                _ => {
+                    import = false;
+                    text.push_str(token);
+                }
+            }
+        }
+        let substitutions = [(&self.old_package, &self.new_package, packages), (&self.old_module, &self.new_module, modules)]
+            .into_iter()
+            .filter(|(_, _, count)| *count > 0)
+            .map(|(from, to, count)| Substitution { from: from.clone(), to: to.clone(), count })
+            .collect();
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+/// Renames the namespaces of a C# project, named after the project in PascalCase, in the
+/// `namespace` declarations, block or file-scoped, and the `using` directives of C# sources and of
+/// Razor pages, like `namespace OldName.Core;` and `using static Acme.OldName.Helpers;`. Only whole
+/// segments of the dotted name are renamed, so `using OldNameTools;` is left alone.
+pub struct CSharpNamespaces {
+    old_namespace: String,
+    new_namespace: String,
+}
+
+impl CSharpNamespaces {
+    pub(crate) fn new(old_name: &NormalizedName, new_name: &NormalizedName) -> Self {
+        let namespace = |name: &NormalizedName| CaseInfo { separator: None, part_type: CaseType::Capitalise }.convert(name.clone());
+        Self { old_namespace: namespace(old_name), new_namespace: namespace(new_name) }
+    }
+}
+
+impl Replacer for CSharpNamespaces {
+    fn name(&self) -> &str {
+        "csharp namespaces"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        let extension = file.path.extension().map(|extension| extension.to_string_lossy().to_lowercase()).unwrap_or_default();
+        if !matches!(extension.as_str(), "cs" | "cshtml" | "razor") || self.old_namespace == self.new_namespace {
+            return Ok(Edits::Replaced { text: file.text.to_string(), substitutions: vec![] });
+        }
+        let mut text = String::with_capacity(file.text.len());
+        let mut count = 0;
+        // Inside the name of a namespace declaration or using directive, which ends at the first
+        // ";", "{" or line break, or at the "(" of a using statement
+        let mut directive = false;
+        for span in syntax::spans(file.text, Language::CLike) {
+            let token = &file.text[span.range];
+            match span.node {
+                Node::Identifier if directive && token == self.old_namespace => {
+                    text.push_str(&self.new_namespace);
+                    count += 1;
+                }
+                Node::Identifier if !directive => {
+                    directive = matches!(token, "namespace" | "using");
+                    text.push_str(token);
+                }
+                Node::Other if directive && token.contains([';', '{', '\n', '(']) => {
+                    directive = false;
+                    text.push_str(token);
+                }
+                Node::String => {
+                    directive = false;
+                    text.push_str(token);
+                }
+                _ => text.push_str(token),
+            }
+        }
+        let substitutions = (count > 0)
+            .then(|| Substitution { from: self.old_namespace.clone(), to: self.new_namespace.clone(), count })
+            .into_iter()
+            .collect();
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+/// Renames a Homebrew formula, whose class is named after the formula by Homebrew's own rules: the
+/// formula "old-name" is the class OldName, with the rest of every word lowercased, a "+" turned into
+/// "x" and the "@" of a versioned formula into "AT", so "old-name@2" is OldNameAT2. The class of
+/// `class OldName < Formula` is renamed to the class of the new formula, which the case variants
+/// don't always spell the way Homebrew expects, e.g. with acronyms.
+pub struct HomebrewFormulae {
+    old_class: String,
+    new_class: String,
+}
+
+impl HomebrewFormulae {
+    pub(crate) fn new(old_name: &NormalizedName, new_name: &NormalizedName) -> Self {
+        Self { old_class: formula_class(&formula_name(old_name)), new_class: formula_class(&formula_name(new_name)) }
+    }
+}
+
+// The name of the formula, for a name like "old-name" or "Old Name"
+fn formula_name(name: &NormalizedName) -> String {
+    let valid = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.' | '+' | '@');
+    if !name.original.is_empty() && name.original.chars().all(valid) {
+        name.original.clone()
+    } else {
+        CaseInfo { separator: Some('-'), part_type: CaseType::LowerCase }.convert(name.clone())
+    }
+}
+
+// The class of a formula, like Homebrew's Formulary.class_s: "old-name@2" is OldNameAT2
+fn formula_class(formula: &str) -> String {
+    let mut class = String::with_capacity(formula.len());
+    let mut chars = formula.chars().peekable();
+    let mut first = true;
+    while let Some(c) = chars.next() {
+        if first {
+            class.extend(c.to_uppercase());
+            first = false;
+        } else if matches!(c, '-' | '_' | '.' | ' ') && chars.peek().is_some_and(char::is_ascii_alphanumeric) {
+            class.extend(chars.next().into_iter().flat_map(char::to_uppercase));
+        } else if c == '+' {
+            class.push('x');
+        } else if c == '@' && class.chars().next_back().is_some() && chars.peek().is_some_and(char::is_ascii_digit) {
+            class.push_str("AT");
+        } else {
+            class.extend(c.to_lowercase());
+        }
+    }
+    class
+}
+
+impl Replacer for HomebrewFormulae {
+    fn name(&self) -> &str {
+        "homebrew formulae"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        let is_ruby = file.path.extension().is_some_and(|extension| extension == "rb");
+        if !is_ruby || self.old_class == self.new_class {
+            return Ok(Edits::Replaced { text: file.text.to_string(), substitutions: vec![] });
+        }
+        // The class of the formula or of a version of it, like OldNameAT2, where it is declared
+        let (text, count) = replace_words(
+            file.text,
+            &self.old_class,
+            &self.new_class,
+            |before| before.strip_suffix(' ').is_some_and(|before| before.trim_end_matches(' ').ends_with("class")),
+            |after| {
+                let after = after.strip_prefix("AT").map_or(after, |version| version.trim_start_matches(|c: char| c.is_ascii_alphanumeric()));
+                after.trim_start().starts_with('<')
+            },
+        );
+        let substitutions =
+            if count > 0 { vec![Substitution { from: self.old_class.clone(), to: self.new_class.clone(), count }] } else { vec![] };
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+/// Renames the text domain of a WordPress plugin or theme where it is used as one, also when it isn't
+/// one of the case variants, like "myplug": the quoted arguments of the translation functions like
+/// "__( 'Hello', 'myplug' )" and of load_plugin_textdomain, which are strings of exactly the text
+/// domain, the "textdomain" of block.json and the Text Domain header.
+pub struct TextDomains<'a> {
+    old_domain: &'a str,
+    new_domain: &'a str,
+}
+
+impl<'a> TextDomains<'a> {
+    pub(crate) fn new(old_domain: &'a str, new_domain: &'a str) -> Self {
+        Self { old_domain, new_domain }
+    }
+}
+
+impl Replacer for TextDomains<'_> {
+    fn name(&self) -> &str {
+        "text domains"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        let extension = file.path.extension().map(|extension| extension.to_string_lossy().to_lowercase()).unwrap_or_default();
+        if !matches!(extension.as_str(), "php" | "js" | "jsx" | "mjs" | "ts" | "tsx" | "json" | "css") {
+            return Ok(Edits::Replaced { text: file.text.to_string(), substitutions: vec![] });
+        }
+        let mut text = file.text.to_string();
+        let mut count = 0;
+        for quote in ['\'', '"'] {
+            let (quoted, found) = replace_words(&text, &format!("{quote}{}{quote}", self.old_domain), &format!("{quote}{}{quote}", self.new_domain), |_| true, |_| true);
+            text = quoted;
+            count += found;
+        }
+        let (text, found) = replace_words(
+            &text,
+            self.old_domain,
+            self.new_domain,
+            |before| before.trim_end_matches([' ', '\t']).ends_with("Text Domain:"),
+            |after| {
+                let after = after.trim_start_matches([' ', '\t']);
+                after.is_empty() || after.starts_with(['\r', '\n', '*'])
+            },
+        );
+        count += found;
+        let substitutions =
+            if count > 0 { vec![Substitution { from: self.old_domain.to_string(), to: self.new_domain.to_string(), count }] } else { vec![] };
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+/// Renames an npm package whose scope changes, e.g. "@acme/old-lib" to "@globex/new-lib", where it is
+/// quoted as a package name or the start of a path into the package, like the dependencies in
+/// package.json, "require('@acme/old-lib/lib')" or "import x from '@acme/old-lib'". Other packages
+/// that start with the name, like "@acme/old-lib-utils", are left to the case variants.
+pub struct NpmPackage<'a> {
+    old_package: &'a str,
+    new_package: &'a str,
+}
+
+impl<'a> NpmPackage<'a> {
+    pub(crate) fn new(old_package: &'a str, new_package: &'a str) -> Self {
+        Self { old_package, new_package }
+    }
+
+    pub(crate) fn rename(&self, text: &str) -> (String, Vec<Substitution>) {
+        let mut out = String::with_capacity(text.len());
+        let mut copied_until = 0;
+        let mut count = 0;
+        for (start, _) in text.match_indices(self.old_package) {
+            let end = start + self.old_package.len();
+            let quoted = text[..start].ends_with(['"', '\'', '`']) && text[end..].starts_with(['"', '\'', '`', '/']);
+            if quoted && start >= copied_until {
+                out.push_str(&text[copied_until..start]);
+                out.push_str(self.new_package);
+                copied_until = end;
+                count += 1;
+            }
+        }
+        out.push_str(&text[copied_until..]);
+        let substitutions = if count > 0 {
+            vec![Substitution { from: self.old_package.to_string(), to: self.new_package.to_string(), count }]
+        } else {
+            vec![]
+        };
+        (out, substitutions)
+    }
+}
+
+impl Replacer for NpmPackage<'_> {
+    fn name(&self) -> &str {
+        "npm package"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        let (text, substitutions) = self.rename(file.text);
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+/// Replaces a domain, also in email addresses, URLs and subdomains like "api.old.example", but not
+/// inside other domains like "gold.example" or "old.example.org". Domains are case-insensitive.
+pub struct Domains<'a> {
+    old_domain: &'a str,
+    new_domain: &'a str,
+}
+
+impl<'a> Domains<'a> {
+    pub(crate) fn new(old_domain: &'a str, new_domain: &'a str) -> Self {
+        Self { old_domain, new_domain }
+    }
+}
+
+impl Replacer for Domains<'_> {
+    fn name(&self) -> &str {
+        "domains"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        // Lower casing ASCII keeps the byte offsets
+        let lower = file.text.to_ascii_lowercase();
+        let old_domain = self.old_domain.to_ascii_lowercase();
+        let is_label = |byte: u8| byte.is_ascii_alphanumeric() || byte == b'-';
+        let bytes = lower.as_bytes();
+
+        let mut text = String::with_capacity(file.text.len());
+        let mut count = 0;
+        let mut copied_until = 0;
+        for (start, _) in lower.match_indices(&old_domain) {
+            let end = start + old_domain.len();
+            let inside_label = start > 0 && is_label(bytes[start - 1]);
+            let longer = bytes.get(end).is_some_and(|&byte| is_label(byte))
+                || (bytes.get(end) == Some(&b'.') && bytes.get(end + 1).is_some_and(|&byte| is_label(byte)));
+            if inside_label || longer {
+                continue;
+            }
+            text.push_str(&file.text[copied_until..start]);
+            text.push_str(self.new_domain);
+            count += 1;
+            copied_until = end;
+        }
+        text.push_str(&file.text[copied_until..]);
+        let substitutions = if count > 0 {
+            vec![Substitution { from: self.old_domain.to_string(), to: self.new_domain.to_string(), count }]
+        } else {
+            vec![]
+        };
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+/// Updates the copyright holder, and optionally the year range, in license headers
+pub struct Copyright<'a> {
+    old_holder: &'a str,
+    new_holder: &'a str,
+    year: Option<u32>,
+}
+
+impl<'a> Copyright<'a> {
+    pub(crate) fn new(old_holder: &'a str, new_holder: &'a str, year: Option<u32>) -> Self {
+        Self { old_holder, new_holder, year }
+    }
+}
+
+impl Replacer for Copyright<'_> {
+    fn name(&self) -> &str {
+        "copyright"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        let (text, substitutions) = copyright::update(file.path, file.text, self.old_holder, self.new_holder, self.year);
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+/// Replaces the UUIDs in the files matching glob patterns with fresh ones, consistently across files,
+/// and the GUIDs identifying projects in Visual Studio solutions and projects
+pub struct Uuids<'a> {
+    patterns: &'a [String],
+    /// The lower case GUIDs of the projects, when they are regenerated
+    project_guids: Option<&'a HashSet<String>>,
+    regenerator: &'a mut Regenerator,
+}
+
+impl<'a> Uuids<'a> {
+    pub(crate) fn new(patterns: &'a [String], regenerator: &'a mut Regenerator) -> Self {
+        Self { patterns, project_guids: None, regenerator }
+    }
+
+    pub(crate) fn with_project_guids(mut self, project_guids: Option<&'a HashSet<String>>) -> Self {
+        self.project_guids = project_guids;
+        self
+    }
+}
+
+impl Replacer for Uuids<'_> {
+    fn name(&self) -> &str {
+        "uuids"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        let (text, substitutions) = match self.project_guids {
+            _ if self.patterns.iter().any(|pattern| glob::matches(pattern, file.path)) => self.regenerator.replace(file.text),
+            Some(guids) if solution::is_solution_file(file.path) => {
+                self.regenerator.replace_matching(file.text, |uuid| guids.contains(&uuid.to_ascii_lowercase()))
+            }
+            _ => (file.text.to_string(), vec![]),
+        };
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+// A plugin sees the original content and contributes replacements that are made in the text
+impl Replacer for Plugin {
+    fn name(&self) -> &str {
+        self.command()
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        let response = self.process(file.path, file.original, file.old_name, file.new_name)?;
+        if response.veto {
+            return Ok(Edits::Veto);
+        }
+        let mut text = file.text.to_string();
+        let mut substitutions = vec![];
+        for (from, to) in response.replacements {
+            let count = text.matches(&from).count();
+            if count > 0 {
+                text = text.replace(&from, &to);
+                substitutions.push(Substitution { from, to, count });
+            }
+        }
+        Ok(Edits::Replaced { text, substitutions })
+    }
+}
+
+// A replacer shared with the application that registered it, so it can be used for several runs
+impl<R: Replacer + ?Sized> Replacer for Arc<Mutex<R>> {
+    fn name(&self) -> &str {
+        // The name can't be borrowed from behind the lock
+        "custom replacer"
+    }
+
+    fn replace(&mut self, file: &File) -> Result<Edits, String> {
+        self.lock().map_err(|_| "A previous replacement panicked".to_string())?.replace(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CaseInfo;
+
+    #[test]
+    fn test_case_variants() {
+        let (old_name, new_name) = (CaseInfo::detect("my-project").1, CaseInfo::detect("new-project").1);
+        let file = File {
+            path: Path::new("a.rs"),
+            original: "struct MyProject;",
+            text: "struct MyProject;",
+            old_name: "my-project",
+            new_name: "new-project",
+        };
+        let edits = CaseVariants::new(&old_name, &new_name).replace(&file).unwrap();
+        let substitution = Substitution { from: "MyProject".to_string(), to: "NewProject".to_string(), count: 1 };
+        assert_eq!(edits, Edits::Replaced { text: "struct NewProject;".to_string(), substitutions: vec![substitution] });
+    }
+
+    #[test]
+    fn test_rust_crates() {
+        let (old_name, new_name) = (CaseInfo::detect("my-project_rs").1, CaseInfo::detect("new-project").1);
+        let text = "extern crate my_project_rs;\nuse my_project_rs::{a, b};\nfn f() { my_project_rs :: c(); let my_project_rs = 1; }\n";
+        let file = File { path: Path::new("src/lib.rs"), original: text, text, old_name: "", new_name: "" };
+        let edits = RustCrates::new(&old_name, &new_name).replace(&file).unwrap();
+        let substitution = Substitution { from: "my_project_rs".to_string(), to: "new_project".to_string(), count: 3 };
+        let text = "extern crate new_project;\nuse new_project::{a, b};\nfn f() { new_project :: c(); let my_project_rs = 1; }\n";
+        assert_eq!(edits, Edits::Replaced { text: text.to_string(), substitutions: vec![substitution] });
+
+        let (old_name, new_name) = (CaseInfo::detect("my-project").1, CaseInfo::detect("new-project").1);
+        let text = "#[cfg(feature = \"my-project-tls\")]\nfn f() {\n    let level = env!(\"MY_PROJECT_LOG\");\n    let path = std::env::var(\"MY_PROJECT_HOME\");\n    \
+                    let s = \"my-project\";\n}\n";
+        let file = File { path: Path::new("src/main.rs"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, substitutions } = RustCrates::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        let expected = "#[cfg(feature = \"new-project-tls\")]\nfn f() {\n    let level = env!(\"NEW_PROJECT_LOG\");\n    let path = std::env::var(\"NEW_PROJECT_HOME\");\n    \
+                        let s = \"my-project\";\n}\n";
+        assert_eq!(text, expected);
+        assert_eq!(substitutions.iter().map(|s| s.count).sum::<usize>(), 3);
+
+        // Macro bodies, use groups and attributes are parsed, and a file that isn't Rust is left alone
+        let text = "#!/usr/bin/env run-cargo-script\nuse {my_project::a, b};\n#[cfg_attr(feature = \"my-project-serde\", derive(Debug))]\n\
+                    struct S;\nfn g() {\n    println!(\"{}\", my_project::name());\n    assert!(cfg!(feature = \"my-project-tls\"));\n}\n";
+        let file = File { path: Path::new("src/main.rs"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, .. } = RustCrates::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        let expected = "#!/usr/bin/env run-cargo-script\nuse {new_project::a, b};\n#[cfg_attr(feature = \"new-project-serde\", derive(Debug))]\n\
+                        struct S;\nfn g() {\n    println!(\"{}\", new_project::name());\n    assert!(cfg!(feature = \"new-project-tls\"));\n}\n";
+        assert_eq!(text, expected);
+        let text = "use {{crate_name}}::my_project::a;\n";
+        let file = File { path: Path::new("template/main.rs"), original: text, text, old_name: "", new_name: "" };
+        assert_eq!(RustCrates::new(&old_name, &new_name).replace(&file).unwrap(), Edits::Replaced { text: text.to_string(), substitutions: vec![] });
+
+        let text = "[package]\nname = \"my-project\"\ndescription = \"my-project\"\n\n[features]\nmy-project-tls = [\"dep:my-project-tls-sys\"]\n\n\
+                    [[bin]]\nname = \"my-project-cli\"\n";
+        let file = File { path: Path::new("Cargo.toml"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, .. } = RustCrates::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        let expected = "[package]\nname = \"new-project\"\ndescription = \"my-project\"\n\n[features]\nnew-project-tls = [\"dep:new-project-tls-sys\"]\n\n\
+                        [[bin]]\nname = \"new-project-cli\"\n";
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn test_python_packages() {
+        let (old_name, new_name) = (CaseInfo::detect("my-project_py").1, CaseInfo::detect("New Project").1);
+        let text = "[project]\nname = \"My.Project-Py\"\ndescription = \"my-project_py tool\"\n\n[project.scripts]\n\
+                    my-project_py = \"my_project_py.cli:main\"\n\n[tool.hatch.build.targets.wheel]\npackages = [\"src/my_project_py\"]\n";
+        let file = File { path: Path::new("pyproject.toml"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, .. } = PythonPackages::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        let expected = "[project]\nname = \"new-project\"\ndescription = \"my-project_py tool\"\n\n[project.scripts]\n\
+                        new-project = \"new_project.cli:main\"\n\n[tool.hatch.build.targets.wheel]\npackages = [\"src/new_project\"]\n";
+        assert_eq!(text, expected);
+
+        let text = "import my_project_py\nfrom my_project_py.cli import main\nprint(my_project_py.__version__, version(\"my-project-py\"), \"my_project_py\")\n";
+        let file = File { path: Path::new("src/app.py"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, substitutions } = PythonPackages::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        let expected = "import new_project\nfrom new_project.cli import main\nprint(new_project.__version__, version(\"new-project\"), \"my_project_py\")\n";
+        assert_eq!(text, expected);
+        assert_eq!(substitutions.iter().map(|s| s.count).sum::<usize>(), 4);
+    }
+
+    #[test]
+    fn test_ruby_gems() {
+        let (old_name, new_name) = (CaseInfo::detect("old-gem").1, CaseInfo::detect("shiny_gem").1);
+        let text = "require \"old/gem\"\nrequire 'old/gem/version'\nrequire \"old/gemstone\"\n\
+                    module Old::Gem\n  VERSION = Old::Gem::VERSION\n  Old::Gemstone\nend\n";
+        let file = File { path: Path::new("lib/old/gem.rb"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, substitutions } = RubyGems::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        let expected = "require \"shiny_gem\"\nrequire 'shiny_gem/version'\nrequire \"old/gemstone\"\n\
+                        module ShinyGem\n  VERSION = ShinyGem::VERSION\n  Old::Gemstone\nend\n";
+        assert_eq!(text, expected);
+        assert_eq!(substitutions.iter().map(|s| s.count).sum::<usize>(), 4);
+
+        let text = "Gem::Specification.new do |spec|\n  spec.name = \"old-gem\"\n  spec.summary = \"old-gem\"\nend\n";
+        let file = File { path: Path::new("old-gem.gemspec"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, .. } = RubyGems::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        assert_eq!(text, "Gem::Specification.new do |spec|\n  spec.name = \"shiny_gem\"\n  spec.summary = \"old-gem\"\nend\n");
+        assert_eq!(gem_constant("my_gem-rails"), "MyGem::Rails");
+
+        let (old_name, new_name) = (CaseInfo::detect("été-gem").1, CaseInfo::detect("new-gem").1);
+        let text = "require \"été_gem\"\nmodule ÉtéGem\nend\n";
+        let file = File { path: Path::new("lib/x.rb"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, .. } = RubyGems::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        assert_eq!(text, "require \"new/gem\"\nmodule New::Gem\nend\n");
+    }
+
+    #[test]
+    fn test_elixir_apps() {
+        let (old_name, new_name) = (CaseInfo::detect("old-app").1, CaseInfo::detect("Shiny App").1);
+        let text = "defmodule OldApp.MixProject do\n  def project, do: [app: :old_app]\n  \
+                    def application, do: [mod: {OldApp.Application, []}]\nend\n\
+                    config :old_app, OldAppWeb.Endpoint, pubsub_server: :old_app_web\n\
+                    OldApps.run(:old_apps, Other.OldApp, %{old_app: 1}, x::old_app)\n";
+        let file = File { path: Path::new("mix.exs"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, substitutions } = ElixirApps::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        let expected = "defmodule ShinyApp.MixProject do\n  def project, do: [app: :shiny_app]\n  \
+                        def application, do: [mod: {ShinyApp.Application, []}]\nend\n\
+                        config :shiny_app, ShinyAppWeb.Endpoint, pubsub_server: :shiny_app_web\n\
+                        OldApps.run(:old_apps, Other.OldApp, %{old_app: 1}, x::old_app)\n";
+        assert_eq!(text, expected);
+        assert_eq!(substitutions.iter().map(|s| s.count).sum::<usize>(), 6);
+        assert_eq!(elixir_alias("my_app2"), "MyApp2");
+
+        let (old_name, new_name) = (CaseInfo::detect("été-app").1, CaseInfo::detect("new-app").1);
+        let text = "defmodule ÉtéApp do\n  def project, do: [app: :été_app]\nend\n";
+        let file = File { path: Path::new("mix.exs"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, .. } = ElixirApps::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        assert_eq!(text, "defmodule NewApp do\n  def project, do: [app: :new_app]\nend\n");
+    }
+
+    #[test]
+    fn test_swift_packages() {
+        let (old_name, new_name) = (CaseInfo::detect("old-kit").1, CaseInfo::detect("shiny-kit").1);
+        let text = "let package = Package(\n    name: \"old-kit\",\n    products: [.library(name: \"OldKit\", targets: [\"OldKit\"])],\n    \
+                    targets: [\n        .target(name: \"OldKit\", path: \"Sources/OldKit\"),\n        \
+                    .testTarget(name: \"OldKitTests\", dependencies: [\"OldKit\", \"OldKitchen\"]),\n    ]\n)\n";
+        let file = File { path: Path::new("Package.swift"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, substitutions } = SwiftPackages::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        let expected = "let package = Package(\n    name: \"shiny-kit\",\n    products: [.library(name: \"ShinyKit\", targets: [\"ShinyKit\"])],\n    \
+                        targets: [\n        .target(name: \"ShinyKit\", path: \"Sources/ShinyKit\"),\n        \
+                        .testTarget(name: \"ShinyKitTests\", dependencies: [\"ShinyKit\", \"OldKitchen\"]),\n    ]\n)\n";
+        assert_eq!(text, expected);
+        assert_eq!(substitutions.iter().map(|s| s.count).sum::<usize>(), 7);
+
+        let text = "import Foundation\n@testable import OldKitCore\nimport struct OldKit.Widget\nlet name = \"OldKit\" // import OldKit\n";
+        let file = File { path: Path::new("Tests/OldKitTests/WidgetTests.swift"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, .. } = SwiftPackages::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        assert_eq!(text, "import Foundation\n@testable import ShinyKitCore\nimport struct ShinyKit.Widget\nlet name = \"OldKit\" // import OldKit\n");
+    }
+
+    #[test]
+    fn test_csharp_namespaces() {
+        let (old_name, new_name) = (CaseInfo::detect("old-app").1, CaseInfo::detect("shiny-app").1);
+        let text = "global using OldApp.Models;\nusing static Acme.OldApp.Helpers;\nusing Tools = OldAppTools.Tools;\n\
+                    namespace OldApp.Core;\n\nclass C { void M() { using (OldApp.Open()) { } } }\n";
+        let file = File { path: Path::new("src/C.cs"), original: text, text, old_name: "", new_name: "" };
+        let edits = CSharpNamespaces::new(&old_name, &new_name).replace(&file).unwrap();
+        let expected = "global using ShinyApp.Models;\nusing static Acme.ShinyApp.Helpers;\nusing Tools = OldAppTools.Tools;\n\
+                        namespace ShinyApp.Core;\n\nclass C { void M() { using (OldApp.Open()) { } } }\n";
+        let substitution = Substitution { from: "OldApp".to_string(), to: "ShinyApp".to_string(), count: 3 };
+        assert_eq!(edits, Edits::Replaced { text: expected.to_string(), substitutions: vec![substitution] });
+
+        let text = "namespace OldApp\n{\n    // namespace OldApp\n}\n";
+        let file = File { path: Path::new("src/Block.cs"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, .. } = CSharpNamespaces::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        assert_eq!(text, "namespace ShinyApp\n{\n    // namespace OldApp\n}\n");
+    }
+
+    #[test]
+    fn test_text_domains() {
+        let text = "<?php
+/**
+ * Plugin Name: My Plugin
+ * Text Domain: myplug
+ */
+load_plugin_textdomain( 'myplug', false, 'myplug/languages' );
+echo esc_html__( \"Settings\", \"myplug\" );
+function myplug_init() {}
+";
+        let file = File { path: Path::new("my-plugin.php"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, substitutions } = TextDomains::new("myplug", "new-plugin").replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        assert_eq!(text, "<?php
+/**
+ * Plugin Name: My Plugin
+ * Text Domain: new-plugin
+ */
+load_plugin_textdomain( 'new-plugin', false, 'myplug/languages' );
+echo esc_html__( \"Settings\", \"new-plugin\" );
+function myplug_init() {}
+");
+        assert_eq!(substitutions.iter().map(|s| s.count).sum::<usize>(), 3);
+    }
+
+    #[test]
+    fn test_homebrew_formulae() {
+        let (old_name, new_name) = (CaseInfo::detect("old-tool").1, CaseInfo::detect("shiny-tool.js").1);
+        let text = "class OldToolAT2 < Formula
+  desc \"OldTool\"
+end
+class OldTool  < Formula
+end
+class OldTools < Formula
+end
+";
+        let file = File { path: Path::new("Formula/old-tool@2.rb"), original: text, text, old_name: "", new_name: "" };
+        let Edits::Replaced { text, substitutions } = HomebrewFormulae::new(&old_name, &new_name).replace(&file).unwrap() else {
+            panic!("vetoed");
+        };
+        assert_eq!(text, "class ShinyToolJsAT2 < Formula
+  desc \"OldTool\"
+end
+class ShinyToolJs  < Formula
+end
+class OldTools < Formula
+end
+");
+        assert_eq!(substitutions.iter().map(|s| s.count).sum::<usize>(), 2);
+        assert_eq!(formula_class("my-API_tool+@1.2"), "MyApiToolxAT12");
+    }
+
+    #[test]
+    fn test_npm_package() {
+        let text = "import a from '@acme/old-lib';\nrequire(\"@acme/old-lib/lib\");\n\"@acme/old-lib-utils\" @acme/old-lib\n";
+        let file = File { path: Path::new("index.js"), original: text, text, old_name: "", new_name: "" };
+        let edits = NpmPackage::new("@acme/old-lib", "@globex/new-lib").replace(&file).unwrap();
+        let substitution = Substitution { from: "@acme/old-lib".to_string(), to: "@globex/new-lib".to_string(), count: 2 };
+        let text = "import a from '@globex/new-lib';\nrequire(\"@globex/new-lib/lib\");\n\"@acme/old-lib-utils\" @acme/old-lib\n";
+        assert_eq!(edits, Edits::Replaced { text: text.to_string(), substitutions: vec![substitution] });
+    }
+
+    #[test]
+    fn test_domains() {
+        let text = "Ada <ada@Acme.com>, https://api.acme.com/v1 acme.com. gold-acme.com acme.com.au acme.community";
+        let file = File { path: Path::new("README.md"), original: text, text, old_name: "", new_name: "" };
+        let edits = Domains::new("acme.com", "globex.io").replace(&file).unwrap();
+        let substitution = Substitution { from: "acme.com".to_string(), to: "globex.io".to_string(), count: 3 };
+        let text = "Ada <ada@globex.io>, https://api.globex.io/v1 globex.io. gold-acme.com acme.com.au acme.community";
+        assert_eq!(edits, Edits::Replaced { text: text.to_string(), substitutions: vec![substitution] });
+    }
+
+    #[test]
+    fn test_case_variants_nodes() {
+        let (old_name, new_name) = (CaseInfo::detect("my-project").1, CaseInfo::detect("new-project").1);
+        let text = "// my_project\nlet my_project = \"my_project\"; let n = 0x1my_project;";
+        let file = File { path: Path::new("a.rs"), original: text, text, old_name: "my-project", new_name: "new-project" };
+        let edits = CaseVariants::new(&old_name, &new_name).with_nodes(Some(&[Node::Identifier, Node::Comment])).replace(&file).unwrap();
+        let substitution = Substitution { from: "my_project".to_string(), to: "new_project".to_string(), count: 2 };
+        let text = "// new_project\nlet new_project = \"my_project\"; let n = 0x1my_project;".to_string();
+        assert_eq!(edits, Edits::Replaced { text, substitutions: vec![substitution] });
+    }
+}
@@ -0,0 +1,128 @@
+use std::path::Path;
+
+use crate::plan::Plan;
+use crate::{Context, Operation};
+
+// Longest Markdown table, forges limit the size of pull request descriptions
+const MARKDOWN_ROWS: usize = 200;
+
+// Format of the report of the operations of a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    // One row per operation, for reviewing a plan in a spreadsheet
+    Csv,
+    // The plan with the options and the hashes of the files, which "apply" executes after review
+    Json,
+}
+
+pub fn render(format: Format, ctx: &Context) -> String {
+    match format {
+        Format::Csv => csv(&ctx.operations),
+        Format::Json => format!("{}\n", Plan::from_context(ctx).to_json()),
+    }
+}
+
+fn csv(operations: &[Operation]) -> String {
+    let mut out = String::from("old_path,new_path,action,substitutions,note\r\n");
+    for operation in operations {
+        let row = [
+            operation.input.to_string_lossy().to_string(),
+            operation.output.to_string_lossy().to_string(),
+            operation.action.name().to_string(),
+            operation.substitutions.to_string(),
+            operation.note.clone().unwrap_or_default(),
+        ];
+        out.push_str(&row.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+// A Markdown summary of the renamed paths and the files whose content changed, e.g. for the
+// description of a pull request. Paths are relative to the parent directory of the project.
+pub fn markdown(ctx: &Context) -> String {
+    let relative = |path: &Path, root: &Path| {
+        let root = root.parent().unwrap_or(Path::new(""));
+        path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/")
+    };
+    let rows = ctx
+        .operations
+        .iter()
+        .filter(|operation| operation.input.file_name() != operation.output.file_name() || operation.substitutions > 0)
+        .map(|operation| (relative(&operation.input, &ctx.input), relative(&operation.output, &ctx.output), operation.substitutions))
+        .collect::<Vec<_>>();
+    let mut out = format!(
+        "Renamed {} files and directories and made {} substitutions.\n",
+        ctx.stats.renamed, ctx.stats.substitutions
+    );
+    if !rows.is_empty() {
+        out.push_str("\n| Old path | New path | Substitutions |\n| --- | --- | --- |\n");
+        for (input, output, substitutions) in rows.iter().take(MARKDOWN_ROWS) {
+            out.push_str(&format!("| `{input}` | `{output}` | {substitutions} |\n"));
+        }
+        if rows.len() > MARKDOWN_ROWS {
+            out.push_str(&format!("\n...and {} more.\n", rows.len() - MARKDOWN_ROWS));
+        }
+    }
+    out
+}
+
+// Quote a field that contains a separator, quote or line break (RFC 4180)
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Action;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_csv() {
+        let operations = vec![
+            Operation {
+                action: Action::Write,
+                input: PathBuf::from("my-project/my-project.txt"),
+                output: PathBuf::from("new-project/new-project.txt"),
+                substitutions: 2,
+                note: None,
+            },
+            Operation {
+                action: Action::Copy,
+                input: PathBuf::from("my-project/a, \"b\".png"),
+                output: PathBuf::from("new-project/a, \"b\".png"),
+                substitutions: 0,
+                note: Some("generated file, content unchanged".to_string()),
+            },
+        ];
+        assert_eq!(
+            csv(&operations),
+            "old_path,new_path,action,substitutions,note\r\n\
+             my-project/my-project.txt,new-project/new-project.txt,write,2,\r\n\
+             \"my-project/a, \"\"b\"\".png\",\"new-project/a, \"\"b\"\".png\",copy,0,\"generated file, content unchanged\"\r\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        let out = crate::output::Output::new(crate::output::Verbosity::Quiet, Default::default(), Default::default());
+        let mut ctx = Context::new(Path::new("repo/my-project"), "new-project", out);
+        ctx.record(Action::CreateDirectory, Path::new("repo/my-project"), Path::new("repo/new-project"), 0);
+        ctx.record(Action::Write, Path::new("repo/my-project/my-project.txt"), Path::new("repo/new-project/new-project.txt"), 2);
+        ctx.record(Action::Copy, Path::new("repo/my-project/logo.png"), Path::new("repo/new-project/logo.png"), 0);
+        ctx.stats.renamed = 1;
+        ctx.stats.substitutions = 2;
+        assert_eq!(
+            markdown(&ctx),
+            "Renamed 1 files and directories and made 2 substitutions.\n\n\
+             | Old path | New path | Substitutions |\n| --- | --- | --- |\n\
+             | `my-project` | `new-project` | 0 |\n\
+             | `my-project/my-project.txt` | `new-project/new-project.txt` | 2 |\n"
+        );
+    }
+}
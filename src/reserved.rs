@@ -0,0 +1,60 @@
+// Device names that Windows reserves in every directory, with or without an extension
+// See https://learn.microsoft.com/en-us/windows/win32/fileio/naming-a-file#naming-conventions
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1",
+    "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+// Reason why a file or directory name is unusable on Windows, if it is
+pub fn problem(name: &str) -> Option<&'static str> {
+    let base = name.split('.').next().unwrap_or_default().trim_end_matches(' ');
+    if RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(base)) {
+        Some("is a reserved device name on Windows")
+    } else if name.ends_with('.') || name.ends_with(' ') {
+        Some("ends with a dot or space, which Windows strips")
+    } else {
+        None
+    }
+}
+
+// Make the name usable on Windows by appending an underscore to a reserved device name and
+// replacing trailing dots and spaces with one
+pub fn escape(name: &str) -> String {
+    let trimmed = name.trim_end_matches(['.', ' ']);
+    let mut escaped = if trimmed.len() < name.len() { format!("{trimmed}_") } else { name.to_string() };
+    if problem(&escaped).is_some() {
+        let base_len = escaped.find('.').unwrap_or(escaped.len());
+        escaped.insert(base_len, '_');
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problem() {
+        assert!(problem("CON").is_some());
+        assert!(problem("nul").is_some());
+        assert!(problem("aux.rs").is_some());
+        assert!(problem("com1.tar.gz").is_some());
+        assert!(problem("project.").is_some());
+        assert!(problem("project ").is_some());
+        assert!(problem("console").is_none());
+        assert!(problem("my-aux.rs").is_none());
+        assert!(problem("com10").is_none());
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("CON"), "CON_");
+        assert_eq!(escape("aux.rs"), "aux_.rs");
+        assert_eq!(escape("project. "), "project_");
+        assert_eq!(escape("nul."), "nul_");
+        assert_eq!(escape("project.rs"), "project.rs");
+        for name in ["CON", "aux.rs", "project. ", "nul.", "lpt1 .txt"] {
+            assert_eq!(problem(&escape(name)), None, "{name}");
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::ops::Range;
+use std::path::Path;
+
+// The sections of a spec file that can follow the %changelog
+const SECTIONS: [&str; 16] = [
+    "%package",
+    "%description",
+    "%prep",
+    "%build",
+    "%install",
+    "%check",
+    "%clean",
+    "%files",
+    "%pre",
+    "%post",
+    "%preun",
+    "%postun",
+    "%pretrans",
+    "%posttrans",
+    "%generate_buildrequires",
+    "%conf",
+];
+
+// Whether the file is the spec file of an RPM package
+pub fn is_spec(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "spec")
+}
+
+// The ranges of a spec file the name is renamed in: every line but the Source, Patch and URL tags,
+// which locate the upstream sources, and the entries of the %changelog, the history of the
+// package. So the Name, the subpackages, the %files paths and the systemd units of the scriptlets
+// are renamed together.
+pub fn names(text: &str) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = vec![];
+    let mut changelog = false;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let section = line.split_whitespace().next().unwrap_or_default();
+        if section == "%changelog" {
+            changelog = true;
+        } else if SECTIONS.contains(&section) {
+            changelog = false;
+        }
+        let tag = line.split_once(':').map(|(tag, _)| tag.trim().to_ascii_lowercase()).unwrap_or_default();
+        let source = tag == "url" || ["source", "patch"].iter().any(|prefix| tag.strip_prefix(prefix).is_some_and(|n| n.chars().all(|c| c.is_ascii_digit())));
+        if (changelog && section != "%changelog") || source {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(last) if last.end == start => last.end = offset,
+            _ => ranges.push(start..offset),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        let text = "Name: my-app\nURL: https://github.com/acme/my-app\nSource0: %{url}/archive/my-app-%{version}.tar.gz\n\n\
+                    %post\n%systemd_post my-app.service\n\n%files\n%{_bindir}/my-app\n\n%changelog\n\
+                    * Mon Jan 01 2024 A <a@my-app.org> - 1.0-1\n- First my-app release\n";
+        let kept = names(text).into_iter().fold(String::new(), |kept, range| kept + &text[range]);
+        assert_eq!(kept, "Name: my-app\n\n%post\n%systemd_post my-app.service\n\n%files\n%{_bindir}/my-app\n\n%changelog\n");
+        assert!(is_spec(Path::new("my-app.spec")));
+    }
+}
@@ -0,0 +1,121 @@
+use std::path::{Component, Path};
+
+use crate::json::Value;
+
+// A rule that findings refer to, e.g. a kind of problem found by doctor
+pub struct Rule {
+    pub id: &'static str,
+    pub description: &'static str,
+}
+
+// A single finding of a report
+pub struct Result<'a> {
+    pub rule_id: &'static str,
+    // "error", "warning" or "note"
+    pub level: &'static str,
+    pub message: &'a str,
+    pub path: &'a Path,
+    pub line: Option<usize>,
+}
+
+// A SARIF 2.1.0 log with a single run, as read by GitHub code scanning and IDEs
+pub fn report(rules: &[Rule], results: &[Result]) -> Value {
+    let rules = rules
+        .iter()
+        .map(|rule| {
+            object(vec![
+                ("id", rule.id.into()),
+                ("shortDescription", object(vec![("text", rule.description.into())])),
+            ])
+        })
+        .collect();
+    let results = results.iter().map(result).collect();
+    let driver = object(vec![
+        ("name", env!("CARGO_PKG_NAME").into()),
+        ("version", env!("CARGO_PKG_VERSION").into()),
+        ("rules", Value::Array(rules)),
+    ]);
+    object(vec![
+        ("$schema", "https://json.schemastore.org/sarif-2.1.0.json".into()),
+        ("version", "2.1.0".into()),
+        (
+            "runs",
+            Value::Array(vec![object(vec![
+                ("tool", object(vec![("driver", driver)])),
+                ("results", Value::Array(results)),
+            ])]),
+        ),
+    ])
+}
+
+fn result(result: &Result) -> Value {
+    let mut location = vec![("artifactLocation", object(vec![("uri", uri(result.path).into())]))];
+    if let Some(line) = result.line {
+        location.push(("region", object(vec![("startLine", line.into())])));
+    }
+    object(vec![
+        ("ruleId", result.rule_id.into()),
+        ("level", result.level.into()),
+        ("message", object(vec![("text", result.message.into())])),
+        ("locations", Value::Array(vec![object(vec![("physicalLocation", object(location))])])),
+    ])
+}
+
+// Relative URI of the path from the current directory, which code scanning resolves against the
+// repository root, or the absolute path if it is outside of it
+fn uri(path: &Path) -> String {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|current_dir| absolute.strip_prefix(current_dir).ok().map(Path::to_path_buf))
+        .unwrap_or(absolute);
+    let components = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Prefix(prefix) => Some(prefix.as_os_str().to_string_lossy().to_string()),
+            Component::Normal(name) => Some(percent_encode(&name.to_string_lossy())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if relative.is_absolute() {
+        format!("file:///{}", components.join("/"))
+    } else {
+        components.join("/")
+    }
+}
+
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn object(entries: Vec<(&str, Value)>) -> Value {
+    Value::Object(entries.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let rules = [Rule { id: "leftover_name", description: "The old name is left" }];
+        let results = [Result {
+            rule_id: "leftover_name",
+            level: "error",
+            message: "old-project",
+            path: Path::new("src/my file.rs"),
+            line: Some(3),
+        }];
+        let report = report(&rules, &results).to_string();
+        assert!(report.starts_with(r#"{"$schema":"https://json.schemastore.org/sarif-2.1.0.json","version":"2.1.0","runs":[{"tool":{"driver":{"name":"project-renamer","#));
+        assert!(report.contains(r#""rules":[{"id":"leftover_name","shortDescription":{"text":"The old name is left"}}]"#));
+        assert!(report.ends_with(
+            r#""results":[{"ruleId":"leftover_name","level":"error","message":{"text":"old-project"},"locations":[{"physicalLocation":{"artifactLocation":{"uri":"src/my%20file.rs"},"region":{"startLine":3}}}]}]}]}"#
+        ));
+    }
+}
@@ -0,0 +1,45 @@
+use std::path::Path;
+
+// File names of private keys and credentials
+const NAMES: [&str; 10] =
+    ["id_rsa", "id_dsa", "id_ecdsa", "id_ed25519", ".netrc", "_netrc", ".pgpass", ".htpasswd", "credentials", "secring.gpg"];
+
+// Extensions of key material and keystores
+const EXTENSIONS: [&str; 12] = ["pem", "key", "p8", "p12", "pfx", "jks", "keystore", "bks", "ppk", "kdbx", "gpg", "der"];
+
+// Variants of .env files that are meant to be committed and hold no real secrets
+const ENV_EXAMPLES: [&str; 4] = ["example", "sample", "template", "dist"];
+
+// Whether the file likely holds credentials or key material, whose content must not be rewritten
+// without being asked to: a changed secret breaks logins, and a changed key is corrupted.
+pub fn is_secret(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let name = name.to_ascii_lowercase();
+    if NAMES.contains(&name.as_str()) {
+        return true;
+    }
+    if name == ".env" || name.ends_with(".env") {
+        return true;
+    }
+    if let Some(variant) = name.strip_prefix(".env.") {
+        return !ENV_EXAMPLES.contains(&variant);
+    }
+    path.extension().and_then(|extension| extension.to_str()).is_some_and(|extension| EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_secret() {
+        for path in [".env", "config/.env.production", "prod.env", "certs/server.pem", "tls.KEY", ".ssh/id_ed25519", "release.jks", ".aws/credentials"] {
+            assert!(is_secret(Path::new(path)), "{path}");
+        }
+        for path in [".env.example", ".env.sample", "id_rsa.pub", "src/keys.rs", "environment.ts", "README.md"] {
+            assert!(!is_secret(Path::new(path)), "{path}");
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::SEPARATORS;
+
+// Largest file that is scanned when looking for the parts of a concatenated name
+const MAX_SCANNED_FILE_SIZE: u64 = 1024 * 1024;
+
+// Split a concatenated name into parts using how it is written in the project. Every occurrence of
+// the name with separators or case changes between its letters (e.g. "my_project" or "MyProject"
+// for "myproject") counts as evidence for splitting at those positions. The most common split with
+// the wanted number of parts wins, otherwise the most common split.
+pub fn segment_from_project(root: &Path, name: &str, wanted_parts: usize) -> Option<Vec<String>> {
+    let word = name.to_lowercase().chars().collect::<Vec<_>>();
+    if word.len() < 2 {
+        return None;
+    }
+    let mut counts = HashMap::new();
+    scan(root, &word, &mut counts);
+
+    let best = |breaks: &Vec<usize>| counts.get(breaks).copied().unwrap_or(0);
+    let mut splits = counts.keys().cloned().collect::<Vec<_>>();
+    splits.sort();
+    let preferred = splits
+        .iter()
+        .filter(|breaks| breaks.len() + 1 == wanted_parts)
+        .max_by_key(|breaks| best(breaks));
+    let breaks = preferred.or_else(|| splits.iter().max_by_key(|breaks| best(breaks)))?;
+    Some(split_at(&word, breaks))
+}
+
+fn split_at(word: &[char], breaks: &[usize]) -> Vec<String> {
+    let mut parts = vec![];
+    let mut start = 0;
+    for &end in breaks.iter().chain(std::iter::once(&word.len())) {
+        parts.push(word[start..end].iter().collect());
+        start = end;
+    }
+    parts
+}
+
+fn scan(path: &Path, word: &[char], counts: &mut HashMap<Vec<usize>, usize>) {
+    if let Some(file_name) = path.file_name() {
+        count_occurrences(&file_name.to_string_lossy(), word, counts);
+    }
+    if path.is_dir() {
+        let Ok(entries) = path.read_dir() else {
+            return;
+        };
+        for entry in entries.flatten() {
+            scan(&entry.path(), word, counts);
+        }
+    } else if path.metadata().is_ok_and(|metadata| metadata.len() <= MAX_SCANNED_FILE_SIZE)
+        && let Ok(content) = std::fs::read_to_string(path)
+    {
+        count_occurrences(&content, word, counts);
+    }
+}
+
+// Count the ways the word is split in the text, keyed by the positions of the splits in the word
+fn count_occurrences(text: &str, word: &[char], counts: &mut HashMap<Vec<usize>, usize>) {
+    for (start, c) in text.char_indices() {
+        if c.to_lowercase().eq(std::iter::once(word[0]))
+            && let Some(breaks) = match_breaks(&text[start..], word)
+        {
+            *counts.entry(breaks).or_insert(0) += 1;
+        }
+    }
+}
+
+// Match the word at the start of the text, allowing a single separator or a change from lower to
+// upper case between its letters. Returns the positions of the splits if there were any.
+fn match_breaks(text: &str, word: &[char]) -> Option<Vec<usize>> {
+    let mut breaks = vec![];
+    let mut index = 0;
+    let mut previous: Option<char> = None;
+    for c in text.chars() {
+        if c.to_lowercase().eq(std::iter::once(word[index])) {
+            let case_change = previous.is_some_and(|p| p.is_lowercase()) && c.is_uppercase();
+            if index > 0 && case_change && breaks.last() != Some(&index) {
+                breaks.push(index);
+            }
+            index += 1;
+            if index == word.len() {
+                return if breaks.is_empty() { None } else { Some(breaks) };
+            }
+        } else if index > 0 && SEPARATORS.contains(&c) && breaks.last() != Some(&index) {
+            breaks.push(index);
+        } else {
+            return None;
+        }
+        previous = Some(c);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(name: &str) -> Vec<char> {
+        name.chars().collect()
+    }
+
+    #[test]
+    fn test_match_breaks() {
+        assert_eq!(match_breaks("my_project", &word("myproject")), Some(vec![2]));
+        assert_eq!(match_breaks("MyProject", &word("myproject")), Some(vec![2]));
+        assert_eq!(match_breaks("MY-COOL-PROJECT", &word("mycoolproject")), Some(vec![2, 6]));
+        assert_eq!(match_breaks("myproject", &word("myproject")), None);
+        assert_eq!(match_breaks("my__project", &word("myproject")), None);
+        assert_eq!(match_breaks("my_prefix", &word("myproject")), None);
+    }
+
+    #[test]
+    fn test_segment_from_project() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-segment-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "use mycool_project;\nMyCoolProject::new()\nmy-cool-project").unwrap();
+
+        assert_eq!(
+            segment_from_project(&dir, "mycoolproject", 3),
+            Some(vec!["my".to_string(), "cool".to_string(), "project".to_string()])
+        );
+        assert_eq!(
+            segment_from_project(&dir, "mycoolproject", 2),
+            Some(vec!["mycool".to_string(), "project".to_string()])
+        );
+        assert_eq!(segment_from_project(&dir, "unrelated", 2), None);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,308 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::json::{self, Value};
+use crate::output::{Event, Output, Verbosity};
+use crate::{CaseInfo, Context, find_reserved_names, run, verify};
+
+// Standard JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+// Implementation-defined error code for a rename that failed
+const SERVER_ERROR: i64 = -32000;
+
+// Largest request body that is accepted
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+// How long a client may take to send its request before the connection is dropped
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Serve the JSON-RPC API over HTTP on the address until the process is stopped. Every connection is
+// handled on its own thread. The API has no authentication and renames any path it is given, so
+// only loopback addresses are accepted, which keeps it out of reach of other machines.
+pub fn serve(address: &str, out: Output) -> std::io::Result<()> {
+    let addresses = address.to_socket_addrs()?.collect::<Vec<_>>();
+    if let Some(address) = addresses.iter().find(|address| !address.ip().is_loopback()) {
+        let message = format!("{} is not a loopback address, and the API has no authentication", address.ip());
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, message));
+    }
+    let listener = TcpListener::bind(addresses.as_slice())?;
+    let address = listener.local_addr()?.to_string();
+    out.emit(Event::ServerListening { address: &address });
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                std::thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, out) {
+                        out.error(&format!("Failed to handle connection: {e}"));
+                    }
+                });
+            }
+            Err(e) => out.error(&format!("Failed to accept connection: {e}")),
+        }
+    }
+    Ok(())
+}
+
+// Read a single HTTP request and answer it. Browsers send an Origin with cross-site requests and
+// can't send a JSON content type without a preflight, so a web page can't drive the API.
+fn handle_connection(stream: TcpStream, out: Output) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let method = request_line.split_whitespace().next().unwrap_or_default().to_string();
+
+    let (mut content_length, mut content_type, mut origin) = (0, String::new(), false);
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => content_length = value.trim().parse().unwrap_or(0),
+            "content-type" => content_type = value.trim().to_ascii_lowercase(),
+            "origin" => origin = true,
+            _ => {}
+        }
+    }
+
+    let (status, body) = if method != "POST" {
+        ("405 Method Not Allowed", error_response(Value::Null, INVALID_REQUEST, "Only POST requests are supported"))
+    } else if origin {
+        ("403 Forbidden", error_response(Value::Null, INVALID_REQUEST, "Requests from browsers are not accepted"))
+    } else if content_type.split(';').next().map(str::trim) != Some("application/json") {
+        ("415 Unsupported Media Type", error_response(Value::Null, INVALID_REQUEST, "Expected Content-Type: application/json"))
+    } else if content_length > MAX_BODY_SIZE {
+        ("413 Payload Too Large", error_response(Value::Null, INVALID_REQUEST, "Request body is too large"))
+    } else {
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+        ("200 OK", handle_request(&String::from_utf8_lossy(&body), out))
+    };
+
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+// Answer a JSON-RPC request body with a JSON-RPC response body
+pub fn handle_request(body: &str, out: Output) -> String {
+    let request = match json::parse(body) {
+        Ok(request) => request,
+        Err(e) => return error_response(Value::Null, PARSE_ERROR, &e.to_string()),
+    };
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    if request.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+        return error_response(id, INVALID_REQUEST, "Expected a JSON-RPC 2.0 request");
+    }
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return error_response(id, INVALID_REQUEST, "Missing method");
+    };
+    out.emit(Event::ServerRequest { method });
+
+    let params = request.get("params").cloned().unwrap_or(Value::Object(vec![]));
+    let handler: fn(&Value) -> Result<Value, (i64, String)> = match method {
+        "plan" => |params| rename(params, true),
+        "apply" => |params| rename(params, false),
+        "verify" => verify,
+        _ => return error_response(id, METHOD_NOT_FOUND, &format!("Unknown method: {method}")),
+    };
+    // A bug in the rename answers this request with an error instead of leaving it unanswered
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| handler(&params)))
+        .unwrap_or_else(|_| Err((INTERNAL_ERROR, format!("The {method} request failed unexpectedly"))));
+    match result {
+        Ok(result) => Value::Object(vec![
+            ("jsonrpc".to_string(), "2.0".into()),
+            ("id".to_string(), id),
+            ("result".to_string(), result),
+        ])
+        .to_string(),
+        Err((code, message)) => error_response(id, code, &message),
+    }
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> String {
+    Value::Object(vec![
+        ("jsonrpc".to_string(), "2.0".into()),
+        ("id".to_string(), id),
+        (
+            "error".to_string(),
+            Value::Object(vec![
+                ("code".to_string(), Value::Number(code as f64)),
+                ("message".to_string(), message.into()),
+            ]),
+        ),
+    ])
+    .to_string()
+}
+
+fn string_param<'a>(params: &'a Value, name: &str) -> Result<&'a str, (i64, String)> {
+    params
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| (INVALID_PARAMS, format!("Missing string parameter: {name}")))
+}
+
+// Plan or apply a rename of params.input to params.name
+fn rename(params: &Value, dry_run: bool) -> Result<Value, (i64, String)> {
+    let input = PathBuf::from(string_param(params, "input")?);
+    let name = string_param(params, "name")?;
+    if !input.exists() {
+        return Err((INVALID_PARAMS, format!("Input does not exist: {}", input.display())));
+    }
+
+    let out = Output::new(Verbosity::Quiet, Default::default(), Default::default());
+    let mut ctx = Context::new(&input, name, out);
+    ctx.dry_run = dry_run;
+    if let Some((path, reason)) = find_reserved_names(&ctx).first() {
+        return Err((INVALID_PARAMS, format!("New name {reason}: {}", path.display())));
+    }
+    run(&mut ctx).map_err(|message| (SERVER_ERROR, message))?;
+
+    let operations = ctx
+        .operations
+        .iter()
+        .map(|operation| {
+            Value::Object(vec![
+                ("action".to_string(), operation.action.name().into()),
+                ("input".to_string(), operation.input.to_string_lossy().as_ref().into()),
+                ("output".to_string(), operation.output.to_string_lossy().as_ref().into()),
+                ("substitutions".to_string(), operation.substitutions.into()),
+            ])
+        })
+        .collect();
+    Ok(Value::Object(vec![
+        ("output".to_string(), ctx.output.to_string_lossy().as_ref().into()),
+        ("renamed".to_string(), ctx.stats.renamed.into()),
+        ("substitutions".to_string(), ctx.stats.substitutions.into()),
+        ("operations".to_string(), Value::Array(operations)),
+    ]))
+}
+
+// List the occurrences of params.name left in params.path
+fn verify(params: &Value) -> Result<Value, (i64, String)> {
+    let path = PathBuf::from(string_param(params, "path")?);
+    let name = CaseInfo::detect(string_param(params, "name")?).1;
+    if !path.exists() {
+        return Err((INVALID_PARAMS, format!("Path does not exist: {}", path.display())));
+    }
+
+    let occurrences = verify::find_occurrences(&path, &name)
+        .into_iter()
+        .map(|occurrence| {
+            Value::Object(vec![
+                ("path".to_string(), occurrence.path.to_string_lossy().as_ref().into()),
+                ("line".to_string(), occurrence.line.map_or(Value::Null, Value::from)),
+                ("text".to_string(), occurrence.text.into()),
+            ])
+        })
+        .collect::<Vec<_>>();
+    Ok(Value::Object(vec![
+        ("clean".to_string(), occurrences.is_empty().into()),
+        ("occurrences".to_string(), Value::Array(occurrences)),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet() -> Output {
+        Output::new(Verbosity::Quiet, Default::default(), Default::default())
+    }
+
+    #[test]
+    fn test_serve_loopback_only() {
+        let error = serve("0.0.0.0:0", quiet()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), "0.0.0.0 is not a loopback address, and the API has no authentication");
+        assert!(serve("192.168.1.10:0", quiet()).is_err());
+    }
+
+    #[test]
+    fn test_handle_request_errors() {
+        let response = json::parse(&handle_request("{", quiet())).unwrap();
+        assert_eq!(response.get("error").and_then(|e| e.get("code")), Some(&Value::Number(-32700.0)));
+
+        let response = json::parse(&handle_request(r#"{"jsonrpc":"2.0","id":7,"method":"nope"}"#, quiet())).unwrap();
+        assert_eq!(response.get("id"), Some(&Value::Number(7.0)));
+        assert_eq!(response.get("error").and_then(|e| e.get("code")), Some(&Value::Number(-32601.0)));
+
+        let response = json::parse(&handle_request(r#"{"jsonrpc":"2.0","id":1,"method":"plan","params":{}}"#, quiet())).unwrap();
+        assert_eq!(response.get("error").and_then(|e| e.get("code")), Some(&Value::Number(-32602.0)));
+    }
+
+    // Send the request over a connection and return the status line of the response
+    fn status(request: &str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        client.write_all(request.as_bytes()).unwrap();
+        handle_connection(listener.accept().unwrap().0, quiet()).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        response.lines().next().unwrap_or_default().to_string()
+    }
+
+    #[test]
+    fn test_handle_connection() {
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"nope"}"#;
+        let request = |headers: &str| format!("POST / HTTP/1.1\r\n{headers}Content-Length: {}\r\n\r\n{body}", body.len());
+        assert_eq!(status(&request("Content-Type: application/json\r\n")), "HTTP/1.1 200 OK");
+        assert_eq!(status(&request("Content-Type: application/json; charset=utf-8\r\n")), "HTTP/1.1 200 OK");
+        assert_eq!(status(&request("Content-Type: text/plain\r\n")), "HTTP/1.1 415 Unsupported Media Type");
+        assert_eq!(status(&request("")), "HTTP/1.1 415 Unsupported Media Type");
+        let cross_site = "Content-Type: application/json\r\nOrigin: https://example.com\r\n";
+        assert_eq!(status(&request(cross_site)), "HTTP/1.1 403 Forbidden");
+    }
+
+    #[test]
+    fn test_plan_apply_verify() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-serve-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("old-project")).unwrap();
+        std::fs::write(dir.join("old-project/old_project.txt"), "OldProject").unwrap();
+        let input = json::string(&dir.join("old-project").to_string_lossy());
+
+        // Planning doesn't touch the filesystem
+        let request = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"plan","params":{{"input":{input},"name":"new-project"}}}}"#);
+        let response = json::parse(&handle_request(&request, quiet())).unwrap();
+        let result = response.get("result").unwrap();
+        assert_eq!(result.get("substitutions"), Some(&Value::Number(1.0)));
+        assert!(matches!(result.get("operations"), Some(Value::Array(operations)) if operations.len() == 2));
+        assert!(!dir.join("new-project").exists());
+
+        let request = request.replace("\"plan\"", "\"apply\"");
+        json::parse(&handle_request(&request, quiet())).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("new-project/new_project.txt")).unwrap(), "NewProject");
+
+        let verify = |path: &str| {
+            let path = json::string(&dir.join(path).to_string_lossy());
+            let request = format!(r#"{{"jsonrpc":"2.0","id":2,"method":"verify","params":{{"path":{path},"name":"old-project"}}}}"#);
+            let response = json::parse(&handle_request(&request, quiet())).unwrap();
+            response.get("result").and_then(|r| r.get("clean")).cloned()
+        };
+        assert_eq!(verify("new-project"), Some(Value::Bool(true)));
+        assert_eq!(verify("old-project"), Some(Value::Bool(false)));
+
+        // A rename that fails is answered with an error, and the server keeps running
+        std::fs::write(dir.join("other-project"), "").unwrap();
+        let request = request.replace("new-project", "other-project");
+        let response = json::parse(&handle_request(&request, quiet())).unwrap();
+        assert_eq!(response.get("error").and_then(|e| e.get("code")), Some(&Value::Number(-32000.0)));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,59 @@
+use std::path::Path;
+
+use crate::{uuid, xml};
+
+// The elements of MSBuild projects that hold the GUID of the project itself
+const GUID_ELEMENTS: [&str; 2] = ["ProjectGuid", "SharedGUID"];
+
+// Whether the file is a Visual Studio solution or an MSBuild project that refers to projects by
+// GUID, like "App.sln", "App.csproj" or "App.vcxproj", or the "App.projitems" of a shared project
+pub fn is_solution_file(path: &Path) -> bool {
+    let extension = path.extension().map(|extension| extension.to_string_lossy().to_lowercase()).unwrap_or_default();
+    extension == "sln" || extension == "projitems" || (extension.ends_with("proj") && extension.len() > 4)
+}
+
+// The GUIDs identifying the projects of a solution or the project itself, in lower case without
+// braces: the last GUID of every `Project("{type}") = "Name", "path", "{guid}"` line and the
+// SolutionGuid of a solution, and the ProjectGuid of a project. The GUIDs of the project types are
+// left out, Visual Studio only knows the projects by them.
+pub fn project_guids(path: &Path, text: &str) -> Vec<String> {
+    let mut guids = vec![];
+    if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("sln")) {
+        for line in text.lines().map(str::trim_start) {
+            let braced = line.split('{').skip(1).filter_map(|part| part.split_once('}')).map(|(guid, _)| guid);
+            if line.starts_with("Project(") {
+                guids.extend(braced.last());
+            } else if line.starts_with("SolutionGuid") {
+                guids.extend(braced.take(1));
+            }
+        }
+    } else {
+        for (path, range) in xml::values(text).unwrap_or_default() {
+            if path.last().is_some_and(|element| GUID_ELEMENTS.contains(&element.as_str())) {
+                guids.push(text[range].trim().trim_start_matches('{').trim_end_matches('}'));
+            }
+        }
+    }
+    guids.into_iter().filter(|guid| uuid::is_uuid(guid.as_bytes())).map(str::to_ascii_lowercase).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_guids() {
+        let sln = "Project(\"{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}\") = \"App\", \"App\\App.csproj\", \"{5E1F3D2A-0B4C-4D6E-8F7A-9B0C1D2E3F4A}\"\n\
+                   EndProject\nGlobal\n\tGlobalSection(ExtensibilityGlobals) = postSolution\n\t\t\
+                   SolutionGuid = {0A1B2C3D-4E5F-4A6B-8C7D-8E9F0A1B2C3D}\n\tEndGlobalSection\nEndGlobal\n";
+        assert_eq!(project_guids(Path::new("App.sln"), sln), ["5e1f3d2a-0b4c-4d6e-8f7a-9b0c1d2e3f4a", "0a1b2c3d-4e5f-4a6b-8c7d-8e9f0a1b2c3d"]);
+
+        let csproj = "<Project><PropertyGroup><ProjectGuid>{5E1F3D2A-0B4C-4D6E-8F7A-9B0C1D2E3F4A}</ProjectGuid></PropertyGroup>\
+                      <ItemGroup><ProjectReference Include=\"..\\Lib\\Lib.csproj\"><Project>{11111111-2222-4333-8444-555555555555}</Project>\
+                      </ProjectReference></ItemGroup></Project>";
+        assert_eq!(project_guids(Path::new("App.csproj"), csproj), ["5e1f3d2a-0b4c-4d6e-8f7a-9b0c1d2e3f4a"]);
+        assert!(is_solution_file(Path::new("App.vcxproj")));
+        assert!(!is_solution_file(Path::new("App.vcxproj.filters")));
+        assert!(!is_solution_file(Path::new("notes.proj.txt")));
+    }
+}
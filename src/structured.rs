@@ -0,0 +1,116 @@
+use std::ops::Range;
+use std::path::Path;
+
+use crate::{ini, json, toml, xml, yaml};
+
+// A value of a data file: the keys and indexes leading to it and the range of its text
+pub type Located = (Vec<String>, Range<usize>);
+
+// A data file format whose values can be addressed by key paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Yaml,
+    Json,
+    Xml,
+    Toml,
+    Ini,
+}
+
+impl Format {
+    pub fn detect(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "yaml" | "yml" => Some(Format::Yaml),
+            "json" => Some(Format::Json),
+            "toml" => Some(Format::Toml),
+            "ini" => Some(Format::Ini),
+            "xml" | "pom" | "csproj" | "fsproj" | "vbproj" | "props" | "targets" | "nuspec" | "plist" => Some(Format::Xml),
+            // Keil µVision and IAR Embedded Workbench projects and workspaces
+            "uvprojx" | "uvoptx" | "uvproj" | "uvopt" | "ewp" | "eww" | "ewd" => Some(Format::Xml),
+            _ => None,
+        }
+    }
+}
+
+// The ranges of the values at the key paths in the text, like "metadata.name" or
+// "metadata.labels.*" in YAML, "package.name" in TOML and "env:*.board" in INI, JSON pointers like
+// "/repository/url" in JSON, or element paths like "project/artifactId" and "manifest/@package" in
+// XML, where "*" matches any key, index or element. A TOML, JSON or XML file that doesn't parse has
+// none.
+pub fn values(text: &str, format: Format, keys: &[String]) -> Vec<Range<usize>> {
+    let (values, patterns) = match format {
+        Format::Yaml => (yaml::values(text), dotted(keys)),
+        Format::Toml => (toml::values(text).unwrap_or_default(), dotted(keys)),
+        // Keys like "board_build.ldscript" have dots of their own, only the section is split off
+        Format::Ini => (
+            ini::values(text),
+            keys.iter().map(|key| key.split_once('.').map_or(vec![key.clone()], |(section, key)| vec![section.to_string(), key.to_string()])).collect(),
+        ),
+        Format::Json => (json::string_values(text).unwrap_or_default(), keys.iter().map(|key| pointer(key)).collect::<Vec<_>>()),
+        Format::Xml => (
+            xml::values(text).unwrap_or_default(),
+            keys.iter().map(|key| key.trim_start_matches('/').split('/').map(str::to_string).collect()).collect(),
+        ),
+    };
+    values
+        .into_iter()
+        .filter(|(path, _)| patterns.iter().any(|pattern| matches(pattern, path)))
+        .map(|(_, range)| range)
+        .collect()
+}
+
+fn dotted(keys: &[String]) -> Vec<Vec<String>> {
+    keys.iter().map(|key| key.split('.').map(str::to_string).collect()).collect()
+}
+
+// The keys of a JSON pointer (RFC 6901), where "~1" stands for "/" and "~0" for "~"
+fn pointer(pointer: &str) -> Vec<String> {
+    pointer.strip_prefix('/').unwrap_or(pointer).split('/').map(|key| key.replace("~1", "/").replace("~0", "~")).collect()
+}
+
+pub fn matches(pattern: &[String], path: &[String]) -> bool {
+    pattern.len() == path.len() && pattern.iter().zip(path).all(|(segment, key)| segment == "*" || segment == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values() {
+        let text = "metadata:\n  name: my-app\n  labels:\n    app: my-app\n    tier: my-app-web\nspec:\n  name: my-app\n";
+        let keys = ["metadata.name".to_string(), "metadata.labels.*".to_string()];
+        let found = values(text, Format::Yaml, &keys).into_iter().map(|range| &text[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-app", "my-app", "my-app-web"]);
+        assert_eq!(Format::detect(Path::new("k8s/deploy.YML")), Some(Format::Yaml));
+        assert_eq!(Format::detect(Path::new("README.md")), None);
+
+        let text = "[package]\nname = \"my-app\" # my-app\ndescription = \"my-app\"\n\n[tool.poetry]\nname = 'my-app'\n";
+        let keys = ["package.name".to_string(), "tool.poetry.name".to_string()];
+        let found = values(text, Format::Toml, &keys).into_iter().map(|range| &text[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-app", "my-app"]);
+        assert_eq!(Format::detect(Path::new("pyproject.toml")), Some(Format::Toml));
+
+        let text = r#"{"name": "my-app", "repository": {"url": "https://x/my-app"}, "a/b": "my-app", "main": "my-app.js"}"#;
+        let keys = ["/name".to_string(), "/repository/*".to_string(), "/a~1b".to_string()];
+        let found = values(text, Format::Json, &keys).into_iter().map(|range| &text[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-app", "https://x/my-app", "my-app"]);
+        assert!(values("{", Format::Json, &keys).is_empty());
+
+        let text = "<project><groupId>com.my-app</groupId><artifactId>my-app</artifactId>\
+                    <dependencies><dependency><artifactId>my-app-core</artifactId></dependency></dependencies>\
+                    <description><![CDATA[my-app <3]]></description></project>";
+        let keys = ["project/artifactId".to_string(), "/project/*/dependency/artifactId".to_string()];
+        let found = values(text, Format::Xml, &keys).into_iter().map(|range| &text[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["my-app", "my-app-core"]);
+        assert_eq!(Format::detect(Path::new("App/App.csproj")), Some(Format::Xml));
+        assert_eq!(Format::detect(Path::new("Info.plist")), Some(Format::Xml));
+        assert_eq!(Format::detect(Path::new("MDK-ARM/my-app.uvprojx")), Some(Format::Xml));
+
+        let text = "[env:my-app]\nboard = my-app\nboard_build.ldscript = my_app.ld\n";
+        let keys = ["*".to_string(), "env:my-app.board_build.ldscript".to_string()];
+        let found = values(text, Format::Ini, &keys).into_iter().map(|range| &text[range]).collect::<Vec<_>>();
+        assert_eq!(found, ["env:my-app", "my_app.ld"]);
+        assert_eq!(Format::detect(Path::new("platformio.ini")), Some(Format::Ini));
+    }
+}
@@ -0,0 +1,298 @@
+use std::ops::Range;
+use std::path::Path;
+
+// The kind of a token in source code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Node {
+    Identifier,
+    String,
+    Comment,
+    // Everything else, e.g. keywords are identifiers but numbers and punctuation are not
+    Other,
+}
+
+impl Node {
+    // The node kinds that can be chosen in the config
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "identifiers" => Some(Node::Identifier),
+            "strings" => Some(Node::String),
+            "comments" => Some(Node::Comment),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Node::Identifier => "identifiers",
+            Node::String => "strings",
+            Node::Comment => "comments",
+            Node::Other => "other",
+        }
+    }
+}
+
+// Where the name is replaced in source code, on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReplaceIn {
+    // Everywhere, also in a recognized language
+    All,
+    // Identifiers
+    Code,
+    Strings,
+    Comments,
+}
+
+impl ReplaceIn {
+    // The kinds of tokens to replace in, None for everywhere
+    pub fn nodes(values: &[ReplaceIn]) -> Option<Vec<Node>> {
+        if values.is_empty() || values.contains(&ReplaceIn::All) {
+            return None;
+        }
+        let nodes = values.iter().map(|value| match value {
+            ReplaceIn::All => unreachable!(),
+            ReplaceIn::Code => Node::Identifier,
+            ReplaceIn::Strings => Node::String,
+            ReplaceIn::Comments => Node::Comment,
+        });
+        let mut unique: Vec<Node> = vec![];
+        for node in nodes {
+            if !unique.contains(&node) {
+                unique.push(node);
+            }
+        }
+        Some(unique)
+    }
+}
+
+// Languages with a tokenizer, grouped by their comment and string syntax
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    // C, C++, C#, Java, Kotlin, Swift, Scala, Dart
+    CLike,
+    // JavaScript and TypeScript, with template strings
+    JavaScript,
+    // With raw strings in backticks
+    Go,
+    // With raw strings and lifetimes
+    Rust,
+    Python,
+    Ruby,
+}
+
+impl Language {
+    pub fn detect(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        Some(match extension.as_str() {
+            "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" | "cs" | "java" | "kt" | "kts" | "swift" | "scala" | "dart" => {
+                Language::CLike
+            }
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => Language::JavaScript,
+            "go" => Language::Go,
+            "rs" => Language::Rust,
+            "py" | "pyi" => Language::Python,
+            "rb" => Language::Ruby,
+            _ => return None,
+        })
+    }
+
+    fn hash_comments(self) -> bool {
+        matches!(self, Language::Python | Language::Ruby)
+    }
+}
+
+// A token, or a run of Other tokens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub node: Node,
+    pub range: Range<usize>,
+}
+
+// Split source code into spans that cover all of it. This is a tokenizer, not a parser: it knows
+// enough of the comment and string syntax of the language to never find a name inside another
+// kind of token, e.g. a number suffix or a string in a comment. That is all the node kinds of the
+// config need, and tree-sitter and its grammars, which are C libraries, aren't available to the
+// offline build. A file with syntax errors is still tokenized, where a parser would give up.
+pub fn spans(text: &str, language: Language) -> Vec<Span> {
+    let bytes = text.as_bytes();
+    let mut spans: Vec<Span> = vec![];
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let (node, end) = token(bytes, pos, language);
+        match spans.last_mut() {
+            Some(last) if last.node == Node::Other && node == Node::Other => last.range.end = end,
+            _ => spans.push(Span { node, range: pos..end }),
+        }
+        pos = end;
+    }
+    spans
+}
+
+// The kind and end of the token at pos. Tokens only start and end at ASCII bytes, so they are
+// always on char boundaries.
+fn token(bytes: &[u8], pos: usize, language: Language) -> (Node, usize) {
+    let rest = &bytes[pos..];
+    if (language.hash_comments() && rest[0] == b'#') || (!language.hash_comments() && rest.starts_with(b"//")) {
+        return (Node::Comment, find(bytes, pos, b"\n").map_or(bytes.len(), |end| end - 1));
+    }
+    if !language.hash_comments() && rest.starts_with(b"/*") {
+        return (Node::Comment, find(bytes, pos + 2, b"*/").unwrap_or(bytes.len()));
+    }
+    if language == Language::Python && (rest.starts_with(b"\"\"\"") || rest.starts_with(b"'''")) {
+        return (Node::String, find(bytes, pos + 3, &rest[..3]).unwrap_or(bytes.len()));
+    }
+    match rest[0] {
+        b'"' => (Node::String, string_end(bytes, pos + 1, b'"', true)),
+        b'\'' if language == Language::Rust => rust_quote(bytes, pos),
+        b'\'' => (Node::String, string_end(bytes, pos + 1, b'\'', language.hash_comments())),
+        b'`' if language == Language::JavaScript => (Node::String, string_end(bytes, pos + 1, b'`', true)),
+        b'`' if language == Language::Go => (Node::String, find(bytes, pos + 1, b"`").unwrap_or(bytes.len())),
+        byte if is_identifier(byte) && !byte.is_ascii_digit() => {
+            let end = pos + rest.iter().take_while(|&&byte| is_identifier(byte)).count();
+            if language == Language::Rust
+                && matches!(&bytes[pos..end], b"r" | b"br")
+                && let Some(end) = rust_raw_string_end(bytes, end)
+            {
+                return (Node::String, end);
+            }
+            (Node::Identifier, end)
+        }
+        // Numbers with their suffixes, e.g. 0x1f or 10usize
+        byte if byte.is_ascii_digit() => (Node::Other, pos + rest.iter().take_while(|&&byte| is_identifier(byte)).count()),
+        _ => (Node::Other, pos + 1),
+    }
+}
+
+// Letters, digits and the parts of non-ASCII chars
+fn is_identifier(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$' || byte >= 0x80
+}
+
+// The position after the first needle from the position on
+fn find(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    bytes.get(from..)?.windows(needle.len()).position(|window| window == needle).map(|index| from + index + needle.len())
+}
+
+// The position after the closing quote, skipping escaped chars. Strings that can't span lines end
+// at the line break when they aren't closed.
+fn string_end(bytes: &[u8], from: usize, quote: u8, multiline: bool) -> usize {
+    let mut pos = from;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'\\' => pos += 1,
+            b'\n' if !multiline => return pos,
+            byte if byte == quote => return pos + 1,
+            _ => {}
+        }
+        pos += 1;
+    }
+    bytes.len()
+}
+
+// A char literal like 'a' or '\n', or the quote of a lifetime like 'a
+fn rust_quote(bytes: &[u8], pos: usize) -> (Node, usize) {
+    match bytes.get(pos + 1) {
+        Some(b'\\') => (Node::String, string_end(bytes, pos + 1, b'\'', false)),
+        Some(&byte) => {
+            // Length of the UTF-8 char
+            let len = match byte {
+                0..0x80 => 1,
+                0x80..0xe0 => 2,
+                0xe0..0xf0 => 3,
+                _ => 4,
+            };
+            if bytes.get(pos + 1 + len) == Some(&b'\'') {
+                (Node::String, pos + 2 + len)
+            } else {
+                (Node::Other, pos + 1)
+            }
+        }
+        None => (Node::Other, pos + 1),
+    }
+}
+
+// The end of a raw string like r#"..."# after its prefix, if it is one
+fn rust_raw_string_end(bytes: &[u8], from: usize) -> Option<usize> {
+    let hashes = bytes[from..].iter().take_while(|&&byte| byte == b'#').count();
+    if bytes.get(from + hashes) != Some(&b'"') {
+        return None;
+    }
+    let mut closing = vec![b'"'];
+    closing.extend(std::iter::repeat_n(b'#', hashes));
+    Some(find(bytes, from + hashes + 1, &closing).unwrap_or(bytes.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes(text: &str, language: Language) -> Vec<(Node, &str)> {
+        spans(text, language).into_iter().map(|span| (span.node, &text[span.range])).collect()
+    }
+
+    #[test]
+    fn test_spans() {
+        assert_eq!(
+            nodes("fn f<'a>(x: &'a str) -> char { 'é' } // x\nlet s = r#\"a \" b\"#; 10usize", Language::Rust),
+            vec![
+                (Node::Identifier, "fn"),
+                (Node::Other, " "),
+                (Node::Identifier, "f"),
+                (Node::Other, "<'"),
+                (Node::Identifier, "a"),
+                (Node::Other, ">("),
+                (Node::Identifier, "x"),
+                (Node::Other, ": &'"),
+                (Node::Identifier, "a"),
+                (Node::Other, " "),
+                (Node::Identifier, "str"),
+                (Node::Other, ") -> "),
+                (Node::Identifier, "char"),
+                (Node::Other, " { "),
+                (Node::String, "'é'"),
+                (Node::Other, " } "),
+                (Node::Comment, "// x"),
+                (Node::Other, "\n"),
+                (Node::Identifier, "let"),
+                (Node::Other, " "),
+                (Node::Identifier, "s"),
+                (Node::Other, " = "),
+                (Node::String, "r#\"a \" b\"#"),
+                (Node::Other, "; 10usize"),
+            ]
+        );
+        assert_eq!(
+            nodes("x = '''a\n'b'''  # it's\n\"c\\\"\"", Language::Python),
+            vec![
+                (Node::Identifier, "x"),
+                (Node::Other, " = "),
+                (Node::String, "'''a\n'b'''"),
+                (Node::Other, "  "),
+                (Node::Comment, "# it's"),
+                (Node::Other, "\n"),
+                (Node::String, "\"c\\\"\""),
+            ]
+        );
+        assert_eq!(
+            nodes("/* a */`b ${c}`", Language::JavaScript),
+            vec![(Node::Comment, "/* a */"), (Node::String, "`b ${c}`")]
+        );
+    }
+
+    #[test]
+    fn test_replace_in() {
+        assert_eq!(ReplaceIn::nodes(&[]), None);
+        assert_eq!(ReplaceIn::nodes(&[ReplaceIn::Code, ReplaceIn::All]), None);
+        assert_eq!(
+            ReplaceIn::nodes(&[ReplaceIn::Strings, ReplaceIn::Comments, ReplaceIn::Strings]),
+            Some(vec![Node::String, Node::Comment])
+        );
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Language::detect(Path::new("src/main.RS")), Some(Language::Rust));
+        assert_eq!(Language::detect(Path::new("README.md")), None);
+        assert_eq!(Language::detect(Path::new("Makefile")), None);
+    }
+}
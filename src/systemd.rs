@@ -0,0 +1,73 @@
+use std::ops::Range;
+use std::path::Path;
+
+use crate::ini;
+
+// The types of systemd units, the extensions of their unit files
+const UNIT_TYPES: [&str; 11] = ["service", "socket", "timer", "path", "target", "mount", "automount", "swap", "slice", "scope", "device"];
+
+// The keys whose values are the names of other units, like `After=old-name.socket`
+const UNIT_REFERENCES: [&str; 22] = [
+    "Requires",
+    "Requisite",
+    "Wants",
+    "BindsTo",
+    "PartOf",
+    "Upholds",
+    "Conflicts",
+    "Before",
+    "After",
+    "OnFailure",
+    "OnSuccess",
+    "PropagatesReloadTo",
+    "ReloadPropagatedFrom",
+    "JoinsNamespaceOf",
+    "Unit",
+    "Service",
+    "Sockets",
+    "WantedBy",
+    "RequiredBy",
+    "UpheldBy",
+    "Also",
+    "Alias",
+];
+
+// Whether the file is a systemd unit file, like "old-name.service" or "old-name@.timer", or a
+// drop-in of one, like "old-name.service.d/override.conf"
+pub fn is_unit_file(path: &Path) -> bool {
+    let is_unit = |name: &str| name.rsplit_once('.').is_some_and(|(_, extension)| UNIT_TYPES.contains(&extension));
+    let drop_in = || {
+        path.extension().is_some_and(|extension| extension == "conf")
+            && path.parent().and_then(Path::file_name).is_some_and(|dir| dir.to_string_lossy().strip_suffix(".d").is_some_and(is_unit))
+    };
+    path.file_name().is_some_and(|name| is_unit(&name.to_string_lossy())) || drop_in()
+}
+
+// The ranges of the values of a unit file the name is renamed in: the Description, the commands of
+// the Exec keys like ExecStart and the names of the units it refers to. The users, groups and
+// directories like `StateDirectory=old-name` are left out, they name the state already on the
+// hosts the unit runs on.
+pub fn names(text: &str) -> Vec<Range<usize>> {
+    let renamed = |key: &str| key == "Description" || key.starts_with("Exec") || UNIT_REFERENCES.contains(&key);
+    ini::values(text).into_iter().filter(|(path, _)| path.len() == 2 && renamed(&path[1])).map(|(_, range)| range).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        let text = "[Unit]\nDescription=My App server\nAfter=network.target my-app.socket\nRequires=my-app.socket\n\n\
+                    [Service]\nUser=my-app\nStateDirectory=my-app\nExecStartPre=/usr/bin/my-app migrate\nExecStart=/usr/bin/my-app \\\n    \
+                    --serve\n\n[Install]\nWantedBy=multi-user.target\n";
+        let found = names(text).into_iter().map(|range| &text[range]).collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            ["My App server", "network.target my-app.socket", "my-app.socket", "/usr/bin/my-app migrate", "/usr/bin/my-app \\", "--serve", "multi-user.target"]
+        );
+        assert!(is_unit_file(Path::new("deploy/my-app@.service")));
+        assert!(is_unit_file(Path::new("my-app.service.d/override.conf")));
+        assert!(!is_unit_file(Path::new("nginx/my-app.conf")));
+    }
+}
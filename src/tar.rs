@@ -0,0 +1,259 @@
+use std::io::{Read, Write};
+
+// Size of a tar block, headers and file contents are padded to it
+const BLOCK_SIZE: usize = 512;
+
+// Entry types
+pub const FILE: u8 = b'0';
+pub const SYMLINK: u8 = b'2';
+pub const DIRECTORY: u8 = b'5';
+
+// A single entry of a tar archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub kind: u8,
+    pub mode: u32,
+    pub mtime: u64,
+    pub link: String,
+    pub data: Vec<u8>,
+}
+
+// Writes a ustar archive. Names and link targets that don't fit the header are stored in PAX
+// extended headers.
+pub struct Writer<W: Write> {
+    out: W,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+
+    // Write the header of an entry, its content of the given size must follow with `content`
+    pub fn header(&mut self, name: &str, kind: u8, mode: u32, mtime: u64, size: u64, link: &str) -> std::io::Result<()> {
+        if name.len() > 100 || link.len() > 100 {
+            let mut records = String::new();
+            if name.len() > 100 {
+                records.push_str(&pax_record("path", name));
+            }
+            if link.len() > 100 {
+                records.push_str(&pax_record("linkpath", link));
+            }
+            let block = header_block("././@PaxHeader", b'x', 0o644, 0, records.len() as u64, "");
+            self.out.write_all(&block)?;
+            self.content(&mut records.as_bytes())?;
+        }
+        let block = header_block(truncate(name, 100), kind, mode, mtime, size, truncate(link, 100));
+        self.out.write_all(&block)
+    }
+
+    // Write the content of an entry, padded to a full block
+    pub fn content(&mut self, reader: &mut impl Read) -> std::io::Result<u64> {
+        let written = std::io::copy(reader, &mut self.out)?;
+        let padding = (BLOCK_SIZE - written as usize % BLOCK_SIZE) % BLOCK_SIZE;
+        self.out.write_all(&vec![0; padding])?;
+        Ok(written)
+    }
+
+    pub fn entry(&mut self, entry: &Entry) -> std::io::Result<()> {
+        self.header(&entry.name, entry.kind, entry.mode, entry.mtime, entry.data.len() as u64, &entry.link)?;
+        self.content(&mut entry.data.as_slice()).map(|_| ())
+    }
+
+    // An archive ends with two empty blocks
+    pub fn finish(mut self) -> std::io::Result<W> {
+        self.out.write_all(&[0; 2 * BLOCK_SIZE])?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+// Read all entries of an archive
+pub fn read(bytes: &[u8]) -> Result<Vec<Entry>, String> {
+    let mut entries = vec![];
+    let mut pos = 0;
+    let (mut long_name, mut long_link) = (None, None);
+    while pos + BLOCK_SIZE <= bytes.len() {
+        let block = &bytes[pos..pos + BLOCK_SIZE];
+        if block.iter().all(|&b| b == 0) {
+            break;
+        }
+        let expected = parse_octal(&block[148..156])?;
+        let checksum = block
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| if (148..156).contains(&i) { b' ' as u64 } else { b as u64 })
+            .sum::<u64>();
+        if checksum != expected {
+            return Err(format!("Invalid header checksum at byte {pos}"));
+        }
+        let size = parse_octal(&block[124..136])? as usize;
+        let data = bytes
+            .get(pos + BLOCK_SIZE..pos + BLOCK_SIZE + size)
+            .ok_or("Unexpected end of archive")?
+            .to_vec();
+        pos += BLOCK_SIZE + size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+
+        match block[156] {
+            // PAX extended header for the next entry
+            b'x' => {
+                for (key, value) in parse_pax(&data)? {
+                    match key.as_str() {
+                        "path" => long_name = Some(value),
+                        "linkpath" => long_link = Some(value),
+                        _ => {}
+                    }
+                }
+            }
+            // GNU long name or link target for the next entry
+            b'L' => long_name = Some(c_string(&data)),
+            b'K' => long_link = Some(c_string(&data)),
+            // PAX global header
+            b'g' => {}
+            kind => {
+                let mut name = c_string(&block[..100]);
+                let prefix = c_string(&block[345..500]);
+                if &block[257..262] == b"ustar" && !prefix.is_empty() {
+                    name = format!("{prefix}/{name}");
+                }
+                entries.push(Entry {
+                    name: long_name.take().unwrap_or(name),
+                    kind: if kind == 0 { FILE } else { kind },
+                    mode: parse_octal(&block[100..108])? as u32,
+                    mtime: parse_octal(&block[136..148])?,
+                    link: long_link.take().unwrap_or_else(|| c_string(&block[157..257])),
+                    data,
+                });
+            }
+        }
+    }
+    Ok(entries)
+}
+
+// A ustar header block
+fn header_block(name: &str, kind: u8, mode: u32, mtime: u64, size: u64, link: &str) -> [u8; BLOCK_SIZE] {
+    let mut block = [0; BLOCK_SIZE];
+    block[..name.len()].copy_from_slice(name.as_bytes());
+    octal(&mut block[100..108], mode as u64);
+    octal(&mut block[108..116], 0);
+    octal(&mut block[116..124], 0);
+    octal(&mut block[124..136], size);
+    octal(&mut block[136..148], mtime);
+    block[156] = kind;
+    block[157..157 + link.len()].copy_from_slice(link.as_bytes());
+    block[257..263].copy_from_slice(b"ustar\0");
+    block[263..265].copy_from_slice(b"00");
+
+    // The checksum is calculated with the checksum field filled with spaces
+    block[148..156].fill(b' ');
+    let checksum = block.iter().map(|&b| b as u64).sum();
+    octal(&mut block[148..155], checksum);
+    block
+}
+
+// Zero padded octal number terminated by a NUL byte
+fn octal(field: &mut [u8], value: u64) {
+    let width = field.len() - 1;
+    let digits = format!("{value:0width$o}");
+    field[..width].copy_from_slice(&digits.as_bytes()[digits.len() - width..]);
+    field[width] = 0;
+}
+
+fn parse_octal(field: &[u8]) -> Result<u64, String> {
+    let text = String::from_utf8_lossy(field);
+    let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    if text.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(text, 8).map_err(|_| format!("Invalid number in header: {text}"))
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).to_string()
+}
+
+// "<length> <key>=<value>\n" where the length includes itself
+fn pax_record(key: &str, value: &str) -> String {
+    let content = format!(" {key}={value}\n");
+    let mut length = content.len() + 1;
+    while (length.to_string().len() + content.len()) != length {
+        length = length.to_string().len() + content.len();
+    }
+    format!("{length}{content}")
+}
+
+fn parse_pax(mut data: &[u8]) -> Result<Vec<(String, String)>, String> {
+    let mut records = vec![];
+    while !data.is_empty() {
+        let space = data.iter().position(|&b| b == b' ').ok_or("Invalid PAX record")?;
+        let length: usize = std::str::from_utf8(&data[..space])
+            .ok()
+            .and_then(|length| length.parse().ok())
+            .filter(|&length| length > space && length <= data.len())
+            .ok_or("Invalid PAX record length")?;
+        let record = String::from_utf8_lossy(&data[space + 1..length - 1]).to_string();
+        let (key, value) = record.split_once('=').ok_or("Invalid PAX record")?;
+        records.push((key.to_string(), value.to_string()));
+        data = &data[length..];
+    }
+    Ok(records)
+}
+
+// The longest prefix of the string with at most the given number of bytes
+fn truncate(s: &str, max: usize) -> &str {
+    let mut end = s.len().min(max);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pax_record() {
+        assert_eq!(pax_record("path", "a"), "9 path=a\n");
+        assert_eq!(pax_record("path", &"a".repeat(100)), format!("110 path={}\n", "a".repeat(100)));
+        assert_eq!(parse_pax(pax_record("path", "a").as_bytes()), Ok(vec![("path".to_string(), "a".to_string())]));
+    }
+
+    #[test]
+    fn test_header_block() {
+        let block = header_block("my-project/a.txt", FILE, 0o644, 0, 5, "");
+        assert_eq!(&block[..16], b"my-project/a.txt");
+        assert_eq!(&block[124..136], b"00000000005\0");
+        let checksum = block.iter().enumerate().map(|(i, &b)| if (148..156).contains(&i) { b' ' as u64 } else { b as u64 }).sum::<u64>();
+        assert_eq!(std::str::from_utf8(&block[148..154]).unwrap(), format!("{checksum:06o}"));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let entries = vec![
+            Entry { name: "my-project/".to_string(), kind: DIRECTORY, mode: 0o755, mtime: 1, link: String::new(), data: vec![] },
+            Entry {
+                name: format!("my-project/{}.txt", "long".repeat(30)),
+                kind: FILE,
+                mode: 0o644,
+                mtime: 2,
+                link: String::new(),
+                data: b"my-project".to_vec(),
+            },
+            Entry { name: "my-project/link".to_string(), kind: SYMLINK, mode: 0o777, mtime: 3, link: "a.txt".to_string(), data: vec![] },
+        ];
+        let mut writer = Writer::new(vec![]);
+        for entry in &entries {
+            writer.entry(entry).unwrap();
+        }
+        let bytes = writer.finish().unwrap();
+        assert_eq!(bytes.len() % BLOCK_SIZE, 0);
+        assert_eq!(read(&bytes), Ok(entries));
+
+        let mut corrupt = bytes.clone();
+        corrupt[0] = b'x';
+        assert!(read(&corrupt).is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use crate::{flatpak, json, toml};
+
+// The reverse-DNS identifier of a Tauri app: the `identifier` of its tauri.conf.json or Tauri.toml,
+// or of the platform specific ones like tauri.macos.conf.json, at the root since Tauri 2 and in
+// "tauri.bundle" before
+pub fn app_ids(path: &Path, text: &str) -> Vec<String> {
+    let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let values = if name.starts_with("tauri.") && name.ends_with(".conf.json") {
+        json::string_values(text).unwrap_or_default()
+    } else if name.starts_with("Tauri.") && name.ends_with(".toml") {
+        toml::values(text).unwrap_or_default()
+    } else {
+        vec![]
+    };
+    let mut ids = vec![];
+    for (path, range) in values {
+        let declared = match path.as_slice() {
+            [key] => key == "identifier",
+            [tauri, bundle, key] => tauri == "tauri" && bundle == "bundle" && key == "identifier",
+            _ => false,
+        };
+        let id = text[range].to_string();
+        if declared && flatpak::is_app_id(&id) && !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_ids() {
+        let config = r#"{"productName": "My App", "identifier": "com.example.my-app", "app": {"windows": [{"title": "My App"}]}}"#;
+        assert_eq!(app_ids(Path::new("src-tauri/tauri.conf.json"), config), ["com.example.my-app"]);
+        let config = r#"{"package": {"productName": "My App"}, "tauri": {"bundle": {"identifier": "com.example.myApp"}}}"#;
+        assert_eq!(app_ids(Path::new("src-tauri/tauri.macos.conf.json"), config), ["com.example.myApp"]);
+        assert_eq!(app_ids(Path::new("src-tauri/Tauri.toml"), "product-name = \"My App\"\nidentifier = \"com.example.MyApp\"\n"), ["com.example.MyApp"]);
+        assert!(app_ids(Path::new("package.json"), r#"{"identifier": "com.example.MyApp"}"#).is_empty());
+    }
+}
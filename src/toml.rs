@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use crate::structured::Located;
+
+// The string values of a TOML document with the path of keys leading to them, e.g.
+// ["package", "name"], in the order they occur. Array items and the tables of an array of tables
+// like [[bin]] are addressed by their index. Only the ranges of the strings are returned, without
+// their quotes, so comments, whitespace and key order stay as they are. None when the document
+// doesn't parse. Only replacing string values in place needs no editable document model like that
+// of toml_edit, which the offline build doesn't have.
+pub fn values(text: &str) -> Option<Vec<Located>> {
+    Some(scan(text)?.values)
+}
+
+// The keys of the key/value pairs of the tables of a TOML document, with the path leading to them
+// including the key itself, e.g. ["features", "default"]. Keys of inline tables and table headers
+// are left out.
+pub fn keys(text: &str) -> Option<Vec<Located>> {
+    Some(scan(text)?.keys)
+}
+
+fn scan(text: &str) -> Option<Scanner<'_>> {
+    let mut scanner = Scanner { text, pos: 0, values: vec![], keys: vec![] };
+    let mut table: Vec<String> = vec![];
+    // Number of tables seen of each array of tables
+    let mut arrays: HashMap<Vec<String>, usize> = HashMap::new();
+    loop {
+        scanner.skip_blank();
+        let Some(c) = scanner.peek() else {
+            break;
+        };
+        if c == '[' {
+            let array = scanner.rest().starts_with("[[");
+            scanner.pos += if array { 2 } else { 1 };
+            let keys = scanner.keys()?;
+            scanner.expect(if array { "]]" } else { "]" })?;
+            table = keys.clone();
+            if array {
+                let count = arrays.entry(keys).or_default();
+                table.push(count.to_string());
+                *count += 1;
+            }
+        } else {
+            let mut path = table.clone();
+            let start = scanner.pos;
+            path.extend(scanner.keys()?);
+            let key = &scanner.text[start..scanner.pos];
+            scanner.keys.push((path.clone(), start..start + key.trim_end().len()));
+            scanner.expect("=")?;
+            scanner.value(path)?;
+        }
+        scanner.skip_spaces();
+        scanner.skip_comment();
+        if !matches!(scanner.peek(), None | Some('\n' | '\r')) {
+            return None;
+        }
+    }
+    Some(scanner)
+}
+
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+    values: Vec<Located>,
+    keys: Vec<Located>,
+}
+
+impl Scanner<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_spaces(&mut self) {
+        self.pos = self.text.len() - self.rest().trim_start_matches([' ', '\t']).len();
+    }
+
+    fn skip_comment(&mut self) {
+        if self.rest().starts_with('#') {
+            self.pos += self.rest().find('\n').unwrap_or(self.rest().len());
+        }
+    }
+
+    // Whitespace, newlines and comments between key/value pairs and array items
+    fn skip_blank(&mut self) {
+        loop {
+            self.pos = self.text.len() - self.rest().trim_start().len();
+            if !self.rest().starts_with('#') {
+                break;
+            }
+            self.skip_comment();
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Option<()> {
+        self.skip_spaces();
+        self.rest().starts_with(token).then(|| self.pos += token.len())
+    }
+
+    // A dotted key like `tool.poetry.name` or `"quoted key"`
+    fn keys(&mut self) -> Option<Vec<String>> {
+        let mut keys = vec![];
+        loop {
+            self.skip_spaces();
+            let key = match self.peek()? {
+                quote @ ('"' | '\'') => {
+                    let end = self.rest()[1..].find(quote)? + 1;
+                    let key = self.rest()[1..end].to_string();
+                    self.pos += end + 1;
+                    key
+                }
+                _ => {
+                    let length = self.rest().find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-')).unwrap_or(self.rest().len());
+                    if length == 0 {
+                        return None;
+                    }
+                    let key = self.rest()[..length].to_string();
+                    self.pos += length;
+                    key
+                }
+            };
+            keys.push(key);
+            self.skip_spaces();
+            if !self.rest().starts_with('.') {
+                return Some(keys);
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self, path: Vec<String>) -> Option<()> {
+        self.skip_spaces();
+        for delimiter in ["\"\"\"", "'''", "\"", "'"] {
+            if self.rest().starts_with(delimiter) {
+                let start = self.pos + delimiter.len();
+                let end = start + string_end(&self.text[start..], delimiter)?;
+                // A newline right after the opening delimiter of a multi-line string is trimmed
+                let start = match delimiter.len() {
+                    3 => end - self.text[start..end].trim_start_matches(['\r', '\n']).len(),
+                    _ => start,
+                };
+                self.values.push((path, start..end));
+                self.pos = end + delimiter.len();
+                return Some(());
+            }
+        }
+        match self.peek()? {
+            '[' => {
+                self.pos += 1;
+                let mut index = 0;
+                loop {
+                    self.skip_blank();
+                    if self.rest().starts_with(']') {
+                        break;
+                    }
+                    let mut item = path.clone();
+                    item.push(index.to_string());
+                    self.value(item)?;
+                    index += 1;
+                    self.skip_blank();
+                    if self.rest().starts_with(',') {
+                        self.pos += 1;
+                    }
+                }
+                self.pos += 1;
+            }
+            '{' => {
+                self.pos += 1;
+                loop {
+                    self.skip_spaces();
+                    if self.rest().starts_with('}') {
+                        break;
+                    }
+                    let mut key = path.clone();
+                    key.extend(self.keys()?);
+                    self.expect("=")?;
+                    self.value(key)?;
+                    self.skip_spaces();
+                    if self.rest().starts_with(',') {
+                        self.pos += 1;
+                    }
+                }
+                self.pos += 1;
+            }
+            // Numbers, booleans and dates
+            _ => {
+                let length = self.rest().find([',', ']', '}', '#', '\n', '\r']).unwrap_or(self.rest().len());
+                if self.rest()[..length].trim().is_empty() {
+                    return None;
+                }
+                self.pos += length;
+            }
+        }
+        Some(())
+    }
+}
+
+// The offset of the closing delimiter of a string, skipping escapes in basic strings
+fn string_end(text: &str, delimiter: &str) -> Option<usize> {
+    let mut index = 0;
+    while index < text.len() {
+        let rest = &text[index..];
+        if let Some(after) = rest.strip_prefix(delimiter) {
+            // Quotes right before the closing delimiter of a multi-line string belong to the string
+            let extra = after.len() - after.trim_start_matches(&delimiter[..1]).len();
+            return Some(index + if delimiter.len() == 3 { extra.min(2) } else { 0 });
+        }
+        if delimiter.len() == 1 && rest.starts_with('\n') {
+            return None;
+        }
+        if delimiter.starts_with('"') && rest.starts_with('\\') {
+            index += 1 + rest[1..].chars().next().map_or(0, char::len_utf8);
+        } else {
+            index += rest.chars().next()?.len_utf8();
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values() {
+        let text = "# my-app\n\
+                    [package]\n\
+                    name = \"my-app\" # the name\n\
+                    version = \"0.1.0\"\n\
+                    authors = [\n  \"my-app team\",\n  'x',\n]\n\
+                    edition = 2021\n\n\
+                    [dependencies]\n\
+                    my-app-core = { path = \"../my-app-core\", version = \"1\" }\n\
+                    serde.version = \"1\"\n\n\
+                    [[bin]]\n\
+                    name = \"my-app\"\n\
+                    [[bin]]\n\
+                    name = \"my-app-cli\"\n\
+                    [\"tool\".poetry]\n\
+                    description = \"\"\"\nThe \\\"my-app\\\" tool\"\"\"\n";
+        let values = values(text).unwrap().into_iter().map(|(path, range)| (path.join("."), &text[range])).collect::<Vec<_>>();
+        let values = values.iter().map(|(path, value)| (path.as_str(), *value)).collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            [
+                ("package.name", "my-app"),
+                ("package.version", "0.1.0"),
+                ("package.authors.0", "my-app team"),
+                ("package.authors.1", "x"),
+                ("dependencies.my-app-core.path", "../my-app-core"),
+                ("dependencies.my-app-core.version", "1"),
+                ("dependencies.serde.version", "1"),
+                ("bin.0.name", "my-app"),
+                ("bin.1.name", "my-app-cli"),
+                ("tool.poetry.description", "The \\\"my-app\\\" tool"),
+            ]
+        );
+        let keys = keys(text).unwrap().into_iter().map(|(path, range)| (path.join("."), &text[range])).collect::<Vec<_>>();
+        assert_eq!(keys[4], ("dependencies.my-app-core".to_string(), "my-app-core"));
+        assert_eq!(keys[5], ("dependencies.serde.version".to_string(), "serde.version"));
+        assert!(super::values("[package\nname = \"my-app\"").is_none());
+        assert!(super::values("name = \"my-app\" trailing").is_none());
+    }
+}
@@ -0,0 +1,164 @@
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+// Move a file or directory. A rename can't cross filesystems (EXDEV), in which case the path is
+// copied to the destination and removed afterwards.
+pub fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    match std::fs::rename(from, to) {
+        Err(e) if e.kind() == ErrorKind::CrossesDevices => copy_and_remove(from, to).map_err(|e| {
+            Error::new(
+                e.kind(),
+                format!("{} and {} are on different filesystems and copying failed: {e}", from.display(), to.display()),
+            )
+        }),
+        result => result,
+    }
+}
+
+fn copy_and_remove(from: &Path, to: &Path) -> std::io::Result<()> {
+    copy_recursive(from, to)?;
+    if from.is_dir() {
+        std::fs::remove_dir_all(from)
+    } else {
+        std::fs::remove_file(from)
+    }
+}
+
+// Copy a file or a directory with everything in it. Symbolic links are copied as links on Unix.
+pub fn copy_recursive(from: &Path, to: &Path) -> std::io::Result<()> {
+    let metadata = std::fs::symlink_metadata(from)?;
+    if metadata.is_dir() {
+        std::fs::create_dir_all(to)?;
+        for entry in from.read_dir()? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        std::fs::set_permissions(to, metadata.permissions())
+    } else if metadata.is_symlink() {
+        copy_symlink(from, to)
+    } else {
+        std::fs::copy(from, to).map(|_| ())
+    }
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::copy(from, to).map(|_| ())
+}
+
+// Whether unmodified files are cloned with a reflink (copy-on-write) instead of copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Reflink {
+    // Clone when the filesystem supports it, copy otherwise
+    #[default]
+    Auto,
+    // Always clone, fail if the filesystem doesn't support it
+    Always,
+    // Always copy
+    Never,
+}
+
+// Copy a file, cloning it if requested. Clones share their data with the original until either is
+// modified, which makes copying large files nearly instant on Btrfs, XFS and APFS.
+pub fn copy_file(from: &Path, to: &Path, reflink: Reflink) -> std::io::Result<()> {
+    match reflink {
+        Reflink::Never => std::fs::copy(from, to).map(|_| ()),
+        Reflink::Always => clone_file(from, to)
+            .map_err(|e| Error::new(e.kind(), format!("Failed to clone {} with a reflink: {e}", from.display()))),
+        Reflink::Auto => clone_file(from, to).or_else(|_| std::fs::copy(from, to).map(|_| ())),
+    }
+}
+
+// Clone with the FICLONE ioctl, the libc functions are always linked by std
+#[cfg(all(target_os = "linux", any(target_arch = "x86", target_arch = "x86_64", target_arch = "arm", target_arch = "aarch64", target_arch = "riscv64")))]
+fn clone_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    unsafe extern "C" {
+        fn ioctl(fd: std::ffi::c_int, request: std::ffi::c_ulong, ...) -> std::ffi::c_int;
+    }
+    // _IOW(0x94, 9, int) from linux/fs.h
+    const FICLONE: std::ffi::c_ulong = 0x40049409;
+
+    let source = std::fs::File::open(from)?;
+    let destination = std::fs::OpenOptions::new().write(true).create_new(true).open(to)?;
+    // SAFETY: both file descriptors are open for the duration of the call
+    if unsafe { ioctl(destination.as_raw_fd(), FICLONE, source.as_raw_fd()) } == -1 {
+        let error = Error::last_os_error();
+        drop(destination);
+        let _ = std::fs::remove_file(to);
+        return Err(error);
+    }
+    std::fs::set_permissions(to, source.metadata()?.permissions())
+}
+
+#[cfg(target_os = "macos")]
+fn clone_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    use std::ffi::{CString, c_char, c_int};
+    use std::os::unix::ffi::OsStrExt;
+
+    unsafe extern "C" {
+        fn clonefile(src: *const c_char, dst: *const c_char, flags: u32) -> c_int;
+    }
+    let from = CString::new(from.as_os_str().as_bytes()).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    let to = CString::new(to.as_os_str().as_bytes()).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    // SAFETY: both paths are valid NUL-terminated strings for the duration of the call
+    if unsafe { clonefile(from.as_ptr(), to.as_ptr(), 0) } == -1 {
+        return Err(Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(
+    all(target_os = "linux", any(target_arch = "x86", target_arch = "x86_64", target_arch = "arm", target_arch = "aarch64", target_arch = "riscv64")),
+    target_os = "macos"
+)))]
+fn clone_file(_from: &Path, _to: &Path) -> std::io::Result<()> {
+    Err(Error::new(ErrorKind::Unsupported, "reflinks are not supported on this platform"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_path() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-transfer-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("a/nested")).unwrap();
+        std::fs::write(dir.join("a/nested/file.txt"), "content").unwrap();
+
+        move_path(&dir.join("a"), &dir.join("b")).unwrap();
+        assert!(!dir.join("a").exists());
+        assert_eq!(std::fs::read_to_string(dir.join("b/nested/file.txt")).unwrap(), "content");
+
+        // The fallback used across filesystems
+        copy_and_remove(&dir.join("b"), &dir.join("c")).unwrap();
+        assert!(!dir.join("b").exists());
+        assert_eq!(std::fs::read_to_string(dir.join("c/nested/file.txt")).unwrap(), "content");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_copy_file() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-reflink-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.bin"), [0, 1, 2, 255]).unwrap();
+
+        copy_file(&dir.join("a.bin"), &dir.join("never.bin"), Reflink::Never).unwrap();
+        assert_eq!(std::fs::read(dir.join("never.bin")).unwrap(), [0, 1, 2, 255]);
+        copy_file(&dir.join("a.bin"), &dir.join("auto.bin"), Reflink::Auto).unwrap();
+        assert_eq!(std::fs::read(dir.join("auto.bin")).unwrap(), [0, 1, 2, 255]);
+
+        // Depends on the filesystem of the temporary directory, but never leaves a partial file
+        match copy_file(&dir.join("a.bin"), &dir.join("always.bin"), Reflink::Always) {
+            Ok(()) => assert_eq!(std::fs::read(dir.join("always.bin")).unwrap(), [0, 1, 2, 255]),
+            Err(_) => assert!(!dir.join("always.bin").exists()),
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
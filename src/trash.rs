@@ -0,0 +1,119 @@
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use crate::transfer;
+
+// Move a file or directory to the trash of the user, returning where it ended up
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn trash(path: &Path) -> std::io::Result<PathBuf> {
+    // See https://specifications.freedesktop.org/trash-spec/latest/
+    let trash_dir = match std::env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        Some(data_home) => PathBuf::from(data_home).join("Trash"),
+        None => home_dir()?.join(".local/share/Trash"),
+    };
+    trash_in(path, &trash_dir)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn trash_in(path: &Path, trash_dir: &Path) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(trash_dir.join("files"))?;
+    std::fs::create_dir_all(trash_dir.join("info"))?;
+
+    let path = std::path::absolute(path)?;
+    let name = path.file_name().unwrap().to_string_lossy().to_string();
+    let deletion_date = &crate::output::timestamp(std::time::SystemTime::now())[..19];
+    // Claim a unique name by creating its info file, which also lets file managers restore it
+    let mut counter = 1;
+    let (target, info) = loop {
+        let candidate = if counter == 1 { name.clone() } else { format!("{name}.{counter}") };
+        let info = trash_dir.join("info").join(format!("{candidate}.trashinfo"));
+        let content = format!("[Trash Info]\nPath={}\nDeletionDate={deletion_date}\n", encode_path(&path));
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&info) {
+            Ok(mut file) if !trash_dir.join("files").join(&candidate).exists() => {
+                std::io::Write::write_all(&mut file, content.as_bytes())?;
+                break (trash_dir.join("files").join(candidate), info);
+            }
+            Ok(_) => {
+                let _ = std::fs::remove_file(&info);
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+        counter += 1;
+    };
+    transfer::move_path(&path, &target).inspect_err(|_| {
+        let _ = std::fs::remove_file(info);
+    })?;
+    Ok(target)
+}
+
+#[cfg(target_os = "macos")]
+pub fn trash(path: &Path) -> std::io::Result<PathBuf> {
+    let trash_dir = home_dir()?.join(".Trash");
+    let name = path.file_name().unwrap().to_string_lossy().to_string();
+    let mut target = trash_dir.join(&name);
+    let mut counter = 2;
+    while target.exists() {
+        target = trash_dir.join(format!("{name} {counter}"));
+        counter += 1;
+    }
+    transfer::move_path(path, &target)?;
+    Ok(target)
+}
+
+#[cfg(not(unix))]
+pub fn trash(_path: &Path) -> std::io::Result<PathBuf> {
+    Err(Error::new(ErrorKind::Unsupported, "moving to the trash is not supported on this platform"))
+}
+
+#[cfg(unix)]
+fn home_dir() -> std::io::Result<PathBuf> {
+    std::env::home_dir()
+        .filter(|home| !home.as_os_str().is_empty())
+        .ok_or_else(|| Error::new(ErrorKind::NotFound, "the home directory is unknown"))
+}
+
+// Percent-encode an absolute path for the Path key of a .trashinfo file
+#[cfg(all(unix, not(target_os = "macos")))]
+fn encode_path(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut encoded = String::new();
+    for &byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+#[cfg(all(test, unix, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_path() {
+        assert_eq!(encode_path(Path::new("/home/me/my project/é")), "/home/me/my%20project/%C3%A9");
+    }
+
+    #[test]
+    fn test_trash() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-trash-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        std::fs::write(dir.join("my-project/a.txt"), "a").unwrap();
+        let trash_dir = dir.join("data/Trash");
+
+        let target = trash_in(&dir.join("my-project"), &trash_dir).unwrap();
+        assert_eq!(target, dir.join("data/Trash/files/my-project"));
+        assert!(!dir.join("my-project").exists());
+        assert_eq!(std::fs::read_to_string(target.join("a.txt")).unwrap(), "a");
+        let info = std::fs::read_to_string(dir.join("data/Trash/info/my-project.trashinfo")).unwrap();
+        assert!(info.starts_with(&format!("[Trash Info]\nPath={}\nDeletionDate=", encode_path(&dir.join("my-project")))));
+
+        // Names already in the trash are kept
+        std::fs::create_dir_all(dir.join("my-project")).unwrap();
+        assert_eq!(trash_in(&dir.join("my-project"), &trash_dir).unwrap(), dir.join("data/Trash/files/my-project.2"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
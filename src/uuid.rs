@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use crate::Substitution;
+
+// A random (version 4) UUID like "3f2c5e1a-9b7d-4c2e-8f1a-0d6b4e9c7a21". The randomness comes from
+// the hasher keys std seeds from the operating system.
+pub fn generate() -> String {
+    let random = || {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos());
+        hasher.finish()
+    };
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&random().to_le_bytes());
+    bytes[8..].copy_from_slice(&random().to_le_bytes());
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = bytes.iter().map(|byte| format!("{byte:02x}")).collect::<String>();
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+// Replaces every UUID with a fresh one, the same one for every occurrence, in every text it is given
+#[derive(Debug, Default)]
+pub struct Regenerator {
+    // Fresh UUIDs by the lower case old ones
+    fresh: HashMap<String, String>,
+}
+
+impl Regenerator {
+    pub fn replace(&mut self, text: &str) -> (String, Vec<Substitution>) {
+        self.replace_matching(text, |_| true)
+    }
+
+    // Replace only the UUIDs the filter accepts, e.g. the ones identifying projects
+    pub fn replace_matching(&mut self, text: &str, filter: impl Fn(&str) -> bool) -> (String, Vec<Substitution>) {
+        let bytes = text.as_bytes();
+        let mut out = String::with_capacity(text.len());
+        let mut substitutions: Vec<Substitution> = vec![];
+        let mut copied_until = 0;
+        let mut pos = 0;
+        while pos + 36 <= bytes.len() {
+            let bounded = (pos == 0 || !bytes[pos - 1].is_ascii_alphanumeric())
+                && bytes.get(pos + 36).is_none_or(|byte| !byte.is_ascii_alphanumeric());
+            if !bounded || !is_uuid(&bytes[pos..pos + 36]) || !filter(&text[pos..pos + 36]) {
+                pos += 1;
+                continue;
+            }
+            let old = &text[pos..pos + 36];
+            let fresh = self.fresh.entry(old.to_ascii_lowercase()).or_insert_with(generate);
+            // Keep the case of the old one, e.g. upper case in Visual Studio solutions
+            let new = if old.bytes().any(|byte| byte.is_ascii_uppercase()) { fresh.to_ascii_uppercase() } else { fresh.clone() };
+            match substitutions.iter_mut().find(|s| s.from == old) {
+                Some(existing) => existing.count += 1,
+                None => substitutions.push(Substitution { from: old.to_string(), to: new.clone(), count: 1 }),
+            }
+            out.push_str(&text[copied_until..pos]);
+            out.push_str(&new);
+            pos += 36;
+            copied_until = pos;
+        }
+        out.push_str(&text[copied_until..]);
+        (out, substitutions)
+    }
+}
+
+// Hex digits in groups of 8, 4, 4, 4 and 12 separated by hyphens
+pub fn is_uuid(bytes: &[u8]) -> bool {
+    bytes.iter().enumerate().all(|(index, byte)| match index {
+        8 | 13 | 18 | 23 => *byte == b'-',
+        _ => byte.is_ascii_hexdigit(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let (a, b) = (generate(), generate());
+        assert_ne!(a, b);
+        assert!(is_uuid(a.as_bytes()));
+        assert_eq!(&a[14..15], "4");
+    }
+
+    #[test]
+    fn test_regenerator() {
+        let mut regenerator = Regenerator::default();
+        let (sln, substitutions) =
+            regenerator.replace("Project(\"{8BC9CEB8-8B4A-11D0-8D11-00A0C91BC942}\") x0a1b2c3d-0000-0000-0000-000000000000");
+        assert_eq!(substitutions.len(), 1);
+        let fresh = &substitutions[0].to;
+        assert_eq!(sln, format!("Project(\"{{{fresh}}}\") x0a1b2c3d-0000-0000-0000-000000000000"));
+        assert_eq!(fresh.to_ascii_uppercase(), *fresh);
+
+        // The same UUID in another file gets the same fresh one
+        let (csproj, _) = regenerator.replace("<ProjectGuid>8bc9ceb8-8b4a-11d0-8d11-00a0c91bc942</ProjectGuid>");
+        assert_eq!(csproj, format!("<ProjectGuid>{}</ProjectGuid>", fresh.to_ascii_lowercase()));
+
+        let text = "{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC} {8BC9CEB8-8B4A-11D0-8D11-00A0C91BC942}";
+        let (text, substitutions) = regenerator.replace_matching(text, |uuid| uuid.starts_with("8BC9"));
+        assert_eq!(text, format!("{{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}} {{{fresh}}}"));
+        assert_eq!(substitutions.len(), 1);
+    }
+}
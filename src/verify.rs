@@ -0,0 +1,100 @@
+use std::path::{Path, PathBuf};
+
+use crate::{CaseInfo, NormalizedName};
+
+// An occurrence of a name in a file or directory name (without a line) or in the content of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub text: String,
+}
+
+// Find all occurrences of any case variant of the name in the names and contents of the files and
+// directories below root
+pub fn find_occurrences(root: &Path, name: &NormalizedName) -> Vec<Occurrence> {
+    let variants = CaseInfo::all_cases()
+        .into_iter()
+        .map(|case_info| case_info.convert(name.clone()))
+        .collect::<Vec<_>>();
+    let mut occurrences = vec![];
+    if root.is_dir() {
+        scan_directory(root, &variants, &mut occurrences);
+    } else {
+        scan_file(root, &variants, &mut occurrences);
+    }
+    occurrences
+}
+
+fn contains_any(text: &str, variants: &[String]) -> bool {
+    variants.iter().any(|variant| text.contains(variant.as_str()))
+}
+
+fn scan_directory(dir: &Path, variants: &[String], occurrences: &mut Vec<Occurrence>) {
+    let Ok(entries) = dir.read_dir() else {
+        return;
+    };
+    let mut paths = entries.flatten().map(|entry| entry.path()).collect::<Vec<_>>();
+    paths.sort();
+    for path in paths {
+        let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+        if contains_any(&file_name, variants) {
+            occurrences.push(Occurrence {
+                path: path.clone(),
+                line: None,
+                text: file_name,
+            });
+        }
+        if path.is_dir() {
+            scan_directory(&path, variants, occurrences);
+        } else {
+            scan_file(&path, variants, occurrences);
+        }
+    }
+}
+
+fn scan_file(path: &Path, variants: &[String], occurrences: &mut Vec<Occurrence>) {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return;
+    };
+    for (index, line) in content.lines().enumerate() {
+        if contains_any(line, variants) {
+            occurrences.push(Occurrence {
+                path: path.to_path_buf(),
+                line: Some(index + 1),
+                text: line.trim().to_string(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_occurrences() {
+        let dir = std::env::temp_dir().join(format!("project-renamer-find-occurrences-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("old-project")).unwrap();
+        std::fs::write(dir.join("old-project/readme.md"), "# Title\n\nUses OLD_PROJECT\n").unwrap();
+        std::fs::write(dir.join("clean.txt"), "nothing\n").unwrap();
+
+        let occurrences = find_occurrences(&dir, &CaseInfo::detect("old-project").1);
+        assert_eq!(
+            occurrences,
+            vec![
+                Occurrence {
+                    path: dir.join("old-project"),
+                    line: None,
+                    text: "old-project".to_string(),
+                },
+                Occurrence {
+                    path: dir.join("old-project/readme.md"),
+                    line: Some(3),
+                    text: "Uses OLD_PROJECT".to_string(),
+                },
+            ]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,63 @@
+use std::path::Path;
+
+use crate::json;
+
+// The IDs a VS Code extension contributes in its package.json, which are prefixed with a name of the
+// extension like "myExt.doThing": its commands, configuration properties, views and view
+// containers, and the ones of its activation events like "onCommand:myExt.doThing"
+pub fn contributed_ids(path: &Path, text: &str) -> Vec<String> {
+    if path.file_name().is_none_or(|name| name != "package.json") {
+        return vec![];
+    }
+    let (Ok(values), Ok(keys)) = (json::string_values(text), json::keys(text)) else {
+        return vec![];
+    };
+    if !values.iter().any(|(path, _)| path.len() == 2 && path[0] == "engines" && path[1] == "vscode") {
+        return vec![];
+    }
+    let mut ids = vec![];
+    for (path, range) in &values {
+        let path = path.iter().map(String::as_str).collect::<Vec<_>>();
+        let id = match path.as_slice() {
+            ["contributes", "commands", _, "command"] | ["contributes", "views" | "viewsContainers", _, _, "id"] => &text[range.clone()],
+            ["activationEvents", _] => text[range.clone()].split_once(':').map_or("", |(_, id)| id),
+            _ => continue,
+        };
+        ids.push(id.to_string());
+    }
+    for (path, range) in &keys {
+        let path = path.iter().map(String::as_str).collect::<Vec<_>>();
+        if let ["contributes", "configuration", "properties", _] | ["contributes", "configuration", _, "properties", _] = path.as_slice() {
+            ids.push(text[range.clone()].to_string());
+        }
+    }
+    let mut found: Vec<String> = vec![];
+    for id in ids {
+        if id.contains('.') && !found.contains(&id) {
+            found.push(id);
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contributed_ids() {
+        let manifest = r#"{
+            "name": "my-ext",
+            "engines": {"vscode": "^1.80.0"},
+            "activationEvents": ["onCommand:myExt.doThing", "onStartupFinished"],
+            "contributes": {
+                "commands": [{"command": "myExt.doThing", "title": "Do Thing"}],
+                "configuration": {"title": "My Ext", "properties": {"myExt.enable": {"type": "boolean"}}},
+                "viewsContainers": {"activitybar": [{"id": "myExt.sidebar", "title": "My Ext"}]},
+                "views": {"myExt.sidebar": [{"id": "myExt.items", "name": "Items"}]}
+            }
+        }"#;
+        assert_eq!(contributed_ids(Path::new("package.json"), manifest), ["myExt.doThing", "myExt.sidebar", "myExt.items", "myExt.enable"]);
+        assert!(contributed_ids(Path::new("package.json"), r#"{"name": "my-ext", "main": "lib/my.ext.js"}"#).is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// How often the source tree is scanned for changes. Changes are found by comparing snapshots rather
+// than with the notifications of notify, which the offline build doesn't have. Polling also sees
+// changes on network and container mounts that send no notifications.
+pub const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+// State of a single file or directory used to detect changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryState {
+    is_dir: bool,
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+// State of every file and directory in a tree, keyed by the path relative to the root
+pub type Snapshot = BTreeMap<PathBuf, EntryState>;
+
+// A change between two snapshots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Modified(PathBuf),
+    Removed(PathBuf),
+}
+
+// Take a snapshot of the tree at root. A single file results in a snapshot with an empty relative
+// path.
+pub fn snapshot(root: &Path) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    collect(root, Path::new(""), &mut snapshot);
+    snapshot
+}
+
+fn collect(path: &Path, relative: &Path, snapshot: &mut Snapshot) {
+    let Ok(metadata) = path.metadata() else {
+        return;
+    };
+    snapshot.insert(
+        relative.to_path_buf(),
+        EntryState {
+            is_dir: metadata.is_dir(),
+            modified: metadata.modified().ok(),
+            len: if metadata.is_dir() { 0 } else { metadata.len() },
+        },
+    );
+    if metadata.is_dir() {
+        let Ok(entries) = path.read_dir() else {
+            return;
+        };
+        for entry in entries.flatten() {
+            collect(&entry.path(), &relative.join(entry.file_name()), snapshot);
+        }
+    }
+}
+
+// List the changes from old to new. Removed entries come first so a file replaced by a directory
+// (or the other way around) is removed before it is recreated. Directories whose content changed
+// are not reported, only the changed entries in them.
+pub fn diff(old: &Snapshot, new: &Snapshot) -> Vec<Change> {
+    let mut changes = vec![];
+    for (path, state) in old {
+        match new.get(path) {
+            None => changes.push(Change::Removed(path.clone())),
+            Some(new_state) if new_state.is_dir != state.is_dir => changes.push(Change::Removed(path.clone())),
+            _ => {}
+        }
+    }
+    for (path, state) in new {
+        match old.get(path) {
+            Some(old_state) if old_state.is_dir == state.is_dir && (state.is_dir || old_state == state) => {}
+            _ => changes.push(Change::Modified(path.clone())),
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn file(secs: u64, len: u64) -> EntryState {
+        EntryState { is_dir: false, modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs)), len }
+    }
+
+    fn dir(secs: u64) -> EntryState {
+        EntryState { is_dir: true, modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs)), len: 0 }
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = Snapshot::from([
+            (PathBuf::from(""), dir(1)),
+            (PathBuf::from("same.txt"), file(1, 10)),
+            (PathBuf::from("changed.txt"), file(1, 10)),
+            (PathBuf::from("removed.txt"), file(1, 10)),
+            (PathBuf::from("became-dir"), file(1, 10)),
+        ]);
+        let new = Snapshot::from([
+            (PathBuf::from(""), dir(2)),
+            (PathBuf::from("same.txt"), file(1, 10)),
+            (PathBuf::from("changed.txt"), file(2, 10)),
+            (PathBuf::from("added.txt"), file(2, 5)),
+            (PathBuf::from("became-dir"), dir(2)),
+        ]);
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                Change::Removed(PathBuf::from("became-dir")),
+                Change::Removed(PathBuf::from("removed.txt")),
+                Change::Modified(PathBuf::from("added.txt")),
+                Change::Modified(PathBuf::from("became-dir")),
+                Change::Modified(PathBuf::from("changed.txt")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use crate::{CaseInfo, CaseType, NormalizedName};
+
+// The extensions of the translation files named after the text domain, like "old-name-de_DE.mo"
+const TRANSLATIONS: [&str; 4] = [".po", ".mo", ".json", ".l10n.php"];
+
+// The text domain a plugin or theme declares in its header: the Text Domain of the main PHP file of a
+// plugin, the one with a Plugin Name, or of the style.css of a theme
+pub fn text_domain(path: &Path, text: &str) -> Option<String> {
+    let name = path.file_name()?.to_string_lossy();
+    let declared = if name == "style.css" {
+        header(text, "Theme Name")
+    } else if name.ends_with(".php") {
+        header(text, "Plugin Name")
+    } else {
+        None
+    };
+    declared?;
+    header(text, "Text Domain").filter(|domain| !domain.is_empty()).map(str::to_string)
+}
+
+// The value of a field of the header, the way WordPress' get_file_data reads it: in the first 8 KB,
+// on a line starting with the field after any comment markers
+fn header<'a>(text: &'a str, field: &str) -> Option<&'a str> {
+    text.get(..8192).unwrap_or(text).lines().find_map(|line| {
+        let value = line.trim_start_matches([' ', '\t', '/', '*', '#', '@']).strip_prefix(field)?.strip_prefix(':')?;
+        Some(value.trim().trim_end_matches("*/").trim())
+    })
+}
+
+// The text domain of the new name, the slug of the plugin or theme like "new-name"
+pub fn slug(name: &NormalizedName) -> String {
+    if !name.original.is_empty() && name.original.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+        name.original.clone()
+    } else {
+        CaseInfo { separator: Some('-'), part_type: CaseType::LowerCase }.convert(name.clone())
+    }
+}
+
+// The new name of a file named after the text domain: the main PHP file of the plugin, the POT
+// template and the translations of a locale like "old-name-de_DE.po"
+pub fn renamed_file(name: &str, old_domain: &str, new_domain: &str) -> Option<String> {
+    let rest = name.strip_prefix(old_domain)?;
+    let renamed = rest == ".php" || rest == ".pot" || (rest.starts_with('-') && TRANSLATIONS.iter().any(|extension| rest.ends_with(extension)));
+    renamed.then(|| format!("{new_domain}{rest}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_domain() {
+        let plugin = "<?php\n/**\n * Plugin Name: My Plugin\n * Text Domain: myplug\n * Domain Path: /languages\n */\n";
+        assert_eq!(text_domain(Path::new("my-plugin.php"), plugin).as_deref(), Some("myplug"));
+        assert_eq!(text_domain(Path::new("uninstall.php"), "<?php\n// Text Domain: myplug\n"), None);
+        let theme = "/*\nTheme Name: My Theme\nText Domain: my-theme\n*/\n";
+        assert_eq!(text_domain(Path::new("style.css"), theme).as_deref(), Some("my-theme"));
+
+        assert_eq!(renamed_file("myplug.php", "myplug", "new-plugin").as_deref(), Some("new-plugin.php"));
+        assert_eq!(renamed_file("myplug-de_DE.l10n.php", "myplug", "new-plugin").as_deref(), Some("new-plugin-de_DE.l10n.php"));
+        assert_eq!(renamed_file("myplug-admin.php", "myplug", "new-plugin"), None);
+    }
+}
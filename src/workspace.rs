@@ -0,0 +1,122 @@
+use std::path::{Path, PathBuf};
+
+use crate::fs::Filesystem;
+
+// The repository itself, build output and installed dependencies never hold references to members
+const SKIPPED_DIRECTORIES: [&str; 3] = [".git", "node_modules", "target"];
+
+// Root of the Cargo, npm or pnpm workspace, Maven reactor or Gradle build the member is in, the
+// closest ancestor directory with a workspace manifest or Gradle settings. Maven aggregators nest,
+// and the modules of the outermost one may depend on each other.
+pub fn find_root(fs: &dyn Filesystem, member: &Path) -> Option<PathBuf> {
+    let member = fs.absolute(member).ok()?;
+    let root = member.ancestors().skip(1).find(|dir| is_root(fs, dir))?;
+    Some(root.ancestors().take_while(|dir| is_aggregator(fs, dir)).last().unwrap_or(root).to_path_buf())
+}
+
+fn is_root(fs: &dyn Filesystem, dir: &Path) -> bool {
+    let contains = |file: &str, needle: &str| read(fs, &dir.join(file)).is_some_and(|content| content.contains(needle));
+    let is_file = |file: &str| fs.exists(&dir.join(file)) && !fs.is_dir(&dir.join(file));
+    is_file("pnpm-workspace.yaml")
+        || is_file("settings.gradle")
+        || is_file("settings.gradle.kts")
+        || contains("package.json", "\"workspaces\"")
+        || read(fs, &dir.join("Cargo.toml")).is_some_and(|content| content.lines().any(|line| line.trim() == "[workspace]"))
+        || is_aggregator(fs, dir)
+}
+
+// A Maven POM that lists modules
+fn is_aggregator(fs: &dyn Filesystem, dir: &Path) -> bool {
+    read(fs, &dir.join("pom.xml")).is_some_and(|content| content.contains("<modules>"))
+}
+
+fn read(fs: &dyn Filesystem, path: &Path) -> Option<String> {
+    String::from_utf8(fs.read(path).ok()?).ok()
+}
+
+// Files in the workspace that may reference a member by its name or path: the package manifests
+// with the dependency declarations and workspace member lists, and the TypeScript and Jest
+// configurations with path aliases. The given paths, e.g. the member itself, are left out.
+pub fn reference_files(fs: &dyn Filesystem, root: &Path, skip: &[&Path]) -> Vec<PathBuf> {
+    files(fs, root, skip).into_iter().filter(|path| is_reference_file(&path.file_name().unwrap().to_string_lossy())).collect()
+}
+
+// Every file in the directory except the given paths and the skipped directories
+pub fn files(fs: &dyn Filesystem, root: &Path, skip: &[&Path]) -> Vec<PathBuf> {
+    let skip = skip.iter().filter_map(|path| fs.absolute(path).ok()).collect::<Vec<_>>();
+    let mut files = vec![];
+    if let Ok(root) = fs.absolute(root) {
+        visit(fs, &root, &skip, &mut files);
+    }
+    files
+}
+
+fn visit(fs: &dyn Filesystem, dir: &Path, skip: &[PathBuf], files: &mut Vec<PathBuf>) {
+    let Ok(paths) = fs.read_dir(dir) else {
+        return;
+    };
+    for path in paths {
+        let name = path.file_name().unwrap().to_string_lossy();
+        if skip.contains(&path) {
+            continue;
+        }
+        if fs.is_dir(&path) {
+            if !SKIPPED_DIRECTORIES.contains(&name.as_ref()) && !fs.is_symlink(&path) {
+                visit(fs, &path, skip, files);
+            }
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+fn is_reference_file(name: &str) -> bool {
+    matches!(
+        name,
+        "Cargo.toml" | "package.json" | "pnpm-workspace.yaml" | "pom.xml" | "settings.gradle" | "settings.gradle.kts" | "build.gradle" | "build.gradle.kts"
+    )
+        || (name.starts_with("tsconfig") || name.starts_with("jsconfig")) && name.ends_with(".json")
+        || name.starts_with("jest.config.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_reference_file() {
+        for name in ["Cargo.toml", "package.json", "pnpm-workspace.yaml", "pom.xml", "build.gradle.kts", "tsconfig.json", "tsconfig.base.json", "jest.config.ts"] {
+            assert!(is_reference_file(name), "{name}");
+        }
+        for name in ["Cargo.lock", "main.rs", "tsconfig.md", "jest.setup.js"] {
+            assert!(!is_reference_file(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_find_root() {
+        let disk = crate::fs::Disk::default();
+        let dir = std::env::temp_dir().join(format!("project-renamer-workspace-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("cargo/crates/my-lib")).unwrap();
+        std::fs::write(dir.join("cargo/Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+        std::fs::create_dir_all(dir.join("npm/packages/my-lib")).unwrap();
+        std::fs::write(dir.join("npm/package.json"), r#"{"workspaces": ["packages/*"]}"#).unwrap();
+        std::fs::create_dir_all(dir.join("none/my-lib")).unwrap();
+        std::fs::create_dir_all(dir.join("maven/services/my-lib")).unwrap();
+        std::fs::write(dir.join("maven/pom.xml"), "<project><modules><module>services</module></modules></project>").unwrap();
+        std::fs::write(dir.join("maven/services/pom.xml"), "<project><modules><module>my-lib</module></modules></project>").unwrap();
+
+        assert_eq!(find_root(&disk, &dir.join("cargo/crates/my-lib")), Some(dir.join("cargo")));
+        assert_eq!(find_root(&disk, &dir.join("npm/packages/my-lib")), Some(dir.join("npm")));
+        assert_eq!(find_root(&disk, &dir.join("none/my-lib")), None);
+        assert_eq!(find_root(&disk, &dir.join("maven/services/my-lib")), Some(dir.join("maven")));
+
+        std::fs::create_dir_all(dir.join("cargo/crates/app/node_modules/my-lib")).unwrap();
+        std::fs::write(dir.join("cargo/crates/app/Cargo.toml"), "").unwrap();
+        std::fs::write(dir.join("cargo/crates/app/node_modules/my-lib/package.json"), "").unwrap();
+        std::fs::write(dir.join("cargo/crates/my-lib/Cargo.toml"), "").unwrap();
+        let files = reference_files(&disk, &dir.join("cargo"), &[&dir.join("cargo/crates/my-lib")]);
+        assert_eq!(files, vec![dir.join("cargo/Cargo.toml"), dir.join("cargo/crates/app/Cargo.toml")]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
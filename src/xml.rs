@@ -0,0 +1,175 @@
+use crate::structured::Located;
+
+// The text content and attribute values of an XML document with the path of element names leading
+// to them, e.g. ["project", "artifactId"] or ["manifest", "@package"] for an attribute, in the order
+// they occur. The ranges are of the raw text, so entities and the markup of CDATA sections around
+// them stay as they are. In property lists a value in a <dict> is named by its <key>, e.g.
+// ["plist", "dict", "CFBundleName"]. None when the tags don't match.
+pub fn values(text: &str) -> Option<Vec<Located>> {
+    let mut values = vec![];
+    let mut stack: Vec<String> = vec![];
+    // The last <key> of a property list <dict>, which names the element that follows
+    let mut plist_key: Option<String> = None;
+    let mut key_text = false;
+    let mut pos = 0;
+    while pos < text.len() {
+        let rest = &text[pos..];
+        if !rest.starts_with('<') {
+            let end = rest.find('<').map_or(text.len(), |end| pos + end);
+            let content = &text[pos..end];
+            let trimmed = content.trim();
+            if !trimmed.is_empty() {
+                if stack.is_empty() {
+                    return None;
+                }
+                let start = pos + content.len() - content.trim_start().len();
+                if key_text {
+                    plist_key = Some(trimmed.to_string());
+                }
+                values.push((stack.clone(), start..start + trimmed.len()));
+            }
+            pos = end;
+        } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata.find("]]>")?;
+            if end > 0 {
+                values.push((stack.clone(), pos + 9..pos + 9 + end));
+            }
+            pos += 9 + end + 3;
+        } else if rest.starts_with("<!--") {
+            pos += rest.find("-->")? + 3;
+        } else if rest.starts_with("<?") {
+            pos += rest.find("?>")? + 2;
+        } else if rest.starts_with("<!") {
+            // A document type declaration, with an internal subset in brackets
+            let end = match (rest.find('['), rest.find('>')) {
+                (Some(open), Some(close)) if open < close => open + rest[open..].find("]>")? + 1,
+                (_, close) => close?,
+            };
+            pos += end + 1;
+        } else if let Some(closing) = rest.strip_prefix("</") {
+            let end = closing.find('>')?;
+            let name = closing[..end].trim();
+            let open = stack.pop()?;
+            if open != name && !plist_value(&open, name) {
+                return None;
+            }
+            key_text = false;
+            pos += 2 + end + 1;
+        } else {
+            let end = tag_end(rest)?;
+            let tag = &rest[1..end];
+            let self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let name_end = tag.find(|c: char| c.is_whitespace()).unwrap_or(tag.len());
+            let name = &tag[..name_end];
+            let in_dict = stack.last().is_some_and(|parent| parent == "dict") && stack.first().is_some_and(|root| root == "plist");
+            let segment = match plist_key.take() {
+                Some(key) if in_dict && name != "key" => key,
+                _ => name.to_string(),
+            };
+            key_text = in_dict && name == "key";
+            stack.push(segment);
+            for (attribute, range) in attributes(tag, pos + 1) {
+                let mut path = stack.clone();
+                path.push(format!("@{attribute}"));
+                values.push((path, range));
+            }
+            if self_closing {
+                stack.pop();
+                key_text = false;
+            }
+            pos += end + 1;
+        }
+    }
+    stack.is_empty().then_some(values)
+}
+
+// A value element of a property list is on the stack under the name of its key, so its closing tag
+// has a different name
+fn plist_value(open: &str, name: &str) -> bool {
+    !open.is_empty() && matches!(name, "string" | "integer" | "real" | "true" | "false" | "date" | "data" | "array" | "dict")
+}
+
+// The offset of the ">" that ends the tag at the start of the text, outside of attribute values
+fn tag_end(text: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '>') => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+// The names of the attributes of a tag and the ranges of their values, offset by the start of the
+// tag in the text
+fn attributes(tag: &str, offset: usize) -> Vec<(String, std::ops::Range<usize>)> {
+    let mut attributes = vec![];
+    let mut rest = tag;
+    let mut pos = 0;
+    while let Some(equals) = rest.find('=') {
+        let name = rest[..equals].split_whitespace().last().unwrap_or_default().to_string();
+        let after = &rest[equals + 1..];
+        let skipped = after.len() - after.trim_start().len();
+        let Some(quote) = after.trim_start().chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let value_start = equals + 1 + skipped + 1;
+        let Some(length) = rest[value_start..].find(quote) else {
+            break;
+        };
+        attributes.push((name, offset + pos + value_start..offset + pos + value_start + length));
+        let consumed = value_start + length + 1;
+        pos += consumed;
+        rest = &rest[consumed..];
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(text: &str) -> Vec<(String, &str)> {
+        values(text).unwrap().into_iter().map(|(path, range)| (path.join("/"), &text[range])).collect()
+    }
+
+    #[test]
+    fn test_values() {
+        let pom = "<?xml version=\"1.0\"?>\n<!-- my-app -->\n<project xmlns=\"http://maven.apache.org/POM/4.0.0\">\n  \
+                   <artifactId>my-app</artifactId>\n  <description><![CDATA[my-app & more]]></description>\n  \
+                   <name>My &amp; app</name>\n  <module path='my-app'/>\n</project>\n";
+        let found = paths(pom);
+        let found = found.iter().map(|(path, value)| (path.as_str(), *value)).collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                ("project/@xmlns", "http://maven.apache.org/POM/4.0.0"),
+                ("project/artifactId", "my-app"),
+                ("project/description", "my-app & more"),
+                ("project/name", "My &amp; app"),
+                ("project/module/@path", "my-app"),
+            ]
+        );
+
+        let plist = "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"x.dtd\">\n<plist version=\"1.0\"><dict>\n  \
+                     <key>CFBundleName</key>\n  <string>my-app</string>\n  <key>CFBundleVersion</key><string>1</string>\n</dict></plist>";
+        let found = paths(plist);
+        let found = found.iter().map(|(path, value)| (path.as_str(), *value)).collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                ("plist/@version", "1.0"),
+                ("plist/dict/key", "CFBundleName"),
+                ("plist/dict/CFBundleName", "my-app"),
+                ("plist/dict/key", "CFBundleVersion"),
+                ("plist/dict/CFBundleVersion", "1"),
+            ]
+        );
+
+        assert!(values("<a><b></a>").is_none());
+    }
+}
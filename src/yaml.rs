@@ -0,0 +1,189 @@
+use std::ops::Range;
+
+use crate::structured::Located;
+
+// A mapping key or sequence item the scanner is inside of
+struct Frame {
+    indent: usize,
+    // The key, or the index of a sequence item
+    segment: String,
+    item: bool,
+    // Number of sequence items seen under this key
+    items: usize,
+}
+
+// The scalar values of a YAML file with the path of keys and sequence indexes leading to them, in
+// the order they occur. The file is scanned line by line and only the ranges of the values are
+// returned, so the comments, anchors and formatting around them stay as they are. Block style
+// mappings and sequences, quoted and plain scalars and block scalars ("|" and ">") are supported,
+// values in flow style like "[a, b]" are skipped. Every document of a multi-document file starts at
+// the root.
+pub fn values(text: &str) -> Vec<Located> {
+    let mut values = vec![];
+    let mut stack: Vec<Frame> = vec![];
+    let mut root_items = 0;
+    // The indentation and path of a block scalar whose lines follow
+    let mut block: Option<(usize, Vec<String>)> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+        let indent = content.len() - content.trim_start_matches(' ').len();
+        let rest = &content[indent..];
+
+        if let Some((block_indent, path)) = &block {
+            if rest.is_empty() {
+                continue;
+            }
+            if indent > *block_indent {
+                values.push((path.clone(), start + indent..start + content.len()));
+                continue;
+            }
+            block = None;
+        }
+        if rest.is_empty() || rest.starts_with('#') {
+            continue;
+        }
+        if rest.starts_with("---") || rest.starts_with("...") {
+            stack.clear();
+            root_items = 0;
+            continue;
+        }
+
+        // Sequence items, "- value" or "- key: value" which starts a mapping in the item
+        let mut column = indent;
+        let mut rest = rest;
+        while rest == "-" || rest.starts_with("- ") {
+            while stack.last().is_some_and(|frame| frame.indent > column || (frame.indent == column && frame.item)) {
+                stack.pop();
+            }
+            let index = match stack.last_mut() {
+                Some(parent) => {
+                    parent.items += 1;
+                    parent.items - 1
+                }
+                None => {
+                    root_items += 1;
+                    root_items - 1
+                }
+            };
+            stack.push(Frame { indent: column, segment: index.to_string(), item: true, items: 0 });
+            let after = rest[1..].trim_start_matches(' ');
+            column += rest.len() - after.len();
+            rest = after;
+        }
+        if rest.is_empty() {
+            continue;
+        }
+
+        let value_start = match split_key(rest) {
+            Some((key, after)) => {
+                while stack.last().is_some_and(|frame| frame.indent >= column) {
+                    stack.pop();
+                }
+                stack.push(Frame { indent: column, segment: key, item: false, items: 0 });
+                rest.len() - after.len()
+            }
+            None => 0,
+        };
+        let path = stack.iter().map(|frame| frame.segment.clone()).collect::<Vec<_>>();
+        let base = start + column;
+        match scalar(&rest[value_start..]) {
+            Some(Scalar::Inline(range)) => values.push((path, base + value_start + range.start..base + value_start + range.end)),
+            Some(Scalar::Block) => block = Some((column, path)),
+            None => {}
+        }
+    }
+    values
+}
+
+// The key of a "key: value" line and the text after the colon
+fn split_key(line: &str) -> Option<(String, &str)> {
+    if let Some(quote) = line.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let end = line[1..].find(quote)? + 1;
+        let after = line[end + 1..].trim_start_matches(' ');
+        let after = after.strip_prefix(':').filter(|after| after.is_empty() || after.starts_with(' '))?;
+        return Some((line[1..end].to_string(), after));
+    }
+    let colon = line.match_indices(':').map(|(index, _)| index).find(|index| {
+        let after = &line[index + 1..];
+        after.is_empty() || after.starts_with(' ')
+    })?;
+    let key = &line[..colon];
+    (!key.contains(" #") && !key.starts_with(['[', '{', '#'])).then(|| (key.trim_end().to_string(), &line[colon + 1..]))
+}
+
+enum Scalar {
+    // The range of the value in the text after the key
+    Inline(Range<usize>),
+    // A "|" or ">" block scalar, its value is on the following lines
+    Block,
+}
+
+// The scalar value of the text after a key or sequence item dash, without tags, anchors and
+// comments. None for nested collections, aliases and flow style.
+fn scalar(text: &str) -> Option<Scalar> {
+    let mut start = text.len() - text.trim_start_matches(' ').len();
+    // Tags like "!!str" and anchors like "&name" come before the value
+    while text[start..].starts_with(['!', '&']) {
+        let token = text[start..].find(' ').unwrap_or(text.len() - start);
+        start += token;
+        start += text[start..].len() - text[start..].trim_start_matches(' ').len();
+    }
+    let value = &text[start..];
+    if value.is_empty() || value.starts_with(['#', '*', '[', '{']) {
+        return None;
+    }
+    if value.starts_with(['|', '>']) {
+        return Some(Scalar::Block);
+    }
+    if let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let end = value[1..].find(quote).map_or(value.len(), |end| end + 1);
+        return Some(Scalar::Inline(start + 1..start + end));
+    }
+    let end = value.find(" #").unwrap_or(value.len());
+    Some(Scalar::Inline(start..start + value[..end].trim_end().len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values() {
+        let text = "# my-app\n\
+                    apiVersion: v1\n\
+                    metadata:\n  \
+                      name: my-app # the name\n  \
+                      labels:\n    \
+                        app.kubernetes.io/name: &name \"my-app\"\n\
+                    spec:\n  \
+                      containers:\n  \
+                      - name: my-app\n    \
+                        image: registry/my-app:1.0\n    \
+                        args: [--name, my-app]\n  \
+                      - name: sidecar\n  \
+                      description: |\n    \
+                        Runs my-app\n\n    \
+                        and more\n\
+                    ---\n\
+                    kind: Service\n";
+        let values = values(text).into_iter().map(|(path, range)| (path.join("."), &text[range])).collect::<Vec<_>>();
+        let values = values.iter().map(|(path, value)| (path.as_str(), *value)).collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            [
+                ("apiVersion", "v1"),
+                ("metadata.name", "my-app"),
+                ("metadata.labels.app.kubernetes.io/name", "my-app"),
+                ("spec.containers.0.name", "my-app"),
+                ("spec.containers.0.image", "registry/my-app:1.0"),
+                ("spec.containers.1.name", "sidecar"),
+                ("spec.description", "Runs my-app"),
+                ("spec.description", "and more"),
+                ("kind", "Service"),
+            ]
+        );
+    }
+}